@@ -37,6 +37,8 @@ pub struct APIConfig {
     pub max_subscriptions_per_connection: u32,
     /// max length for logging for requests and responses. Logs bigger than this limit will be truncated.
     pub max_log_length: u32,
+    /// maximum number of events returned by an event query, also used when the query has no limit
+    pub max_events_per_request: u32,
     /// host filtering.
     pub allow_hosts: Vec<String>,
     /// batch request limit. 0 means disabled.
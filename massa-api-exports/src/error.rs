@@ -50,6 +50,8 @@ pub enum ApiError {
     InternalServerError(String),
     /// Factory error: {0}
     FactoryError(#[from] FactoryError),
+    /// Operations rejected by the pool: {0}
+    OperationsRejected(String),
 }
 
 impl From<ApiError> for ErrorObjectOwned {
@@ -73,6 +75,7 @@ impl From<ApiError> for ErrorObjectOwned {
             ApiError::MissingConfig(_) => -32018,
             ApiError::WrongAPI => -32019,
             ApiError::FactoryError(_) => -32020,
+            ApiError::OperationsRejected(_) => -32021,
         };
 
         ErrorObject::owned(code, err.to_string(), None::<()>)
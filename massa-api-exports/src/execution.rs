@@ -1,7 +1,10 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_final_state::StateChanges;
-use massa_models::{address::Address, output_event::SCOutputEvent, slot::Slot};
+use massa_models::{
+    address::Address, amount::Amount, operation::OperationType, output_event::SCOutputEvent,
+    slot::Slot,
+};
 use serde::{Deserialize, Serialize};
 use std::{collections::VecDeque, fmt::Display};
 
@@ -66,6 +69,9 @@ pub struct ReadOnlyBytecodeExecution {
     /// whether to start execution from final or active state. Default false
     #[serde(default)]
     pub is_final: bool,
+    /// past final slot from which to start execution instead, overriding `is_final`. Optional
+    #[serde(default)]
+    pub at_slot: Option<Slot>,
 }
 
 /// read SC call request
@@ -84,4 +90,114 @@ pub struct ReadOnlyCall {
     /// whether to start execution from final or active state. Default false
     #[serde(default)]
     pub is_final: bool,
+    /// past final slot from which to start execution instead, overriding `is_final`. Optional
+    #[serde(default)]
+    pub at_slot: Option<Slot>,
+}
+
+/// operation simulation request
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct OperationSimulation {
+    /// address of the sender of the operation
+    pub sender_address: Address,
+    /// fee paid by the sender
+    pub fee: Amount,
+    /// content of the operation, unsigned
+    pub op: OperationType,
+    /// whether to start execution from final or active state. Default false
+    #[serde(default)]
+    pub is_final: bool,
+}
+
+/// Change of the balance of an address
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BalanceChange {
+    /// address
+    pub address: Address,
+    /// balance before the execution
+    pub balance_before: Amount,
+    /// balance after the execution
+    pub balance_after: Amount,
+}
+
+/// The response to an operation simulation request.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OperationSimulationResponse {
+    /// The slot at which the simulation occurred.
+    pub executed_at: Slot,
+    /// The result of the simulation. Nothing is returned on success.
+    pub result: ReadOnlyResult,
+    /// The gas used by the operation
+    pub gas_used: u64,
+    /// The balances changed by the operation, fee included
+    pub balance_changes: Vec<BalanceChange>,
+    /// The output events generated by the operation.
+    pub output_events: VecDeque<SCOutputEvent>,
+    /// state changes caused by the operation, including the ledger changes and the created asynchronous messages
+    pub state_changes: StateChanges,
+}
+
+impl Display for OperationSimulationResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Simulated at slot: {}", self.executed_at)?;
+        writeln!(
+            f,
+            "Result: {}",
+            match &self.result {
+                ReadOnlyResult::Error(e) =>
+                    format!("an error occurred during the simulation: {}", e),
+                ReadOnlyResult::Ok(_) => "success".to_string(),
+            }
+        )?;
+        writeln!(f, "Gas used: {}", self.gas_used)?;
+        if !self.balance_changes.is_empty() {
+            writeln!(f, "Balance changes:")?;
+            for change in self.balance_changes.iter() {
+                writeln!(
+                    f,
+                    "\t{}: {} -> {}",
+                    change.address, change.balance_before, change.balance_after
+                )?;
+            }
+        }
+        if !self.state_changes.async_pool_changes.0.is_empty() {
+            writeln!(
+                f,
+                "Asynchronous message changes: {}",
+                self.state_changes.async_pool_changes.0.len()
+            )?;
+        }
+        if !self.output_events.is_empty() {
+            writeln!(f, "Generated events:",)?;
+            for event in self.output_events.iter() {
+                writeln!(f, "{}", event)?; // id already displayed in event
+            }
+        }
+        Ok(())
+    }
+}
+
+/// gas estimation request
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct GasEstimationRequest {
+    /// address of the sender of the operation
+    pub sender_address: Address,
+    /// content of the `CallSC` or `ExecuteSC` operation, its `max_gas` is ignored
+    pub op: OperationType,
+}
+
+/// The response to a gas estimation request.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GasEstimationResponse {
+    /// The lowest gas limit with which the operation succeeds
+    pub min_gas: u64,
+    /// The gas limit to use, with a safety margin
+    pub recommended_gas: u64,
+}
+
+impl Display for GasEstimationResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Minimal gas: {}", self.min_gas)?;
+        writeln!(f, "Recommended gas: {}", self.recommended_gas)
+    }
 }
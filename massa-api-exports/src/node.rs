@@ -3,10 +3,11 @@
 use massa_models::node::NodeId;
 use massa_models::stats::{ConsensusStats, ExecutionStats, NetworkStats};
 use massa_models::{config::CompactConfig, slot::Slot, version::Version};
+use massa_protocol_exports::{ConnectionStats, PeerMessageCounts, TransportType};
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-use std::net::IpAddr;
+use std::net::{IpAddr, SocketAddr};
 
 /// node status
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -89,3 +90,86 @@ impl std::fmt::Display for NodeStatus {
         Ok(())
     }
 }
+
+/// peer connected to the node
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PeerInfo {
+    /// node id of the peer
+    pub node_id: NodeId,
+    /// address of the connection
+    pub address: SocketAddr,
+    /// category of the peer, `default` if it is in none of the configured categories
+    pub category: String,
+    /// true if the connection is outgoing, false if incoming
+    pub is_outgoing: bool,
+    /// listeners announced by the peer
+    pub listeners: BTreeMap<SocketAddr, TransportType>,
+    /// bytes sent to the peer
+    pub bytes_sent: u64,
+    /// bytes received from the peer
+    pub bytes_received: u64,
+    /// messages received from the peer, by handler
+    pub messages_received: PeerMessageCounts,
+    /// last measured round trip time: the one of the handshake, refreshed by the peer tester
+    pub ping: Option<MassaTime>,
+    /// time at which the handshake completed
+    pub handshake_at: Option<MassaTime>,
+    /// reputation score of the peer, negative if it misbehaved
+    pub score: i64,
+    /// connection and peer tester statistics of the listeners of the peer
+    pub connection_stats: BTreeMap<SocketAddr, ConnectionStats>,
+}
+
+impl std::fmt::Display for PeerInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Node's ID: {} / IP address: {} / {} connection / category: {}",
+            self.node_id,
+            self.address,
+            if self.is_outgoing { "Out" } else { "In" },
+            self.category
+        )?;
+        writeln!(
+            f,
+            "\tTraffic: {} bytes sent / {} bytes received",
+            self.bytes_sent, self.bytes_received
+        )?;
+        writeln!(
+            f,
+            "\tMessages received: {} block / {} operation / {} endorsement / {} peer",
+            self.messages_received.block,
+            self.messages_received.operation,
+            self.messages_received.endorsement,
+            self.messages_received.peer_management
+        )?;
+        if let Some(ping) = self.ping {
+            writeln!(f, "\tPing: {} ms", ping.to_millis())?;
+        }
+        writeln!(f, "\tReputation score: {}", self.score)?;
+        if let Some(handshake_at) = self.handshake_at {
+            writeln!(f, "\tHandshake: {}", handshake_at.format_instant())?;
+        }
+        for (addr, transport) in &self.listeners {
+            write!(f, "\tListener: {} ({:?})", addr, transport)?;
+            if let Some(stats) = self.connection_stats.get(addr) {
+                if let Some(last_test_success) = stats.last_test_success {
+                    write!(
+                        f,
+                        " / last test success: {}",
+                        last_test_success.format_instant()
+                    )?;
+                }
+                if let Some(last_test_failure) = stats.last_test_failure {
+                    write!(
+                        f,
+                        " / last test failure: {}",
+                        last_test_failure.format_instant()
+                    )?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
@@ -3,6 +3,7 @@
 use massa_models::{
     block_id::BlockId,
     operation::{OperationId, SecureShareOperation},
+    slot::Slot,
 };
 
 use massa_signature::{PublicKey, Signature};
@@ -65,6 +66,50 @@ impl std::fmt::Display for OperationInfo {
     }
 }
 
+/// Step of the lifecycle of an operation
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum OperationStatus {
+    /// the operation was accepted in the pool
+    AcceptedInPool,
+    /// the operation was rejected by the admission policy of the pool
+    RejectedByPool {
+        /// code of the rejection reason
+        reason: String,
+    },
+    /// the operation was included in a block
+    IncludedInBlock {
+        /// id of the block
+        block_id: BlockId,
+        /// slot of the block
+        slot: Slot,
+    },
+    /// the operation was executed in a candidate slot
+    Executed {
+        /// slot of the execution, None if it happened before the subscription
+        slot: Option<Slot>,
+        /// true if the execution succeeded
+        success: bool,
+    },
+    /// the execution of the operation became final
+    Final {
+        /// slot of the execution, None if it became final before the subscription
+        slot: Option<Slot>,
+        /// true if the execution succeeded
+        success: bool,
+    },
+    /// the last slot in which the operation could be executed became final without executing it
+    Expired,
+}
+
+/// Status change of an operation
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct OperationStatusUpdate {
+    /// id of the operation
+    pub operation_id: OperationId,
+    /// new status of the operation
+    pub status: OperationStatus,
+}
+
 #[cfg(test)]
 mod tests {
     use jsonrpsee::core::__reexports::serde_json::{self, Value};
@@ -35,6 +35,14 @@ impl<T: Serialize> PagedVec<T> {
             _total_count: total_count,
         }
     }
+
+    /// Creates a Paged Vec from the elements of a page that was already selected among `total_count` elements
+    pub fn from_page(res: Vec<T>, total_count: usize) -> Self {
+        PagedVec {
+            res,
+            _total_count: total_count,
+        }
+    }
 }
 
 impl<T: Serialize> Serialize for PagedVec<T> {
@@ -11,18 +11,24 @@ use jsonrpsee::core::{Error as JsonRpseeError, RpcResult, SubscriptionResult};
 use jsonrpsee::{PendingSubscriptionSink, SubscriptionMessage};
 use massa_api_exports::config::APIConfig;
 use massa_api_exports::error::ApiError;
+use massa_api_exports::operation::{OperationStatus, OperationStatusUpdate};
 use massa_api_exports::page::{PageRequest, PagedVec, PagedVecV2};
 use massa_api_exports::ApiRequest;
 use massa_consensus_exports::{ConsensusChannels, ConsensusController};
-use massa_execution_exports::ExecutionController;
+use massa_execution_exports::{ExecutionChannels, ExecutionController, SlotExecutionOutput};
 use massa_models::address::Address;
+use massa_models::block::SecureShareBlock;
 use massa_models::block_id::BlockId;
+use massa_models::operation::{OperationId, SecureShareOperation};
+use massa_models::prehash::PreHashMap;
 use massa_models::slot::Slot;
 use massa_models::timeslots::get_latest_block_slot_at_timestamp;
 use massa_models::version::Version;
-use massa_pool_exports::PoolChannels;
+use massa_pool_exports::{OperationRejectionReason, PoolChannels};
+use massa_storage::Storage;
 use massa_time::MassaTime;
 use serde::Serialize;
+use tokio::sync::broadcast::error::RecvError;
 use tokio_stream::wrappers::BroadcastStream;
 
 impl API<ApiV2> {
@@ -31,7 +37,9 @@ impl API<ApiV2> {
         consensus_controller: Box<dyn ConsensusController>,
         consensus_channels: ConsensusChannels,
         execution_controller: Box<dyn ExecutionController>,
+        execution_channels: ExecutionChannels,
         pool_channels: PoolChannels,
+        storage: Storage,
         api_settings: APIConfig,
         version: Version,
     ) -> Self {
@@ -39,7 +47,9 @@ impl API<ApiV2> {
             consensus_controller,
             consensus_channels,
             execution_controller,
+            execution_channels,
             pool_channels,
+            storage,
             api_settings,
             version,
         })
@@ -151,6 +161,278 @@ impl MassaApiServer for API<ApiV2> {
     ) -> SubscriptionResult {
         broadcast_via_ws(self.0.pool_channels.operation_sender.clone(), pending).await
     }
+
+    async fn subscribe_operations_status(
+        &self,
+        pending: PendingSubscriptionSink,
+        operation_ids: Vec<OperationId>,
+    ) -> SubscriptionResult {
+        if operation_ids.len() as u64 > self.0.api_settings.max_arguments {
+            pending
+                .reject(ApiError::BadRequest("too many arguments".into()))
+                .await;
+            return Ok(());
+        }
+
+        // subscribe before reading the current statuses so that no change is missed
+        let mut operation_receiver = self.0.pool_channels.operation_sender.subscribe();
+        let mut rejection_receiver = self.0.pool_channels.operation_rejection_sender.subscribe();
+        let mut block_receiver = self.0.consensus_channels.block_sender.subscribe();
+        let mut slot_execution_output_receiver = self
+            .0
+            .execution_channels
+            .slot_execution_output_sender
+            .subscribe();
+
+        // the validity of the operations already known by the node allows detecting their expiry
+        let mut watched = WatchedOperations::new(self.0.api_settings.thread_count);
+        {
+            let read_ops = self.0.storage.read_operations();
+            for operation_id in operation_ids {
+                let validity = read_ops.get(&operation_id).map(|op| {
+                    (
+                        op.content_creator_address
+                            .get_thread(self.0.api_settings.thread_count),
+                        op.content.expire_period,
+                    )
+                });
+                watched.watch(operation_id, validity);
+            }
+        }
+        let mut updates = self.refresh_watched_operations(&mut watched);
+
+        let sink = pending.accept().await?;
+        loop {
+            for update in updates {
+                let notif = SubscriptionMessage::from_json(&update)?;
+                if sink.send(notif).await.is_err() {
+                    return Ok(());
+                }
+            }
+            if watched.operations.is_empty() {
+                return Ok(());
+            }
+
+            let res = tokio::select! {
+                _ = sink.closed() => return Ok(()),
+                res = operation_receiver.recv() => res.map(|op| watched.on_operation_accepted(&op)),
+                res = rejection_receiver.recv() => {
+                    res.map(|(operation_id, reason)| watched.on_operation_rejected(operation_id, reason))
+                }
+                res = block_receiver.recv() => res.map(|block| watched.on_block(&block)),
+                res = slot_execution_output_receiver.recv() => {
+                    res.map(|output| watched.on_slot_execution_output(&output))
+                }
+            };
+            updates = match res {
+                Ok(updates) => updates,
+                // some changes were missed: read the current statuses again
+                Err(RecvError::Lagged(_)) => self.refresh_watched_operations(&mut watched),
+                Err(RecvError::Closed) => return Ok(()),
+            };
+        }
+    }
+}
+
+impl API<ApiV2> {
+    /// Read the current execution status of the watched operations, and expire them against the final execution cursor
+    fn refresh_watched_operations(
+        &self,
+        watched: &mut WatchedOperations,
+    ) -> Vec<OperationStatusUpdate> {
+        let operation_ids: Vec<OperationId> = watched.operations.keys().copied().collect();
+        let exec_statuses = self
+            .0
+            .execution_controller
+            .get_ops_exec_status(&operation_ids);
+        let final_slot = self.0.execution_controller.get_stats().final_cursor;
+        watched.on_exec_statuses(operation_ids.into_iter().zip(exec_statuses), &final_slot)
+    }
+}
+
+/// Watched operation that is neither final nor expired
+struct WatchedOperation {
+    /// thread and expire period of the operation, once known
+    validity: Option<(u8, u64)>,
+    /// result of the latest candidate execution that was notified
+    executed: Option<bool>,
+}
+
+/// Operations watched by a subscription to their status
+struct WatchedOperations {
+    /// watched operations that are neither final nor expired
+    operations: PreHashMap<OperationId, WatchedOperation>,
+    /// thread count
+    thread_count: u8,
+}
+
+impl WatchedOperations {
+    fn new(thread_count: u8) -> Self {
+        WatchedOperations {
+            operations: PreHashMap::default(),
+            thread_count,
+        }
+    }
+
+    /// Start watching an operation, with its thread and expire period if known
+    fn watch(&mut self, operation_id: OperationId, validity: Option<(u8, u64)>) {
+        self.operations.insert(
+            operation_id,
+            WatchedOperation {
+                validity,
+                executed: None,
+            },
+        );
+    }
+
+    /// The current execution statuses of the watched operations were read.
+    /// Only the changes since the latest notified statuses are returned.
+    fn on_exec_statuses(
+        &mut self,
+        exec_statuses: impl Iterator<Item = (OperationId, (Option<bool>, Option<bool>))>,
+        final_slot: &Slot,
+    ) -> Vec<OperationStatusUpdate> {
+        let mut updates = Vec::new();
+        for (operation_id, (speculative_status, final_status)) in exec_statuses {
+            let Some(watched) = self.operations.get_mut(&operation_id) else {
+                continue;
+            };
+            let status = match (speculative_status, final_status) {
+                (_, Some(success)) => {
+                    self.operations.remove(&operation_id);
+                    OperationStatus::Final {
+                        slot: None,
+                        success,
+                    }
+                }
+                (Some(success), None) => {
+                    if watched.executed == Some(success) {
+                        continue;
+                    }
+                    watched.executed = Some(success);
+                    OperationStatus::Executed {
+                        slot: None,
+                        success,
+                    }
+                }
+                (None, None) => continue,
+            };
+            updates.push(OperationStatusUpdate {
+                operation_id,
+                status,
+            });
+        }
+        self.expire(final_slot, &mut updates);
+        updates
+    }
+
+    /// An operation was accepted in the pool
+    fn on_operation_accepted(&mut self, op: &SecureShareOperation) -> Vec<OperationStatusUpdate> {
+        let Some(watched) = self.operations.get_mut(&op.id) else {
+            return Vec::new();
+        };
+        watched.validity = Some((
+            op.content_creator_address.get_thread(self.thread_count),
+            op.content.expire_period,
+        ));
+        vec![OperationStatusUpdate {
+            operation_id: op.id,
+            status: OperationStatus::AcceptedInPool,
+        }]
+    }
+
+    /// An operation was rejected by the pool.
+    /// It remains watched as other nodes may still include it.
+    fn on_operation_rejected(
+        &self,
+        operation_id: OperationId,
+        reason: OperationRejectionReason,
+    ) -> Vec<OperationStatusUpdate> {
+        if !self.operations.contains_key(&operation_id) {
+            return Vec::new();
+        }
+        vec![OperationStatusUpdate {
+            operation_id,
+            status: OperationStatus::RejectedByPool {
+                reason: reason.code().to_string(),
+            },
+        }]
+    }
+
+    /// A block was added to the graph
+    fn on_block(&self, block: &SecureShareBlock) -> Vec<OperationStatusUpdate> {
+        block
+            .content
+            .operations
+            .iter()
+            .filter(|operation_id| self.operations.contains_key(operation_id))
+            .map(|operation_id| OperationStatusUpdate {
+                operation_id: *operation_id,
+                status: OperationStatus::IncludedInBlock {
+                    block_id: block.id,
+                    slot: block.content.header.content.slot,
+                },
+            })
+            .collect()
+    }
+
+    /// A slot was executed or finalized
+    fn on_slot_execution_output(
+        &mut self,
+        output: &SlotExecutionOutput,
+    ) -> Vec<OperationStatusUpdate> {
+        let (output, is_final) = match output {
+            SlotExecutionOutput::ExecutedSlot(output) => (output, false),
+            SlotExecutionOutput::FinalizedSlot(output) => (output, true),
+        };
+        let slot = &output.slot;
+
+        let mut updates = Vec::new();
+        for (operation_id, (success, _)) in &output.state_changes.executed_ops_changes {
+            let Some(watched) = self.operations.get_mut(operation_id) else {
+                continue;
+            };
+            let status = if is_final {
+                self.operations.remove(operation_id);
+                OperationStatus::Final {
+                    slot: Some(*slot),
+                    success: *success,
+                }
+            } else {
+                watched.executed = Some(*success);
+                OperationStatus::Executed {
+                    slot: Some(*slot),
+                    success: *success,
+                }
+            };
+            updates.push(OperationStatusUpdate {
+                operation_id: *operation_id,
+                status,
+            });
+        }
+
+        if is_final {
+            self.expire(slot, &mut updates);
+        }
+        updates
+    }
+
+    /// Expire the watched operations that can no longer be executed once `final_slot` is final
+    fn expire(&mut self, final_slot: &Slot, updates: &mut Vec<OperationStatusUpdate>) {
+        self.operations
+            .retain(|operation_id, watched| match watched.validity {
+                Some((thread, expire_period))
+                    if Slot::new(expire_period, thread) <= *final_slot =>
+                {
+                    updates.push(OperationStatusUpdate {
+                        operation_id: *operation_id,
+                        status: OperationStatus::Expired,
+                    });
+                    false
+                }
+                _ => true,
+            });
+    }
 }
 
 // Brodcast the stream(sender) content via a WebSocket
@@ -187,3 +469,177 @@ async fn broadcast_via_ws<T: Serialize + Send + Clone + 'static>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_execution_exports::{EventStore, ExecutionOutput};
+    use massa_final_state::StateChanges;
+    use massa_models::amount::Amount;
+    use massa_models::config::THREAD_COUNT;
+    use massa_models::operation::{Operation, OperationSerializer, OperationType};
+    use massa_signature::KeyPair;
+
+    fn create_operation(keypair: &KeyPair, expire_period: u64) -> SecureShareOperation {
+        let content = Operation {
+            fee: Amount::default(),
+            op: OperationType::RollBuy { roll_count: 1 },
+            expire_period,
+        };
+        Operation::new_verifiable(content, OperationSerializer::new(), keypair).unwrap()
+    }
+
+    fn slot_output(
+        slot: Slot,
+        executed_ops: &[(OperationId, bool)],
+        is_final: bool,
+    ) -> SlotExecutionOutput {
+        let output = ExecutionOutput {
+            slot,
+            block_info: None,
+            state_changes: StateChanges {
+                executed_ops_changes: executed_ops
+                    .iter()
+                    .map(|(operation_id, success)| (*operation_id, (*success, slot)))
+                    .collect(),
+                ..Default::default()
+            },
+            events: EventStore::default(),
+            traces: Vec::new(),
+        };
+        if is_final {
+            SlotExecutionOutput::FinalizedSlot(output)
+        } else {
+            SlotExecutionOutput::ExecutedSlot(output)
+        }
+    }
+
+    fn update(operation_id: OperationId, status: OperationStatus) -> OperationStatusUpdate {
+        OperationStatusUpdate {
+            operation_id,
+            status,
+        }
+    }
+
+    #[test]
+    fn test_watched_operations_lifecycle() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let thread = Address::from_public_key(&keypair.get_public_key()).get_thread(THREAD_COUNT);
+        let op = create_operation(&keypair, 10);
+        let unwatched_op = create_operation(&keypair, 11);
+        let mut watched = WatchedOperations::new(THREAD_COUNT);
+        watched.watch(op.id, None);
+
+        // the validity of the operation is learnt from the pool
+        assert_eq!(
+            watched.on_operation_accepted(&op),
+            vec![update(op.id, OperationStatus::AcceptedInPool)]
+        );
+        assert!(watched.on_operation_accepted(&unwatched_op).is_empty());
+        assert_eq!(watched.operations[&op.id].validity, Some((thread, 10)));
+        assert!(watched
+            .on_operation_rejected(
+                unwatched_op.id,
+                OperationRejectionReason::SenderLimitReached
+            )
+            .is_empty());
+
+        // candidate executions
+        let slot = Slot::new(5, thread);
+        assert_eq!(
+            watched.on_slot_execution_output(&slot_output(slot, &[(op.id, true)], false)),
+            vec![update(
+                op.id,
+                OperationStatus::Executed {
+                    slot: Some(slot),
+                    success: true
+                }
+            )]
+        );
+
+        // a status read again after missed changes is only notified if it changed
+        let final_slot = Slot::new(4, 0);
+        assert!(watched
+            .on_exec_statuses([(op.id, (Some(true), None))].into_iter(), &final_slot)
+            .is_empty());
+        assert_eq!(
+            watched.on_exec_statuses([(op.id, (Some(false), None))].into_iter(), &final_slot),
+            vec![update(
+                op.id,
+                OperationStatus::Executed {
+                    slot: None,
+                    success: false
+                }
+            )]
+        );
+
+        // the final execution ends the watch
+        assert_eq!(
+            watched.on_slot_execution_output(&slot_output(slot, &[(op.id, true)], true)),
+            vec![update(
+                op.id,
+                OperationStatus::Final {
+                    slot: Some(slot),
+                    success: true
+                }
+            )]
+        );
+        assert!(watched.operations.is_empty());
+    }
+
+    #[test]
+    fn test_watched_operations_expiry() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let thread = Address::from_public_key(&keypair.get_public_key()).get_thread(THREAD_COUNT);
+        let expired_op = create_operation(&keypair, 1);
+        let final_op = create_operation(&keypair, 2);
+        let unknown_op = create_operation(&keypair, 3);
+        let mut watched = WatchedOperations::new(THREAD_COUNT);
+        watched.watch(expired_op.id, Some((thread, 3)));
+        watched.watch(final_op.id, Some((thread, 3)));
+        watched.watch(unknown_op.id, None);
+
+        // the last slot of the operations is not final yet
+        let exec_statuses = || {
+            [
+                (expired_op.id, (None, None)),
+                (final_op.id, (Some(true), Some(true))),
+                (unknown_op.id, (None, None)),
+            ]
+            .into_iter()
+        };
+        let before_expiry = Slot::new(3, thread).get_prev_slot(THREAD_COUNT).unwrap();
+        assert_eq!(
+            watched.on_exec_statuses(exec_statuses(), &before_expiry),
+            vec![update(
+                final_op.id,
+                OperationStatus::Final {
+                    slot: None,
+                    success: true
+                }
+            )]
+        );
+
+        // operations whose validity is unknown never expire
+        assert_eq!(
+            watched.on_exec_statuses(exec_statuses(), &Slot::new(3, thread)),
+            vec![update(expired_op.id, OperationStatus::Expired)]
+        );
+        assert_eq!(
+            watched
+                .operations
+                .keys()
+                .copied()
+                .collect::<Vec<OperationId>>(),
+            vec![unknown_op.id]
+        );
+
+        // expiry on a finalized slot of another thread
+        watched.watch(expired_op.id, Some((thread, 3)));
+        let other_thread_slot = Slot::new(4, (thread + 1) % THREAD_COUNT);
+        assert_eq!(
+            watched.on_slot_execution_output(&slot_output(other_thread_slot, &[], true)),
+            vec![update(expired_op.id, OperationStatus::Expired)]
+        );
+    }
+}
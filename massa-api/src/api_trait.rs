@@ -6,6 +6,7 @@ use massa_api_exports::page::PagedVecV2;
 use massa_api_exports::ApiRequest;
 use massa_models::address::Address;
 use massa_models::block_id::BlockId;
+use massa_models::operation::OperationId;
 use massa_models::version::Version;
 
 /// Exposed API methods
@@ -57,4 +58,17 @@ pub trait MassaApi {
 		item = Operation
 	)]
     async fn subscribe_new_operations(&self) -> SubscriptionResult;
+
+    /// Status changes of a set of operations: accepted or rejected by the pool, included in a block,
+    /// executed, final or expired. The subscription ends when all the operations are final or expired.
+    /// Apart from the current execution status sent first, the changes are only known if the broadcasts are enabled.
+    #[subscription(
+        name = "subscribe_operations_status" => "operations_status",
+        unsubscribe = "unsubscribe_operations_status",
+        item = OperationStatusUpdate
+    )]
+    async fn subscribe_operations_status(
+        &self,
+        operation_ids: Vec<OperationId>,
+    ) -> SubscriptionResult;
 }
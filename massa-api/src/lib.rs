@@ -17,14 +17,18 @@ use massa_api_exports::{
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
     endorsement::EndorsementInfo,
     error::ApiError::WrongAPI,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
-    node::NodeStatus,
+    execution::{
+        ExecuteReadOnlyResponse, GasEstimationRequest, GasEstimationResponse, OperationSimulation,
+        OperationSimulationResponse, ReadOnlyBytecodeExecution, ReadOnlyCall,
+    },
+    node::{NodeStatus, PeerInfo},
     operation::{OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
     TimeInterval,
 };
+use massa_archive::Archive;
 use massa_consensus_exports::{ConsensusChannels, ConsensusController};
-use massa_execution_exports::ExecutionController;
+use massa_execution_exports::{ExecutionChannels, ExecutionController};
 use massa_models::clique::Clique;
 use massa_models::composite::PubkeySig;
 use massa_models::node::NodeId;
@@ -32,13 +36,25 @@ use massa_models::operation::OperationId;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
 use massa_models::{
-    address::Address, block::Block, block_id::BlockId, endorsement::EndorsementId,
-    execution::EventFilter, slot::Slot, version::Version,
+    address::Address,
+    block::Block,
+    block_id::BlockId,
+    endorsement::EndorsementId,
+    execution::{
+        AddressHistoryEntry, AsyncMessageEntry, AsyncMessageFilter, EventFilter, ExecutionTrace,
+        ExecutionTraceId,
+    },
+    ip_list::{IpListEntry, IpNetwork},
+    slot::Slot,
+    state_proof::StateProof,
+    stats::FeeStats,
+    version::Version,
 };
 use massa_pool_exports::{PoolChannels, PoolController};
 use massa_pos_exports::SelectorController;
-use massa_protocol_exports::{ProtocolConfig, ProtocolController};
+use massa_protocol_exports::{KnownPeerInfo, ProtocolConfig, ProtocolController};
 use massa_storage::Storage;
+use massa_time::MassaTime;
 use massa_versioning::keypair_factory::KeyPairFactory;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
@@ -77,6 +93,8 @@ pub struct Public {
     pub node_id: NodeId,
     /// keypair factory
     pub keypair_factory: KeyPairFactory,
+    /// archive of final blocks, operations and endorsements (only on archive nodes)
+    pub archive: Option<Arc<Archive>>,
 }
 
 /// Private API content
@@ -102,8 +120,12 @@ pub struct ApiV2 {
     pub consensus_channels: ConsensusChannels,
     /// link to the execution component
     pub execution_controller: Box<dyn ExecutionController>,
+    /// link(channels) to the execution component
+    pub execution_channels: ExecutionChannels,
     /// link(channels) to the pool component
     pub pool_channels: PoolChannels,
+    /// Massa storage
+    pub storage: Storage,
     /// API settings
     pub api_settings: APIConfig,
     /// node version
@@ -241,6 +263,23 @@ pub trait MassaRpc {
         arg: Vec<ReadOnlyCall>,
     ) -> RpcResult<Vec<ExecuteReadOnlyResponse>>;
 
+    /// Simulate the execution of unsigned operations, as if they were included in a block.
+    /// Returns the gas used and the effects of each operation, which are not applied.
+    #[method(name = "simulate_operation")]
+    async fn simulate_operation(
+        &self,
+        arg: Vec<OperationSimulation>,
+    ) -> RpcResult<Vec<OperationSimulationResponse>>;
+
+    /// Estimate the gas limit of a `CallSC` or `ExecuteSC` operation against the candidate state.
+    /// Only one operation is accepted per call.
+    /// Returns the lowest gas limit with which the operation succeeds, and a recommended gas limit with a safety margin.
+    #[method(name = "estimate_gas")]
+    async fn estimate_gas(
+        &self,
+        arg: Vec<GasEstimationRequest>,
+    ) -> RpcResult<Vec<GasEstimationResponse>>;
+
     /// Remove a vector of addresses used to stake.
     /// No confirmation to expect.
     #[method(name = "remove_staking_addresses")]
@@ -255,10 +294,15 @@ pub trait MassaRpc {
     #[method(name = "node_ban_by_ip")]
     async fn node_ban_by_ip(&self, arg: Vec<IpAddr>) -> RpcResult<()>;
 
-    /// Bans given node id.
+    /// Bans given node id(s), with an optional reason and an optional duration after which the ban expires.
     /// No confirmation to expect.
     #[method(name = "node_ban_by_id")]
-    async fn node_ban_by_id(&self, arg: Vec<NodeId>) -> RpcResult<()>;
+    async fn node_ban_by_id(
+        &self,
+        arg: Vec<NodeId>,
+        reason: Option<String>,
+        duration: Option<MassaTime>,
+    ) -> RpcResult<()>;
 
     /// Returns node peers whitelist IP address(es).
     #[method(name = "node_peers_whitelist")]
@@ -276,34 +320,34 @@ pub trait MassaRpc {
     #[method(name = "node_remove_from_peers_whitelist")]
     async fn node_remove_from_peers_whitelist(&self, arg: Vec<IpAddr>) -> RpcResult<()>;
 
-    /// Returns node bootstrap whitelist IP address(es).
+    /// Returns node bootstrap whitelist entries.
     #[method(name = "node_bootstrap_whitelist")]
-    async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpAddr>>;
+    async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpListEntry>>;
 
     /// Allow everyone to bootstrap from the node.
     /// remove bootstrap whitelist configuration file.
     #[method(name = "node_bootstrap_whitelist_allow_all")]
     async fn node_bootstrap_whitelist_allow_all(&self) -> RpcResult<()>;
 
-    /// Add IP address(es) to node bootstrap whitelist.
+    /// Add entries (IP address or CIDR range, optional expiry time and reason) to node bootstrap whitelist.
     #[method(name = "node_add_to_bootstrap_whitelist")]
-    async fn node_add_to_bootstrap_whitelist(&self, arg: Vec<IpAddr>) -> RpcResult<()>;
+    async fn node_add_to_bootstrap_whitelist(&self, arg: Vec<IpListEntry>) -> RpcResult<()>;
 
-    /// Remove IP address(es) to bootstrap whitelist.
+    /// Remove IP address(es) or CIDR range(s) from bootstrap whitelist.
     #[method(name = "node_remove_from_bootstrap_whitelist")]
-    async fn node_remove_from_bootstrap_whitelist(&self, arg: Vec<IpAddr>) -> RpcResult<()>;
+    async fn node_remove_from_bootstrap_whitelist(&self, arg: Vec<IpNetwork>) -> RpcResult<()>;
 
-    /// Returns node bootstrap blacklist IP address(es).
+    /// Returns node bootstrap blacklist entries.
     #[method(name = "node_bootstrap_blacklist")]
-    async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpAddr>>;
+    async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpListEntry>>;
 
-    /// Add IP address(es) to node bootstrap blacklist.
+    /// Add entries (IP address or CIDR range, optional expiry time and reason) to node bootstrap blacklist.
     #[method(name = "node_add_to_bootstrap_blacklist")]
-    async fn node_add_to_bootstrap_blacklist(&self, arg: Vec<IpAddr>) -> RpcResult<()>;
+    async fn node_add_to_bootstrap_blacklist(&self, arg: Vec<IpListEntry>) -> RpcResult<()>;
 
-    /// Remove IP address(es) to bootstrap blacklist.
+    /// Remove IP address(es) or CIDR range(s) from bootstrap blacklist.
     #[method(name = "node_remove_from_bootstrap_blacklist")]
-    async fn node_remove_from_bootstrap_blacklist(&self, arg: Vec<IpAddr>) -> RpcResult<()>;
+    async fn node_remove_from_bootstrap_blacklist(&self, arg: Vec<IpNetwork>) -> RpcResult<()>;
 
     /// Unban given IP address(es).
     /// No confirmation to expect.
@@ -315,6 +359,14 @@ pub trait MassaRpc {
     #[method(name = "node_unban_by_id")]
     async fn node_unban_by_id(&self, arg: Vec<NodeId>) -> RpcResult<()>;
 
+    /// Returns the peers known by the node, with their state, ban and connection statistics.
+    #[method(name = "node_known_peers")]
+    async fn node_known_peers(&self) -> RpcResult<Vec<KnownPeerInfo>>;
+
+    /// Returns the connected peers, with their traffic, message counts, handshake timings and connection statistics.
+    #[method(name = "get_peers")]
+    async fn get_peers(&self) -> RpcResult<Vec<PeerInfo>>;
+
     /// Summary of the current state: time, last final blocks (hash, thread, slot, timestamp), clique count, connected nodes count.
     #[method(name = "get_status")]
     async fn get_status(&self) -> RpcResult<NodeStatus>;
@@ -363,6 +415,41 @@ pub trait MassaRpc {
     #[method(name = "get_addresses")]
     async fn get_addresses(&self, arg: Vec<Address>) -> RpcResult<Vec<AddressInfo>>;
 
+    /// Returns the final executed operations involving an address, most recent first, with their execution status.
+    #[method(name = "get_address_history")]
+    async fn get_address_history(
+        &self,
+        address: Address,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<AddressHistoryEntry>>;
+
+    /// Returns the messages of the final asynchronous pool matching a filter, in expected execution order,
+    /// with their validity window, trigger status and rank in the next batch.
+    #[method(name = "get_async_messages")]
+    async fn get_async_messages(
+        &self,
+        filter: AsyncMessageFilter,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<AsyncMessageEntry>>;
+
+    /// Returns the proof of inclusion (or exclusion) of a raw key in the final state, with the state tree root it leads to
+    /// and the final slot it was built at. Only available on nodes that maintain the state tree.
+    #[method(name = "get_state_proof")]
+    async fn get_state_proof(&self, key: Vec<u8>) -> RpcResult<StateProof>;
+
+    /// Returns the traces of executed operations and asynchronous messages (one per id, null if not available).
+    /// Executions are traced only if enabled in the node configuration.
+    #[method(name = "get_execution_traces")]
+    async fn get_execution_traces(
+        &self,
+        arg: Vec<ExecutionTraceId>,
+    ) -> RpcResult<Vec<Option<ExecutionTrace>>>;
+
+    /// Returns the fee statistics of the operation pool, per thread,
+    /// with the fees recommended for an operation to be included within `target_delay` periods.
+    #[method(name = "get_fee_estimate")]
+    async fn get_fee_estimate(&self, target_delay: u64) -> RpcResult<FeeStats>;
+
     /// Adds operations to pool. Returns operations that were ok and sent to pool.
     #[method(name = "send_operations")]
     async fn send_operations(&self, arg: Vec<OperationInput>) -> RpcResult<Vec<OperationId>>;
@@ -11,8 +11,11 @@ use massa_api_exports::{
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
     endorsement::EndorsementInfo,
     error::ApiError,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
-    node::NodeStatus,
+    execution::{
+        ExecuteReadOnlyResponse, GasEstimationRequest, GasEstimationResponse, OperationSimulation,
+        OperationSimulationResponse, ReadOnlyBytecodeExecution, ReadOnlyCall,
+    },
+    node::{NodeStatus, PeerInfo},
     operation::{OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
     ListType, ScrudOperation, TimeInterval,
@@ -20,19 +23,35 @@ use massa_api_exports::{
 use massa_execution_exports::ExecutionController;
 use massa_hash::Hash;
 use massa_models::{
-    address::Address, block::Block, block_id::BlockId, clique::Clique, composite::PubkeySig,
-    endorsement::EndorsementId, execution::EventFilter, node::NodeId, operation::OperationId,
-    output_event::SCOutputEvent, prehash::PreHashSet, slot::Slot,
+    address::Address,
+    block::Block,
+    block_id::BlockId,
+    clique::Clique,
+    composite::PubkeySig,
+    endorsement::EndorsementId,
+    execution::{
+        AddressHistoryEntry, AsyncMessageEntry, AsyncMessageFilter, EventFilter, ExecutionTrace,
+        ExecutionTraceId,
+    },
+    ip_list::{IpListEntry, IpNetwork},
+    node::NodeId,
+    operation::OperationId,
+    output_event::SCOutputEvent,
+    prehash::PreHashSet,
+    slot::Slot,
+    state_proof::StateProof,
+    stats::FeeStats,
 };
-use massa_protocol_exports::{PeerId, ProtocolController};
+use massa_protocol_exports::{KnownPeerInfo, PeerConnectionType, PeerId, ProtocolController};
 use massa_signature::KeyPair;
+use massa_time::MassaTime;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
 use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::{collections::BTreeSet, sync::Mutex};
+use std::{collections::BTreeMap, sync::Mutex};
 use std::{
     fs::{remove_file, OpenOptions},
     sync::Condvar,
@@ -125,6 +144,20 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<_>()
     }
 
+    async fn simulate_operation(
+        &self,
+        _reqs: Vec<OperationSimulation>,
+    ) -> RpcResult<Vec<OperationSimulationResponse>> {
+        crate::wrong_api::<_>()
+    }
+
+    async fn estimate_gas(
+        &self,
+        _reqs: Vec<GasEstimationRequest>,
+    ) -> RpcResult<Vec<GasEstimationResponse>> {
+        crate::wrong_api::<_>()
+    }
+
     async fn remove_staking_addresses(&self, addresses: Vec<Address>) -> RpcResult<()> {
         let node_wallet = self.0.node_wallet.clone();
 
@@ -159,7 +192,12 @@ impl MassaRpcServer for API<Private> {
         );
     }
 
-    async fn node_ban_by_id(&self, ids: Vec<NodeId>) -> RpcResult<()> {
+    async fn node_ban_by_id(
+        &self,
+        ids: Vec<NodeId>,
+        reason: Option<String>,
+        duration: Option<MassaTime>,
+    ) -> RpcResult<()> {
         let protocol_controller = self.0.protocol_controller.clone();
         //TODO: Change when unify node id and peer id
         let peer_ids = ids
@@ -167,7 +205,7 @@ impl MassaRpcServer for API<Private> {
             .map(|id| PeerId::from_public_key(id.get_public_key()))
             .collect();
         protocol_controller
-            .ban_peers(peer_ids)
+            .ban_peers(peer_ids, reason, duration)
             .map_err(|e| ApiError::ProtocolError(e).into())
     }
 
@@ -183,6 +221,40 @@ impl MassaRpcServer for API<Private> {
             .map_err(|e| ApiError::ProtocolError(e).into())
     }
 
+    async fn node_known_peers(&self) -> RpcResult<Vec<KnownPeerInfo>> {
+        let protocol_controller = self.0.protocol_controller.clone();
+        protocol_controller
+            .get_known_peers()
+            .map_err(|e| ApiError::ProtocolError(e).into())
+    }
+
+    async fn get_peers(&self) -> RpcResult<Vec<PeerInfo>> {
+        let protocol_controller = self.0.protocol_controller.clone();
+        let peers = protocol_controller
+            .get_peers()
+            .map_err(ApiError::ProtocolError)?;
+        Ok(peers
+            .into_iter()
+            .map(|peer| PeerInfo {
+                node_id: NodeId::new(peer.peer_id.get_public_key()),
+                address: peer.address,
+                category: peer.category,
+                is_outgoing: match peer.connection_type {
+                    PeerConnectionType::IN => false,
+                    PeerConnectionType::OUT => true,
+                },
+                listeners: peer.listeners.into_iter().collect(),
+                bytes_sent: peer.bytes_sent,
+                bytes_received: peer.bytes_received,
+                messages_received: peer.messages_received,
+                ping: peer.ping,
+                handshake_at: peer.handshake_at,
+                score: peer.score,
+                connection_stats: peer.connection_stats.into_iter().collect(),
+            })
+            .collect())
+    }
+
     async fn node_unban_by_ip(&self, _ips: Vec<IpAddr>) -> RpcResult<()> {
         //TODO: Reinvoke
         // let network_command_sender = self.0.network_command_sender.clone();
@@ -238,6 +310,37 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<AddressInfo>>()
     }
 
+    async fn get_address_history(
+        &self,
+        _: Address,
+        _: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<AddressHistoryEntry>> {
+        crate::wrong_api::<PagedVec<AddressHistoryEntry>>()
+    }
+
+    async fn get_async_messages(
+        &self,
+        _: AsyncMessageFilter,
+        _: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<AsyncMessageEntry>> {
+        crate::wrong_api::<PagedVec<AsyncMessageEntry>>()
+    }
+
+    async fn get_state_proof(&self, _: Vec<u8>) -> RpcResult<StateProof> {
+        crate::wrong_api::<StateProof>()
+    }
+
+    async fn get_execution_traces(
+        &self,
+        _: Vec<ExecutionTraceId>,
+    ) -> RpcResult<Vec<Option<ExecutionTrace>>> {
+        crate::wrong_api::<Vec<Option<ExecutionTrace>>>()
+    }
+
+    async fn get_fee_estimate(&self, _: u64) -> RpcResult<FeeStats> {
+        crate::wrong_api::<FeeStats>()
+    }
+
     async fn send_operations(&self, _: Vec<OperationInput>) -> RpcResult<Vec<OperationId>> {
         crate::wrong_api::<Vec<OperationId>>()
     }
@@ -283,8 +386,8 @@ impl MassaRpcServer for API<Private> {
         );
     }
 
-    async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
-        read_ips_from_jsonfile(
+    async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpListEntry>> {
+        read_active_entries_from_jsonfile(
             self.0.api_settings.bootstrap_whitelist_path.clone(),
             &ListType::Whitelist,
         )
@@ -300,44 +403,50 @@ impl MassaRpcServer for API<Private> {
         })
     }
 
-    async fn node_add_to_bootstrap_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_add_to_bootstrap_whitelist(&self, entries: Vec<IpListEntry>) -> RpcResult<()> {
         run_scrud_operation(
             self.0.api_settings.bootstrap_whitelist_path.clone(),
-            ips,
+            entries,
             ListType::Whitelist,
             ScrudOperation::Create,
         )
     }
 
-    async fn node_remove_from_bootstrap_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_remove_from_bootstrap_whitelist(
+        &self,
+        networks: Vec<IpNetwork>,
+    ) -> RpcResult<()> {
         run_scrud_operation(
             self.0.api_settings.bootstrap_whitelist_path.clone(),
-            ips,
+            networks.into_iter().map(IpListEntry::from).collect(),
             ListType::Whitelist,
             ScrudOperation::Delete,
         )
     }
 
-    async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpAddr>> {
-        read_ips_from_jsonfile(
+    async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpListEntry>> {
+        read_active_entries_from_jsonfile(
             self.0.api_settings.bootstrap_blacklist_path.clone(),
             &ListType::Blacklist,
         )
     }
 
-    async fn node_add_to_bootstrap_blacklist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_add_to_bootstrap_blacklist(&self, entries: Vec<IpListEntry>) -> RpcResult<()> {
         run_scrud_operation(
             self.0.api_settings.bootstrap_blacklist_path.clone(),
-            ips,
+            entries,
             ListType::Blacklist,
             ScrudOperation::Create,
         )
     }
 
-    async fn node_remove_from_bootstrap_blacklist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_remove_from_bootstrap_blacklist(
+        &self,
+        networks: Vec<IpNetwork>,
+    ) -> RpcResult<()> {
         run_scrud_operation(
             self.0.api_settings.bootstrap_blacklist_path.clone(),
-            ips,
+            networks.into_iter().map(IpListEntry::from).collect(),
             ListType::Blacklist,
             ScrudOperation::Delete,
         )
@@ -348,25 +457,31 @@ impl MassaRpcServer for API<Private> {
     }
 }
 
-/// Run Search, Create, Read, Update, Delete operation on bootstrap list of IP(s)
+/// Run Search, Create, Read, Update, Delete operation on bootstrap list entries.
+/// Entries are identified by their network range: a created entry replaces the previous one.
 fn run_scrud_operation(
     bootstrap_list_file: PathBuf,
-    ips: Vec<IpAddr>,
+    entries: Vec<IpListEntry>,
     list_type: ListType,
     scrud_operation: ScrudOperation,
 ) -> RpcResult<()> {
+    let entries = entries.into_iter().map(|entry| (entry.network, entry));
     match scrud_operation {
         ScrudOperation::Create => get_file_len(bootstrap_list_file.clone(), &list_type, true)
             .and_then(|length| {
                 if length == 0 {
-                    write_ips_to_jsonfile(bootstrap_list_file, BTreeSet::from_iter(ips), &list_type)
+                    write_entries_to_jsonfile(
+                        bootstrap_list_file,
+                        BTreeMap::from_iter(entries),
+                        &list_type,
+                    )
                 } else {
-                    read_ips_from_jsonfile(bootstrap_list_file.clone(), &list_type)
-                        .map(BTreeSet::from_iter)
-                        .and_then(|mut list_ips: BTreeSet<IpAddr>| {
-                            list_ips.extend(ips);
-                            write_ips_to_jsonfile(bootstrap_list_file, list_ips, &list_type)
-                        })
+                    read_entries_from_jsonfile(bootstrap_list_file.clone(), &list_type).and_then(
+                        |mut list_entries| {
+                            list_entries.extend(entries);
+                            write_entries_to_jsonfile(bootstrap_list_file, list_entries, &list_type)
+                        },
+                    )
                 }
             }),
         ScrudOperation::Delete => get_file_len(bootstrap_list_file.clone(), &list_type, false)
@@ -378,21 +493,21 @@ fn run_scrud_operation(
                     ))
                     .into())
                 } else {
-                    read_ips_from_jsonfile(bootstrap_list_file.clone(), &list_type)
-                        .map(BTreeSet::from_iter)
-                        .and_then(|mut list_ips: BTreeSet<IpAddr>| {
-                            if list_ips.is_empty() {
+                    read_entries_from_jsonfile(bootstrap_list_file.clone(), &list_type).and_then(
+                        |mut list_entries| {
+                            if list_entries.is_empty() {
                                 return Err(ApiError::InternalServerError(format!(
                                     "failed to execute delete operation, bootstrap {} is empty",
                                     list_type
                                 ))
                                 .into());
                             }
-                            ips.into_iter().for_each(|ip| {
-                                list_ips.remove(&ip);
+                            entries.for_each(|(network, _)| {
+                                list_entries.remove(&network);
                             });
-                            write_ips_to_jsonfile(bootstrap_list_file, list_ips, &list_type)
-                        })
+                            write_entries_to_jsonfile(bootstrap_list_file, list_entries, &list_type)
+                        },
+                    )
                 }
             }),
         _ => Err(ApiError::BadRequest(format!(
@@ -431,11 +546,25 @@ fn get_file_len(
         })
 }
 
-/// Read bootstrap list IP(s) from json file
-fn read_ips_from_jsonfile(
+/// Read the bootstrap list entries that did not expire from json file
+fn read_active_entries_from_jsonfile(
+    bootstrap_list_file: PathBuf,
+    list_type: &ListType,
+) -> RpcResult<Vec<IpListEntry>> {
+    let now = MassaTime::now().map_err(ApiError::from)?;
+    read_entries_from_jsonfile(bootstrap_list_file, list_type).map(|entries| {
+        entries
+            .into_values()
+            .filter(|entry| !entry.is_expired(now))
+            .collect()
+    })
+}
+
+/// Read bootstrap list entries from json file, by network range
+fn read_entries_from_jsonfile(
     bootstrap_list_file: PathBuf,
     list_type: &ListType,
-) -> RpcResult<Vec<IpAddr>> {
+) -> RpcResult<BTreeMap<IpNetwork, IpListEntry>> {
     std::fs::read_to_string(bootstrap_list_file)
         .map_err(|e| {
             ApiError::InternalServerError(format!(
@@ -445,22 +574,34 @@ fn read_ips_from_jsonfile(
             .into()
         })
         .and_then(|bootstrap_list_str| {
-            serde_json::from_str(&bootstrap_list_str).map_err(|e| {
-                ApiError::InternalServerError(format!(
-                    "failed to parse bootstrap {} configuration file: {}",
-                    list_type, e
-                ))
-                .into()
-            })
+            serde_json::from_str::<Vec<IpListEntry>>(&bootstrap_list_str)
+                .map(|entries| {
+                    entries
+                        .into_iter()
+                        .map(|entry| (entry.network, entry))
+                        .collect()
+                })
+                .map_err(|e| {
+                    ApiError::InternalServerError(format!(
+                        "failed to parse bootstrap {} configuration file: {}",
+                        list_type, e
+                    ))
+                    .into()
+                })
         })
 }
 
-/// Write bootstrap list IP(s) from json file
-fn write_ips_to_jsonfile(
+/// Write bootstrap list entries to json file, without the expired ones
+fn write_entries_to_jsonfile(
     bootstrap_list_file: PathBuf,
-    ips: BTreeSet<IpAddr>,
+    entries: BTreeMap<IpNetwork, IpListEntry>,
     list_type: &ListType,
 ) -> RpcResult<()> {
+    let now = MassaTime::now().map_err(ApiError::from)?;
+    let entries: Vec<IpListEntry> = entries
+        .into_values()
+        .filter(|entry| !entry.is_expired(now))
+        .collect();
     OpenOptions::new()
         .write(true)
         .create(true)
@@ -474,7 +615,7 @@ fn write_ips_to_jsonfile(
             .into()
         })
         .and_then(|file| {
-            serde_json::to_writer_pretty(file, &ips).map_err(|e| {
+            serde_json::to_writer_pretty(file, &entries).map_err(|e| {
                 ApiError::InternalServerError(format!(
                     "failed to write bootstrap {} configuration file: {}",
                     list_type, e
@@ -12,17 +12,23 @@ use massa_api_exports::{
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
     endorsement::EndorsementInfo,
     error::ApiError,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyResult},
-    node::NodeStatus,
+    execution::{
+        BalanceChange, ExecuteReadOnlyResponse, GasEstimationRequest, GasEstimationResponse,
+        OperationSimulation, OperationSimulationResponse, ReadOnlyBytecodeExecution, ReadOnlyCall,
+        ReadOnlyResult,
+    },
+    node::{NodeStatus, PeerInfo},
     operation::{OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
     slot::SlotAmount,
     TimeInterval,
 };
+use massa_archive::Archive;
 use massa_consensus_exports::block_status::DiscardReason;
 use massa_consensus_exports::ConsensusController;
 use massa_execution_exports::{
-    ExecutionController, ExecutionStackElement, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+    ExecutionController, ExecutionStackElement, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ReadOnlyExecutionTarget,
 };
 use massa_models::{
     address::Address,
@@ -35,7 +41,11 @@ use massa_models::{
     endorsement::EndorsementId,
     endorsement::SecureShareEndorsement,
     error::ModelsError,
-    execution::EventFilter,
+    execution::{
+        AddressHistoryEntry, AsyncMessageEntry, AsyncMessageFilter, EventFilter, ExecutionTrace,
+        ExecutionTraceId,
+    },
+    ip_list::{IpListEntry, IpNetwork},
     node::NodeId,
     operation::OperationDeserializer,
     operation::OperationId,
@@ -44,13 +54,17 @@ use massa_models::{
     prehash::{PreHashMap, PreHashSet},
     secure_share::SecureShareDeserializer,
     slot::{IndexedSlot, Slot},
+    state_proof::StateProof,
+    stats::FeeStats,
     timeslots,
     timeslots::{get_latest_block_slot_at_timestamp, time_range_to_slot_range},
     version::Version,
 };
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
-use massa_protocol_exports::{PeerConnectionType, ProtocolConfig, ProtocolController};
+use massa_protocol_exports::{
+    KnownPeerInfo, PeerConnectionType, ProtocolConfig, ProtocolController,
+};
 use massa_serialization::{DeserializeError, Deserializer};
 use massa_storage::Storage;
 use massa_time::MassaTime;
@@ -60,6 +74,7 @@ use massa_versioning::{
 };
 use std::collections::BTreeMap;
 use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
 
 impl API<Public> {
     /// generate a new public API
@@ -75,6 +90,7 @@ impl API<Public> {
         node_id: NodeId,
         storage: Storage,
         mip_store: MipStore,
+        archive: Option<Arc<Archive>>,
     ) -> Self {
         API(Public {
             consensus_controller,
@@ -88,6 +104,7 @@ impl API<Public> {
             protocol_config,
             storage,
             keypair_factory: KeyPairFactory { mip_store },
+            archive,
         })
     }
 }
@@ -133,6 +150,7 @@ impl MassaRpcServer for API<Public> {
             bytecode,
             operation_datastore,
             is_final,
+            at_slot,
         } in reqs
         {
             let address = if let Some(addr) = address {
@@ -186,6 +204,7 @@ impl MassaRpcServer for API<Public> {
                     operation_datastore: op_datastore,
                 }],
                 is_final,
+                at_slot,
             };
 
             // run
@@ -230,6 +249,7 @@ impl MassaRpcServer for API<Public> {
             parameter,
             caller_address,
             is_final,
+            at_slot,
         } in reqs
         {
             let caller_address = if let Some(addr) = caller_address {
@@ -274,6 +294,7 @@ impl MassaRpcServer for API<Public> {
                     },
                 ],
                 is_final,
+                at_slot,
             };
 
             // run
@@ -302,6 +323,111 @@ impl MassaRpcServer for API<Public> {
         Ok(res)
     }
 
+    async fn simulate_operation(
+        &self,
+        reqs: Vec<OperationSimulation>,
+    ) -> RpcResult<Vec<OperationSimulationResponse>> {
+        if reqs.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+
+        let mut res: Vec<OperationSimulationResponse> = Vec::with_capacity(reqs.len());
+        for OperationSimulation {
+            sender_address,
+            fee,
+            op,
+            is_final,
+        } in reqs
+        {
+            // translate request
+            let req = ReadOnlyExecutionRequest {
+                max_gas: 0,
+                target: ReadOnlyExecutionTarget::Operation {
+                    sender_addr: sender_address,
+                    fee,
+                    op,
+                },
+                call_stack: Vec::new(),
+                is_final,
+                at_slot: None,
+            };
+
+            // run
+            let result = self.0.execution_controller.execute_readonly_request(req);
+
+            // map result
+            let result = OperationSimulationResponse {
+                executed_at: result
+                    .as_ref()
+                    .map_or_else(|_| Slot::new(0, 0), |v| v.out.slot),
+                result: match &result {
+                    Err(err) => {
+                        ReadOnlyResult::Error(format!("operation simulation failed: {}", err))
+                    }
+                    Ok(ReadOnlyExecutionOutput {
+                        execution_error: Some(err),
+                        ..
+                    }) => ReadOnlyResult::Error(format!("operation execution failed: {}", err)),
+                    Ok(_) => ReadOnlyResult::Ok(Vec::new()),
+                },
+                gas_used: result.as_ref().map_or_else(|_| 0, |v| v.gas_cost),
+                balance_changes: result.as_ref().map_or_else(
+                    |_| Vec::new(),
+                    |v| {
+                        v.balance_changes
+                            .iter()
+                            .map(|(address, (balance_before, balance_after))| BalanceChange {
+                                address: *address,
+                                balance_before: *balance_before,
+                                balance_after: *balance_after,
+                            })
+                            .collect()
+                    },
+                ),
+                output_events: result
+                    .as_ref()
+                    .map_or_else(|_| Default::default(), |v| v.out.events.clone().0),
+                state_changes: result.map_or_else(|_| Default::default(), |v| v.out.state_changes),
+            };
+
+            res.push(result);
+        }
+
+        // return result
+        Ok(res)
+    }
+
+    async fn estimate_gas(
+        &self,
+        reqs: Vec<GasEstimationRequest>,
+    ) -> RpcResult<Vec<GasEstimationResponse>> {
+        // each estimation runs a series of read-only executions: only one is accepted per call
+        if reqs.len() > 1 {
+            return Err(ApiError::BadRequest(
+                "gas can only be estimated for one operation per request".into(),
+            )
+            .into());
+        }
+
+        let mut res: Vec<GasEstimationResponse> = Vec::with_capacity(reqs.len());
+        for GasEstimationRequest { sender_address, op } in reqs {
+            // the read-only executions are waited for synchronously: keep them off the async runtime
+            let execution_controller = self.0.execution_controller.clone();
+            let estimation = tokio::task::spawn_blocking(move || {
+                execution_controller.estimate_gas(sender_address, op)
+            })
+            .await
+            .map_err(|err| ApiError::InternalServerError(err.to_string()))?
+            .map_err(ApiError::from)?;
+            res.push(GasEstimationResponse {
+                min_gas: estimation.min_gas,
+                recommended_gas: estimation.recommended_gas,
+            });
+        }
+
+        Ok(res)
+    }
+
     async fn remove_staking_addresses(&self, _: Vec<Address>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
@@ -314,7 +440,12 @@ impl MassaRpcServer for API<Public> {
         crate::wrong_api::<()>()
     }
 
-    async fn node_ban_by_id(&self, _: Vec<NodeId>) -> RpcResult<()> {
+    async fn node_ban_by_id(
+        &self,
+        _: Vec<NodeId>,
+        _: Option<String>,
+        _: Option<MassaTime>,
+    ) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
 
@@ -326,6 +457,14 @@ impl MassaRpcServer for API<Public> {
         crate::wrong_api::<()>()
     }
 
+    async fn node_known_peers(&self) -> RpcResult<Vec<KnownPeerInfo>> {
+        crate::wrong_api::<Vec<KnownPeerInfo>>()
+    }
+
+    async fn get_peers(&self) -> RpcResult<Vec<PeerInfo>> {
+        crate::wrong_api::<Vec<PeerInfo>>()
+    }
+
     async fn get_status(&self) -> RpcResult<NodeStatus> {
         let execution_controller = self.0.execution_controller.clone();
         let consensus_controller = self.0.consensus_controller.clone();
@@ -486,27 +625,39 @@ impl MassaRpcServer for API<Public> {
     }
 
     async fn get_operations(&self, ops: Vec<OperationId>) -> RpcResult<Vec<OperationInfo>> {
-        // get the operations and the list of blocks that contain them from storage
-        let storage_info: Vec<(SecureShareOperation, PreHashSet<BlockId>)> = {
+        // get the operations and the list of blocks that contain them from storage,
+        // falling back to the archive (if enabled) for the operations that were pruned
+        let storage_info: Vec<(SecureShareOperation, PreHashSet<BlockId>, bool)> = {
             let read_blocks = self.0.storage.read_blocks();
             let read_ops = self.0.storage.read_operations();
             ops.iter()
                 .filter_map(|id| {
-                    read_ops.get(id).cloned().map(|op| {
-                        (
-                            op,
-                            read_blocks
-                                .get_blocks_by_operation(id)
-                                .cloned()
-                                .unwrap_or_default(),
-                        )
-                    })
+                    read_ops
+                        .get(id)
+                        .cloned()
+                        .map(|op| {
+                            (
+                                op,
+                                read_blocks
+                                    .get_blocks_by_operation(id)
+                                    .cloned()
+                                    .unwrap_or_default(),
+                                false,
+                            )
+                        })
+                        .or_else(|| {
+                            self.0
+                                .archive
+                                .as_ref()?
+                                .get_operation(id)
+                                .map(|(op, in_blocks)| (op, in_blocks, true))
+                        })
                 })
                 .collect()
         };
 
-        // keep only the ops id (found in storage)
-        let ops: Vec<OperationId> = storage_info.iter().map(|(op, _)| op.id).collect();
+        // keep only the ops id (found in storage or in the archive)
+        let ops: Vec<OperationId> = storage_info.iter().map(|(op, _, _)| op.id).collect();
 
         let api_cfg = self.0.api_settings.clone();
         if ops.len() as u64 > api_cfg.max_arguments {
@@ -542,13 +693,18 @@ impl MassaRpcServer for API<Public> {
             is_operation_final.into_iter(),
             statuses.into_iter(),
         );
-        for (id, (operation, in_blocks), in_pool, is_operation_final, op_exec_status) in
+        for (id, (operation, in_blocks, archived), in_pool, is_operation_final, op_exec_status) in
             zipped_iterator
         {
             res.push(OperationInfo {
                 id,
                 in_pool,
-                is_operation_final,
+                // archived operations are included in final blocks
+                is_operation_final: if archived {
+                    Some(true)
+                } else {
+                    is_operation_final
+                },
                 thread: operation
                     .content_creator_address
                     .get_thread(api_cfg.thread_count),
@@ -563,27 +719,39 @@ impl MassaRpcServer for API<Public> {
     }
 
     async fn get_endorsements(&self, eds: Vec<EndorsementId>) -> RpcResult<Vec<EndorsementInfo>> {
-        // get the endorsements and the list of blocks that contain them from storage
-        let storage_info: Vec<(SecureShareEndorsement, PreHashSet<BlockId>)> = {
+        // get the endorsements and the list of blocks that contain them from storage,
+        // falling back to the archive (if enabled) for the endorsements that were pruned
+        let storage_info: Vec<(SecureShareEndorsement, PreHashSet<BlockId>, bool)> = {
             let read_blocks = self.0.storage.read_blocks();
             let read_endos = self.0.storage.read_endorsements();
             eds.iter()
                 .filter_map(|id| {
-                    read_endos.get(id).cloned().map(|ed| {
-                        (
-                            ed,
-                            read_blocks
-                                .get_blocks_by_endorsement(id)
-                                .cloned()
-                                .unwrap_or_default(),
-                        )
-                    })
+                    read_endos
+                        .get(id)
+                        .cloned()
+                        .map(|ed| {
+                            (
+                                ed,
+                                read_blocks
+                                    .get_blocks_by_endorsement(id)
+                                    .cloned()
+                                    .unwrap_or_default(),
+                                false,
+                            )
+                        })
+                        .or_else(|| {
+                            self.0
+                                .archive
+                                .as_ref()?
+                                .get_endorsement(id)
+                                .map(|(ed, in_blocks)| (ed, in_blocks, true))
+                        })
                 })
                 .collect()
         };
 
-        // keep only the ops found in storage
-        let eds: Vec<EndorsementId> = storage_info.iter().map(|(ed, _)| ed.id).collect();
+        // keep only the endorsements found in storage or in the archive
+        let eds: Vec<EndorsementId> = storage_info.iter().map(|(ed, _, _)| ed.id).collect();
 
         // ask pool whether it carries the operations
         let in_pool = self.0.pool_command_sender.contains_endorsements(&eds);
@@ -599,7 +767,7 @@ impl MassaRpcServer for API<Public> {
         let is_final: Vec<bool> = {
             let involved_blocks: Vec<BlockId> = storage_info
                 .iter()
-                .flat_map(|(_ed, bs)| bs.iter())
+                .flat_map(|(_ed, bs, _archived)| bs.iter())
                 .unique()
                 .cloned()
                 .collect();
@@ -612,9 +780,12 @@ impl MassaRpcServer for API<Public> {
                 .collect();
             storage_info
                 .iter()
-                .map(|(_ed, bs)| {
-                    bs.iter()
-                        .any(|b| block_statuses.get(b) == Some(&BlockGraphStatus::Final))
+                .map(|(_ed, bs, archived)| {
+                    // archived endorsements are included in final blocks
+                    *archived
+                        || bs
+                            .iter()
+                            .any(|b| block_statuses.get(b) == Some(&BlockGraphStatus::Final))
                 })
                 .collect()
         };
@@ -627,7 +798,7 @@ impl MassaRpcServer for API<Public> {
             in_pool.into_iter(),
             is_final.into_iter()
         );
-        for (id, (endorsement, in_blocks), in_pool, is_final) in zipped_iterator {
+        for (id, (endorsement, in_blocks, _archived), in_pool, is_final) in zipped_iterator {
             res.push(EndorsementInfo {
                 id,
                 endorsement,
@@ -642,7 +813,7 @@ impl MassaRpcServer for API<Public> {
     }
 
     /// gets a block(s). Returns nothing if not found
-    /// only active blocks are returned
+    /// only active blocks are returned, as well as archived final blocks on archive nodes
     async fn get_blocks(&self, ids: Vec<BlockId>) -> RpcResult<Vec<BlockInfo>> {
         let consensus_controller = self.0.consensus_controller.clone();
         let blocks = ids
@@ -651,7 +822,18 @@ impl MassaRpcServer for API<Public> {
                 let content = if let Some(wrapped_block) = self.0.storage.read_blocks().get(&id) {
                     wrapped_block.content.clone()
                 } else {
-                    return None;
+                    // the block is not in memory anymore: look for it in the archive
+                    let archived_block = self.0.archive.as_ref()?.get_block(&id)?;
+                    return Some(BlockInfo {
+                        id,
+                        content: Some(BlockInfoContent {
+                            is_final: true,
+                            is_in_blockclique: false,
+                            is_candidate: false,
+                            is_discarded: false,
+                            block: archived_block.content,
+                        }),
+                    });
                 };
 
                 if let Some(graph_status) = consensus_controller
@@ -934,6 +1116,68 @@ impl MassaRpcServer for API<Public> {
         Ok(res)
     }
 
+    async fn get_address_history(
+        &self,
+        address: Address,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<AddressHistoryEntry>> {
+        // only the entries up to the end of the requested page are read
+        let max_count = match &page_request {
+            Some(PageRequest { limit, offset }) => offset.saturating_add(1).saturating_mul(*limit),
+            None => usize::MAX,
+        };
+        let history = self
+            .0
+            .execution_controller
+            .get_address_history(&address, max_count);
+        Ok(PagedVec::new(history, page_request))
+    }
+
+    async fn get_async_messages(
+        &self,
+        filter: AsyncMessageFilter,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<AsyncMessageEntry>> {
+        let (offset, max_count) = match &page_request {
+            Some(PageRequest { limit, offset }) => (offset.saturating_mul(*limit), *limit),
+            None => (0, usize::MAX),
+        };
+        let (total_count, messages) = self
+            .0
+            .execution_controller
+            .get_async_messages(&filter, offset, max_count);
+        Ok(PagedVec::from_page(messages, total_count))
+    }
+
+    async fn get_state_proof(&self, key: Vec<u8>) -> RpcResult<StateProof> {
+        match self.0.execution_controller.get_state_proof(&key) {
+            Some(proof) => Ok(proof),
+            None => Err(ApiError::BadRequest(
+                "state proofs are not enabled on this node".to_string(),
+            )
+            .into()),
+        }
+    }
+
+    async fn get_execution_traces(
+        &self,
+        ids: Vec<ExecutionTraceId>,
+    ) -> RpcResult<Vec<Option<ExecutionTrace>>> {
+        if ids.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+        Ok(self.0.execution_controller.get_execution_traces(&ids))
+    }
+
+    async fn get_fee_estimate(&self, target_delay: u64) -> RpcResult<FeeStats> {
+        if target_delay == 0 {
+            return Err(
+                ApiError::BadRequest("target delay must be at least 1 period".into()).into(),
+            );
+        }
+        Ok(self.0.pool_command_sender.get_fee_stats(target_delay))
+    }
+
     async fn send_operations(&self, ops: Vec<OperationInput>) -> RpcResult<Vec<OperationId>> {
         let mut cmd_sender = self.0.pool_command_sender.clone();
         let protocol_sender = self.0.protocol_controller.clone();
@@ -983,6 +1227,22 @@ impl MassaRpcServer for API<Public> {
                 Err(e) => Err(e),
             })
             .collect::<RpcResult<Vec<SecureShareOperation>>>()?;
+
+        // refuse the whole batch if the pool does not admit some operations
+        let rejections: Vec<String> = cmd_sender
+            .check_operations_admission(&verified_ops)
+            .into_iter()
+            .zip(&verified_ops)
+            .filter_map(|(admission, op)| {
+                admission
+                    .err()
+                    .map(|reason| format!("{}: {}", op.id, reason))
+            })
+            .collect();
+        if !rejections.is_empty() {
+            return Err(ApiError::OperationsRejected(rejections.join(", ")).into());
+        }
+
         to_send.store_operations(verified_ops.clone());
         let ids: Vec<OperationId> = verified_ops.iter().map(|op| op.id).collect();
         cmd_sender.add_operations(to_send.clone());
@@ -1002,10 +1262,18 @@ impl MassaRpcServer for API<Public> {
     /// * emitter address
     /// * original caller address
     /// * operation id
+    ///
+    /// and paginated with the `offset` and `limit` fields of the filter
     async fn get_filtered_sc_output_event(
         &self,
-        filter: EventFilter,
+        mut filter: EventFilter,
     ) -> RpcResult<Vec<SCOutputEvent>> {
+        let max_events = self.0.api_settings.max_events_per_request as usize;
+        filter.limit = Some(
+            filter
+                .limit
+                .map_or(max_events, |limit| limit.min(max_events)),
+        );
         let events = self
             .0
             .execution_controller
@@ -1027,31 +1295,31 @@ impl MassaRpcServer for API<Public> {
         crate::wrong_api::<()>()
     }
 
-    async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
-        crate::wrong_api::<Vec<IpAddr>>()
+    async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpListEntry>> {
+        crate::wrong_api::<Vec<IpListEntry>>()
     }
 
     async fn node_bootstrap_whitelist_allow_all(&self) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
 
-    async fn node_add_to_bootstrap_whitelist(&self, _: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_add_to_bootstrap_whitelist(&self, _: Vec<IpListEntry>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
 
-    async fn node_remove_from_bootstrap_whitelist(&self, _: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_remove_from_bootstrap_whitelist(&self, _: Vec<IpNetwork>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
 
-    async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpAddr>> {
-        crate::wrong_api::<Vec<IpAddr>>()
+    async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpListEntry>> {
+        crate::wrong_api::<Vec<IpListEntry>>()
     }
 
-    async fn node_add_to_bootstrap_blacklist(&self, _: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_add_to_bootstrap_blacklist(&self, _: Vec<IpListEntry>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
 
-    async fn node_remove_from_bootstrap_blacklist(&self, _: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_remove_from_bootstrap_blacklist(&self, _: Vec<IpNetwork>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
 
@@ -0,0 +1,618 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::ArchiveConfig;
+use massa_hash::{Hash, HASH_SIZE_BYTES};
+use massa_models::{
+    address::Address,
+    block::{Block, BlockDeserializer, BlockDeserializerArgs, SecureShareBlock},
+    block_id::BlockId,
+    endorsement::{EndorsementId, SecureShareEndorsement},
+    operation::{Operation, OperationDeserializer, OperationId, SecureShareOperation},
+    prehash::PreHashSet,
+    secure_share::{Id, SecureShareDeserializer, SecureShareSerializer},
+    slot::{Slot, SLOT_KEY_SIZE},
+};
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
+use massa_storage::Storage;
+use rocksdb::{ColumnFamilyDescriptor, Direction, IteratorMode, Options, WriteBatch, DB};
+use std::ops::{Bound, RangeBounds};
+use tracing::warn;
+
+const OPEN_ERROR: &str = "critical: rocksdb open operation failed";
+const CRUD_ERROR: &str = "critical: rocksdb crud operation failed";
+const CF_ERROR: &str = "critical: rocksdb column family operation failed";
+const SER_ERROR: &str = "critical: archived object serialization failed";
+const DESER_ERROR: &str = "critical: archived object deserialization failed";
+
+/// block hash => serialized block
+const BLOCKS_CF: &str = "blocks";
+/// operation hash => serialized operation
+const OPERATIONS_CF: &str = "operations";
+/// operation hash + block hash => ()
+const OPERATION_BLOCKS_CF: &str = "operation_blocks";
+/// endorsement hash + block hash => ()
+const ENDORSEMENT_BLOCKS_CF: &str = "endorsement_blocks";
+/// slot + block hash => ()
+const BLOCKS_BY_SLOT_CF: &str = "blocks_by_slot";
+/// creator address + slot + block hash => ()
+const BLOCKS_BY_CREATOR_CF: &str = "blocks_by_creator";
+/// creator address + slot + operation hash => ()
+const OPERATIONS_BY_CREATOR_CF: &str = "operations_by_creator";
+/// creator address + slot + endorsement hash => ()
+const ENDORSEMENTS_BY_CREATOR_CF: &str = "endorsements_by_creator";
+
+/// On-disk store of every finalized block, operation and endorsement.
+///
+/// The archive is append-only: objects are never pruned.
+pub struct Archive {
+    /// RocksDB database
+    db: DB,
+    /// Block deserializer
+    block_deserializer: SecureShareDeserializer<Block, BlockDeserializer>,
+    /// Operation deserializer
+    operation_deserializer: SecureShareDeserializer<Operation, OperationDeserializer>,
+}
+
+impl Archive {
+    /// Open (or create) the archive at the path given in the configuration
+    pub fn new(config: ArchiveConfig) -> Self {
+        let mut db_opts = Options::default();
+        db_opts.create_if_missing(true);
+        db_opts.create_missing_column_families(true);
+
+        let db = DB::open_cf_descriptors(
+            &db_opts,
+            &config.path,
+            vec![
+                ColumnFamilyDescriptor::new(BLOCKS_CF, Options::default()),
+                ColumnFamilyDescriptor::new(OPERATIONS_CF, Options::default()),
+                ColumnFamilyDescriptor::new(OPERATION_BLOCKS_CF, Options::default()),
+                ColumnFamilyDescriptor::new(ENDORSEMENT_BLOCKS_CF, Options::default()),
+                ColumnFamilyDescriptor::new(BLOCKS_BY_SLOT_CF, Options::default()),
+                ColumnFamilyDescriptor::new(BLOCKS_BY_CREATOR_CF, Options::default()),
+                ColumnFamilyDescriptor::new(OPERATIONS_BY_CREATOR_CF, Options::default()),
+                ColumnFamilyDescriptor::new(ENDORSEMENTS_BY_CREATOR_CF, Options::default()),
+            ],
+        )
+        .expect(OPEN_ERROR);
+
+        Self {
+            db,
+            block_deserializer: SecureShareDeserializer::new(BlockDeserializer::new(
+                BlockDeserializerArgs {
+                    thread_count: config.thread_count,
+                    max_operations_per_block: config.max_operations_per_block,
+                    endorsement_count: config.endorsement_count,
+                    max_denunciations_per_block_header: config.max_denunciations_per_block_header,
+                    last_start_period: None,
+                },
+            )),
+            operation_deserializer: SecureShareDeserializer::new(OperationDeserializer::new(
+                config.max_datastore_value_length,
+                config.max_function_name_length,
+                config.max_parameter_size,
+                config.max_op_datastore_entry_count,
+                config.max_op_datastore_key_length,
+                config.max_op_datastore_value_length,
+            )),
+        }
+    }
+
+    /// Persist a final block along with its operations and endorsements.
+    ///
+    /// # Arguments
+    /// * `block_id`: id of the final block
+    /// * `storage`: storage instance holding the block and its operations
+    pub fn archive_final_block(&self, block_id: &BlockId, storage: &Storage) {
+        let block = match storage.read_blocks().get(block_id) {
+            Some(block) => block.clone(),
+            None => {
+                warn!(
+                    "final block {} not found in storage, not archived",
+                    block_id
+                );
+                return;
+            }
+        };
+        let slot = block.content.header.content.slot;
+        let slot_key = slot.to_bytes_key();
+        let block_hash = block_id.get_hash().to_bytes();
+        let serializer = SecureShareSerializer::new();
+        let mut batch = WriteBatch::default();
+
+        // block and its indexes
+        let mut serialized_block = Vec::new();
+        serializer
+            .serialize(&block, &mut serialized_block)
+            .expect(SER_ERROR);
+        batch.put_cf(self.cf(BLOCKS_CF), block_hash, serialized_block);
+        batch.put_cf(
+            self.cf(BLOCKS_BY_SLOT_CF),
+            [&slot_key[..], &block_hash[..]].concat(),
+            b"",
+        );
+        batch.put_cf(
+            self.cf(BLOCKS_BY_CREATOR_CF),
+            address_index_key(&block.content_creator_address, &slot, block_hash),
+            b"",
+        );
+
+        // endorsements are stored inside the block header
+        for endorsement in block.content.header.content.endorsements.iter() {
+            let endorsement_hash = endorsement.id.get_hash().to_bytes();
+            batch.put_cf(
+                self.cf(ENDORSEMENT_BLOCKS_CF),
+                [&endorsement_hash[..], &block_hash[..]].concat(),
+                b"",
+            );
+            batch.put_cf(
+                self.cf(ENDORSEMENTS_BY_CREATOR_CF),
+                address_index_key(
+                    &endorsement.content_creator_address,
+                    &slot,
+                    endorsement_hash,
+                ),
+                b"",
+            );
+        }
+
+        // operations and their indexes
+        {
+            let ops = storage.read_operations();
+            for op_id in block.content.operations.iter() {
+                let op = match ops.get(op_id) {
+                    Some(op) => op,
+                    None => {
+                        warn!(
+                            "operation {} of final block {} not found in storage, not archived",
+                            op_id, block_id
+                        );
+                        continue;
+                    }
+                };
+                let op_hash = op_id.get_hash().to_bytes();
+                let mut serialized_op = Vec::new();
+                serializer
+                    .serialize(op, &mut serialized_op)
+                    .expect(SER_ERROR);
+                batch.put_cf(self.cf(OPERATIONS_CF), op_hash, serialized_op);
+                batch.put_cf(
+                    self.cf(OPERATION_BLOCKS_CF),
+                    [&op_hash[..], &block_hash[..]].concat(),
+                    b"",
+                );
+                batch.put_cf(
+                    self.cf(OPERATIONS_BY_CREATOR_CF),
+                    address_index_key(&op.content_creator_address, &slot, op_hash),
+                    b"",
+                );
+            }
+        }
+
+        self.db.write(batch).expect(CRUD_ERROR);
+    }
+
+    /// Check whether a block is archived
+    pub fn contains_block(&self, block_id: &BlockId) -> bool {
+        self.db
+            .get_pinned_cf(self.cf(BLOCKS_CF), block_id.get_hash().to_bytes())
+            .expect(CRUD_ERROR)
+            .is_some()
+    }
+
+    /// Get an archived block
+    pub fn get_block(&self, block_id: &BlockId) -> Option<SecureShareBlock> {
+        let serialized = self
+            .db
+            .get_cf(self.cf(BLOCKS_CF), block_id.get_hash().to_bytes())
+            .expect(CRUD_ERROR)?;
+        let (_, block) = self
+            .block_deserializer
+            .deserialize::<DeserializeError>(&serialized)
+            .expect(DESER_ERROR);
+        Some(block)
+    }
+
+    /// Get an archived operation along with the ids of the final blocks that include it
+    pub fn get_operation(
+        &self,
+        operation_id: &OperationId,
+    ) -> Option<(SecureShareOperation, PreHashSet<BlockId>)> {
+        let op_hash = operation_id.get_hash().to_bytes();
+        let serialized = self
+            .db
+            .get_cf(self.cf(OPERATIONS_CF), op_hash)
+            .expect(CRUD_ERROR)?;
+        let (_, operation) = self
+            .operation_deserializer
+            .deserialize::<DeserializeError>(&serialized)
+            .expect(DESER_ERROR);
+        Some((operation, self.get_operation_block_ids(operation_id)))
+    }
+
+    /// Get the ids of the archived blocks that include an operation
+    pub fn get_operation_block_ids(&self, operation_id: &OperationId) -> PreHashSet<BlockId> {
+        self.prefix_suffixes(OPERATION_BLOCKS_CF, operation_id.get_hash().to_bytes())
+            .into_iter()
+            .map(BlockId::new)
+            .collect()
+    }
+
+    /// Get an archived endorsement along with the ids of the final blocks that include it
+    pub fn get_endorsement(
+        &self,
+        endorsement_id: &EndorsementId,
+    ) -> Option<(SecureShareEndorsement, PreHashSet<BlockId>)> {
+        let block_ids = self.get_endorsement_block_ids(endorsement_id);
+        let endorsement = block_ids.iter().find_map(|block_id| {
+            self.get_block(block_id)?
+                .content
+                .header
+                .content
+                .endorsements
+                .into_iter()
+                .find(|endorsement| &endorsement.id == endorsement_id)
+        })?;
+        Some((endorsement, block_ids))
+    }
+
+    /// Get the ids of the archived blocks that include an endorsement
+    pub fn get_endorsement_block_ids(&self, endorsement_id: &EndorsementId) -> PreHashSet<BlockId> {
+        self.prefix_suffixes(ENDORSEMENT_BLOCKS_CF, endorsement_id.get_hash().to_bytes())
+            .into_iter()
+            .map(BlockId::new)
+            .collect()
+    }
+
+    /// Get the ids of at most `max_count` archived blocks within a slot range, starting from the range start
+    pub fn get_block_ids_by_slot_range<R>(
+        &self,
+        slot_range: R,
+        max_count: usize,
+    ) -> PreHashSet<BlockId>
+    where
+        R: RangeBounds<Slot>,
+    {
+        let start_key = match slot_range.start_bound() {
+            Bound::Included(slot) | Bound::Excluded(slot) => slot.to_bytes_key().to_vec(),
+            Bound::Unbounded => Vec::new(),
+        };
+        let mut res = PreHashSet::default();
+        if max_count == 0 {
+            return res;
+        }
+        for (key, _) in self
+            .db
+            .iterator_cf(
+                self.cf(BLOCKS_BY_SLOT_CF),
+                IteratorMode::From(&start_key, Direction::Forward),
+            )
+            .flatten()
+        {
+            let slot = Slot::from_bytes_key(
+                key[..SLOT_KEY_SIZE]
+                    .try_into()
+                    .expect("critical: invalid slot key length"),
+            );
+            if !slot_range.contains(&slot) {
+                if let Bound::Excluded(start) = slot_range.start_bound() {
+                    if &slot == start {
+                        continue;
+                    }
+                }
+                break;
+            }
+            res.insert(BlockId::new(hash_from_key_suffix(&key)));
+            if res.len() >= max_count {
+                break;
+            }
+        }
+        res
+    }
+
+    /// Get the ids of the `max_count` latest archived blocks created by an address
+    pub fn get_block_ids_by_creator(
+        &self,
+        address: &Address,
+        max_count: usize,
+    ) -> PreHashSet<BlockId> {
+        self.latest_address_index_hashes(BLOCKS_BY_CREATOR_CF, address, max_count)
+            .into_iter()
+            .map(BlockId::new)
+            .collect()
+    }
+
+    /// Get the ids of the `max_count` latest archived operations created by an address
+    pub fn get_operation_ids_by_creator(
+        &self,
+        address: &Address,
+        max_count: usize,
+    ) -> PreHashSet<OperationId> {
+        self.latest_address_index_hashes(OPERATIONS_BY_CREATOR_CF, address, max_count)
+            .into_iter()
+            .map(OperationId::new)
+            .collect()
+    }
+
+    /// Get the ids of the `max_count` latest archived endorsements created by an address
+    pub fn get_endorsement_ids_by_creator(
+        &self,
+        address: &Address,
+        max_count: usize,
+    ) -> PreHashSet<EndorsementId> {
+        self.latest_address_index_hashes(ENDORSEMENTS_BY_CREATOR_CF, address, max_count)
+            .into_iter()
+            .map(EndorsementId::new)
+            .collect()
+    }
+
+    /// Get a column family handle
+    fn cf(&self, name: &str) -> &rocksdb::ColumnFamily {
+        self.db.cf_handle(name).expect(CF_ERROR)
+    }
+
+    /// Collect the trailing hashes of all the keys of a column family starting with `prefix`
+    fn prefix_suffixes(&self, cf: &str, prefix: &[u8]) -> Vec<Hash> {
+        self.db
+            .prefix_iterator_cf(self.cf(cf), prefix)
+            .flatten()
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .map(|(key, _)| hash_from_key_suffix(&key))
+            .collect()
+    }
+
+    /// Collect the object hashes of the `max_count` latest slots of an address index, most recent first
+    fn latest_address_index_hashes(
+        &self,
+        cf: &str,
+        address: &Address,
+        max_count: usize,
+    ) -> Vec<Hash> {
+        let prefix = address.to_prefixed_bytes();
+        // all the keys of the address are `prefix + slot + hash`: start from the highest possible one
+        let last_key = [&prefix[..], &[u8::MAX; SLOT_KEY_SIZE + HASH_SIZE_BYTES][..]].concat();
+        self.db
+            .iterator_cf(
+                self.cf(cf),
+                IteratorMode::From(&last_key, Direction::Reverse),
+            )
+            .flatten()
+            .take_while(|(key, _)| key.starts_with(&prefix))
+            .take(max_count)
+            .map(|(key, _)| hash_from_key_suffix(&key))
+            .collect()
+    }
+}
+
+/// Build an index key: `address + slot + object hash`
+fn address_index_key(address: &Address, slot: &Slot, hash: &[u8; HASH_SIZE_BYTES]) -> Vec<u8> {
+    [
+        &address.to_prefixed_bytes()[..],
+        &slot.to_bytes_key()[..],
+        &hash[..],
+    ]
+    .concat()
+}
+
+/// Read the object hash stored at the end of an index key
+fn hash_from_key_suffix(key: &[u8]) -> Hash {
+    Hash::from_bytes(
+        key[key.len() - HASH_SIZE_BYTES..]
+            .try_into()
+            .expect("critical: invalid archive key length"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_factory_exports::test_exports::create_empty_block;
+    use massa_models::{
+        amount::Amount,
+        block::BlockSerializer,
+        block_header::{BlockHeader, BlockHeaderSerializer},
+        endorsement::{Endorsement, EndorsementSerializer},
+        operation::{
+            compute_operations_hash, OperationIdSerializer, OperationSerializer, OperationType,
+        },
+        secure_share::SecureShareContent,
+    };
+    use massa_signature::KeyPair;
+    use tempfile::TempDir;
+
+    fn archive_config(path: std::path::PathBuf) -> ArchiveConfig {
+        ArchiveConfig {
+            path,
+            thread_count: 32,
+            endorsement_count: 16,
+            max_operations_per_block: 5000,
+            max_denunciations_per_block_header: 128,
+            max_datastore_value_length: 10_000_000,
+            max_function_name_length: u16::MAX,
+            max_parameter_size: 10_000_000,
+            max_op_datastore_entry_count: 128,
+            max_op_datastore_key_length: u8::MAX,
+            max_op_datastore_value_length: 10_000_000,
+        }
+    }
+
+    /// Create a block endorsed by `endorser` and including `operations`
+    fn create_block(
+        keypair: &KeyPair,
+        endorser: &KeyPair,
+        slot: Slot,
+        operations: &[SecureShareOperation],
+    ) -> SecureShareBlock {
+        let parents: Vec<BlockId> = (0..32u8)
+            .map(|i| BlockId::generate_from_hash(Hash::compute_from(&[i])))
+            .collect();
+        let endorsement = Endorsement::new_verifiable(
+            Endorsement {
+                slot,
+                index: 0,
+                endorsed_block: parents[slot.thread as usize],
+            },
+            EndorsementSerializer::new(),
+            endorser,
+        )
+        .unwrap();
+        let op_ids: Vec<OperationId> = operations.iter().map(|op| op.id).collect();
+        let header = BlockHeader::new_verifiable(
+            BlockHeader {
+                current_version: 0,
+                announced_version: None,
+                slot,
+                parents,
+                operation_merkle_root: compute_operations_hash(
+                    &op_ids,
+                    &OperationIdSerializer::new(),
+                ),
+                endorsements: vec![endorsement],
+                denunciations: Vec::new(),
+            },
+            BlockHeaderSerializer::new(),
+            keypair,
+        )
+        .unwrap();
+        Block::new_verifiable(
+            Block {
+                header,
+                operations: op_ids,
+            },
+            BlockSerializer::new(),
+            keypair,
+        )
+        .unwrap()
+    }
+
+    /// Create a transaction sent by `keypair`
+    fn create_operation(keypair: &KeyPair, expire_period: u64) -> SecureShareOperation {
+        Operation::new_verifiable(
+            Operation {
+                fee: Amount::zero(),
+                expire_period,
+                op: OperationType::Transaction {
+                    recipient_address: Address::from_public_key(&keypair.get_public_key()),
+                    amount: Amount::zero(),
+                },
+            },
+            OperationSerializer::new(),
+            keypair,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_archive_block_and_indexes() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = Archive::new(archive_config(temp_dir.path().to_path_buf()));
+
+        let keypair = KeyPair::generate(0).unwrap();
+        let creator = Address::from_public_key(&keypair.get_public_key());
+        let block = create_empty_block(&keypair, &Slot::new(3, 1));
+        let mut storage = Storage::create_root();
+        storage.store_block(block.clone());
+
+        archive.archive_final_block(&block.id, &storage);
+        drop(storage);
+
+        let archived = archive.get_block(&block.id).unwrap();
+        assert_eq!(archived.id, block.id);
+        assert_eq!(archived.serialized_data, block.serialized_data);
+
+        assert!(archive
+            .get_block_ids_by_slot_range(Slot::new(3, 0)..=Slot::new(3, 1), 10)
+            .contains(&block.id));
+        assert!(archive
+            .get_block_ids_by_slot_range(..Slot::new(3, 1), 10)
+            .is_empty());
+        assert!(archive
+            .get_block_ids_by_slot_range((Bound::Excluded(Slot::new(3, 1)), Bound::Unbounded), 10)
+            .is_empty());
+        assert!(archive
+            .get_block_ids_by_creator(&creator, 10)
+            .contains(&block.id));
+    }
+
+    #[test]
+    fn test_archive_operations_and_endorsements() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = Archive::new(archive_config(temp_dir.path().to_path_buf()));
+
+        let keypair = KeyPair::generate(0).unwrap();
+        let endorser_keypair = KeyPair::generate(0).unwrap();
+        let sender_keypair = KeyPair::generate(0).unwrap();
+        let creator = Address::from_public_key(&keypair.get_public_key());
+        let endorser = Address::from_public_key(&endorser_keypair.get_public_key());
+        let sender = Address::from_public_key(&sender_keypair.get_public_key());
+
+        let op_1 = create_operation(&sender_keypair, 10);
+        let op_2 = create_operation(&sender_keypair, 11);
+        let block_1 = create_block(
+            &keypair,
+            &endorser_keypair,
+            Slot::new(3, 1),
+            &[op_1.clone()],
+        );
+        let block_2 = create_block(
+            &keypair,
+            &endorser_keypair,
+            Slot::new(4, 1),
+            &[op_2.clone()],
+        );
+        let endorsement_1 = block_1.content.header.content.endorsements[0].clone();
+        let endorsement_2 = block_2.content.header.content.endorsements[0].clone();
+
+        let mut storage = Storage::create_root();
+        storage.store_operations(vec![op_1.clone(), op_2.clone()]);
+        storage.store_block(block_1.clone());
+        storage.store_block(block_2.clone());
+        archive.archive_final_block(&block_1.id, &storage);
+        archive.archive_final_block(&block_2.id, &storage);
+        drop(storage);
+
+        // objects are read back along with the blocks including them
+        let (archived_op, op_block_ids) = archive.get_operation(&op_1.id).unwrap();
+        assert_eq!(archived_op.serialized_data, op_1.serialized_data);
+        assert_eq!(op_block_ids, PreHashSet::from_iter([block_1.id]));
+        let (archived_endorsement, endorsement_block_ids) =
+            archive.get_endorsement(&endorsement_2.id).unwrap();
+        assert_eq!(archived_endorsement.id, endorsement_2.id);
+        assert_eq!(endorsement_block_ids, PreHashSet::from_iter([block_2.id]));
+
+        // objects are indexed by their own creator
+        assert_eq!(
+            archive.get_operation_ids_by_creator(&sender, 10),
+            PreHashSet::from_iter([op_1.id, op_2.id])
+        );
+        assert_eq!(
+            archive.get_endorsement_ids_by_creator(&endorser, 10),
+            PreHashSet::from_iter([endorsement_1.id, endorsement_2.id])
+        );
+        assert!(archive
+            .get_operation_ids_by_creator(&creator, 10)
+            .is_empty());
+        assert!(archive
+            .get_endorsement_ids_by_creator(&sender, 10)
+            .is_empty());
+
+        // creator queries keep the most recent objects, slot range queries the earliest ones
+        assert_eq!(
+            archive.get_block_ids_by_creator(&creator, 1),
+            PreHashSet::from_iter([block_2.id])
+        );
+        assert_eq!(
+            archive.get_operation_ids_by_creator(&sender, 1),
+            PreHashSet::from_iter([op_2.id])
+        );
+        assert_eq!(
+            archive.get_endorsement_ids_by_creator(&endorser, 1),
+            PreHashSet::from_iter([endorsement_2.id])
+        );
+        assert_eq!(
+            archive.get_block_ids_by_slot_range(.., 1),
+            PreHashSet::from_iter([block_1.id])
+        );
+        assert!(archive.get_block_ids_by_creator(&creator, 0).is_empty());
+    }
+}
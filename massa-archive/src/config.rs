@@ -0,0 +1,30 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use std::path::PathBuf;
+
+/// Archive configuration
+#[derive(Debug, Clone)]
+pub struct ArchiveConfig {
+    /// Path to the archive database
+    pub path: PathBuf,
+    /// Number of threads
+    pub thread_count: u8,
+    /// Number of endorsements per block
+    pub endorsement_count: u32,
+    /// Maximum number of operations per block
+    pub max_operations_per_block: u32,
+    /// Maximum number of denunciations in a block header
+    pub max_denunciations_per_block_header: u32,
+    /// Maximum datastore value length
+    pub max_datastore_value_length: u64,
+    /// Maximum function name length
+    pub max_function_name_length: u16,
+    /// Maximum parameters size
+    pub max_parameter_size: u32,
+    /// Maximum number of entries in an operation datastore
+    pub max_op_datastore_entry_count: u64,
+    /// Maximum length of an operation datastore key
+    pub max_op_datastore_key_length: u8,
+    /// Maximum length of an operation datastore value
+    pub max_op_datastore_value_length: u64,
+}
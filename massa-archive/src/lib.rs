@@ -0,0 +1,18 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+//!
+//! # General description
+//!
+//! The archive keeps every finalized block, together with its operations and endorsements,
+//! in an on-disk store that is never pruned. It is used by archive/indexer nodes so that
+//! the public APIs can still serve objects once they have been dropped from `massa_storage`.
+//!
+//! Objects are indexed by id, slot and creator.
+//! Final blocks are written by a dedicated worker, so that a slow disk does not hold back consensus.
+
+mod archive;
+mod config;
+mod worker;
+
+pub use archive::*;
+pub use config::*;
+pub use worker::*;
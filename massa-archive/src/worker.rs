@@ -0,0 +1,101 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::Archive;
+use massa_channel::{receiver::MassaReceiver, sender::MassaSender, MassaChannel};
+use massa_models::block_id::BlockId;
+use massa_storage::Storage;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use tracing::{info, warn};
+
+/// Commands sent to the archive worker
+enum ArchiveCommand {
+    /// Archive final blocks, held by the storage along with their operations
+    ArchiveFinalBlocks(Vec<BlockId>, Storage),
+    /// Stop the worker once the blocks received before are archived
+    Stop,
+}
+
+/// Sends the final blocks to the archive worker,
+/// so that they are written to disk outside of the thread that finalizes them
+#[derive(Clone)]
+pub struct ArchiveWriter {
+    command_sender: MassaSender<ArchiveCommand>,
+}
+
+impl ArchiveWriter {
+    /// Queue final blocks to be archived.
+    /// This only blocks if the archive worker is lagging by more than its channel size.
+    ///
+    /// # Arguments
+    /// * `block_ids`: ids of the final blocks
+    /// * `storage`: storage instance holding the blocks and their operations
+    pub fn archive_final_blocks(&self, block_ids: Vec<BlockId>, storage: Storage) {
+        if self
+            .command_sender
+            .send(ArchiveCommand::ArchiveFinalBlocks(block_ids, storage))
+            .is_err()
+        {
+            warn!("archive worker stopped: final blocks not archived");
+        }
+    }
+}
+
+/// Allows stopping the archive worker
+pub struct ArchiveManager {
+    command_sender: MassaSender<ArchiveCommand>,
+    join_handle: Option<JoinHandle<()>>,
+}
+
+impl ArchiveManager {
+    /// Stop the archive worker after it archived the blocks already queued
+    pub fn stop(&mut self) {
+        info!("stopping archive worker...");
+        if let Some(join_handle) = self.join_handle.take() {
+            let _ = self.command_sender.send(ArchiveCommand::Stop);
+            if let Err(err) = join_handle.join() {
+                warn!("archive worker panicked: {:?}", err);
+            }
+        }
+        info!("archive worker stopped");
+    }
+}
+
+/// Start the worker writing the final blocks to the archive
+///
+/// # Arguments
+/// * `archive`: archive the blocks are written to
+/// * `channel_size`: number of batches of final blocks that can be queued before the senders block
+pub fn start_archive_worker(
+    archive: Arc<Archive>,
+    channel_size: usize,
+) -> (ArchiveWriter, ArchiveManager) {
+    let (command_sender, command_receiver) =
+        MassaChannel::new("archive_command".to_string(), Some(channel_size));
+    let join_handle = std::thread::Builder::new()
+        .name("archive_worker".into())
+        .spawn(move || run_archive_worker(archive, command_receiver))
+        .expect("failed to spawn thread : archive_worker");
+    (
+        ArchiveWriter {
+            command_sender: command_sender.clone(),
+        },
+        ArchiveManager {
+            command_sender,
+            join_handle: Some(join_handle),
+        },
+    )
+}
+
+fn run_archive_worker(archive: Arc<Archive>, command_receiver: MassaReceiver<ArchiveCommand>) {
+    while let Ok(command) = command_receiver.recv() {
+        match command {
+            ArchiveCommand::ArchiveFinalBlocks(block_ids, storage) => {
+                for block_id in block_ids.iter() {
+                    archive.archive_final_block(block_id, &storage);
+                }
+            }
+            ArchiveCommand::Stop => break,
+        }
+    }
+}
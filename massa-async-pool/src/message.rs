@@ -661,6 +661,8 @@ impl Deserializer<AsyncMessage> for AsyncMessageDeserializer {
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct AsyncMessageInfo {
+    pub sender: Address,
+    pub destination: Address,
     pub validity_start: Slot,
     pub validity_end: Slot,
     pub max_gas: u64,
@@ -671,6 +673,8 @@ pub struct AsyncMessageInfo {
 impl From<AsyncMessage> for AsyncMessageInfo {
     fn from(value: AsyncMessage) -> Self {
         Self {
+            sender: value.sender,
+            destination: value.destination,
             validity_start: value.validity_start,
             validity_end: value.validity_end,
             max_gas: value.max_gas,
@@ -1025,6 +1029,8 @@ impl Applicable<AsyncMessageUpdate> for AsyncMessage {
 impl Applicable<AsyncMessageUpdate> for AsyncMessageInfo {
     /// extends the `AsyncMessage` with a `AsyncMessageUpdate`
     fn apply(&mut self, update: AsyncMessageUpdate) {
+        update.sender.apply_to(&mut self.sender);
+        update.destination.apply_to(&mut self.destination);
         update.max_gas.apply_to(&mut self.max_gas);
         update.validity_start.apply_to(&mut self.validity_start);
         update.validity_end.apply_to(&mut self.validity_end);
@@ -4,6 +4,7 @@ use massa_final_state::{FinalState, FinalStateError};
 use massa_logging::massa_trace;
 use massa_metrics::MassaMetrics;
 use massa_models::{node::NodeId, slot::Slot, streaming_step::StreamingStep, version::Version};
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use massa_signature::PublicKey;
 use massa_time::MassaTime;
 use massa_versioning::versioning::{ComponentStateTypeId, MipInfo, MipState, StateAtError};
@@ -25,7 +26,10 @@ use tracing::{debug, info, warn};
 use crate::{
     bindings::BootstrapClientBinder,
     error::BootstrapError,
-    messages::{BootstrapClientMessage, BootstrapServerMessage},
+    messages::{
+        BootstrapClientMessage, BootstrapClientMessageDeserializer,
+        BootstrapClientMessageSerializer, BootstrapServerMessage,
+    },
     settings::IpType,
     BootstrapConfig, GlobalBootstrapState,
 };
@@ -142,6 +146,11 @@ fn stream_final_state_and_consensus(
                         send_last_start_period: false,
                     };
 
+                    // Persist the new message so that the bootstrap can be resumed after a restart
+                    if let Err(err) = save_bootstrap_cursor(cfg, next_bootstrap_message) {
+                        warn!("could not save bootstrap cursor: {}", err);
+                    }
+
                     // Logs for an easier diagnostic if needed
                     debug!(
                         "client final state bootstrap cursors: {:?}",
@@ -166,17 +175,8 @@ fn stream_final_state_and_consensus(
                     return Ok(());
                 }
                 BootstrapServerMessage::SlotTooOld => {
-                    info!("Slot is too old retry bootstrap from scratch");
-                    *next_bootstrap_message = BootstrapClientMessage::AskBootstrapPart {
-                        last_slot: None,
-                        last_state_step: StreamingStep::Started,
-                        last_versioning_step: StreamingStep::Started,
-                        last_consensus_step: StreamingStep::Started,
-                        send_last_start_period: true,
-                    };
-                    let mut write_final_state = global_bootstrap_state.final_state.write();
-                    write_final_state.reset();
-                    return Err(BootstrapError::GeneralError(String::from("Slot too old")));
+                    // Keep the cursor: another server may still be able to resume from it
+                    return Err(BootstrapError::SlotTooOld);
                 }
                 // At this point, we have successfully received the next message from the server, and it's an error-message String
                 BootstrapServerMessage::BootstrapError { error } => {
@@ -197,6 +197,131 @@ fn stream_final_state_and_consensus(
     }
 }
 
+/// Message starting the bootstrap of the final state and consensus from scratch
+pub(crate) fn initial_bootstrap_message() -> BootstrapClientMessage {
+    BootstrapClientMessage::AskBootstrapPart {
+        last_slot: None,
+        last_state_step: StreamingStep::Started,
+        last_versioning_step: StreamingStep::Started,
+        last_consensus_step: StreamingStep::Started,
+        send_last_start_period: true,
+    }
+}
+
+/// Writes the next message to send to a bootstrap server to disk.
+/// The state received so far is already written in the db, so the bootstrap can be resumed from it after a restart.
+pub(crate) fn save_bootstrap_cursor(
+    cfg: &BootstrapConfig,
+    next_bootstrap_message: &BootstrapClientMessage,
+) -> Result<(), BootstrapError> {
+    let mut buffer = Vec::new();
+    BootstrapClientMessageSerializer::new().serialize(next_bootstrap_message, &mut buffer)?;
+    if let Some(parent) = cfg.bootstrap_cursor_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // write to a temporary file first so that a crash never leaves a partially written cursor
+    let tmp_path = cfg.bootstrap_cursor_path.with_extension("tmp");
+    std::fs::write(&tmp_path, buffer)?;
+    std::fs::rename(tmp_path, &cfg.bootstrap_cursor_path)?;
+    Ok(())
+}
+
+/// Removes the bootstrap cursor from the disk, if any
+fn remove_bootstrap_cursor(cfg: &BootstrapConfig) {
+    if let Err(err) = std::fs::remove_file(&cfg.bootstrap_cursor_path) {
+        if err.kind() != io::ErrorKind::NotFound {
+            warn!("could not remove bootstrap cursor: {}", err);
+        }
+    }
+}
+
+/// Loads the cursor of a bootstrap interrupted during a previous run.
+///
+/// The db is written before the cursor, so its change id can only be ahead of the cursor slot.
+/// If the cursor cannot be used, the partially bootstrapped final state is reset and `None` is returned.
+pub(crate) fn load_bootstrap_cursor(
+    cfg: &BootstrapConfig,
+    final_state: &Arc<RwLock<FinalState>>,
+) -> Option<BootstrapClientMessage> {
+    let content = match std::fs::read(&cfg.bootstrap_cursor_path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return None,
+        Err(err) => {
+            warn!("could not read bootstrap cursor: {}", err);
+            Vec::new()
+        }
+    };
+
+    let deserializer = BootstrapClientMessageDeserializer::new(
+        cfg.thread_count,
+        cfg.max_datastore_key_length,
+        cfg.max_consensus_block_ids,
+    );
+    let db_slot = final_state.read().db.read().get_change_id().ok();
+    match deserializer.deserialize::<DeserializeError>(&content) {
+        Ok((
+            rest,
+            BootstrapClientMessage::AskBootstrapPart {
+                last_slot: Some(last_slot),
+                last_state_step,
+                last_versioning_step,
+                ..
+            },
+        )) if rest.is_empty() && db_slot.map_or(false, |db_slot| db_slot >= last_slot) => {
+            info!(
+                "Resuming the bootstrap interrupted during a previous run from slot {}",
+                last_slot
+            );
+            Some(BootstrapClientMessage::AskBootstrapPart {
+                last_slot: Some(last_slot),
+                last_state_step,
+                last_versioning_step,
+                // the consensus blocks and the start period were only kept in memory
+                last_consensus_step: StreamingStep::Started,
+                send_last_start_period: true,
+            })
+        }
+        _ => {
+            warn!("Cannot resume the bootstrap interrupted during a previous run, starting from scratch");
+            final_state.write().reset();
+            remove_bootstrap_cursor(cfg);
+            None
+        }
+    }
+}
+
+/// Servers that answered that they can no longer resume the bootstrap from our cursor
+#[derive(Default)]
+pub(crate) struct SlotTooOldServers {
+    /// slot of the cursor the servers were asked to resume from
+    cursor_slot: Option<Slot>,
+    /// servers that cannot resume from that cursor
+    servers: HashSet<NodeId>,
+}
+
+impl SlotTooOldServers {
+    /// Notes that a server cannot resume from the cursor at `cursor_slot`.
+    /// Returns true if none of the `server_count` servers can: the bootstrap must then restart from scratch.
+    pub(crate) fn insert(
+        &mut self,
+        cursor_slot: Option<Slot>,
+        node_id: NodeId,
+        server_count: usize,
+    ) -> bool {
+        // the answers about a previous cursor do not tell anything about the current one
+        if cursor_slot != self.cursor_slot {
+            self.servers.clear();
+            self.cursor_slot = cursor_slot;
+        }
+        self.servers.insert(node_id);
+        if self.servers.len() < server_count {
+            return false;
+        }
+        self.servers.clear();
+        true
+    }
+}
+
 /// Gets the state from a bootstrap server (internal private function)
 /// needs to be CANCELLABLE
 fn bootstrap_from_server(
@@ -476,15 +601,13 @@ pub fn get_state(
     let filtered_bootstrap_list = get_bootstrap_list_iter(bootstrap_config)?;
 
     let mut next_bootstrap_message: BootstrapClientMessage =
-        BootstrapClientMessage::AskBootstrapPart {
-            last_slot: None,
-            last_state_step: StreamingStep::Started,
-            last_versioning_step: StreamingStep::Started,
-            last_consensus_step: StreamingStep::Started,
-            send_last_start_period: true,
-        };
+        load_bootstrap_cursor(bootstrap_config, &final_state)
+            .unwrap_or_else(initial_bootstrap_message);
     let mut global_bootstrap_state = GlobalBootstrapState::new(final_state);
 
+    // Servers that no longer have the changes since the slot of our cursor in their history
+    let mut slot_too_old_servers = SlotTooOldServers::default();
+
     let limit = bootstrap_config.rate_limit;
     loop {
         // check for interuption
@@ -522,6 +645,28 @@ pub fn get_state(
                         Err(BootstrapError::ReceivedError(error)) => {
                             warn!("Error received from bootstrap server: {}", error)
                         }
+                        Err(BootstrapError::SlotTooOld) => {
+                            let cursor_slot = match &next_bootstrap_message {
+                                BootstrapClientMessage::AskBootstrapPart { last_slot, .. } => {
+                                    *last_slot
+                                }
+                                _ => None,
+                            };
+                            // Restart from scratch only when no server can resume from our cursor
+                            if slot_too_old_servers.insert(
+                                cursor_slot,
+                                *node_id,
+                                filtered_bootstrap_list.len(),
+                            ) {
+                                info!("No bootstrap server can resume from our cursor anymore, retry bootstrap from scratch");
+                                next_bootstrap_message = initial_bootstrap_message();
+                                global_bootstrap_state.graph = None;
+                                global_bootstrap_state.final_state.write().reset();
+                                remove_bootstrap_cursor(bootstrap_config);
+                            } else {
+                                info!("Bootstrap server {} cannot resume from our cursor anymore, trying another one", addr);
+                            }
+                        }
                         Err(e) => {
                             warn!("Error while bootstrapping: {}", &e);
                             // We allow unused result because we don't care if an error is thrown when sending the error message to the server we will close the socket anyway.
@@ -532,7 +677,10 @@ pub fn get_state(
                                 Some(bootstrap_config.write_error_timeout.into()),
                             );
                         }
-                        Ok(()) => return Ok(global_bootstrap_state),
+                        Ok(()) => {
+                            remove_bootstrap_cursor(bootstrap_config);
+                            return Ok(global_bootstrap_state);
+                        }
                     }
                 }
                 Err(e) => {
@@ -601,7 +749,10 @@ fn get_bootstrap_list_iter(
     Ok(filtered_bootstrap_list)
 }
 
-fn warn_user_about_versioning_updates(updated: Vec<MipInfo>, added: BTreeMap<MipInfo, MipState>) {
+pub(crate) fn warn_user_about_versioning_updates(
+    updated: Vec<MipInfo>,
+    added: BTreeMap<MipInfo, MipState>,
+) {
     if !added.is_empty() {
         for (mip_info, mip_state) in added.iter() {
             let now = MassaTime::now().expect("Cannot get current time");
@@ -67,6 +67,8 @@ pub enum BootstrapError {
     WhiteListed(String),
     /// The bootstrap process ended prematurely - e.g. too much time elapsed
     Interupted(String),
+    /// the changes since our bootstrap cursor are no longer in the history of the server
+    SlotTooOld,
 }
 
 /// # Platform-specific behavior
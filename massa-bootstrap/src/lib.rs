@@ -27,6 +27,7 @@ mod listener;
 mod messages;
 mod server;
 mod settings;
+mod snapshot;
 mod tools;
 /// white/black list
 pub mod white_black_list;
@@ -40,6 +41,10 @@ pub use messages::{
 pub use server::{start_bootstrap_server, BootstrapManager};
 pub use settings::IpType;
 pub use settings::{BootstrapConfig, BootstrapServerMessageDeserializerArgs};
+pub use snapshot::{
+    export_snapshot, get_state_from_snapshot, import_snapshot, load_final_graph, save_final_graph,
+    FinalGraph, SnapshotHeader,
+};
 
 #[cfg(test)]
 pub(crate) mod tests;
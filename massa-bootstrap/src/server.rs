@@ -495,7 +495,13 @@ pub fn stream_bootstrap_information(
         let last_start_period;
         let last_slot_before_downtime;
 
-        let slot_too_old = false;
+        // The changes made since the client cursor must still be in our history to resume from it
+        if let Some(slot) = last_slot {
+            let slot_too_old = final_state.read().db.read().is_change_id_outdated(&slot);
+            if slot_too_old {
+                return server.send_msg(write_timeout, BootstrapServerMessage::SlotTooOld);
+            }
+        }
 
         // Scope of the final state read
         {
@@ -621,10 +627,6 @@ pub fn stream_bootstrap_information(
             send_last_start_period = false;
         }
 
-        if slot_too_old {
-            return server.send_msg(write_timeout, BootstrapServerMessage::SlotTooOld);
-        }
-
         // Setup final state global cursor
         let final_state_global_step =
             if last_state_step.finished() && last_versioning_step.finished() {
@@ -30,6 +30,8 @@ pub struct BootstrapConfig {
     pub bootstrap_whitelist_path: PathBuf,
     /// Path to the bootstrap blacklist file. This whitelist define IPs that will not be able to bootstrap on your node. This list is optional.
     pub bootstrap_blacklist_path: PathBuf,
+    /// Path of the file where the cursor of an ongoing bootstrap is saved, to resume it after a restart.
+    pub bootstrap_cursor_path: PathBuf,
     /// Port to listen if we choose to allow other nodes to use us as bootstrap node.
     pub listen_addr: Option<SocketAddr>,
     /// connection timeout
@@ -0,0 +1,627 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Offline snapshots of the final state.
+//!
+//! A snapshot file holds what a node would otherwise stream from a bootstrap server:
+//! the STATE_CF and VERSIONING_CF entries of the final state, the slot they are attached to,
+//! the hash of the state, and the consensus final blocks required to start at that slot.
+//!
+//! The consensus graph only lives in memory, so a node saves its final blocks to a local file
+//! on shutdown (`save_final_graph`). A snapshot can then be exported from the stopped node (`export_snapshot`),
+//! and imported by a new node in place of bootstrapping (`get_state_from_snapshot`).
+//! The importing node is given the expected state hash from a trusted source, and checks the snapshot against it
+//! before writing anything to its ledger.
+//!
+//! File layout:
+//! * magic bytes and format version
+//! * length of the header (u64, big endian) and header: node version, state hash and `FinalGraph`
+//! * records, each one starting with a tag byte: state entry, versioning entry, or end of file.
+//!   State and versioning entries are followed by their key and value, each prefixed by its length (u64, big endian)
+
+use crate::{
+    client::warn_user_about_versioning_updates, error::BootstrapError, BootstrapConfig,
+    GlobalBootstrapState,
+};
+use massa_consensus_exports::{
+    bootstrapable_graph::{
+        BootstrapableGraph, BootstrapableGraphDeserializer, BootstrapableGraphSerializer,
+    },
+    ConsensusController,
+};
+use massa_db_exports::{
+    Key, MassaDBController, MassaIteratorMode, StreamBatch, Value, STATE_CF,
+    STATE_HASH_INITIAL_BYTES, VERSIONING_CF,
+};
+use massa_final_state::{FinalState, FinalStateError};
+use massa_hash::{Hash, HashXof, HashXofDeserializer, HashXofSerializer, HASH_XOF_SIZE_BYTES};
+use massa_models::{
+    block::BlockDeserializerArgs,
+    slot::{Slot, SlotDeserializer, SlotSerializer},
+    streaming_step::StreamingStep,
+    version::{Version, VersionDeserializer, VersionSerializer},
+};
+use massa_serialization::{
+    DeserializeError, Deserializer, OptionDeserializer, OptionSerializer, SerializeError,
+    Serializer, U64VarIntDeserializer, U64VarIntSerializer,
+};
+use nom::{
+    error::{context, ContextError, ParseError},
+    sequence::tuple,
+    IResult, Parser,
+};
+use parking_lot::RwLock;
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    ops::Bound::{Excluded, Included},
+    path::Path,
+    sync::Arc,
+};
+use tracing::info;
+
+/// Magic bytes at the start of a snapshot file
+const SNAPSHOT_MAGIC: &[u8] = b"MASSA_STATE_SNAPSHOT";
+/// Version of the snapshot file format
+const SNAPSHOT_FORMAT_VERSION: u32 = 0;
+
+/// Record tags
+const STATE_ENTRY_TAG: u8 = 0;
+const VERSIONING_ENTRY_TAG: u8 = 1;
+const END_TAG: u8 = 2;
+
+/// Final blocks of the consensus graph, attached to a final state
+#[derive(Debug, Clone)]
+pub struct FinalGraph {
+    /// slot of the final state
+    pub slot: Slot,
+    /// last start period of the final state
+    pub last_start_period: u64,
+    /// last slot before downtime of the final state
+    pub last_slot_before_downtime: Option<Slot>,
+    /// final blocks required to start the consensus at that slot
+    pub graph: BootstrapableGraph,
+}
+
+/// Serializer for `FinalGraph`
+pub struct FinalGraphSerializer {
+    slot_serializer: SlotSerializer,
+    u64_serializer: U64VarIntSerializer,
+    opt_slot_serializer: OptionSerializer<Slot, SlotSerializer>,
+    graph_serializer: BootstrapableGraphSerializer,
+}
+
+impl Default for FinalGraphSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FinalGraphSerializer {
+    /// Creates a new `FinalGraphSerializer`
+    pub fn new() -> Self {
+        Self {
+            slot_serializer: SlotSerializer::new(),
+            u64_serializer: U64VarIntSerializer::new(),
+            opt_slot_serializer: OptionSerializer::new(SlotSerializer::new()),
+            graph_serializer: BootstrapableGraphSerializer::new(),
+        }
+    }
+}
+
+impl Serializer<FinalGraph> for FinalGraphSerializer {
+    fn serialize(&self, value: &FinalGraph, buffer: &mut Vec<u8>) -> Result<(), SerializeError> {
+        self.slot_serializer.serialize(&value.slot, buffer)?;
+        self.u64_serializer
+            .serialize(&value.last_start_period, buffer)?;
+        self.opt_slot_serializer
+            .serialize(&value.last_slot_before_downtime, buffer)?;
+        self.graph_serializer.serialize(&value.graph, buffer)?;
+        Ok(())
+    }
+}
+
+/// Deserializer for `FinalGraph`
+pub struct FinalGraphDeserializer {
+    slot_deserializer: SlotDeserializer,
+    u64_deserializer: U64VarIntDeserializer,
+    opt_slot_deserializer: OptionDeserializer<Slot, SlotDeserializer>,
+    graph_deserializer: BootstrapableGraphDeserializer,
+}
+
+impl FinalGraphDeserializer {
+    /// Creates a new `FinalGraphDeserializer`
+    pub fn new(config: &BootstrapConfig) -> Self {
+        let slot_deserializer = || {
+            SlotDeserializer::new(
+                (Included(0), Included(u64::MAX)),
+                (Included(0), Excluded(config.thread_count)),
+            )
+        };
+        Self {
+            slot_deserializer: slot_deserializer(),
+            u64_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+            opt_slot_deserializer: OptionDeserializer::new(slot_deserializer()),
+            graph_deserializer: BootstrapableGraphDeserializer::new(
+                BlockDeserializerArgs {
+                    thread_count: config.thread_count,
+                    max_operations_per_block: config.max_operations_per_block,
+                    endorsement_count: config.endorsement_count,
+                    max_denunciations_per_block_header: config.max_denunciations_per_block_header,
+                    last_start_period: None,
+                },
+                config.max_bootstrap_blocks_length,
+            ),
+        }
+    }
+}
+
+impl Deserializer<FinalGraph> for FinalGraphDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], FinalGraph, E> {
+        context(
+            "Failed FinalGraph deserialization",
+            tuple((
+                context("Failed slot deserialization", |input| {
+                    self.slot_deserializer.deserialize(input)
+                }),
+                context("Failed last_start_period deserialization", |input| {
+                    self.u64_deserializer.deserialize(input)
+                }),
+                context(
+                    "Failed last_slot_before_downtime deserialization",
+                    |input| self.opt_slot_deserializer.deserialize(input),
+                ),
+                context("Failed graph deserialization", |input| {
+                    self.graph_deserializer.deserialize(input)
+                }),
+            )),
+        )
+        .map(
+            |(slot, last_start_period, last_slot_before_downtime, graph)| FinalGraph {
+                slot,
+                last_start_period,
+                last_slot_before_downtime,
+                graph,
+            },
+        )
+        .parse(buffer)
+    }
+}
+
+/// Header of a snapshot file
+#[derive(Debug, Clone)]
+pub struct SnapshotHeader {
+    /// version of the node that exported the snapshot
+    pub version: Version,
+    /// hash of the final state
+    pub state_hash: HashXof<HASH_XOF_SIZE_BYTES>,
+    /// final blocks attached to the final state
+    pub final_graph: FinalGraph,
+}
+
+/// Serializer for `SnapshotHeader`
+pub struct SnapshotHeaderSerializer {
+    version_serializer: VersionSerializer,
+    hash_serializer: HashXofSerializer,
+    final_graph_serializer: FinalGraphSerializer,
+}
+
+impl Default for SnapshotHeaderSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SnapshotHeaderSerializer {
+    /// Creates a new `SnapshotHeaderSerializer`
+    pub fn new() -> Self {
+        Self {
+            version_serializer: VersionSerializer::new(),
+            hash_serializer: HashXofSerializer::new(),
+            final_graph_serializer: FinalGraphSerializer::new(),
+        }
+    }
+}
+
+impl Serializer<SnapshotHeader> for SnapshotHeaderSerializer {
+    fn serialize(
+        &self,
+        value: &SnapshotHeader,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        self.version_serializer.serialize(&value.version, buffer)?;
+        self.hash_serializer.serialize(&value.state_hash, buffer)?;
+        self.final_graph_serializer
+            .serialize(&value.final_graph, buffer)?;
+        Ok(())
+    }
+}
+
+/// Deserializer for `SnapshotHeader`
+pub struct SnapshotHeaderDeserializer {
+    version_deserializer: VersionDeserializer,
+    hash_deserializer: HashXofDeserializer,
+    final_graph_deserializer: FinalGraphDeserializer,
+}
+
+impl SnapshotHeaderDeserializer {
+    /// Creates a new `SnapshotHeaderDeserializer`
+    pub fn new(config: &BootstrapConfig) -> Self {
+        Self {
+            version_deserializer: VersionDeserializer::new(),
+            hash_deserializer: HashXofDeserializer::new(),
+            final_graph_deserializer: FinalGraphDeserializer::new(config),
+        }
+    }
+}
+
+impl Deserializer<SnapshotHeader> for SnapshotHeaderDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], SnapshotHeader, E> {
+        context(
+            "Failed SnapshotHeader deserialization",
+            tuple((
+                context("Failed version deserialization", |input| {
+                    self.version_deserializer.deserialize(input)
+                }),
+                context("Failed state_hash deserialization", |input| {
+                    self.hash_deserializer.deserialize(input)
+                }),
+                context("Failed final_graph deserialization", |input| {
+                    self.final_graph_deserializer.deserialize(input)
+                }),
+            )),
+        )
+        .map(|(version, state_hash, final_graph)| SnapshotHeader {
+            version,
+            state_hash,
+            final_graph,
+        })
+        .parse(buffer)
+    }
+}
+
+/// Save the final blocks of the consensus graph attached to the current final state.
+/// To be called on shutdown, once the final state is not updated anymore.
+pub fn save_final_graph(
+    path: &Path,
+    final_state: &FinalState,
+    consensus_controller: &dyn ConsensusController,
+) -> Result<(), BootstrapError> {
+    let slot = final_state.db.read().get_change_id()?;
+
+    let mut graph = BootstrapableGraph {
+        final_blocks: Vec::new(),
+    };
+    let mut cursor = StreamingStep::Started;
+    while !cursor.finished() {
+        let (part, _outdated_ids, new_cursor) =
+            consensus_controller.get_bootstrap_part(cursor, StreamingStep::Finished(Some(slot)))?;
+        graph.final_blocks.extend(part.final_blocks);
+        cursor = new_cursor;
+    }
+
+    let final_graph = FinalGraph {
+        slot,
+        last_start_period: final_state.last_start_period,
+        last_slot_before_downtime: final_state.last_slot_before_downtime,
+        graph,
+    };
+    let mut buffer = Vec::new();
+    FinalGraphSerializer::new().serialize(&final_graph, &mut buffer)?;
+    std::fs::write(path, buffer)?;
+    Ok(())
+}
+
+/// Load the final blocks saved by `save_final_graph`
+pub fn load_final_graph(
+    path: &Path,
+    config: &BootstrapConfig,
+) -> Result<FinalGraph, BootstrapError> {
+    let buffer = std::fs::read(path)?;
+    let (_, final_graph) = FinalGraphDeserializer::new(config)
+        .deserialize::<DeserializeError>(&buffer)
+        .map_err(|err| BootstrapError::DeserializeError(format!("invalid final graph: {}", err)))?;
+    Ok(final_graph)
+}
+
+fn write_entry(
+    writer: &mut impl Write,
+    tag: u8,
+    key: &[u8],
+    value: &[u8],
+) -> Result<(), BootstrapError> {
+    writer.write_all(&[tag])?;
+    writer.write_all(&(key.len() as u64).to_be_bytes())?;
+    writer.write_all(key)?;
+    writer.write_all(&(value.len() as u64).to_be_bytes())?;
+    writer.write_all(value)?;
+    Ok(())
+}
+
+fn read_u64(reader: &mut impl Read) -> Result<u64, BootstrapError> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_be_bytes(bytes))
+}
+
+/// Read a length-prefixed byte string, refusing lengths above `max_length`
+fn read_bytes(reader: &mut impl Read, max_length: u64) -> Result<Vec<u8>, BootstrapError> {
+    let length = read_u64(reader)?;
+    if length > max_length {
+        return Err(BootstrapError::DeserializeError(format!(
+            "snapshot record of {} bytes exceeds the maximum of {} bytes",
+            length, max_length
+        )));
+    }
+    let mut bytes = vec![0u8; length as usize];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Export the final state held by a database, with its final blocks, to a snapshot file
+pub fn export_snapshot(
+    db: &dyn MassaDBController,
+    final_graph: FinalGraph,
+    path: &Path,
+    version: Version,
+) -> Result<(), BootstrapError> {
+    let slot = db.get_change_id()?;
+    if final_graph.slot != slot {
+        return Err(BootstrapError::GeneralError(format!(
+            "the final blocks are attached to slot {} but the final state is at slot {}: was the node stopped properly?",
+            final_graph.slot, slot
+        )));
+    }
+
+    let state_hash = db.get_xof_db_hash();
+    let header = SnapshotHeader {
+        version,
+        state_hash,
+        final_graph,
+    };
+    let mut header_bytes = Vec::new();
+    SnapshotHeaderSerializer::new().serialize(&header, &mut header_bytes)?;
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(SNAPSHOT_MAGIC)?;
+    writer.write_all(&SNAPSHOT_FORMAT_VERSION.to_be_bytes())?;
+    writer.write_all(&(header_bytes.len() as u64).to_be_bytes())?;
+    writer.write_all(&header_bytes)?;
+    for (tag, handle_cf) in [
+        (STATE_ENTRY_TAG, STATE_CF),
+        (VERSIONING_ENTRY_TAG, VERSIONING_CF),
+    ] {
+        for (key, value) in db.iterator_cf(handle_cf, MassaIteratorMode::Start) {
+            write_entry(&mut writer, tag, &key, &value)?;
+        }
+    }
+    writer.write_all(&[END_TAG])?;
+    writer.flush()?;
+
+    info!(
+        "Exported the final state at slot {} to a snapshot, with state hash {}",
+        slot, state_hash
+    );
+    Ok(())
+}
+
+fn write_snapshot_entries(
+    db: &mut dyn MassaDBController,
+    state_entries: BTreeMap<Key, Value>,
+    versioning_entries: BTreeMap<Key, Value>,
+    slot: Slot,
+) -> Result<(), BootstrapError> {
+    db.write_batch_bootstrap_client(
+        StreamBatch {
+            new_elements: state_entries,
+            updates_on_previous_elements: BTreeMap::new(),
+            change_id: slot,
+        },
+        StreamBatch {
+            new_elements: versioning_entries,
+            updates_on_previous_elements: BTreeMap::new(),
+            change_id: slot,
+        },
+    )
+    .map_err(|e| {
+        BootstrapError::GeneralError(format!("Cannot write snapshot entries to disk: {}", e))
+    })?;
+    Ok(())
+}
+
+/// Open a snapshot file and read its header, leaving the reader at the first record
+fn open_snapshot(
+    path: &Path,
+    config: &BootstrapConfig,
+    our_version: Version,
+) -> Result<(BufReader<File>, SnapshotHeader), BootstrapError> {
+    let mut reader = BufReader::new(File::open(path)?);
+
+    let mut magic = [0u8; SNAPSHOT_MAGIC.len()];
+    reader.read_exact(&mut magic)?;
+    if magic != SNAPSHOT_MAGIC {
+        return Err(BootstrapError::GeneralError(format!(
+            "{} is not a snapshot file",
+            path.display()
+        )));
+    }
+    let mut format_version = [0u8; 4];
+    reader.read_exact(&mut format_version)?;
+    let format_version = u32::from_be_bytes(format_version);
+    if format_version != SNAPSHOT_FORMAT_VERSION {
+        return Err(BootstrapError::GeneralError(format!(
+            "unsupported snapshot format version {}",
+            format_version
+        )));
+    }
+
+    let header_length = read_u64(&mut reader)?;
+    let mut header_bytes = Vec::new();
+    reader
+        .by_ref()
+        .take(header_length)
+        .read_to_end(&mut header_bytes)?;
+    if header_bytes.len() as u64 != header_length {
+        return Err(BootstrapError::DeserializeError(
+            "truncated snapshot header".to_string(),
+        ));
+    }
+    let (_, header) = SnapshotHeaderDeserializer::new(config)
+        .deserialize::<DeserializeError>(&header_bytes)
+        .map_err(|err| {
+            BootstrapError::DeserializeError(format!("invalid snapshot header: {}", err))
+        })?;
+    if !our_version.is_compatible(&header.version) {
+        return Err(BootstrapError::IncompatibleVersionError(format!(
+            "snapshot was exported by an incompatible version: {} (local node version: {})",
+            header.version, our_version
+        )));
+    }
+
+    Ok((reader, header))
+}
+
+/// Read the records of a snapshot until its end, passing each entry to `on_entry` with its tag
+fn read_snapshot_entries(
+    reader: &mut impl Read,
+    config: &BootstrapConfig,
+    mut on_entry: impl FnMut(u8, Key, Value) -> Result<(), BootstrapError>,
+) -> Result<(), BootstrapError> {
+    loop {
+        let mut tag = [0u8; 1];
+        reader.read_exact(&mut tag)?;
+        match tag[0] {
+            STATE_ENTRY_TAG | VERSIONING_ENTRY_TAG => {
+                let key = read_bytes(reader, config.max_datastore_value_length)?;
+                let value = read_bytes(reader, config.max_datastore_value_length)?;
+                on_entry(tag[0], key, value)?;
+            }
+            END_TAG => return Ok(()),
+            other => {
+                return Err(BootstrapError::DeserializeError(format!(
+                    "invalid snapshot record tag {}",
+                    other
+                )))
+            }
+        }
+    }
+}
+
+/// Import a snapshot file into an empty database.
+///
+/// The state entries of the snapshot are hashed and checked against `expected_state_hash`,
+/// obtained out-of-band from a trusted source (e.g. the `final_state hash` logs of a synced node),
+/// before anything is written to the database: the hash announced by the snapshot itself is not trusted.
+///
+/// Returns the header of the snapshot
+pub fn import_snapshot(
+    db: &mut dyn MassaDBController,
+    path: &Path,
+    config: &BootstrapConfig,
+    our_version: Version,
+    expected_state_hash: &Hash,
+) -> Result<SnapshotHeader, BootstrapError> {
+    // first pass: check the state entries against the expected hash
+    let (mut reader, header) = open_snapshot(path, config, our_version)?;
+    let mut state_hash = HashXof(*STATE_HASH_INITIAL_BYTES);
+    read_snapshot_entries(&mut reader, config, |tag, key, value| {
+        if tag == STATE_ENTRY_TAG {
+            state_hash ^= HashXof::compute_from_tuple(&[key.as_slice(), value.as_slice()]);
+        }
+        Ok(())
+    })?;
+    if Hash::compute_from(state_hash.to_bytes()) != *expected_state_hash {
+        return Err(BootstrapError::GeneralError(format!(
+            "snapshot state hash mismatch: expected {} but the snapshot entries hash to {}",
+            expected_state_hash, state_hash
+        )));
+    }
+    if state_hash != header.state_hash {
+        return Err(BootstrapError::GeneralError(format!(
+            "snapshot state hash mismatch: the snapshot announces {} but its entries hash to {}",
+            header.state_hash, state_hash
+        )));
+    }
+
+    // second pass: write the verified entries
+    let (mut reader, header) = open_snapshot(path, config, our_version)?;
+    let slot = header.final_graph.slot;
+    let mut state_entries = BTreeMap::new();
+    let mut versioning_entries = BTreeMap::new();
+    read_snapshot_entries(&mut reader, config, |tag, key, value| {
+        if tag == STATE_ENTRY_TAG {
+            state_entries.insert(key, value);
+        } else {
+            versioning_entries.insert(key, value);
+        }
+        if (state_entries.len() + versioning_entries.len()) as u64 >= config.max_new_elements {
+            write_snapshot_entries(
+                db,
+                std::mem::take(&mut state_entries),
+                std::mem::take(&mut versioning_entries),
+                slot,
+            )?;
+        }
+        Ok(())
+    })?;
+    write_snapshot_entries(db, state_entries, versioning_entries, slot)?;
+
+    // the file may have changed between the two passes
+    let written_state_hash = db.get_xof_db_hash();
+    if written_state_hash != state_hash {
+        return Err(BootstrapError::GeneralError(format!(
+            "snapshot changed during its import: its entries hash to {} instead of {}",
+            written_state_hash, state_hash
+        )));
+    }
+
+    Ok(header)
+}
+
+/// Get the state from a snapshot file instead of a bootstrap server.
+/// The final state must be empty, and `expected_state_hash` must come from a trusted source.
+pub fn get_state_from_snapshot(
+    bootstrap_config: &BootstrapConfig,
+    final_state: Arc<RwLock<FinalState>>,
+    path: &Path,
+    our_version: Version,
+    expected_state_hash: &Hash,
+) -> Result<GlobalBootstrapState, BootstrapError> {
+    let header = {
+        let mut final_state_guard = final_state.write();
+        let header = import_snapshot(
+            &mut **final_state_guard.db.write(),
+            path,
+            bootstrap_config,
+            our_version,
+            expected_state_hash,
+        )?;
+        final_state_guard.last_start_period = header.final_graph.last_start_period;
+        final_state_guard.last_slot_before_downtime = header.final_graph.last_slot_before_downtime;
+
+        // Update MIP store by reading from the disk
+        let db = final_state_guard.db.clone();
+        let (updated, added) = final_state_guard
+            .mip_store
+            .extend_from_db(db)
+            .map_err(|e| BootstrapError::from(FinalStateError::from(e)))?;
+        warn_user_about_versioning_updates(updated, added);
+        header
+    };
+
+    info!(
+        "State imported from snapshot at slot {}",
+        header.final_graph.slot
+    );
+    Ok(GlobalBootstrapState {
+        final_state,
+        graph: Some(header.final_graph.graph),
+        peers: None,
+    })
+}
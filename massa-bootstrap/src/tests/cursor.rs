@@ -0,0 +1,178 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use super::tools::{get_bootstrap_config, get_dummy_block_id};
+use crate::client::{
+    initial_bootstrap_message, load_bootstrap_cursor, save_bootstrap_cursor, SlotTooOldServers,
+};
+use crate::messages::BootstrapClientMessage;
+use crate::BootstrapConfig;
+use massa_db_exports::{
+    DBBatch, MassaDBConfig, MassaDBController, MassaIteratorMode, ShareableMassaDBController,
+    LEDGER_PREFIX, STATE_CF,
+};
+use massa_db_worker::MassaDB;
+use massa_final_state::{FinalState, FinalStateConfig};
+use massa_models::{node::NodeId, prehash::PreHashSet, slot::Slot, streaming_step::StreamingStep};
+use massa_pos_exports::{MockSelectorController, PoSFinalState};
+use massa_signature::KeyPair;
+use parking_lot::RwLock;
+use std::{path::PathBuf, str::FromStr, sync::Arc};
+use tempfile::TempDir;
+
+fn get_node_id() -> NodeId {
+    NodeId::new(KeyPair::generate(0).unwrap().get_public_key())
+}
+
+/// Bootstrap config saving its cursor in `temp_dir`
+fn get_cursor_config(temp_dir: &TempDir) -> BootstrapConfig {
+    BootstrapConfig {
+        bootstrap_cursor_path: temp_dir.path().join("bootstrap_cursor"),
+        ..get_bootstrap_config(get_node_id())
+    }
+}
+
+/// Final state whose database holds a ledger entry and is attached to `slot`
+fn get_final_state(temp_dir: &TempDir, slot: Slot) -> Arc<RwLock<FinalState>> {
+    let config = FinalStateConfig::default();
+    let db: ShareableMassaDBController =
+        Arc::new(RwLock::new(Box::new(MassaDB::new(MassaDBConfig {
+            path: temp_dir.path().join("db"),
+            max_history_length: 10,
+            max_new_elements: 100,
+            thread_count: config.thread_count,
+            enable_state_tree: false,
+        }))));
+    let mut selector = MockSelectorController::new();
+    selector
+        .expect_clone_box()
+        .returning(|| Box::new(MockSelectorController::new()));
+    let pos_state = PoSFinalState::new(
+        config.pos_config.clone(),
+        "",
+        &PathBuf::from_str("../massa-node/base_config/initial_rolls.json").unwrap(),
+        Box::new(selector),
+        db.clone(),
+    )
+    .unwrap();
+
+    let mut batch = DBBatch::new();
+    batch.insert(
+        format!("{}entry", LEDGER_PREFIX).into_bytes(),
+        Some(b"value".to_vec()),
+    );
+    db.write().write_batch(batch, DBBatch::new(), Some(slot));
+
+    Arc::new(RwLock::new(FinalState::create_final_state(
+        pos_state, config, db,
+    )))
+}
+
+/// Cursor of a bootstrap interrupted after receiving the state at `last_slot`
+fn get_cursor(last_slot: Slot) -> BootstrapClientMessage {
+    BootstrapClientMessage::AskBootstrapPart {
+        last_slot: Some(last_slot),
+        last_state_step: StreamingStep::Ongoing(b"state_key".to_vec()),
+        last_versioning_step: StreamingStep::Started,
+        last_consensus_step: StreamingStep::Ongoing(PreHashSet::from_iter([get_dummy_block_id(
+            "block",
+        )])),
+        send_last_start_period: false,
+    }
+}
+
+fn is_empty(final_state: &Arc<RwLock<FinalState>>) -> bool {
+    final_state
+        .read()
+        .db
+        .read()
+        .iterator_cf(STATE_CF, MassaIteratorMode::Start)
+        .next()
+        .is_none()
+}
+
+#[test]
+fn test_bootstrap_cursor_resume() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = get_cursor_config(&temp_dir);
+    let final_state = get_final_state(&temp_dir, Slot::new(5, 1));
+
+    // nothing to resume without a cursor
+    assert!(load_bootstrap_cursor(&config, &final_state).is_none());
+    assert!(!is_empty(&final_state));
+
+    // the db is written before the cursor: it can be ahead of it
+    save_bootstrap_cursor(&config, &get_cursor(Slot::new(4, 0))).unwrap();
+    match load_bootstrap_cursor(&config, &final_state) {
+        Some(BootstrapClientMessage::AskBootstrapPart {
+            last_slot,
+            last_state_step,
+            last_versioning_step,
+            last_consensus_step,
+            send_last_start_period,
+        }) => {
+            assert_eq!(last_slot, Some(Slot::new(4, 0)));
+            assert_eq!(
+                last_state_step,
+                StreamingStep::Ongoing(b"state_key".to_vec())
+            );
+            assert_eq!(last_versioning_step, StreamingStep::Started);
+            // the consensus blocks and the start period were lost with the previous run
+            assert_eq!(last_consensus_step, StreamingStep::Started);
+            assert!(send_last_start_period);
+        }
+        other => panic!("unexpected resumed message: {:?}", other),
+    }
+    assert!(!is_empty(&final_state));
+    assert!(config.bootstrap_cursor_path.exists());
+}
+
+#[test]
+fn test_bootstrap_cursor_unusable() {
+    // a cursor ahead of the db does not match the bootstrapped state
+    let temp_dir = TempDir::new().unwrap();
+    let config = get_cursor_config(&temp_dir);
+    let final_state = get_final_state(&temp_dir, Slot::new(3, 0));
+    save_bootstrap_cursor(&config, &get_cursor(Slot::new(4, 0))).unwrap();
+    assert!(load_bootstrap_cursor(&config, &final_state).is_none());
+    assert!(is_empty(&final_state));
+    assert!(!config.bootstrap_cursor_path.exists());
+
+    // a cursor that is not an ongoing bootstrap part request cannot be resumed
+    let temp_dir = TempDir::new().unwrap();
+    let config = get_cursor_config(&temp_dir);
+    let final_state = get_final_state(&temp_dir, Slot::new(5, 1));
+    save_bootstrap_cursor(&config, &initial_bootstrap_message()).unwrap();
+    assert!(load_bootstrap_cursor(&config, &final_state).is_none());
+    assert!(is_empty(&final_state));
+    assert!(!config.bootstrap_cursor_path.exists());
+
+    // a corrupted cursor is dropped with the state bootstrapped so far
+    let temp_dir = TempDir::new().unwrap();
+    let config = get_cursor_config(&temp_dir);
+    let final_state = get_final_state(&temp_dir, Slot::new(5, 1));
+    std::fs::write(&config.bootstrap_cursor_path, b"corrupted").unwrap();
+    assert!(load_bootstrap_cursor(&config, &final_state).is_none());
+    assert!(is_empty(&final_state));
+    assert!(!config.bootstrap_cursor_path.exists());
+}
+
+#[test]
+fn test_slot_too_old_servers() {
+    let servers = [get_node_id(), get_node_id(), get_node_id()];
+    let mut slot_too_old_servers = SlotTooOldServers::default();
+    let cursor_slot = Some(Slot::new(4, 0));
+
+    // fail over to the other servers while some of them may still resume from the cursor
+    assert!(!slot_too_old_servers.insert(cursor_slot, servers[0], servers.len()));
+    assert!(!slot_too_old_servers.insert(cursor_slot, servers[0], servers.len()));
+    assert!(!slot_too_old_servers.insert(cursor_slot, servers[1], servers.len()));
+
+    // the cursor moved forward thanks to another server: the previous answers are forgotten
+    let cursor_slot = Some(Slot::new(6, 0));
+    assert!(!slot_too_old_servers.insert(cursor_slot, servers[0], servers.len()));
+    assert!(!slot_too_old_servers.insert(cursor_slot, servers[1], servers.len()));
+
+    // restart from scratch once no server can resume from the cursor
+    assert!(slot_too_old_servers.insert(cursor_slot, servers[2], servers.len()));
+    assert!(!slot_too_old_servers.insert(None, servers[0], servers.len()));
+}
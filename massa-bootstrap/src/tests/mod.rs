@@ -1,5 +1,7 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 mod binders;
+mod cursor;
 mod scenarios;
+mod snapshot;
 pub(crate) mod tools;
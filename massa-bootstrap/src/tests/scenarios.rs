@@ -105,6 +105,7 @@ fn mock_bootstrap_manager(
         max_history_length: 10,
         max_new_elements: 100,
         thread_count: 2,
+        enable_state_tree: false,
     };
     let db = Arc::new(RwLock::new(
         Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
@@ -239,6 +240,7 @@ fn test_bootstrap_server() {
         max_history_length: 10,
         max_new_elements: 100,
         thread_count,
+        enable_state_tree: false,
     };
     let db_server = Arc::new(RwLock::new(
         Box::new(MassaDB::new(db_server_config)) as Box<(dyn MassaDBController + 'static)>
@@ -249,6 +251,7 @@ fn test_bootstrap_server() {
         max_history_length: 10,
         max_new_elements: 100,
         thread_count,
+        enable_state_tree: false,
     };
     let db_client = Arc::new(RwLock::new(
         Box::new(MassaDB::new(db_client_config)) as Box<(dyn MassaDBController + 'static)>
@@ -632,6 +635,7 @@ fn test_bootstrap_accept_err() {
         max_history_length: 10,
         max_new_elements: 100,
         thread_count,
+        enable_state_tree: false,
     };
     let db_server = Arc::new(RwLock::new(
         Box::new(MassaDB::new(db_server_config)) as Box<(dyn MassaDBController + 'static)>
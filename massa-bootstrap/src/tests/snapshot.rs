@@ -0,0 +1,136 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use super::tools::{assert_eq_bootstrap_graph, get_boot_state, get_bootstrap_config};
+use crate::snapshot::{export_snapshot, import_snapshot, FinalGraph};
+use massa_db_exports::{
+    DBBatch, MassaDBConfig, MassaDBController, MassaIteratorMode, STATE_CF, VERSIONING_CF,
+};
+use massa_db_worker::MassaDB;
+use massa_hash::Hash;
+use massa_models::{config::THREAD_COUNT, node::NodeId, slot::Slot, version::Version};
+use massa_signature::KeyPair;
+use std::str::FromStr;
+use tempfile::TempDir;
+
+fn new_db(temp_dir: &TempDir) -> MassaDB {
+    MassaDB::new(MassaDBConfig {
+        path: temp_dir.path().to_path_buf(),
+        max_history_length: 10,
+        max_new_elements: 3,
+        thread_count: THREAD_COUNT,
+        enable_state_tree: false,
+    })
+}
+
+fn assert_is_empty(db: &MassaDB) {
+    for handle_cf in [STATE_CF, VERSIONING_CF] {
+        assert_eq!(
+            db.iterator_cf(handle_cf, MassaIteratorMode::Start).count(),
+            0
+        );
+    }
+}
+
+#[test]
+fn test_snapshot_export_import() {
+    let bootstrap_config =
+        get_bootstrap_config(NodeId::new(KeyPair::generate(0).unwrap().get_public_key()));
+    let version = Version::from_str("TEST.26.1").unwrap();
+    let slot = Slot::new(5, 1);
+
+    // fill a database
+    let temp_dir_export = TempDir::new().unwrap();
+    let mut db_export = new_db(&temp_dir_export);
+    let mut batch = DBBatch::new();
+    let mut versioning_batch = DBBatch::new();
+    for index in 0..10u8 {
+        batch.insert(
+            format!("state_key_{}", index).into_bytes(),
+            Some(format!("state_value_{}", index).into_bytes()),
+        );
+        versioning_batch.insert(vec![index], Some(vec![index; 4]));
+    }
+    db_export.write_batch(batch, versioning_batch, Some(slot));
+
+    let final_graph = FinalGraph {
+        slot,
+        last_start_period: 3,
+        last_slot_before_downtime: Some(Slot::new(2, 0)),
+        graph: get_boot_state(),
+    };
+    let temp_dir_snapshot = TempDir::new().unwrap();
+    let snapshot_path = temp_dir_snapshot.path().join("snapshot");
+
+    // the final blocks must be attached to the final state
+    let stale_final_graph = FinalGraph {
+        slot: Slot::new(4, 0),
+        ..final_graph.clone()
+    };
+    assert!(export_snapshot(&db_export, stale_final_graph, &snapshot_path, version).is_err());
+
+    export_snapshot(&db_export, final_graph.clone(), &snapshot_path, version).unwrap();
+
+    // the snapshot is refused before anything is written if its state hash is not the expected one
+    let expected_state_hash = Hash::compute_from(db_export.get_xof_db_hash().to_bytes());
+    let temp_dir_import = TempDir::new().unwrap();
+    let mut db_import = new_db(&temp_dir_import);
+    assert!(import_snapshot(
+        &mut db_import,
+        &snapshot_path,
+        &bootstrap_config,
+        version,
+        &Hash::compute_from(b"another state"),
+    )
+    .is_err());
+    assert_is_empty(&db_import);
+
+    // import the snapshot in an empty database
+    let header = import_snapshot(
+        &mut db_import,
+        &snapshot_path,
+        &bootstrap_config,
+        version,
+        &expected_state_hash,
+    )
+    .unwrap();
+    assert_eq!(header.version, version);
+    assert_eq!(header.final_graph.slot, slot);
+    assert_eq!(header.final_graph.last_start_period, 3);
+    assert_eq!(
+        header.final_graph.last_slot_before_downtime,
+        Some(Slot::new(2, 0))
+    );
+    assert_eq_bootstrap_graph(&header.final_graph.graph, &final_graph.graph);
+    assert_eq!(db_import.get_change_id().unwrap(), slot);
+    assert_eq!(db_import.get_xof_db_hash(), db_export.get_xof_db_hash());
+    for handle_cf in [STATE_CF, VERSIONING_CF] {
+        assert_eq!(
+            db_import
+                .iterator_cf(handle_cf, MassaIteratorMode::Start)
+                .collect::<Vec<_>>(),
+            db_export
+                .iterator_cf(handle_cf, MassaIteratorMode::Start)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    // a tampered state entry is detected by the hash check
+    let mut content = std::fs::read(&snapshot_path).unwrap();
+    let position = content
+        .windows(13)
+        .position(|window| window == b"state_value_4")
+        .unwrap();
+    content[position + 12] = b'5';
+    std::fs::write(&snapshot_path, content).unwrap();
+    let temp_dir_tampered = TempDir::new().unwrap();
+    let mut db_tampered = new_db(&temp_dir_tampered);
+    assert!(import_snapshot(
+        &mut db_tampered,
+        &snapshot_path,
+        &bootstrap_config,
+        version,
+        &expected_state_hash,
+    )
+    .is_err());
+    assert_is_empty(&db_tampered);
+}
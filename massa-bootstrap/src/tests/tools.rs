@@ -405,6 +405,8 @@ pub fn get_bootstrap_config(bootstrap_public_key: NodeId) -> BootstrapConfig {
         bootstrap_blacklist_path: PathBuf::from(
             "../massa-node/base_config/bootstrap_blacklist.json",
         ),
+        bootstrap_cursor_path: std::env::temp_dir()
+            .join(format!("bootstrap_cursor_{}", bootstrap_public_key)),
         max_clock_delta: MassaTime::from_millis(1000),
         cache_duration: MassaTime::from_millis(10000),
         max_simultaneous_bootstraps: 2,
@@ -1,13 +1,15 @@
 use std::{
     borrow::Cow,
-    collections::HashSet,
-    net::{IpAddr, SocketAddr},
+    collections::BTreeMap,
+    net::SocketAddr,
     path::{Path, PathBuf},
     sync::Arc,
 };
 
 use crate::error::BootstrapError;
 use massa_logging::massa_trace;
+use massa_models::ip_list::{IpListEntry, IpNetwork};
+use massa_time::MassaTime;
 use parking_lot::RwLock;
 use tracing::{info, warn};
 
@@ -35,71 +37,73 @@ impl SharedWhiteBlackList<'_> {
         })
     }
 
-    /// get the white list
-    pub fn get_white_list(&self) -> Option<HashSet<IpAddr>> {
-        self.inner.read().white_list.clone()
+    /// get the entries of the white list that did not expire
+    pub fn get_white_list(&self) -> Option<Vec<IpListEntry>> {
+        let now = MassaTime::now().ok()?;
+        self.inner
+            .read()
+            .white_list
+            .as_ref()
+            .map(|list| active_entries(list, now).cloned().collect())
     }
 
-    /// get the black list
-    pub fn get_black_list(&self) -> Option<HashSet<IpAddr>> {
-        self.inner.read().black_list.clone()
+    /// get the entries of the black list that did not expire
+    pub fn get_black_list(&self) -> Option<Vec<IpListEntry>> {
+        let now = MassaTime::now().ok()?;
+        self.inner
+            .read()
+            .black_list
+            .as_ref()
+            .map(|list| active_entries(list, now).cloned().collect())
     }
 
-    /// Add IP address to the black list
-    pub fn add_ips_to_blacklist(&self, ips: Vec<IpAddr>) -> Result<(), BootstrapError> {
+    /// Add entries to the black list. An entry replaces the previous one with the same network range.
+    pub fn add_to_blacklist(&self, entries: Vec<IpListEntry>) -> Result<(), BootstrapError> {
         let mut write_lock = self.inner.write();
-        if let Some(black_list) = &mut write_lock.black_list {
-            black_list.extend(ips);
-        } else {
-            write_lock.black_list = Some(HashSet::from_iter(ips));
-        };
-        self.write_to_file(&self.black_path, write_lock.black_list.as_ref().unwrap())?;
+        let black_list = write_lock.black_list.get_or_insert_with(BTreeMap::new);
+        black_list.extend(entries.into_iter().map(|entry| (entry.network, entry)));
+        self.write_to_file(&self.black_path, black_list)?;
         Ok(())
     }
 
-    /// Remove IPs address from the black list
-    pub fn remove_ips_from_blacklist(&self, ips: Vec<IpAddr>) -> Result<(), BootstrapError> {
+    /// Remove network ranges from the black list
+    pub fn remove_from_blacklist(&self, networks: Vec<IpNetwork>) -> Result<(), BootstrapError> {
         let mut write_lock = self.inner.write();
         if let Some(black_list) = &mut write_lock.black_list {
-            for ip in ips {
-                black_list.remove(&ip);
+            for network in networks {
+                black_list.remove(&network);
             }
             self.write_to_file(&self.black_path, black_list)?;
         }
         Ok(())
     }
 
-    /// Add IP address to the white list
-    pub fn add_ips_to_whitelist(&self, ips: Vec<IpAddr>) -> Result<(), BootstrapError> {
+    /// Add entries to the white list. An entry replaces the previous one with the same network range.
+    pub fn add_to_whitelist(&self, entries: Vec<IpListEntry>) -> Result<(), BootstrapError> {
         let mut write_lock = self.inner.write();
-        if let Some(white_list) = &mut write_lock.white_list {
-            white_list.extend(ips);
-        } else {
-            write_lock.white_list = Some(HashSet::from_iter(ips));
-        };
-        self.write_to_file(&self.white_path, write_lock.white_list.as_ref().unwrap())?;
+        let white_list = write_lock.white_list.get_or_insert_with(BTreeMap::new);
+        white_list.extend(entries.into_iter().map(|entry| (entry.network, entry)));
+        self.write_to_file(&self.white_path, white_list)?;
         Ok(())
     }
 
-    /// Remove IPs address from the white list
-    pub fn remove_ips_from_whitelist(&self, ips: Vec<IpAddr>) -> Result<(), BootstrapError> {
+    /// Remove network ranges from the white list
+    pub fn remove_from_whitelist(&self, networks: Vec<IpNetwork>) -> Result<(), BootstrapError> {
         let mut write_lock = self.inner.write();
         if let Some(white_list) = &mut write_lock.white_list {
-            for ip in ips {
-                white_list.remove(&ip);
+            for network in networks {
+                white_list.remove(&network);
             }
             self.write_to_file(&self.white_path, white_list)?;
         }
         Ok(())
     }
 
-    /// write list to file
-    fn write_to_file(
-        &self,
-        file_path: &Path,
-        data: &HashSet<IpAddr>,
-    ) -> Result<(), BootstrapError> {
-        let list = serde_json::to_string(data).map_err(|e| {
+    /// write list to file, without the expired entries
+    fn write_to_file(&self, file_path: &Path, data: &IpList) -> Result<(), BootstrapError> {
+        let now = MassaTime::now()?;
+        let entries: Vec<&IpListEntry> = active_entries(data, now).collect();
+        let list = serde_json::to_string_pretty(&entries).map_err(|e| {
             warn!(error = ?e, "failed to serialize list");
             BootstrapError::SerializationError(e.to_string())
         })?;
@@ -143,17 +147,20 @@ impl SharedWhiteBlackList<'_> {
         return Ok(());
 
         let ip = to_canonical(remote_addr.ip());
+        let now = MassaTime::now()?;
         // whether the peer IP address is blacklisted
         let read = self.inner.read();
         if let Some(ip_list) = &read.black_list {
-            if ip_list.contains(&ip) {
-                massa_trace!("bootstrap.lib.run.select.accept.refuse_blacklisted", {"remote_addr": remote_addr});
+            if let Some(entry) =
+                active_entries(ip_list, now).find(|entry| entry.network.contains(&ip))
+            {
+                massa_trace!("bootstrap.lib.run.select.accept.refuse_blacklisted", {"remote_addr": remote_addr, "reason": entry.reason});
                 return Err(BootstrapError::BlackListed(ip.to_string()));
             }
             // whether the peer IP address is not present in the whitelist
         }
         if let Some(ip_list) = &read.white_list {
-            if !ip_list.contains(&ip) {
+            if !active_entries(ip_list, now).any(|entry| entry.network.contains(&ip)) {
                 massa_trace!("bootstrap.lib.run.select.accept.refuse_not_whitelisted", {"remote_addr": remote_addr});
                 return Err(BootstrapError::WhiteListed(ip.to_string()));
             }
@@ -167,7 +174,7 @@ impl WhiteBlackListInner {
     fn update_list(
         whitelist_path: &Path,
         blacklist_path: &Path,
-    ) -> Result<(Option<HashSet<IpAddr>>, Option<HashSet<IpAddr>>), BootstrapError> {
+    ) -> Result<(Option<IpList>, Option<IpList>), BootstrapError> {
         Ok((
             Self::load_list(whitelist_path, false)?,
             Self::load_list(blacklist_path, false)?,
@@ -178,17 +185,14 @@ impl WhiteBlackListInner {
     fn init_list(
         whitelist_path: &Path,
         blacklist_path: &Path,
-    ) -> Result<(Option<HashSet<IpAddr>>, Option<HashSet<IpAddr>>), BootstrapError> {
+    ) -> Result<(Option<IpList>, Option<IpList>), BootstrapError> {
         Ok((
             Self::load_list(whitelist_path, true)?,
             Self::load_list(blacklist_path, true)?,
         ))
     }
 
-    fn load_list(
-        list_path: &Path,
-        is_init: bool,
-    ) -> Result<Option<HashSet<IpAddr>>, BootstrapError> {
+    fn load_list(list_path: &Path, is_init: bool) -> Result<Option<IpList>, BootstrapError> {
         match std::fs::read_to_string(list_path) {
             Err(e) => {
                 if is_init {
@@ -202,7 +206,7 @@ impl WhiteBlackListInner {
             }
             Ok(list) => {
                 let res = Some(
-                    serde_json::from_str::<HashSet<IpAddr>>(list.as_str())
+                    serde_json::from_str::<Vec<IpListEntry>>(list.as_str())
                         .map_err(|e| {
                             BootstrapError::InitListError(format!(
                                 "Failed to parse bootstrap whitelist : {}",
@@ -210,7 +214,7 @@ impl WhiteBlackListInner {
                             ))
                         })?
                         .into_iter()
-                        .map(to_canonical)
+                        .map(|entry| (entry.network, entry))
                         .collect(),
                 );
                 Ok(res)
@@ -219,8 +223,16 @@ impl WhiteBlackListInner {
     }
 }
 
+/// Entries of a white/black list, by network range
+type IpList = BTreeMap<IpNetwork, IpListEntry>;
+
+/// Iterates over the entries of a list that did not expire at time `now`
+fn active_entries(list: &IpList, now: MassaTime) -> impl Iterator<Item = &IpListEntry> {
+    list.values().filter(move |entry| !entry.is_expired(now))
+}
+
 #[derive(Default)]
 pub(crate) struct WhiteBlackListInner {
-    white_list: Option<HashSet<IpAddr>>,
-    black_list: Option<HashSet<IpAddr>>,
+    white_list: Option<IpList>,
+    black_list: Option<IpList>,
 }
@@ -7,9 +7,11 @@ use console::style;
 use massa_api_exports::{
     address::{AddressInfo, CompactAddressInfo},
     datastore::DatastoreEntryInput,
-    execution::{ReadOnlyBytecodeExecution, ReadOnlyCall},
+    execution::{GasEstimationRequest, ReadOnlyBytecodeExecution, ReadOnlyCall},
     operation::OperationInput,
+    page::PageRequest,
 };
+use massa_models::ip_list::{IpListEntry, IpNetwork};
 use massa_models::node::NodeId;
 use massa_models::prehash::PreHashMap;
 use massa_models::timeslots::get_current_latest_block_slot;
@@ -18,7 +20,7 @@ use massa_models::{
     amount::Amount,
     block_id::BlockId,
     endorsement::EndorsementId,
-    execution::EventFilter,
+    execution::{AsyncMessageFilter, EventFilter},
     operation::{Operation, OperationId, OperationType},
     slot::Slot,
 };
@@ -81,11 +83,21 @@ pub enum Command {
 
     #[strum(
         ascii_case_insensitive,
-        props(args = "Id1 Id2 ...", pwd_not_needed = "true"),
-        message = "ban given id(s)"
+        props(
+            args = "Id1 Id2 ... duration=seconds reason=text",
+            pwd_not_needed = "true"
+        ),
+        message = "ban given id(s), optionally for a given duration in seconds and with a reason taking the rest of the line"
     )]
     node_ban_by_id,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the connected peers with their traffic, message counts and connection statistics"
+    )]
+    node_peers,
+
     #[strum(
         ascii_case_insensitive,
         props(pwd_not_needed = "true"),
@@ -123,15 +135,21 @@ pub enum Command {
 
     #[strum(
         ascii_case_insensitive,
-        props(args = "(add, remove or allow-all) [IpAddr]", pwd_not_needed = "true"),
-        message = "Manage bootstrap whitelist IP address(es). No args returns the bootstrap whitelist"
+        props(
+            args = "(add, remove or allow-all) [IpAddr or CIDR range] duration=seconds reason=text",
+            pwd_not_needed = "true"
+        ),
+        message = "Manage bootstrap whitelist IP address(es) and range(s), optionally expiring after a duration. No args returns the bootstrap whitelist"
     )]
     node_bootstrap_whitelist,
 
     #[strum(
         ascii_case_insensitive,
-        props(args = "(add or remove) [IpAddr]", pwd_not_needed = "true"),
-        message = "Manage bootstrap blacklist IP address(es). No args returns the bootstrap blacklist"
+        props(
+            args = "(add or remove) [IpAddr or CIDR range] duration=seconds reason=text",
+            pwd_not_needed = "true"
+        ),
+        message = "Manage bootstrap blacklist IP address(es) and range(s), optionally expiring after a duration. No args returns the bootstrap blacklist"
     )]
     node_bootstrap_blacklist,
 
@@ -149,6 +167,13 @@ pub enum Command {
     )]
     get_status,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "[TargetDelay]", pwd_not_needed = "true"),
+        message = "show the fee statistics of the operation pool per thread, with the fee recommended for an operation to be included within TargetDelay periods (default: 1)"
+    )]
+    get_fee_estimate,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "Address1 Address2 ...", pwd_not_needed = "true"),
@@ -156,6 +181,23 @@ pub enum Command {
     )]
     get_addresses,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Address limit=usize offset=usize", pwd_not_needed = "true"),
+        message = "show the final executed operations involving an address, most recent first (offset is a page index)"
+    )]
+    get_address_history,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "sender=Address destination=Address emission_slot=slot_period,slot_thread emission_index=u64 limit=usize offset=usize",
+            pwd_not_needed = "true"
+        ),
+        message = "show the pending asynchronous messages matching the filters, in expected execution order (offset is a page index)"
+    )]
+    get_async_messages,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "Address Key", pwd_not_needed = "true"),
@@ -187,7 +229,7 @@ pub enum Command {
     #[strum(
         ascii_case_insensitive,
         props(
-            args = "start=slot_period,slot_thread end=slot_period,slot_thread emitter_address=Address caller_address=Address operation_id=OperationId is_final=bool is_error=bool",
+            args = "start=slot_period,slot_thread end=slot_period,slot_thread emitter_address=Address caller_address=Address operation_id=OperationId is_final=bool is_error=bool offset=usize limit=usize",
             pwd_not_needed = "true"
         ),
         message = "show events emitted by smart contracts with various filters"
@@ -266,14 +308,14 @@ pub enum Command {
     #[strum(
         ascii_case_insensitive,
         props(args = "SenderAddress PathToBytecode MaxGas MaxCoins Fee"),
-        message = "create and send an operation containing byte code"
+        message = "create and send an operation containing byte code, MaxGas can be set to auto to use the gas estimated by the node"
     )]
     execute_smart_contract,
 
     #[strum(
         ascii_case_insensitive,
         props(args = "SenderAddress TargetAddress FunctionName Parameter MaxGas Coins Fee"),
-        message = "create and send an operation to call a function of a smart contract"
+        message = "create and send an operation to call a function of a smart contract, MaxGas can be set to auto to use the gas estimated by the node"
     )]
     call_smart_contract,
 
@@ -504,8 +546,9 @@ impl Command {
             }
 
             Command::node_ban_by_id => {
-                let ids = parse_vec::<NodeId>(parameters)?;
-                match client.private.node_ban_by_id(ids).await {
+                let (ids, duration, reason) = split_duration_and_reason(parameters)?;
+                let ids = parse_vec::<NodeId>(&ids)?;
+                match client.private.node_ban_by_id(ids, reason, duration).await {
                     Ok(()) => {
                         if !json {
                             println!("Request of banning successfully sent!")
@@ -516,6 +559,11 @@ impl Command {
                 Ok(Box::new(()))
             }
 
+            Command::node_peers => match client.private.get_peers().await {
+                Ok(peers) => Ok(Box::new(peers)),
+                Err(e) => rpc_error!(e),
+            },
+
             Command::node_stop => {
                 match client.private.stop_node().await {
                     Ok(()) => {
@@ -573,6 +621,20 @@ impl Command {
                 Err(e) => rpc_error!(e),
             },
 
+            Command::get_fee_estimate => {
+                if parameters.len() > 1 {
+                    bail!("wrong number of parameters");
+                }
+                let target_delay = match parameters.first() {
+                    Some(target_delay) => target_delay.parse::<u64>()?,
+                    None => 1,
+                };
+                match client.public.get_fee_estimate(target_delay).await {
+                    Ok(fee_stats) => Ok(Box::new(fee_stats)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
             Command::get_addresses => {
                 let addresses = parse_vec::<Address>(parameters)?;
                 match client.public.get_addresses(addresses).await {
@@ -581,6 +643,73 @@ impl Command {
                 }
             }
 
+            Command::get_address_history => {
+                if parameters.is_empty() {
+                    bail!("missing address parameter");
+                }
+                let address = parameters[0].parse::<Address>()?;
+                let p_list: [&str; 2] = ["limit", "offset"];
+                let mut p: HashMap<&str, &str> = HashMap::new();
+                for v in &parameters[1..] {
+                    let s: Vec<&str> = v.split('=').collect();
+                    if s.len() == 2 && p_list.contains(&s[0]) {
+                        p.insert(s[0], s[1]);
+                    } else {
+                        bail!("invalid parameter: {}, type \"help get_address_history\" to get the list of valid parameters", v);
+                    }
+                }
+                let limit: Option<usize> = parse_key_value(&p, p_list[0])?;
+                let offset: Option<usize> = parse_key_value(&p, p_list[1])?;
+                let page_request = limit.map(|limit| PageRequest {
+                    limit,
+                    offset: offset.unwrap_or_default(),
+                });
+                match client
+                    .public
+                    .get_address_history(address, page_request)
+                    .await
+                {
+                    Ok(history) => Ok(Box::new(history)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::get_async_messages => {
+                let p_list: [&str; 6] = [
+                    "sender",
+                    "destination",
+                    "emission_slot",
+                    "emission_index",
+                    "limit",
+                    "offset",
+                ];
+                let mut p: HashMap<&str, &str> = HashMap::new();
+                for v in parameters {
+                    let s: Vec<&str> = v.split('=').collect();
+                    if s.len() == 2 && p_list.contains(&s[0]) {
+                        p.insert(s[0], s[1]);
+                    } else {
+                        bail!("invalid parameter: {}, type \"help get_async_messages\" to get the list of valid parameters", v);
+                    }
+                }
+                let filter = AsyncMessageFilter {
+                    sender: parse_key_value(&p, p_list[0])?,
+                    destination: parse_key_value(&p, p_list[1])?,
+                    emission_slot: parse_key_value(&p, p_list[2])?,
+                    emission_index: parse_key_value(&p, p_list[3])?,
+                };
+                let limit: Option<usize> = parse_key_value(&p, p_list[4])?;
+                let offset: Option<usize> = parse_key_value(&p, p_list[5])?;
+                let page_request = limit.map(|limit| PageRequest {
+                    limit,
+                    offset: offset.unwrap_or_default(),
+                });
+                match client.public.get_async_messages(filter, page_request).await {
+                    Ok(messages) => Ok(Box::new(messages)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
             Command::get_datastore_entry => {
                 if parameters.len() != 2 {
                     bail!("invalid number of parameters");
@@ -625,7 +754,7 @@ impl Command {
             }
 
             Command::get_filtered_sc_output_event => {
-                let p_list: [&str; 7] = [
+                let p_list: [&str; 9] = [
                     "start",
                     "end",
                     "emitter_address",
@@ -633,6 +762,8 @@ impl Command {
                     "operation_id",
                     "is_final",
                     "is_error",
+                    "offset",
+                    "limit",
                 ];
                 let mut p: HashMap<&str, &str> = HashMap::new();
                 for v in parameters {
@@ -651,6 +782,8 @@ impl Command {
                     original_operation_id: parse_key_value(&p, p_list[4])?,
                     is_final: parse_key_value(&p, p_list[5])?,
                     is_error: parse_key_value(&p, p_list[6])?,
+                    offset: parse_key_value(&p, p_list[7])?,
+                    limit: parse_key_value(&p, p_list[8])?,
                 };
                 match client.public.get_filtered_sc_output_event(filter).await {
                     Ok(events) => Ok(Box::new(events)),
@@ -1012,7 +1145,7 @@ impl Command {
                 }
                 let addr = parameters[0].parse::<Address>()?;
                 let path = parameters[1].parse::<PathBuf>()?;
-                let max_gas = parameters[2].parse::<u64>()?;
+                let max_gas = parse_max_gas(&parameters[2])?;
                 let max_coins = parameters[3].parse::<Amount>()?;
                 let fee = parameters[4].parse::<Amount>()?;
                 if !json {
@@ -1041,20 +1174,16 @@ impl Command {
                 }
                 let datastore = BTreeMap::new();
 
-                send_operation(
-                    client,
-                    wallet,
-                    OperationType::ExecuteSC {
-                        data,
-                        max_gas,
-                        max_coins,
-                        datastore,
-                    },
-                    fee,
-                    addr,
-                    json,
-                )
-                .await
+                let mut op = OperationType::ExecuteSC {
+                    data,
+                    max_gas: max_gas.unwrap_or_default(),
+                    max_coins,
+                    datastore,
+                };
+                if max_gas.is_none() {
+                    fill_max_gas(client, addr, &mut op, json).await?;
+                }
+                send_operation(client, wallet, op, fee, addr, json).await
             }
             Command::call_smart_contract => {
                 let wallet = wallet_opt.as_mut().unwrap();
@@ -1066,7 +1195,7 @@ impl Command {
                 let target_addr = parameters[1].parse::<Address>()?;
                 let target_func = parameters[2].clone();
                 let param = parameters[3].clone().into_bytes();
-                let max_gas = parameters[4].parse::<u64>()?;
+                let max_gas = parse_max_gas(&parameters[4])?;
                 let coins = parameters[5].parse::<Amount>()?;
                 let fee = parameters[6].parse::<Amount>()?;
                 if !json {
@@ -1095,21 +1224,17 @@ impl Command {
                         }
                     }
                 };
-                send_operation(
-                    client,
-                    wallet,
-                    OperationType::CallSC {
-                        target_addr,
-                        target_func,
-                        param,
-                        max_gas,
-                        coins,
-                    },
-                    fee,
-                    addr,
-                    json,
-                )
-                .await
+                let mut op = OperationType::CallSC {
+                    target_addr,
+                    target_func,
+                    param,
+                    max_gas: max_gas.unwrap_or_default(),
+                    coins,
+                };
+                if max_gas.is_none() {
+                    fill_max_gas(client, addr, &mut op, json).await?;
+                }
+                send_operation(client, wallet, op, fee, addr, json).await
             }
             Command::wallet_sign => {
                 let wallet = wallet_opt.as_mut().unwrap();
@@ -1151,6 +1276,7 @@ impl Command {
                         address,
                         operation_datastore: None, // TODO - #3072
                         is_final,
+                        at_slot: None,
                     })
                     .await
                 {
@@ -1186,6 +1312,7 @@ impl Command {
                         parameter,
                         max_gas,
                         is_final,
+                        at_slot: None,
                     })
                     .await
                 {
@@ -1208,12 +1335,16 @@ impl Command {
                     };
                     let args = &parameters[1..];
                     if args.is_empty() {
-                        bail!("[IpAddr] parameter shouldn't be empty");
+                        bail!("[IpAddr or CIDR range] parameter shouldn't be empty");
                     }
-                    let ips = parse_vec::<IpAddr>(args)?;
                     let res: Result<Box<dyn Output>> = match cli_op {
                         ListOperation::Add => {
-                            match client.private.node_add_to_bootstrap_blacklist(ips).await {
+                            let entries = parse_ip_list_entries(args)?;
+                            match client
+                                .private
+                                .node_add_to_bootstrap_blacklist(entries)
+                                .await
+                            {
                                 Ok(()) => {
                                     if !json {
                                         println!(
@@ -1228,7 +1359,7 @@ impl Command {
                         ListOperation::Remove => {
                             match client
                                 .private
-                                .node_remove_from_bootstrap_blacklist(ips)
+                                .node_remove_from_bootstrap_blacklist(parse_vec::<IpNetwork>(args)?)
                                 .await
                             {
                                 Ok(()) => {
@@ -1267,11 +1398,11 @@ impl Command {
                     let res: Result<Box<dyn Output>> = match cli_op {
                         ListOperation::Add => {
                             if args.is_empty() {
-                                bail!("[IpAddr] parameter shouldn't be empty");
+                                bail!("[IpAddr or CIDR range] parameter shouldn't be empty");
                             }
                             match client
                                 .private
-                                .node_add_to_bootstrap_whitelist(parse_vec::<IpAddr>(args)?)
+                                .node_add_to_bootstrap_whitelist(parse_ip_list_entries(args)?)
                                 .await
                             {
                                 Ok(()) => {
@@ -1287,11 +1418,11 @@ impl Command {
                         }
                         ListOperation::Remove => {
                             if args.is_empty() {
-                                bail!("[IpAddr] parameter shouldn't be empty");
+                                bail!("[IpAddr or CIDR range] parameter shouldn't be empty");
                             }
                             match client
                                 .private
-                                .node_remove_from_bootstrap_whitelist(parse_vec::<IpAddr>(args)?)
+                                .node_remove_from_bootstrap_whitelist(parse_vec::<IpNetwork>(args)?)
                                 .await
                             {
                                 Ok(()) => {
@@ -1375,6 +1506,45 @@ impl Command {
     }
 }
 
+/// Parse a `MaxGas` parameter, `auto` meaning that the gas must be estimated by the node
+fn parse_max_gas(parameter: &str) -> Result<Option<u64>> {
+    if parameter.eq_ignore_ascii_case("auto") {
+        Ok(None)
+    } else {
+        Ok(Some(parameter.parse::<u64>()?))
+    }
+}
+
+/// Set the gas limit of a `CallSC` or `ExecuteSC` operation to the one recommended by the node
+async fn fill_max_gas(
+    client: &Client,
+    addr: Address,
+    op: &mut OperationType,
+    json: bool,
+) -> Result<()> {
+    let estimation = match client
+        .public
+        .estimate_gas(GasEstimationRequest {
+            sender_address: addr,
+            op: op.clone(),
+        })
+        .await
+    {
+        Ok(estimation) => estimation,
+        Err(e) => rpc_error!(e),
+    };
+    if !json {
+        println!(
+            "Estimated gas: {} (minimal gas: {})",
+            estimation.recommended_gas, estimation.min_gas
+        );
+    }
+    if let OperationType::CallSC { max_gas, .. } | OperationType::ExecuteSC { max_gas, .. } = op {
+        *max_gas = estimation.recommended_gas;
+    }
+    Ok(())
+}
+
 /// helper to wrap and send an operation with proper validity period
 async fn send_operation(
     client: &Client,
@@ -1439,6 +1609,64 @@ where
         .collect()
 }
 
+/// Splits the arguments from an optional `duration=seconds`
+/// and an optional `reason=text` taking the rest of the arguments
+fn split_duration_and_reason(
+    args: &[String],
+) -> Result<(Vec<String>, Option<MassaTime>, Option<String>)> {
+    let mut remaining_args = Vec::new();
+    let mut duration = None;
+    let mut reason = None;
+    for (index, arg) in args.iter().enumerate() {
+        if let Some(seconds) = arg.strip_prefix("duration=") {
+            let seconds = seconds
+                .parse::<u64>()
+                .map_err(|e| anyhow!("failed to parse duration \"{}\" due to: {}", seconds, e))?;
+            duration = Some(MassaTime::from_millis(seconds.saturating_mul(1000)));
+        } else if let Some(text) = arg.strip_prefix("reason=") {
+            reason = Some(
+                std::iter::once(text)
+                    .chain(args[index + 1..].iter().map(String::as_str))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
+            break;
+        } else {
+            remaining_args.push(arg.clone());
+        }
+    }
+    Ok((remaining_args, duration, reason))
+}
+
+/// Parses bootstrap list entries: IP addresses or CIDR ranges,
+/// followed by an optional `duration=seconds` after which they expire
+/// and an optional `reason=text` taking the rest of the arguments
+fn parse_ip_list_entries(args: &[String]) -> Result<Vec<IpListEntry>> {
+    let (args, duration, reason) = split_duration_and_reason(args)?;
+    let expire_at = match duration {
+        Some(duration) => Some(MassaTime::now()?.saturating_add(duration)),
+        None => None,
+    };
+    let networks = args
+        .iter()
+        .map(|arg| {
+            arg.parse::<IpNetwork>()
+                .map_err(|e| anyhow!("failed to parse \"{}\" due to: {}", arg, e))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if networks.is_empty() {
+        bail!("[IpAddr or CIDR range] parameter shouldn't be empty");
+    }
+    Ok(networks
+        .into_iter()
+        .map(|network| IpListEntry {
+            network,
+            expire_at,
+            reason: reason.clone(),
+        })
+        .collect())
+}
+
 /// reads a file
 async fn get_file_as_byte_vec(filename: &std::path::Path) -> Result<Vec<u8>> {
     Ok(tokio::fs::read(filename).await?)
@@ -4,14 +4,20 @@ use crate::cmds::ExtendedWallet;
 use console::style;
 use erased_serde::{Serialize, Serializer};
 use massa_api_exports::{
-    address::AddressInfo, block::BlockInfo, datastore::DatastoreEntryOutput,
-    endorsement::EndorsementInfo, execution::ExecuteReadOnlyResponse, node::NodeStatus,
+    address::AddressInfo,
+    block::BlockInfo,
+    datastore::DatastoreEntryOutput,
+    endorsement::EndorsementInfo,
+    execution::ExecuteReadOnlyResponse,
+    node::{NodeStatus, PeerInfo},
     operation::OperationInfo,
 };
 use massa_models::composite::PubkeySig;
+use massa_models::execution::{AddressHistoryEntry, AsyncMessageEntry, AsyncMessageStatus};
+use massa_models::ip_list::IpListEntry;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
-use massa_models::stats::{ConsensusStats, ExecutionStats, NetworkStats};
+use massa_models::stats::{ConsensusStats, ExecutionStats, FeeStats, NetworkStats};
 use massa_models::{address::Address, config::CompactConfig, operation::OperationId};
 use massa_signature::{KeyPair, PublicKey};
 use massa_wallet::Wallet;
@@ -491,6 +497,22 @@ impl Output for Vec<IpAddr> {
     }
 }
 
+impl Output for Vec<IpListEntry> {
+    fn pretty_print(&self) {
+        for entry in self {
+            println!("{}", entry);
+        }
+    }
+}
+
+impl Output for Vec<PeerInfo> {
+    fn pretty_print(&self) {
+        for peer in self {
+            println!("{}", peer);
+        }
+    }
+}
+
 impl Output for Vec<OperationInfo> {
     fn pretty_print(&self) {
         for info in self {
@@ -576,6 +598,71 @@ impl Output for Vec<Address> {
     }
 }
 
+impl Output for Vec<AddressHistoryEntry> {
+    fn pretty_print(&self) {
+        for entry in self {
+            println!(
+                "Operation {} executed at slot {} in block {}: {}",
+                Style::Id.style(entry.operation_id),
+                Style::Protocol.style(entry.slot),
+                Style::Block.style(entry.block_id),
+                if entry.success {
+                    Style::Good.style("success")
+                } else {
+                    Style::Bad.style("failed")
+                }
+            );
+        }
+    }
+}
+
+impl Output for Vec<AsyncMessageEntry> {
+    fn pretty_print(&self) {
+        for entry in self {
+            println!(
+                "Message emitted at slot {} (index {}): {} -> {}::{}",
+                Style::Protocol.style(entry.emission_slot),
+                entry.emission_index,
+                Style::Wallet.style(entry.sender),
+                Style::Wallet.style(entry.destination),
+                entry.handler
+            );
+            println!(
+                "\tFee: {} Coins: {} Max gas: {}",
+                Style::Coins.style(entry.fee),
+                Style::Coins.style(entry.coins),
+                entry.max_gas
+            );
+            println!(
+                "\tValidity: from slot {} to slot {} (excluded)",
+                Style::Protocol.style(entry.validity_start),
+                Style::Protocol.style(entry.validity_end)
+            );
+            if entry.has_trigger {
+                println!(
+                    "\tTrigger: {}",
+                    if entry.can_be_executed {
+                        "triggered"
+                    } else {
+                        "not triggered yet"
+                    }
+                );
+            }
+            let status = match entry.status {
+                AsyncMessageStatus::Executable => Style::Good.style(entry.status),
+                AsyncMessageStatus::Expired | AsyncMessageStatus::Starved => {
+                    Style::Bad.style(entry.status)
+                }
+                _ => Style::Pending.style(entry.status),
+            };
+            match entry.execution_rank {
+                Some(rank) => println!("\tStatus: {} (rank {} in the next batch)", status, rank),
+                None => println!("\tStatus: {}", status),
+            }
+        }
+    }
+}
+
 impl Output for Vec<SCOutputEvent> {
     fn pretty_print(&self) {
         for addr in self {
@@ -595,3 +682,9 @@ impl Output for ExecuteReadOnlyResponse {
         println!("{}", self);
     }
 }
+
+impl Output for FeeStats {
+    fn pretty_print(&self) {
+        println!("{}", self);
+    }
+}
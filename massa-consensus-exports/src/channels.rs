@@ -1,3 +1,4 @@
+use massa_archive::{Archive, ArchiveWriter};
 use massa_channel::sender::MassaSender;
 use massa_execution_exports::ExecutionController;
 use massa_models::block::{FilledBlock, SecureShareBlock};
@@ -7,6 +8,7 @@ use massa_models::secure_share::SecureShare;
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
 use massa_protocol_exports::ProtocolController;
+use std::sync::Arc;
 
 use crate::events::ConsensusEvent;
 
@@ -29,4 +31,8 @@ pub struct ConsensusChannels {
     pub block_header_sender: tokio::sync::broadcast::Sender<SecureShare<BlockHeader, BlockId>>,
     /// Channel use by Websocket (if they are enable) to broadcast a new block integrated
     pub filled_block_sender: tokio::sync::broadcast::Sender<FilledBlock>,
+    /// Archive of final blocks, operations and endorsements (only on archive nodes)
+    pub archive: Option<Arc<Archive>>,
+    /// Channel used to send the final blocks to the archive worker (only on archive nodes)
+    pub archive_writer: Option<ArchiveWriter>,
 }
@@ -721,11 +721,23 @@ impl ConsensusState {
             let finalized_blocks = mem::take(&mut self.new_final_blocks);
             let mut final_block_slots = HashMap::with_capacity(finalized_blocks.len());
             let mut final_block_stats = VecDeque::with_capacity(finalized_blocks.len());
+            let mut final_blocks_storage = self.storage.clone_without_refs();
+            let mut archived_block_ids = Vec::new();
             for b_id in finalized_blocks {
-                if let Some(BlockStatus::Active { a_block, .. }) = self.blocks_state.get(&b_id) {
+                if let Some(BlockStatus::Active {
+                    a_block,
+                    storage_or_block,
+                }) = self.blocks_state.get(&b_id)
+                {
                     // add to final blocks to notify execution
                     final_block_slots.insert(a_block.slot, b_id);
 
+                    // add to final blocks to notify pool and to archive
+                    if let StorageOrBlock::Storage(storage) = storage_or_block {
+                        final_blocks_storage.extend(storage.clone());
+                        archived_block_ids.push(b_id);
+                    }
+
                     // add to stats
                     let block_is_from_protocol = self
                         .protocol_blocks
@@ -740,6 +752,21 @@ impl ConsensusState {
             }
             self.final_block_stats.extend(final_block_stats);
 
+            // persist the final blocks to the archive (archive nodes only), from the archive worker
+            if let Some(archive_writer) = &self.channels.archive_writer {
+                if !archived_block_ids.is_empty() {
+                    archive_writer
+                        .archive_final_blocks(archived_block_ids, final_blocks_storage.clone());
+                }
+            }
+
+            // notify pool of the operations included in final blocks
+            if !final_blocks_storage.get_block_refs().is_empty() {
+                self.channels
+                    .pool_controller
+                    .notify_final_blocks(final_blocks_storage);
+            }
+
             // add stale blocks to stats
             let new_stale_block_ids_creators_slots = mem::take(&mut self.new_stale_blocks);
             let timestamp = MassaTime::now()?;
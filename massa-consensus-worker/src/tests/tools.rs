@@ -92,6 +92,8 @@ where
             protocol_controller: protocol_controller.clone_box(),
             pool_controller,
             selector_controller: selector_controller.clone(),
+            archive: None,
+            archive_writer: None,
         },
         None,
         storage.clone(),
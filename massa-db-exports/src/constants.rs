@@ -2,6 +2,8 @@
 pub const METADATA_CF: &str = "metadata";
 pub const STATE_CF: &str = "state";
 pub const VERSIONING_CF: &str = "versioning";
+pub const STATE_TREE_LEAVES_CF: &str = "state_tree_leaves";
+pub const STATE_TREE_NODES_CF: &str = "state_tree_nodes";
 
 // Hash
 pub const STATE_HASH_BYTES_LEN: usize = 512;
@@ -19,6 +21,7 @@ pub const OPEN_ERROR: &str = "critical: rocksdb open operation failed";
 pub const LSMTREE_ERROR: &str = "critical: lsmtree insert / remove open operation failed";
 pub const CRUD_ERROR: &str = "critical: rocksdb crud operation failed";
 pub const STATE_HASH_ERROR: &str = "critical: saved state hash is corrupted";
+pub const STATE_TREE_ERROR: &str = "critical: saved state tree is corrupted";
 
 // Prefixes
 pub const CYCLE_HISTORY_PREFIX: &str = "cycle_history/";
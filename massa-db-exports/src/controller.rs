@@ -1,6 +1,8 @@
 use crate::{DBBatch, Key, MassaDBError, StreamBatch, Value};
 use massa_hash::{HashXof, HASH_XOF_SIZE_BYTES};
-use massa_models::{error::ModelsError, slot::Slot, streaming_step::StreamingStep};
+use massa_models::{
+    error::ModelsError, slot::Slot, state_proof::StateProof, streaming_step::StreamingStep,
+};
 use parking_lot::RwLock;
 use std::{fmt::Debug, sync::Arc};
 
@@ -83,8 +85,17 @@ pub trait MassaDBController: Send + Sync + Debug {
         last_change_id: Option<Slot>,
     ) -> Result<StreamBatch<Slot>, MassaDBError>;
 
+    /// Used for bootstrap servers (check if the changes made since `change_id` can no longer be streamed)
+    ///
+    /// Returns true if `change_id` is older than the current one but was dropped from the change history
+    fn is_change_id_outdated(&self, change_id: &Slot) -> bool;
+
     /// To be called just after bootstrap
     fn recompute_db_hash(&mut self) -> Result<(), MassaDBError>;
+
+    /// Get the proof of inclusion (or exclusion) of a key in STATE_CF, against the current state tree.
+    /// Returns None if the state tree is not maintained.
+    fn get_state_proof(&self, key: &[u8]) -> Option<StateProof>;
 }
 
 /// Similar to RocksDB's IteratorMode
@@ -11,4 +11,6 @@ pub struct MassaDBConfig {
     pub max_new_elements: usize,
     /// Thread count for slot serialization
     pub thread_count: u8,
+    /// Whether to maintain the state tree used to build inclusion proofs of the state entries
+    pub enable_state_tree: bool,
 }
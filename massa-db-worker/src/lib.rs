@@ -1,3 +1,4 @@
 mod massa_db;
+mod state_tree;
 
 pub use crate::massa_db::*;
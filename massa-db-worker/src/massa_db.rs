@@ -2,13 +2,15 @@ use massa_db_exports::{
     DBBatch, Key, MassaDBConfig, MassaDBController, MassaDBError, MassaDirection,
     MassaIteratorMode, StreamBatch, Value, CF_ERROR, CHANGE_ID_DESER_ERROR, CHANGE_ID_KEY,
     CHANGE_ID_SER_ERROR, CRUD_ERROR, METADATA_CF, OPEN_ERROR, STATE_CF, STATE_HASH_ERROR,
-    STATE_HASH_INITIAL_BYTES, STATE_HASH_KEY, VERSIONING_CF,
+    STATE_HASH_INITIAL_BYTES, STATE_HASH_KEY, STATE_TREE_LEAVES_CF, STATE_TREE_NODES_CF,
+    VERSIONING_CF,
 };
 use massa_hash::{HashXof, HASH_XOF_SIZE_BYTES};
 use massa_models::{
     config::MAX_BACKUPS_TO_KEEP,
     error::ModelsError,
     slot::{Slot, SlotDeserializer, SlotSerializer},
+    state_proof::StateProof,
     streaming_step::StreamingStep,
 };
 use massa_serialization::{DeserializeError, Deserializer, Serializer};
@@ -24,6 +26,8 @@ use std::{
     sync::Arc,
 };
 
+use crate::state_tree::StateTree;
+
 /// Wrapped RocksDB database
 ///
 /// In our instance, we use Slot as the ChangeID
@@ -33,6 +37,7 @@ pub type MassaDB = RawMassaDB<Slot, SlotSerializer, SlotDeserializer>;
 ///
 /// The added features are:
 /// - Hash tracking with Lsm-tree, a Sparse Merkle Tree implementation
+/// - Inclusion proofs of the state entries, with a sparse Merkle tree over STATE_CF
 /// - Streaming the database while it is being actively updated
 #[derive()]
 pub struct RawMassaDB<
@@ -189,6 +194,15 @@ where
         })
     }
 
+    /// Used for bootstrap servers (check if the changes made since `change_id` can no longer be streamed)
+    ///
+    /// Returns true if `change_id` is older than the current one but was dropped from the change history
+    pub fn is_change_id_outdated(&self, change_id: &ChangeID) -> bool {
+        change_id < &self.get_change_id().expect(CHANGE_ID_DESER_ERROR)
+            && !(self.change_history.contains_key(change_id)
+                && self.change_history_versioning.contains_key(change_id))
+    }
+
     /// Used for bootstrap servers (get a new batch of data from VERSIONING_CF to stream to the client)
     ///
     /// Returns a StreamBatch<ChangeID>
@@ -323,10 +337,18 @@ where
         let handle_versioning = self.db.cf_handle(VERSIONING_CF).expect(CF_ERROR);
 
         let mut current_xor_hash = self.get_xof_db_hash();
+        let mut state_tree = self
+            .config
+            .enable_state_tree
+            .then(|| StateTree::new(&self.db));
 
         *self.current_batch.lock() = WriteBatch::default();
 
         for (key, value) in changes.iter() {
+            if let Some(state_tree) = state_tree.as_mut() {
+                state_tree.apply_change(key, value.as_deref());
+            }
+
             if let Some(value) = value {
                 self.current_batch.lock().put_cf(handle_state, key, value);
 
@@ -371,6 +393,11 @@ where
             .lock()
             .put_cf(handle_metadata, STATE_HASH_KEY, current_xor_hash.0);
 
+        // Update the state tree
+        if let Some(state_tree) = state_tree {
+            state_tree.write_to_batch(&mut self.current_batch.lock());
+        }
+
         {
             let mut current_batch_guard = self.current_batch.lock();
             let batch = WriteBatch::from_data(current_batch_guard.data());
@@ -554,6 +581,47 @@ where
         Ok(())
     }
 
+    /// Remove the whole state tree
+    pub fn clear_state_tree(&self) -> Result<(), MassaDBError> {
+        let mut batch = WriteBatch::default();
+        for handle_str in [STATE_TREE_LEAVES_CF, STATE_TREE_NODES_CF] {
+            let handle = self.db.cf_handle(handle_str).expect(CF_ERROR);
+            for (key, _) in self.db.iterator_cf(handle, IteratorMode::Start).flatten() {
+                batch.delete_cf(handle, key);
+            }
+        }
+        self.db
+            .write(batch)
+            .map_err(|e| MassaDBError::RocksDBError(format!("Can't write batch to disk: {}", e)))
+    }
+
+    /// Rebuild the state tree from the content of STATE_CF
+    pub fn rebuild_state_tree(&self) -> Result<(), MassaDBError> {
+        let handle_state = self.db.cf_handle(STATE_CF).expect(CF_ERROR);
+
+        self.clear_state_tree()?;
+
+        // Insert the entries by chunks, to bound the memory used by the pending tree changes
+        let mut entries = self
+            .db
+            .iterator_cf(handle_state, IteratorMode::Start)
+            .flatten()
+            .peekable();
+        while entries.peek().is_some() {
+            let mut state_tree = StateTree::new(&self.db);
+            for (key, value) in entries.by_ref().take(self.config.max_new_elements) {
+                state_tree.apply_change(&key, Some(&value[..]));
+            }
+            let mut batch = WriteBatch::default();
+            state_tree.write_to_batch(&mut batch);
+            self.db.write(batch).map_err(|e| {
+                MassaDBError::RocksDBError(format!("Can't write batch to disk: {}", e))
+            })?;
+        }
+
+        Ok(())
+    }
+
     /// Get the current XOF state hash of the database
     pub fn get_xof_db_hash(&self) -> HashXof<HASH_XOF_SIZE_BYTES> {
         self.get_xof_db_hash_opt()
@@ -586,6 +654,8 @@ impl RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
                 ColumnFamilyDescriptor::new(STATE_CF, Options::default()),
                 ColumnFamilyDescriptor::new(METADATA_CF, Options::default()),
                 ColumnFamilyDescriptor::new(VERSIONING_CF, Options::default()),
+                ColumnFamilyDescriptor::new(STATE_TREE_LEAVES_CF, Options::default()),
+                ColumnFamilyDescriptor::new(STATE_TREE_NODES_CF, Options::default()),
             ],
         )
         .expect(OPEN_ERROR);
@@ -615,8 +685,42 @@ impl RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
             });
         }
 
+        // The state tree is built once when it gets enabled on an existing database,
+        // and removed when it gets disabled, as it would not be kept up to date
+        let handle_state = massa_db.db.cf_handle(STATE_CF).expect(CF_ERROR);
+        let handle_leaves = massa_db.db.cf_handle(STATE_TREE_LEAVES_CF).expect(CF_ERROR);
+        let state_is_empty = massa_db
+            .db
+            .iterator_cf(handle_state, IteratorMode::Start)
+            .next()
+            .is_none();
+        let tree_is_empty = massa_db
+            .db
+            .iterator_cf(handle_leaves, IteratorMode::Start)
+            .next()
+            .is_none();
+        if massa_db.config.enable_state_tree {
+            if !state_is_empty && tree_is_empty {
+                massa_db.rebuild_state_tree().expect(CRUD_ERROR);
+            }
+        } else if !tree_is_empty {
+            massa_db.clear_state_tree().expect(CRUD_ERROR);
+        }
+
         massa_db
     }
+
+    /// Get the proof of inclusion (or exclusion) of a key in STATE_CF, against the current state tree.
+    /// Returns None if the state tree is not maintained.
+    pub fn get_state_proof(&self, key: &[u8]) -> Option<StateProof> {
+        if !self.config.enable_state_tree {
+            return None;
+        }
+        let handle_state = self.db.cf_handle(STATE_CF).expect(CF_ERROR);
+        let value = self.db.get_cf(handle_state, key).expect(CRUD_ERROR);
+        let slot = self.get_change_id().expect(CHANGE_ID_DESER_ERROR);
+        Some(StateTree::new(&self.db).get_proof(key, value, slot))
+    }
 }
 
 impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
@@ -832,8 +936,75 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
         self.get_versioning_batch_to_stream(last_versioning_step, last_change_id)
     }
 
+    /// Used for bootstrap servers (check if the changes made since `change_id` can no longer be streamed)
+    fn is_change_id_outdated(&self, change_id: &Slot) -> bool {
+        self.is_change_id_outdated(change_id)
+    }
+
     /// To be called just after bootstrap
     fn recompute_db_hash(&mut self) -> Result<(), MassaDBError> {
         self.recompute_db_hash()
     }
+
+    /// Get the proof of inclusion (or exclusion) of a key in STATE_CF
+    fn get_state_proof(&self, key: &[u8]) -> Option<StateProof> {
+        self.get_state_proof(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn open_db(path: &std::path::Path, enable_state_tree: bool) -> MassaDB {
+        MassaDB::new(MassaDBConfig {
+            path: path.to_path_buf(),
+            max_history_length: 10,
+            max_new_elements: 100,
+            thread_count: 2,
+            enable_state_tree,
+        })
+    }
+
+    #[test]
+    fn test_state_tree_opt_in() {
+        let temp_dir = TempDir::new().unwrap();
+        let key = b"key".to_vec();
+
+        // without the state tree, no proof is served
+        let mut db = open_db(temp_dir.path(), false);
+        let mut batch = DBBatch::new();
+        db.put_or_update_entry_value(&mut batch, key.clone(), b"value");
+        db.write_batch(batch, DBBatch::new(), Some(Slot::new(1, 0)));
+        assert!(db.get_state_proof(&key).is_none());
+        drop(db);
+
+        // enabling it on an existing database builds the tree
+        let db = open_db(temp_dir.path(), true);
+        let proof = db.get_state_proof(&key).unwrap();
+        assert_eq!(proof.value, Some(b"value".to_vec()));
+        assert_eq!(proof.slot, Slot::new(1, 0));
+        assert!(proof.verify(&proof.root));
+        let root = proof.root;
+        drop(db);
+
+        // disabling it removes the tree, which is rebuilt identically when enabled again
+        let mut db = open_db(temp_dir.path(), false);
+        let handle_leaves = db.db.cf_handle(STATE_TREE_LEAVES_CF).expect(CF_ERROR);
+        assert!(db
+            .db
+            .iterator_cf(handle_leaves, IteratorMode::Start)
+            .next()
+            .is_none());
+        let mut batch = DBBatch::new();
+        db.put_or_update_entry_value(&mut batch, b"other".to_vec(), b"value");
+        db.write_batch(batch, DBBatch::new(), Some(Slot::new(1, 1)));
+        drop(db);
+        let db = open_db(temp_dir.path(), true);
+        let proof = db.get_state_proof(&key).unwrap();
+        assert_ne!(proof.root, root);
+        assert_eq!(proof.slot, Slot::new(1, 1));
+        assert!(proof.verify(&proof.root));
+    }
 }
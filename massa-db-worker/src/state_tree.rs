@@ -0,0 +1,384 @@
+//! Sparse Merkle tree authenticating the entries of STATE_CF, see `massa_models::state_proof`.
+//!
+//! Leaves are stored in STATE_TREE_LEAVES_CF (path -> value hash),
+//! and the hashes of the nodes holding at least two leaves in STATE_TREE_NODES_CF (depth + path prefix -> hash).
+//! Every other subtree is either empty or a single leaf, found with a range scan on the leaves.
+
+use massa_db_exports::{
+    CF_ERROR, CRUD_ERROR, STATE_TREE_ERROR, STATE_TREE_LEAVES_CF, STATE_TREE_NODES_CF,
+};
+use massa_hash::{Hash, HASH_SIZE_BYTES};
+use massa_models::slot::Slot;
+use massa_models::state_proof::{
+    state_tree_common_prefix_len, state_tree_leaf_hash, state_tree_node_hash, state_tree_path,
+    state_tree_path_bit, StateProof, StateProofLeaf, STATE_TREE_DEPTH,
+};
+use rocksdb::{Direction, IteratorMode, WriteBatch, DB};
+use std::collections::BTreeMap;
+use std::ops::Bound::{Excluded, Unbounded};
+
+/// A subtree of the state tree
+#[derive(Clone)]
+enum Subtree {
+    /// no leaf
+    Empty,
+    /// a single leaf: its path and value hash
+    Leaf(Hash, Hash),
+    /// at least two leaves: the node hash
+    Node(Hash),
+}
+
+impl Subtree {
+    fn hash(&self) -> Hash {
+        match self {
+            Subtree::Empty => Hash::zero(),
+            Subtree::Leaf(path, value_hash) => state_tree_leaf_hash(path, value_hash),
+            Subtree::Node(hash) => *hash,
+        }
+    }
+}
+
+/// Keep only the first `depth` bits of a path
+fn truncate_path(path: &Hash, depth: usize, fill: bool) -> [u8; HASH_SIZE_BYTES] {
+    let mut bytes = path.into_bytes();
+    for (index, byte) in bytes.iter_mut().enumerate() {
+        let kept_bits = depth.saturating_sub(index * 8).min(8);
+        let mask = if kept_bits == 0 {
+            0u8
+        } else {
+            0xffu8 << (8 - kept_bits)
+        };
+        *byte = if fill { *byte | !mask } else { *byte & mask };
+    }
+    bytes
+}
+
+/// Path of the sibling of the subtree at `depth + 1` along `path`
+fn sibling_path(path: &Hash, depth: usize) -> Hash {
+    let mut bytes = path.into_bytes();
+    bytes[depth / 8] ^= 1 << (7 - depth % 8);
+    Hash::from_bytes(&bytes)
+}
+
+/// Key of a node in STATE_TREE_NODES_CF: its depth followed by the bytes of its path prefix
+fn node_key(path: &Hash, depth: usize) -> Vec<u8> {
+    let prefix = truncate_path(path, depth, false);
+    let mut key = vec![depth as u8];
+    key.extend_from_slice(&prefix[..(depth + 7) / 8]);
+    key
+}
+
+/// Read access to the state tree, with the pending (not yet written) changes applied on top of the database
+pub(crate) struct StateTree<'a> {
+    db: &'a DB,
+    /// pending leaf changes: path -> value hash (None for a deletion)
+    leaves: BTreeMap<[u8; HASH_SIZE_BYTES], Option<Hash>>,
+    /// pending node changes: node key -> node hash (None for a deletion)
+    nodes: BTreeMap<Vec<u8>, Option<Hash>>,
+}
+
+impl<'a> StateTree<'a> {
+    pub fn new(db: &'a DB) -> Self {
+        Self {
+            db,
+            leaves: BTreeMap::new(),
+            nodes: BTreeMap::new(),
+        }
+    }
+
+    /// Get the root of the tree
+    pub fn get_root(&self) -> Hash {
+        self.get_subtree(&Hash::zero(), 0).hash()
+    }
+
+    /// Update the tree with a change on a STATE_CF entry
+    pub fn apply_change(&mut self, key: &[u8], value: Option<&[u8]>) {
+        let path = state_tree_path(key);
+
+        // deepest level at which the subtree of the key holds another leaf
+        let shared_depth = [
+            self.get_neighbor_leaf(&path, false),
+            self.get_neighbor_leaf(&path, true),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|neighbor| state_tree_common_prefix_len(&path, &neighbor))
+        .max();
+
+        let value_hash = value.map(Hash::compute_from);
+        self.leaves.insert(path.into_bytes(), value_hash);
+
+        // no other leaf: there is no node to update
+        let Some(shared_depth) = shared_depth else {
+            return;
+        };
+
+        // below the shared depth, the subtree of the key holds at most its own leaf
+        let mut subtree = match value_hash {
+            Some(value_hash) => Subtree::Leaf(path, value_hash),
+            None => Subtree::Empty,
+        };
+        for depth in (0..=shared_depth).rev() {
+            let sibling = self.get_subtree(&sibling_path(&path, depth), depth + 1);
+            subtree = match (subtree, sibling) {
+                (Subtree::Empty, Subtree::Empty) => Subtree::Empty,
+                (Subtree::Empty, leaf @ Subtree::Leaf(..))
+                | (leaf @ Subtree::Leaf(..), Subtree::Empty) => leaf,
+                (subtree, sibling) => {
+                    if state_tree_path_bit(&path, depth) {
+                        Subtree::Node(state_tree_node_hash(&sibling.hash(), &subtree.hash()))
+                    } else {
+                        Subtree::Node(state_tree_node_hash(&subtree.hash(), &sibling.hash()))
+                    }
+                }
+            };
+            let hash = match subtree {
+                Subtree::Node(hash) => Some(hash),
+                _ => None,
+            };
+            self.nodes.insert(node_key(&path, depth), hash);
+        }
+    }
+
+    /// Add the pending changes to a batch
+    pub fn write_to_batch(self, batch: &mut WriteBatch) {
+        let handle_leaves = self.db.cf_handle(STATE_TREE_LEAVES_CF).expect(CF_ERROR);
+        let handle_nodes = self.db.cf_handle(STATE_TREE_NODES_CF).expect(CF_ERROR);
+        for (path, value_hash) in self.leaves {
+            match value_hash {
+                Some(value_hash) => batch.put_cf(handle_leaves, path, value_hash.to_bytes()),
+                None => batch.delete_cf(handle_leaves, path),
+            }
+        }
+        for (key, hash) in self.nodes {
+            match hash {
+                Some(hash) => batch.put_cf(handle_nodes, key, hash.to_bytes()),
+                None => batch.delete_cf(handle_nodes, key),
+            }
+        }
+    }
+
+    /// Build the proof of a STATE_CF entry, given its current value and the slot of the state
+    pub fn get_proof(&self, key: &[u8], value: Option<Vec<u8>>, slot: Slot) -> StateProof {
+        let path = state_tree_path(key);
+        let mut siblings = Vec::new();
+        let mut other_leaf = None;
+        for depth in 0..=STATE_TREE_DEPTH {
+            match self.get_subtree(&path, depth) {
+                Subtree::Node(_) => {
+                    siblings.push(
+                        self.get_subtree(&sibling_path(&path, depth), depth + 1)
+                            .hash(),
+                    );
+                }
+                Subtree::Leaf(leaf_path, value_hash) => {
+                    if leaf_path != path {
+                        other_leaf = Some(StateProofLeaf {
+                            path: leaf_path,
+                            value_hash,
+                        });
+                    }
+                    break;
+                }
+                Subtree::Empty => break,
+            }
+        }
+        StateProof {
+            key: key.to_vec(),
+            value,
+            siblings,
+            other_leaf,
+            root: self.get_root(),
+            slot,
+        }
+    }
+
+    /// Get the subtree made of the paths sharing the first `depth` bits of `path`
+    fn get_subtree(&self, path: &Hash, depth: usize) -> Subtree {
+        if depth < STATE_TREE_DEPTH {
+            let key = node_key(path, depth);
+            let hash = match self.nodes.get(&key) {
+                Some(hash) => *hash,
+                None => {
+                    let handle = self.db.cf_handle(STATE_TREE_NODES_CF).expect(CF_ERROR);
+                    self.db
+                        .get_cf(handle, &key)
+                        .expect(CRUD_ERROR)
+                        .map(|bytes| {
+                            Hash::from_bytes(bytes.as_slice().try_into().expect(STATE_TREE_ERROR))
+                        })
+                }
+            };
+            if let Some(hash) = hash {
+                return Subtree::Node(hash);
+            }
+        }
+        let leaves = self.get_leaves_in_range(
+            truncate_path(path, depth, false),
+            truncate_path(path, depth, true),
+        );
+        match leaves.as_slice() {
+            [] => Subtree::Empty,
+            [(leaf_path, value_hash)] => Subtree::Leaf(*leaf_path, *value_hash),
+            _ => panic!("{}", STATE_TREE_ERROR),
+        }
+    }
+
+    /// Get (at most) the first two leaves between two paths (included)
+    fn get_leaves_in_range(
+        &self,
+        from: [u8; HASH_SIZE_BYTES],
+        to: [u8; HASH_SIZE_BYTES],
+    ) -> Vec<(Hash, Hash)> {
+        let handle = self.db.cf_handle(STATE_TREE_LEAVES_CF).expect(CF_ERROR);
+        let mut leaves: Vec<(Hash, Hash)> = self
+            .db
+            .iterator_cf(handle, IteratorMode::From(&from, Direction::Forward))
+            .map(|item| item.expect(CRUD_ERROR))
+            .take_while(|(path, _)| path.as_ref() <= to.as_slice())
+            .filter(|(path, _)| !self.leaves.contains_key(path.as_ref()))
+            .take(2)
+            .map(|(path, value_hash)| Self::decode_leaf(&path, &value_hash))
+            .collect();
+        leaves.extend(
+            self.leaves
+                .range(from..=to)
+                .filter_map(|(path, value_hash)| value_hash.map(|v| (Hash::from_bytes(path), v)))
+                .take(2),
+        );
+        leaves.sort_unstable_by_key(|(path, _)| path.into_bytes());
+        leaves.truncate(2);
+        leaves
+    }
+
+    /// Get the path of the closest leaf before or after `path` (excluded)
+    fn get_neighbor_leaf(&self, path: &Hash, forward: bool) -> Option<Hash> {
+        let handle = self.db.cf_handle(STATE_TREE_LEAVES_CF).expect(CF_ERROR);
+        let bytes = path.into_bytes();
+        let direction = if forward {
+            Direction::Forward
+        } else {
+            Direction::Reverse
+        };
+        let from_db: Option<[u8; HASH_SIZE_BYTES]> = self
+            .db
+            .iterator_cf(handle, IteratorMode::From(&bytes, direction))
+            .map(|item| item.expect(CRUD_ERROR))
+            .map(|(leaf_path, _)| leaf_path)
+            .find(|leaf_path| {
+                leaf_path.as_ref() != bytes.as_slice()
+                    && !self.leaves.contains_key(leaf_path.as_ref())
+            })
+            .map(|leaf_path| leaf_path.as_ref().try_into().expect(STATE_TREE_ERROR));
+        let from_pending = if forward {
+            self.leaves
+                .range((Excluded(bytes), Unbounded))
+                .find(|(_, value_hash)| value_hash.is_some())
+        } else {
+            self.leaves
+                .range((Unbounded, Excluded(bytes)))
+                .rev()
+                .find(|(_, value_hash)| value_hash.is_some())
+        }
+        .map(|(leaf_path, _)| *leaf_path);
+        let closest = match (from_db, from_pending) {
+            (Some(a), Some(b)) if forward => Some(a.min(b)),
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        closest.map(|leaf_path| Hash::from_bytes(&leaf_path))
+    }
+
+    fn decode_leaf(path: &[u8], value_hash: &[u8]) -> (Hash, Hash) {
+        (
+            Hash::from_bytes(path.try_into().expect(STATE_TREE_ERROR)),
+            Hash::from_bytes(value_hash.try_into().expect(STATE_TREE_ERROR)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocksdb::{ColumnFamilyDescriptor, Options};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_state_tree_proofs() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut db_opts = Options::default();
+        db_opts.create_if_missing(true);
+        db_opts.create_missing_column_families(true);
+        let db = DB::open_cf_descriptors(
+            &db_opts,
+            temp_dir.path(),
+            vec![
+                ColumnFamilyDescriptor::new(STATE_TREE_LEAVES_CF, Options::default()),
+                ColumnFamilyDescriptor::new(STATE_TREE_NODES_CF, Options::default()),
+            ],
+        )
+        .unwrap();
+
+        let mut entries: BTreeMap<Vec<u8>, Vec<u8>> = (0u32..50)
+            .map(|i| (i.to_be_bytes().to_vec(), vec![i as u8; 3]))
+            .collect();
+
+        // first batch: insert everything, second batch: update some entries and delete others
+        let mut tree = StateTree::new(&db);
+        for (key, value) in entries.iter() {
+            tree.apply_change(key, Some(value.as_slice()));
+        }
+        let mut batch = WriteBatch::default();
+        tree.write_to_batch(&mut batch);
+        db.write(batch).unwrap();
+
+        let mut tree = StateTree::new(&db);
+        for i in 0u32..50 {
+            let key = i.to_be_bytes().to_vec();
+            if i % 3 == 0 {
+                tree.apply_change(&key, None);
+                entries.remove(&key);
+            } else if i % 3 == 1 {
+                tree.apply_change(&key, Some(&[42][..]));
+                entries.insert(key, vec![42]);
+            }
+        }
+        let mut batch = WriteBatch::default();
+        tree.write_to_batch(&mut batch);
+        db.write(batch).unwrap();
+
+        // the root does not depend on the order of the changes
+        let fresh_dir = TempDir::new().unwrap();
+        let fresh_db = DB::open_cf_descriptors(
+            &db_opts,
+            fresh_dir.path(),
+            vec![
+                ColumnFamilyDescriptor::new(STATE_TREE_LEAVES_CF, Options::default()),
+                ColumnFamilyDescriptor::new(STATE_TREE_NODES_CF, Options::default()),
+            ],
+        )
+        .unwrap();
+        let mut fresh_tree = StateTree::new(&fresh_db);
+        for (key, value) in entries.iter().rev() {
+            fresh_tree.apply_change(key, Some(value.as_slice()));
+        }
+        let tree = StateTree::new(&db);
+        let root = tree.get_root();
+        assert_eq!(fresh_tree.get_root(), root);
+
+        for i in 0u32..60 {
+            let key = i.to_be_bytes().to_vec();
+            let value = entries.get(&key).cloned();
+            let proof = tree.get_proof(&key, value.clone(), Slot::new(1, 0));
+            assert!(proof.verify(&root));
+
+            // a proof with a wrong value must be rejected
+            let mut forged = proof.clone();
+            forged.value = match value {
+                Some(_) => Some(vec![7]),
+                None => Some(vec![42]),
+            };
+            assert!(!forged.verify(&root));
+        }
+    }
+}
@@ -6,16 +6,20 @@ use crate::types::{
     ExecutionBlockMetadata, ExecutionQueryRequest, ExecutionQueryResponse, ReadOnlyExecutionRequest,
 };
 use crate::ExecutionError;
-use crate::{ExecutionAddressInfo, ReadOnlyExecutionOutput};
+use crate::{ExecutionAddressInfo, ExecutionOutput, GasEstimation, ReadOnlyExecutionOutput};
 use massa_models::address::Address;
 use massa_models::amount::Amount;
 use massa_models::block_id::BlockId;
 use massa_models::denunciation::DenunciationIndex;
-use massa_models::execution::EventFilter;
-use massa_models::operation::OperationId;
+use massa_models::execution::{
+    AddressHistoryEntry, AsyncMessageEntry, AsyncMessageFilter, EventFilter, ExecutionTrace,
+    ExecutionTraceId,
+};
+use massa_models::operation::{OperationId, OperationType};
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashMap;
 use massa_models::slot::Slot;
+use massa_models::state_proof::StateProof;
 use massa_models::stats::ExecutionStats;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
@@ -94,6 +98,22 @@ pub trait ExecutionController: Send + Sync {
         req: ReadOnlyExecutionRequest,
     ) -> Result<ReadOnlyExecutionOutput, ExecutionError>;
 
+    /// Estimate the gas limit of a `CallSC` or `ExecuteSC` operation,
+    /// by executing it in read-only mode against the candidate state with different gas limits
+    ///
+    /// # arguments
+    /// * `sender_addr`: address of the sender of the operation
+    /// * `op`: content of the operation, its gas limit is ignored
+    ///
+    /// # returns
+    /// The lowest gas limit with which the operation succeeds, and the recommended gas limit,
+    /// or an error if the operation fails even with the highest gas limit allowed.
+    fn estimate_gas(
+        &self,
+        sender_addr: Address,
+        op: OperationType,
+    ) -> Result<GasEstimation, ExecutionError>;
+
     /// Check if a denunciation has been executed given a `DenunciationIndex`
     /// (speculative, final)
     fn get_denunciation_execution_status(
@@ -104,6 +124,36 @@ pub trait ExecutionController: Send + Sync {
     /// Gets information about a batch of addresses
     fn get_addresses_infos(&self, addresses: &[Address]) -> Vec<ExecutionAddressInfo>;
 
+    /// Get at most `max_count` of the latest final executed operations involving an address, most recent first.
+    /// Returns an empty list if the address history is not recorded.
+    fn get_address_history(&self, address: &Address, max_count: usize) -> Vec<AddressHistoryEntry>;
+
+    /// Get the messages of the final asynchronous pool matching a filter, in priority order,
+    /// with their status at the slot following the last final slot.
+    /// Returns the number of matching messages, and at most `max_count` of them starting at `offset`.
+    fn get_async_messages(
+        &self,
+        filter: &AsyncMessageFilter,
+        offset: usize,
+        max_count: usize,
+    ) -> (usize, Vec<AsyncMessageEntry>);
+
+    /// Get at most `max_count` journaled final execution outputs, in slot order, starting at `start_slot` (inclusive).
+    /// Slots missing from the journal are skipped. Returns an empty list if the outputs are not journaled.
+    fn get_final_execution_outputs(
+        &self,
+        start_slot: Slot,
+        max_count: usize,
+    ) -> Vec<ExecutionOutput>;
+
+    /// Get the proof of inclusion (or exclusion) of a raw key in the final state.
+    /// Returns None if the node does not maintain the state tree.
+    fn get_state_proof(&self, key: &[u8]) -> Option<StateProof>;
+
+    /// Get the traces of executed operations and asynchronous messages, candidate or final.
+    /// Returns one optional trace per id, `None` if the execution was not traced or its trace was dropped.
+    fn get_execution_traces(&self, ids: &[ExecutionTraceId]) -> Vec<Option<ExecutionTrace>>;
+
     /// Get execution statistics
     fn get_stats(&self) -> ExecutionStats;
 
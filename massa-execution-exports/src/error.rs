@@ -74,4 +74,6 @@ pub enum ExecutionError {
 pub enum ExecutionQueryError {
     /// Not found: {0}
     NotFound(String),
+    /// Unsupported: {0}
+    Unsupported(String),
 }
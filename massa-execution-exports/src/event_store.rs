@@ -56,49 +56,67 @@ impl EventStore {
     pub fn get_filtered_sc_output_events(&self, filter: &EventFilter) -> VecDeque<SCOutputEvent> {
         self.0
             .iter()
-            .filter(|x| {
-                if let Some(start) = filter.start {
-                    if x.context.slot < start {
-                        return false;
-                    }
-                }
-                if let Some(end) = filter.end {
-                    if x.context.slot >= end {
-                        return false;
-                    }
-                }
-                if let Some(is_final) = filter.is_final {
-                    if x.context.is_final != is_final {
-                        return false;
-                    }
-                }
-                if let Some(is_error) = filter.is_error {
-                    if x.context.is_error != is_error {
-                        return false;
-                    }
-                }
-                match (filter.emitter_address, x.context.call_stack.front()) {
-                    (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
-                    (Some(_), None) => return false,
-                    _ => (),
-                }
-                match (filter.original_caller_address, x.context.call_stack.back()) {
-                    (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
-                    (Some(_), None) => return false,
-                    _ => (),
-                }
-                match (filter.original_operation_id, x.context.origin_operation_id) {
-                    (Some(addr1), Some(addr2)) if addr1 != addr2 => return false,
-                    (Some(_), None) => return false,
-                    _ => (),
-                }
-                true
-            })
+            .filter(|x| event_matches_filter(x, filter))
             .cloned()
             .collect()
     }
 }
 
+/// Checks whether an event matches a filter on:
+/// * start slot
+/// * end slot
+/// * emitter address
+/// * original caller address
+/// * operation id
+/// * is final
+/// * is error
+///
+/// Note that pagination parameters (`offset` and `limit`) are ignored here.
+pub fn event_matches_filter(event: &SCOutputEvent, filter: &EventFilter) -> bool {
+    if let Some(start) = filter.start {
+        if event.context.slot < start {
+            return false;
+        }
+    }
+    if let Some(end) = filter.end {
+        if event.context.slot >= end {
+            return false;
+        }
+    }
+    if let Some(is_final) = filter.is_final {
+        if event.context.is_final != is_final {
+            return false;
+        }
+    }
+    if let Some(is_error) = filter.is_error {
+        if event.context.is_error != is_error {
+            return false;
+        }
+    }
+    match (filter.emitter_address, event.context.call_stack.front()) {
+        (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
+        (Some(_), None) => return false,
+        _ => (),
+    }
+    match (
+        filter.original_caller_address,
+        event.context.call_stack.back(),
+    ) {
+        (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
+        (Some(_), None) => return false,
+        _ => (),
+    }
+    match (
+        filter.original_operation_id,
+        event.context.origin_operation_id,
+    ) {
+        (Some(addr1), Some(addr2)) if addr1 != addr2 => return false,
+        (Some(_), None) => return false,
+        _ => (),
+    }
+    true
+}
+
 #[test]
 fn test_prune() {
     use massa_models::output_event::{EventExecutionContext, SCOutputEvent};
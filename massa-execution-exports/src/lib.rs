@@ -58,15 +58,16 @@ pub use channels::ExecutionChannels;
 pub use controller_traits::MockExecutionController;
 pub use controller_traits::{ExecutionController, ExecutionManager};
 pub use error::{ExecutionError, ExecutionQueryError};
-pub use event_store::EventStore;
+pub use event_store::{event_matches_filter, EventStore};
 pub use massa_sc_runtime::GasCosts;
 pub use settings::{ExecutionConfig, StorageCostsConstants};
 pub use types::{
     ExecutedBlockInfo, ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionOutput,
     ExecutionQueryCycleInfos, ExecutionQueryExecutionStatus, ExecutionQueryRequest,
     ExecutionQueryRequestItem, ExecutionQueryResponse, ExecutionQueryResponseItem,
-    ExecutionQueryStakerInfo, ExecutionStackElement, ReadOnlyCallRequest, ReadOnlyExecutionOutput,
-    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget, SlotExecutionOutput,
+    ExecutionQueryStakerInfo, ExecutionStackElement, GasEstimation, ReadOnlyCallRequest,
+    ReadOnlyExecutionOutput, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+    SlotExecutionOutput,
 };
 
 #[cfg(any(feature = "testing", feature = "gas_calibration"))]
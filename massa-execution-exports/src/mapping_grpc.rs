@@ -18,8 +18,11 @@ use massa_proto_rs::massa::api::v1 as grpc_api;
 use massa_proto_rs::massa::model::v1 as grpc_model;
 
 /// Convert a `grpc_api::ScExecutionEventsRequest` to a `ScExecutionEventsRequest`
+///
+/// `max_events` bounds the number of events returned by an events query
 pub fn to_querystate_filter(
     query: grpc_api::ExecutionQueryRequestItem,
+    max_events: usize,
 ) -> Result<ExecutionQueryRequestItem, ModelsError> {
     if let Some(item) = query.request_item {
         match item {
@@ -131,7 +134,7 @@ pub fn to_querystate_filter(
                 })
             }
             exec::RequestItem::Events(value) => {
-                let event_filter = to_event_filter(value.filters)?;
+                let event_filter = to_event_filter(value.filters, max_events)?;
                 Ok(ExecutionQueryRequestItem::Events(event_filter))
             }
         }
@@ -141,10 +144,17 @@ pub fn to_querystate_filter(
 }
 
 /// Convert a vector of `grpc_model::ScExecutionEventsFilter` to a `EventFilter`
+///
+/// The gRPC filters carry no pagination: at most `max_events` events are returned,
+/// and the following ones can be fetched by moving the start of the slot range forward.
 pub fn to_event_filter(
     sce_filters: Vec<grpc_api::ScExecutionEventsFilter>,
+    max_events: usize,
 ) -> Result<EventFilter, ModelsError> {
-    let mut event_filter = EventFilter::default();
+    let mut event_filter = EventFilter {
+        limit: Some(max_events),
+        ..Default::default()
+    };
     for query in sce_filters {
         if let Some(filter) = query.filter {
             match filter {
@@ -349,6 +359,10 @@ impl From<ExecutionQueryError> for grpc_model::Error {
                 code: 404,
                 message: error,
             },
+            ExecutionQueryError::Unsupported(error) => grpc_model::Error {
+                code: 400,
+                message: error,
+            },
         }
     }
 }
@@ -26,6 +26,20 @@ pub struct ExecutionConfig {
     pub readonly_queue_length: usize,
     /// maximum number of SC output events kept in cache
     pub max_final_events: usize,
+    /// path to the persistent final event store. If None, final events are only kept in memory.
+    pub event_store_path: Option<PathBuf>,
+    /// number of periods during which final events are kept in the persistent event store
+    pub event_store_retention_periods: u64,
+    /// path to the per-address history of final executed operations. If None, the history is not recorded.
+    pub address_history_path: Option<PathBuf>,
+    /// path to the journal of final execution outputs replayed to the stream consumers. If None, outputs are not journaled.
+    pub output_journal_path: Option<PathBuf>,
+    /// number of periods during which final execution outputs are kept in the journal
+    pub output_journal_retention_periods: u64,
+    /// number of periods during which the final ledger can be read at a past final slot. Zero disables historical reads.
+    pub historical_ledger_periods: u64,
+    /// maximum number of traces of final executions kept in memory. If None, executions are not traced.
+    pub max_final_execution_traces: Option<usize>,
     /// maximum available gas for asynchronous messages execution
     pub max_async_gas: u64,
     /// maximum gas per block
@@ -22,6 +22,13 @@ impl Default for ExecutionConfig {
         Self {
             readonly_queue_length: 100,
             max_final_events: 1000,
+            event_store_path: None,
+            event_store_retention_periods: 1000,
+            address_history_path: None,
+            output_journal_path: None,
+            output_journal_retention_periods: 1000,
+            historical_ledger_periods: 0,
+            max_final_execution_traces: None,
             max_async_gas: MAX_ASYNC_GAS,
             thread_count: THREAD_COUNT,
             roll_price: ROLL_PRICE,
@@ -5,7 +5,7 @@
 use crate::types::{ExecutionQueryRequest, ExecutionQueryResponse};
 use crate::{
     ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionController, ExecutionError,
-    ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ExecutionOutput, GasEstimation, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
 };
 use massa_ledger_exports::LedgerEntry;
 use massa_models::denunciation::DenunciationIndex;
@@ -13,11 +13,15 @@ use massa_models::{
     address::Address,
     amount::Amount,
     block_id::BlockId,
-    execution::EventFilter,
-    operation::OperationId,
+    execution::{
+        AddressHistoryEntry, AsyncMessageEntry, AsyncMessageFilter, EventFilter, ExecutionTrace,
+        ExecutionTraceId,
+    },
+    operation::{OperationId, OperationType},
     output_event::SCOutputEvent,
     prehash::{PreHashMap, PreHashSet},
     slot::Slot,
+    state_proof::StateProof,
     stats::ExecutionStats,
 };
 use massa_time::MassaTime;
@@ -195,6 +199,39 @@ impl ExecutionController for MockExecutionController {
         Vec::default()
     }
 
+    fn get_address_history(
+        &self,
+        _address: &Address,
+        _max_count: usize,
+    ) -> Vec<AddressHistoryEntry> {
+        Vec::default()
+    }
+
+    fn get_async_messages(
+        &self,
+        _filter: &AsyncMessageFilter,
+        _offset: usize,
+        _max_count: usize,
+    ) -> (usize, Vec<AsyncMessageEntry>) {
+        (0, Vec::default())
+    }
+
+    fn get_final_execution_outputs(
+        &self,
+        _start_slot: Slot,
+        _max_count: usize,
+    ) -> Vec<ExecutionOutput> {
+        Vec::default()
+    }
+
+    fn get_state_proof(&self, _key: &[u8]) -> Option<StateProof> {
+        None
+    }
+
+    fn get_execution_traces(&self, ids: &[ExecutionTraceId]) -> Vec<Option<ExecutionTrace>> {
+        vec![None; ids.len()]
+    }
+
     fn get_cycle_active_rolls(&self, _cycle: u64) -> BTreeMap<Address, u64> {
         BTreeMap::default()
     }
@@ -211,6 +248,16 @@ impl ExecutionController for MockExecutionController {
         response_rx.recv().unwrap()
     }
 
+    fn estimate_gas(
+        &self,
+        _sender_addr: Address,
+        _op: OperationType,
+    ) -> Result<GasEstimation, ExecutionError> {
+        Err(ExecutionError::RuntimeError(
+            "gas estimation is not supported by the mock".to_string(),
+        ))
+    }
+
     fn get_denunciation_execution_status(
         &self,
         denunciation_index: &DenunciationIndex,
@@ -10,8 +10,8 @@ use massa_models::block_id::BlockId;
 use massa_models::bytecode::Bytecode;
 use massa_models::datastore::Datastore;
 use massa_models::denunciation::DenunciationIndex;
-use massa_models::execution::EventFilter;
-use massa_models::operation::OperationId;
+use massa_models::execution::{EventFilter, ExecutionTrace};
+use massa_models::operation::{OperationId, OperationType};
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
 use massa_models::{
@@ -34,6 +34,9 @@ pub struct ExecutionBlockMetadata {
 pub struct ExecutionQueryRequest {
     /// List of requests
     pub requests: Vec<ExecutionQueryRequestItem>,
+    /// Optional past final slot at which the final ledger items (existence, balance, bytecode, datastore) are read.
+    /// The other items are answered with an error when it is set.
+    pub at_slot: Option<Slot>,
 }
 
 /// Response to a list of execution queries
@@ -222,6 +225,9 @@ pub struct ExecutedBlockInfo {
     pub current_version: u32,
     /// Announced network version (see Versioning doc)
     pub announced_version: Option<u32>,
+    /// Addresses involved in each operation of the block, in block order.
+    /// Only filled when the address history is recorded.
+    pub operations_involved_addresses: Vec<(OperationId, PreHashSet<Address>)>,
 }
 
 /// structure describing the output of a single execution
@@ -235,6 +241,8 @@ pub struct ExecutionOutput {
     pub state_changes: StateChanges,
     /// events emitted by the execution step
     pub events: EventStore,
+    /// traces of the operations and asynchronous messages executed by the execution step, if executions are traced
+    pub traces: Vec<ExecutionTrace>,
 }
 
 /// structure describing the output of a read only execution
@@ -246,6 +254,20 @@ pub struct ReadOnlyExecutionOutput {
     pub gas_cost: u64,
     /// Returned value from the module call
     pub call_result: Vec<u8>,
+    /// Balances of the addresses whose balance was changed by the execution, before and after it
+    pub balance_changes: BTreeMap<Address, (Amount, Amount)>,
+    /// Error that made a simulated operation fail once its fee was paid.
+    /// Its changes were then reverted, except for the fee and the error event.
+    pub execution_error: Option<ExecutionError>,
+}
+
+/// structure describing the result of a gas estimation
+#[derive(Debug, Clone)]
+pub struct GasEstimation {
+    /// Lowest gas limit with which the operation succeeds
+    pub min_gas: u64,
+    /// Gas limit to use, with a safety margin against state changes before the operation is included
+    pub recommended_gas: u64,
 }
 
 /// structure describing different types of read-only execution request
@@ -261,6 +283,9 @@ pub struct ReadOnlyExecutionRequest {
     ///
     /// Whether to start execution from final or active state
     pub is_final: bool,
+    /// Optional past final slot from which to start execution, overriding `is_final`.
+    /// Only the ledger is read at that slot, the rest of the state is read at the latest final slot.
+    pub at_slot: Option<Slot>,
 }
 
 /// structure describing different possible targets of a read-only execution request
@@ -278,6 +303,18 @@ pub enum ReadOnlyExecutionTarget {
         /// Parameter to pass to the target function
         parameter: Vec<u8>,
     },
+
+    /// Execute an unsigned operation as if it was included in a block,
+    /// through the same path as the operations of a block.
+    /// The call stack of the request is ignored: the operation sets its own.
+    Operation {
+        /// Sender of the operation
+        sender_addr: Address,
+        /// Fee paid by the sender
+        fee: Amount,
+        /// Content of the operation
+        op: OperationType,
+    },
 }
 
 /// structure describing a read-only call
@@ -0,0 +1,187 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module implements a persistent per-address history of final executed operations.
+//!
+//! Each final executed operation is recorded once for every address it involves,
+//! under a key made of the address followed by the execution slot and the index of the operation in its block,
+//! so that the most recent history of an address can be listed with a reverse scan of its prefix.
+
+use massa_executed_ops::ExecutedOpsChanges;
+use massa_execution_exports::ExecutedBlockInfo;
+use massa_models::address::{Address, AddressSerializer};
+use massa_models::execution::AddressHistoryEntry;
+use massa_models::slot::{Slot, SLOT_KEY_SIZE};
+use massa_serialization::Serializer;
+use rocksdb::{Direction, IteratorMode, WriteBatch, DB};
+use std::path::PathBuf;
+
+const OPEN_ERROR: &str = "critical: address history rocksdb open operation failed";
+const CRUD_ERROR: &str = "critical: address history rocksdb crud operation failed";
+const ENTRY_SER_ERROR: &str = "critical: address history entry serialization failed";
+const ENTRY_DESER_ERROR: &str = "critical: address history entry deserialization failed";
+const KEY_SER_ERROR: &str = "critical: address history key serialization failed";
+
+/// Persistent per-address history of final executed operations
+pub(crate) struct AddressHistoryDB {
+    /// RocksDB database
+    db: DB,
+    /// Address serializer used to build the keys
+    address_serializer: AddressSerializer,
+}
+
+impl AddressHistoryDB {
+    /// Open (or create) the address history database
+    ///
+    /// # Arguments
+    /// * path: where to store the db
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            db: DB::open_default(path).expect(OPEN_ERROR),
+            address_serializer: AddressSerializer::new(),
+        }
+    }
+
+    /// Serialized address, used as key prefix
+    fn address_prefix(&self, address: &Address) -> Vec<u8> {
+        let mut prefix = Vec::new();
+        self.address_serializer
+            .serialize(address, &mut prefix)
+            .expect(KEY_SER_ERROR);
+        prefix
+    }
+
+    /// Record the operations executed in a final block
+    ///
+    /// # Arguments
+    /// * `slot`: slot of the final block
+    /// * `block_info`: executed block info, holding the addresses involved in each operation of the block
+    /// * `executed_ops_changes`: execution status of the operations executed at that slot
+    pub fn insert_executed_operations(
+        &self,
+        slot: &Slot,
+        block_info: &ExecutedBlockInfo,
+        executed_ops_changes: &ExecutedOpsChanges,
+    ) {
+        let mut batch = WriteBatch::default();
+        for (index, (operation_id, addresses)) in
+            block_info.operations_involved_addresses.iter().enumerate()
+        {
+            // operations that were not executed (e.g. invalid ones) are not part of any history
+            let success = match executed_ops_changes.get(operation_id) {
+                Some((success, _)) => success,
+                None => continue,
+            };
+            let entry = AddressHistoryEntry {
+                operation_id: *operation_id,
+                block_id: block_info.block_id,
+                slot: *slot,
+                success: *success,
+            };
+            let value = serde_json::to_vec(&entry).expect(ENTRY_SER_ERROR);
+            for address in addresses {
+                let mut key = self.address_prefix(address);
+                key.extend(slot.to_bytes_key());
+                key.extend((index as u32).to_be_bytes());
+                batch.put(key, &value);
+            }
+        }
+        self.db.write(batch).expect(CRUD_ERROR);
+    }
+
+    /// Get the latest final executed operations involving an address, most recent first
+    ///
+    /// # Arguments
+    /// * `address`: address whose history is listed
+    /// * `max_count`: maximum number of entries to read
+    pub fn get_address_history(
+        &self,
+        address: &Address,
+        max_count: usize,
+    ) -> Vec<AddressHistoryEntry> {
+        let prefix = self.address_prefix(address);
+        // every key of the address is lower than its prefix followed by the highest slot and index
+        let mut last_key = prefix.clone();
+        last_key.extend([u8::MAX; SLOT_KEY_SIZE + 4]);
+        self.db
+            .iterator(IteratorMode::From(&last_key, Direction::Reverse))
+            .map(|item| item.expect(CRUD_ERROR))
+            .take_while(|(key, _)| key.starts_with(&prefix))
+            .take(max_count)
+            .map(|(_, value)| {
+                serde_json::from_slice::<AddressHistoryEntry>(&value).expect(ENTRY_DESER_ERROR)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_models::block_id::BlockId;
+    use massa_models::operation::OperationId;
+    use massa_models::prehash::PreHashSet;
+    use std::str::FromStr;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_address_history_db() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = AddressHistoryDB::new(temp_dir.path().to_path_buf());
+
+        let addr_a =
+            Address::from_str("AU12hgh5ULW9o8fJE9muLNXhQENaUUswQbxPyDSq8ridnDGu5gRiJ").unwrap();
+        let addr_b =
+            Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+        let block_id =
+            BlockId::from_str("B1q4CBcuYo8YANEV34W4JRWVHrzcYns19VJfyAB7jT4qfitAnMC").unwrap();
+        let op_1 =
+            OperationId::from_str("O1q4CBcuYo8YANEV34W4JRWVHrzcYns19VJfyAB7jT4qfitAnMC").unwrap();
+        let op_2 =
+            OperationId::from_str("O12Lb8bzzFrCMgVf3Zy3KYxJNGNbjJVRjY3xNZAwYpE4A3Vgwa4").unwrap();
+
+        let block_info = ExecutedBlockInfo {
+            block_id,
+            current_version: 0,
+            announced_version: None,
+            operations_involved_addresses: vec![
+                (op_1, PreHashSet::from_iter([addr_a, addr_b])),
+                (op_2, PreHashSet::from_iter([addr_a])),
+            ],
+        };
+        let slot = Slot::new(1, 0);
+        let mut executed_ops_changes = ExecutedOpsChanges::default();
+        executed_ops_changes.insert(op_1, (true, Slot::new(10, 0)));
+        executed_ops_changes.insert(op_2, (false, Slot::new(10, 0)));
+        db.insert_executed_operations(&slot, &block_info, &executed_ops_changes);
+
+        let history_a = db.get_address_history(&addr_a, usize::MAX);
+        assert_eq!(
+            history_a
+                .iter()
+                .map(|entry| (entry.operation_id, entry.success))
+                .collect::<Vec<_>>(),
+            vec![(op_2, false), (op_1, true)]
+        );
+        let history_b = db.get_address_history(&addr_b, usize::MAX);
+        assert_eq!(history_b.len(), 1);
+        assert_eq!(history_b[0].operation_id, op_1);
+        assert_eq!(history_b[0].slot, slot);
+
+        // a later block: only the latest entries are read when the count is limited
+        let later_block_info = ExecutedBlockInfo {
+            operations_involved_addresses: vec![(op_1, PreHashSet::from_iter([addr_a]))],
+            ..block_info
+        };
+        let later_slot = Slot::new(2, 0);
+        db.insert_executed_operations(&later_slot, &later_block_info, &executed_ops_changes);
+        let latest_a = db.get_address_history(&addr_a, 2);
+        assert_eq!(
+            latest_a
+                .iter()
+                .map(|entry| (entry.operation_id, entry.slot))
+                .collect::<Vec<_>>(),
+            vec![(op_1, later_slot), (op_2, slot)]
+        );
+        assert_eq!(db.get_address_history(&addr_b, 2).len(), 1);
+    }
+}
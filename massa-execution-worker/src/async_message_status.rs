@@ -0,0 +1,186 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module computes the status of the messages of an asynchronous pool at a given slot.
+//!
+//! The messages are selected as in `SpeculativeAsyncPool::take_batch_to_execute`:
+//! in priority order, each message that is valid and can be executed is taken if it fits in the remaining gas.
+//! Only the in-memory message infos are used, so that the messages are read from the database
+//! only once they are known to be returned.
+
+use massa_async_pool::{AsyncMessageId, AsyncMessageInfo};
+use massa_models::execution::{AsyncMessageFilter, AsyncMessageStatus};
+use massa_models::slot::Slot;
+
+/// Status of a message at a slot, and its rank in the batch executed at that slot if it is executed
+pub(crate) type AsyncMessageSelection = (AsyncMessageStatus, Option<u64>);
+
+/// Compute the status at `slot` of the messages of an asynchronous pool, given in priority order
+///
+/// # Arguments
+/// * `message_infos`: infos of the messages of the pool, in priority order
+/// * `slot`: slot at which the batch of messages is executed
+/// * `max_gas`: gas available to the messages at that slot
+pub(crate) fn get_async_message_statuses<'a>(
+    message_infos: impl Iterator<Item = (&'a AsyncMessageId, &'a AsyncMessageInfo)>,
+    slot: Slot,
+    max_gas: u64,
+) -> Vec<(
+    &'a AsyncMessageId,
+    &'a AsyncMessageInfo,
+    AsyncMessageSelection,
+)> {
+    let mut available_gas = max_gas;
+    let mut execution_rank = 0;
+    message_infos
+        .map(|(message_id, message_info)| {
+            let selection = if slot < message_info.validity_start {
+                (AsyncMessageStatus::Pending, None)
+            } else if slot >= message_info.validity_end {
+                (AsyncMessageStatus::Expired, None)
+            } else if !message_info.can_be_executed {
+                (AsyncMessageStatus::WaitingTrigger, None)
+            } else if available_gas >= message_info.max_gas {
+                available_gas -= message_info.max_gas;
+                execution_rank += 1;
+                (AsyncMessageStatus::Executable, Some(execution_rank - 1))
+            } else {
+                // skipped by the batch: the following messages may still fit in the remaining gas
+                (AsyncMessageStatus::Starved, None)
+            };
+            (message_id, message_info, selection)
+        })
+        .collect()
+}
+
+/// Whether a message of an asynchronous pool matches a filter
+pub(crate) fn matches_async_message_filter(
+    filter: &AsyncMessageFilter,
+    message_id: &AsyncMessageId,
+    message_info: &AsyncMessageInfo,
+) -> bool {
+    let (_, emission_slot, emission_index) = message_id;
+    filter
+        .emission_slot
+        .map_or(true, |filter_slot| filter_slot == *emission_slot)
+        && filter
+            .emission_index
+            .map_or(true, |filter_index| filter_index == *emission_index)
+        && filter
+            .sender
+            .map_or(true, |sender| sender == message_info.sender)
+        && filter
+            .destination
+            .map_or(true, |destination| destination == message_info.destination)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_async_pool::{AsyncMessage, AsyncMessageTrigger};
+    use massa_models::address::Address;
+    use massa_models::amount::Amount;
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+
+    fn message(
+        emission_index: u64,
+        sender: Address,
+        fee: u64,
+        max_gas: u64,
+        validity_start: u64,
+        trigger: Option<AsyncMessageTrigger>,
+    ) -> AsyncMessage {
+        AsyncMessage::new(
+            Slot::new(1, 0),
+            emission_index,
+            sender,
+            sender,
+            "handler".to_string(),
+            max_gas,
+            Amount::from_raw(fee),
+            Amount::zero(),
+            Slot::new(validity_start, 0),
+            Slot::new(20, 0),
+            Vec::new(),
+            trigger,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_async_message_statuses() {
+        let sender =
+            Address::from_str("AU12htxRWiEm8jDJpJptr6cwEhWNcCSFWstN1MLSa96DDkVM9Y42G").unwrap();
+        let other_sender =
+            Address::from_str("AU12hgh5ULW9o8fJE9muLNXhQENaUUswQbxPyDSq8ridnDGu5gRiJ").unwrap();
+        let trigger = AsyncMessageTrigger {
+            address: sender,
+            datastore_key: None,
+        };
+        // by decreasing fee per gas
+        let messages = [
+            message(0, sender, 600, 100, 1, None),
+            message(1, sender, 500, 100, 15, None),
+            message(2, sender, 400, 100, 1, Some(trigger)),
+            message(3, other_sender, 300, 100, 1, None),
+            message(4, sender, 200, 1000, 1, None),
+            message(5, sender, 100, 100, 1, None),
+        ];
+        let message_infos: BTreeMap<AsyncMessageId, AsyncMessageInfo> = messages
+            .iter()
+            .map(|message| (message.compute_id(), message.clone().into()))
+            .collect();
+
+        let statuses: Vec<AsyncMessageSelection> =
+            get_async_message_statuses(message_infos.iter(), Slot::new(10, 0), 500)
+                .into_iter()
+                .map(|(_, _, selection)| selection)
+                .collect();
+        // the starved message has no rank and does not shift the rank of the next executable one
+        assert_eq!(
+            statuses,
+            vec![
+                (AsyncMessageStatus::Executable, Some(0)),
+                (AsyncMessageStatus::Pending, None),
+                (AsyncMessageStatus::WaitingTrigger, None),
+                (AsyncMessageStatus::Executable, Some(1)),
+                (AsyncMessageStatus::Starved, None),
+                (AsyncMessageStatus::Executable, Some(2)),
+            ]
+        );
+
+        let statuses = get_async_message_statuses(message_infos.iter(), Slot::new(20, 0), 500);
+        assert!(statuses
+            .iter()
+            .all(|(_, _, selection)| *selection == (AsyncMessageStatus::Expired, None)));
+
+        let filter = AsyncMessageFilter {
+            sender: Some(sender),
+            emission_slot: Some(Slot::new(1, 0)),
+            ..Default::default()
+        };
+        let matching: Vec<u64> = message_infos
+            .iter()
+            .filter(|(message_id, message_info)| {
+                matches_async_message_filter(&filter, message_id, message_info)
+            })
+            .map(|((_, _, emission_index), _)| *emission_index)
+            .collect();
+        assert_eq!(matching, vec![0, 1, 2, 4, 5]);
+
+        let filter = AsyncMessageFilter {
+            destination: Some(other_sender),
+            emission_index: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(
+            message_infos
+                .iter()
+                .filter(|(message_id, message_info)| {
+                    matches_async_message_filter(&filter, message_id, message_info)
+                })
+                .count(),
+            1
+        );
+    }
+}
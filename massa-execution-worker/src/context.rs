@@ -27,6 +27,7 @@ use massa_models::address::ExecutionAddressCycleInfo;
 use massa_models::block_id::BlockIdSerializer;
 use massa_models::bytecode::Bytecode;
 use massa_models::denunciation::DenunciationIndex;
+use massa_models::execution::{CallTrace, ExecutionTrace, ExecutionTraceId, ExecutionTraceStep};
 use massa_models::timeslots::get_block_slot_timestamp;
 use massa_models::{
     address::Address,
@@ -89,6 +90,26 @@ pub struct ExecutionContextSnapshot {
     pub unsafe_rng: Xoshiro256PlusPlus,
 }
 
+/// Recorder of the trace of an ongoing execution
+struct ExecutionTraceRecorder {
+    /// traced execution
+    id: ExecutionTraceId,
+    /// calls in progress, the top-level call first
+    calls: Vec<CallTrace>,
+}
+
+impl ExecutionTraceRecorder {
+    /// Close the innermost call in progress, adding it to the steps of its caller
+    fn close_call(&mut self) {
+        if self.calls.len() > 1 {
+            let call = self.calls.pop().expect("empty call trace stack");
+            if let Some(caller) = self.calls.last_mut() {
+                caller.steps.push(ExecutionTraceStep::Call(call));
+            }
+        }
+    }
+}
+
 /// An execution context that needs to be initialized before executing bytecode,
 /// passed to the VM to interact with during bytecode execution (through ABIs),
 /// and read after execution to gather results.
@@ -172,6 +193,12 @@ pub struct ExecutionContext {
 
     /// Address factory
     pub address_factory: AddressFactory,
+
+    /// trace of the ongoing execution, if executions are traced
+    trace_recorder: Option<ExecutionTraceRecorder>,
+
+    /// traces of the executions that happened so far in the context
+    pub traces: Vec<ExecutionTrace>,
 }
 
 impl ExecutionContext {
@@ -235,6 +262,8 @@ impl ExecutionContext {
             config,
             address_factory: AddressFactory { mip_store },
             execution_trail_hash,
+            trace_recorder: None,
+            traces: Default::default(),
         }
     }
 
@@ -538,8 +567,16 @@ impl ExecutionContext {
     }
 
     /// gets the data from a datastore entry of an address if it exists in the speculative ledger, or returns None
-    pub fn get_data_entry(&self, address: &Address, key: &[u8]) -> Option<Vec<u8>> {
-        self.speculative_ledger.get_data_entry(address, key)
+    pub fn get_data_entry(&mut self, address: &Address, key: &[u8]) -> Option<Vec<u8>> {
+        let value = self.speculative_ledger.get_data_entry(address, key);
+        if self.trace_recorder.is_some() {
+            self.trace_step(ExecutionTraceStep::DatastoreRead {
+                address: *address,
+                key: key.to_vec(),
+                value: value.clone(),
+            });
+        }
+        value
     }
 
     /// checks if a datastore entry exists in the speculative ledger
@@ -574,9 +611,28 @@ impl ExecutionContext {
             )));
         }
 
+        // keep a copy of the entry for the trace, if the execution is traced
+        let traced_entry = self
+            .trace_recorder
+            .is_some()
+            .then(|| (key.clone(), data.clone()));
+
         // set data entry
-        self.speculative_ledger
-            .set_data_entry(&self.get_current_address()?, address, key, data)
+        let result = self.speculative_ledger.set_data_entry(
+            &self.get_current_address()?,
+            address,
+            key,
+            data,
+        );
+        if let Some((key, data)) = traced_entry {
+            self.trace_step(ExecutionTraceStep::DatastoreWrite {
+                address: *address,
+                key,
+                value: Some(data),
+                success: result.is_ok(),
+            });
+        }
+        result
     }
 
     /// Appends data to a datastore entry for an address in the speculative ledger.
@@ -615,9 +671,28 @@ impl ExecutionContext {
         // append data
         res_data.extend(data);
 
+        // keep a copy of the entry for the trace, if the execution is traced
+        let traced_entry = self
+            .trace_recorder
+            .is_some()
+            .then(|| (key.clone(), res_data.clone()));
+
         // set data entry
-        self.speculative_ledger
-            .set_data_entry(&self.get_current_address()?, address, key, res_data)
+        let result = self.speculative_ledger.set_data_entry(
+            &self.get_current_address()?,
+            address,
+            key,
+            res_data,
+        );
+        if let Some((key, res_data)) = traced_entry {
+            self.trace_step(ExecutionTraceStep::DatastoreWrite {
+                address: *address,
+                key,
+                value: Some(res_data),
+                success: result.is_ok(),
+            });
+        }
+        result
     }
 
     /// Deletes a datastore entry for an address.
@@ -640,8 +715,18 @@ impl ExecutionContext {
         }
 
         // delete entry
-        self.speculative_ledger
-            .delete_data_entry(&self.get_current_address()?, address, key)
+        let result =
+            self.speculative_ledger
+                .delete_data_entry(&self.get_current_address()?, address, key);
+        if self.trace_recorder.is_some() {
+            self.trace_step(ExecutionTraceStep::DatastoreWrite {
+                address: *address,
+                key: key.to_vec(),
+                value: None,
+                success: result.is_ok(),
+            });
+        }
+        result
     }
 
     /// Transfers coins from one address to another.
@@ -684,8 +769,16 @@ impl ExecutionContext {
         }
 
         // do the transfer
-        self.speculative_ledger
-            .transfer_coins(from_addr, to_addr, amount)
+        let result = self
+            .speculative_ledger
+            .transfer_coins(from_addr, to_addr, amount);
+        self.trace_step(ExecutionTraceStep::Transfer {
+            from: from_addr,
+            to: to_addr,
+            amount,
+            success: result.is_ok(),
+        });
+        result
     }
 
     /// Add a new asynchronous message to speculative pool
@@ -903,6 +996,7 @@ impl ExecutionContext {
             block_info,
             state_changes,
             events: std::mem::take(&mut self.events),
+            traces: std::mem::take(&mut self.traces),
         }
     }
 
@@ -971,9 +1065,85 @@ impl ExecutionContext {
         self.created_event_index += 1;
 
         // Add the event to the context store
+        if self.trace_recorder.is_some() {
+            self.trace_step(ExecutionTraceStep::Event(event.clone()));
+        }
         self.events.push(event);
     }
 
+    /// Starts tracing an execution, if executions are traced.
+    ///
+    /// # Arguments
+    /// * `id`: identifier of the traced execution
+    /// * `address`: address called by the top-level call
+    /// * `coins`: coins sent with the top-level call
+    pub fn trace_start(&mut self, id: ExecutionTraceId, address: Address, coins: Amount) {
+        if self.config.max_final_execution_traces.is_none() {
+            return;
+        }
+        self.trace_recorder = Some(ExecutionTraceRecorder {
+            id,
+            calls: vec![CallTrace {
+                address,
+                coins,
+                gas_used: None,
+                steps: Vec::new(),
+            }],
+        });
+    }
+
+    /// Records a step of the innermost traced call, if an execution is being traced
+    fn trace_step(&mut self, step: ExecutionTraceStep) {
+        if let Some(call) = self
+            .trace_recorder
+            .as_mut()
+            .and_then(|recorder| recorder.calls.last_mut())
+        {
+            call.steps.push(step);
+        }
+    }
+
+    /// Records the start of a nested call, if an execution is being traced
+    pub fn trace_call_start(&mut self, address: Address, coins: Amount) {
+        if let Some(recorder) = self.trace_recorder.as_mut() {
+            recorder.calls.push(CallTrace {
+                address,
+                coins,
+                gas_used: None,
+                steps: Vec::new(),
+            });
+        }
+    }
+
+    /// Records the end of a nested call, if an execution is being traced
+    pub fn trace_call_end(&mut self) {
+        if let Some(recorder) = self.trace_recorder.as_mut() {
+            recorder.close_call();
+        }
+    }
+
+    /// Finishes tracing the ongoing execution, if any, and keeps its trace in the context.
+    /// The calls interrupted by an error are closed.
+    ///
+    /// # Arguments
+    /// * `gas_used`: gas used by the whole execution
+    /// * `error`: error that made the execution fail, if any
+    pub fn trace_finish(&mut self, gas_used: u64, error: Option<String>) {
+        if let Some(mut recorder) = self.trace_recorder.take() {
+            while recorder.calls.len() > 1 {
+                recorder.close_call();
+            }
+            let mut call = recorder.calls.pop().expect("empty call trace stack");
+            call.gas_used = Some(gas_used);
+            self.traces.push(ExecutionTrace {
+                id: recorder.id,
+                slot: self.slot,
+                error,
+                call,
+            });
+        }
+    }
+
     /// Check if an operation was previously executed (to prevent reuse)
     pub fn is_op_executed(&self, op_id: &OperationId) -> bool {
         self.speculative_executed_ops.is_op_executed(op_id)
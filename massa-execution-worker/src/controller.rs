@@ -3,21 +3,32 @@
 //! This module implements an execution controller.
 //! See `massa-execution-exports/controller_traits.rs` for functional details.
 
+use crate::context::ExecutionContext;
 use crate::execution::ExecutionState;
 use crate::request_queue::{RequestQueue, RequestWithResponseSender};
 use massa_channel::MassaChannel;
 use massa_execution_exports::{
     ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionConfig, ExecutionController,
-    ExecutionError, ExecutionManager, ExecutionQueryError, ExecutionQueryExecutionStatus,
-    ExecutionQueryRequest, ExecutionQueryRequestItem, ExecutionQueryResponse,
-    ExecutionQueryResponseItem, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ExecutionError, ExecutionManager, ExecutionOutput, ExecutionQueryError,
+    ExecutionQueryExecutionStatus, ExecutionQueryRequest, ExecutionQueryRequestItem,
+    ExecutionQueryResponse, ExecutionQueryResponseItem, GasEstimation, ReadOnlyExecutionOutput,
+    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
 };
+use massa_models::config::GAS_ESTIMATION_MARGIN_PERCENT;
 use massa_models::denunciation::DenunciationIndex;
-use massa_models::execution::EventFilter;
+use massa_models::execution::{
+    AddressHistoryEntry, AsyncMessageEntry, AsyncMessageFilter, EventFilter, ExecutionTrace,
+    ExecutionTraceId,
+};
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashMap;
+use massa_models::state_proof::StateProof;
 use massa_models::stats::ExecutionStats;
-use massa_models::{address::Address, amount::Amount, operation::OperationId};
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    operation::{OperationId, OperationType},
+};
 use massa_models::{block_id::BlockId, slot::Slot};
 use parking_lot::{Condvar, Mutex, RwLock};
 use std::collections::{BTreeMap, HashMap};
@@ -88,6 +99,86 @@ impl ExecutionInputData {
     }
 }
 
+/// Answers a final ledger query item at a past final slot
+///
+/// # Arguments
+/// * `context`: read-only context reading the final ledger at `slot`, None if `slot` is out of the historical ledger window
+/// * `slot`: past final slot at which the ledger is read
+/// * `req_item`: query item
+fn query_historical_ledger(
+    context: Option<&mut ExecutionContext>,
+    slot: &Slot,
+    req_item: ExecutionQueryRequestItem,
+) -> Result<ExecutionQueryResponseItem, ExecutionQueryError> {
+    let context = context.ok_or_else(|| {
+        ExecutionQueryError::NotFound(format!("Historical ledger at slot {}", slot))
+    })?;
+    match req_item {
+        ExecutionQueryRequestItem::AddressExistsFinal(addr) => Ok(
+            ExecutionQueryResponseItem::Boolean(context.get_balance(&addr).is_some()),
+        ),
+        ExecutionQueryRequestItem::AddressBalanceFinal(addr) => match context.get_balance(&addr) {
+            Some(balance) => Ok(ExecutionQueryResponseItem::Amount(balance)),
+            None => Err(ExecutionQueryError::NotFound(format!("Account {}", addr))),
+        },
+        ExecutionQueryRequestItem::AddressBytecodeFinal(addr) => {
+            match context.get_bytecode(&addr) {
+                Some(bytecode) => Ok(ExecutionQueryResponseItem::Bytecode(bytecode)),
+                None => Err(ExecutionQueryError::NotFound(format!("Account {}", addr))),
+            }
+        }
+        ExecutionQueryRequestItem::AddressDatastoreKeysFinal { addr, prefix } => {
+            match context.get_keys(&addr, &prefix) {
+                Some(keys) => Ok(ExecutionQueryResponseItem::KeyList(keys)),
+                None => Err(ExecutionQueryError::NotFound(format!("Account {}", addr))),
+            }
+        }
+        ExecutionQueryRequestItem::AddressDatastoreValueFinal { addr, key } => {
+            match context.get_data_entry(&addr, &key) {
+                Some(value) => Ok(ExecutionQueryResponseItem::DatastoreValue(value)),
+                None => Err(ExecutionQueryError::NotFound(format!(
+                    "Account {} datastore entry {:?}",
+                    addr, key
+                ))),
+            }
+        }
+        _ => Err(ExecutionQueryError::Unsupported(format!(
+            "only final ledger items can be queried at slot {}",
+            slot
+        ))),
+    }
+}
+
+/// Search the lowest gas limit with which an execution succeeds.
+///
+/// `try_gas_limit` runs the execution with a given gas limit, and returns the gas it used if it succeeded.
+/// The execution must succeed with `max_gas`, and the lowest passing limit is then searched by dichotomy
+/// between the gas it used and `max_gas`. An outer error of `try_gas_limit` interrupts the search.
+pub(crate) fn search_min_gas_limit(
+    max_gas: u64,
+    mut try_gas_limit: impl FnMut(u64) -> Result<Result<u64, ExecutionError>, ExecutionError>,
+) -> Result<u64, ExecutionError> {
+    // the execution must succeed with the highest gas limit
+    let gas_used = try_gas_limit(max_gas)??;
+
+    // the lowest passing gas limit is between the gas used and the highest gas limit
+    let mut passing = max_gas;
+    if gas_used < max_gas && try_gas_limit(gas_used)?.is_ok() {
+        passing = gas_used;
+    } else {
+        let mut failing = gas_used;
+        while passing - failing > 1 {
+            let middle = failing + (passing - failing) / 2;
+            if try_gas_limit(middle)?.is_ok() {
+                passing = middle;
+            } else {
+                failing = middle;
+            }
+        }
+    }
+    Ok(passing)
+}
+
 #[derive(Clone)]
 /// implementation of the execution controller
 pub struct ExecutionControllerImpl {
@@ -138,6 +229,18 @@ impl ExecutionController for ExecutionControllerImpl {
             final_cursor: execution_lock.final_cursor,
             final_state_fingerprint: execution_lock.get_final_state_fingerprint(),
         };
+
+        // only the final ledger can be read at a past final slot
+        if let Some(slot) = req.at_slot {
+            let mut context = execution_lock.get_historical_ledger_context(&slot);
+            resp.responses = req
+                .requests
+                .into_iter()
+                .map(|req_item| query_historical_ledger(context.as_mut(), &slot, req_item))
+                .collect();
+            return resp;
+        }
+
         for req_item in req.requests {
             let resp_item = match req_item {
                 ExecutionQueryRequestItem::AddressExistsCandidate(addr) => {
@@ -419,6 +522,65 @@ impl ExecutionController for ExecutionControllerImpl {
         }
     }
 
+    /// Estimate the gas limit of a `CallSC` or `ExecuteSC` operation
+    /// by a binary search of the lowest gas limit with which its read-only execution succeeds.
+    /// Each attempt is a separate read-only request, so that slots keep being executed during the search.
+    fn estimate_gas(
+        &self,
+        sender_addr: Address,
+        op: OperationType,
+    ) -> Result<GasEstimation, ExecutionError> {
+        if !matches!(
+            op,
+            OperationType::CallSC { .. } | OperationType::ExecuteSC { .. }
+        ) {
+            return Err(ExecutionError::RuntimeError(
+                "gas can only be estimated for CallSC and ExecuteSC operations".to_string(),
+            ));
+        }
+        let max_gas = self.execution_state.read().get_max_readonly_operation_gas();
+
+        // execute the operation with a given gas limit, returns the gas used if it succeeds
+        let try_gas_limit =
+            |gas_limit: u64| -> Result<Result<u64, ExecutionError>, ExecutionError> {
+                let mut op = op.clone();
+                if let OperationType::CallSC { max_gas, .. }
+                | OperationType::ExecuteSC { max_gas, .. } = &mut op
+                {
+                    *max_gas = gas_limit;
+                }
+                match self.execute_readonly_request(ReadOnlyExecutionRequest {
+                    max_gas: gas_limit,
+                    call_stack: Vec::new(),
+                    target: ReadOnlyExecutionTarget::Operation {
+                        sender_addr,
+                        fee: Amount::zero(),
+                        op,
+                    },
+                    is_final: false,
+                    at_slot: None,
+                }) {
+                    Ok(output) => match output.execution_error {
+                        Some(err) => Ok(Err(err)),
+                        None => Ok(Ok(output.gas_cost)),
+                    },
+                    // the request could not be executed at all
+                    Err(err @ ExecutionError::ChannelError(_)) => Err(err),
+                    Err(err) => Ok(Err(err)),
+                }
+            };
+
+        let passing = search_min_gas_limit(max_gas, try_gas_limit)?;
+
+        Ok(GasEstimation {
+            min_gas: passing,
+            recommended_gas: std::cmp::min(
+                passing.saturating_add(passing.saturating_mul(GAS_ESTIMATION_MARGIN_PERCENT) / 100),
+                max_gas,
+            ),
+        })
+    }
+
     /// Check if a denunciation has been executed given a `DenunciationIndex`
     /// Returns a tuple of booleans: `(speculative_execution_status, final_execution_status)`
     fn get_denunciation_execution_status(
@@ -455,6 +617,50 @@ impl ExecutionController for ExecutionControllerImpl {
         res
     }
 
+    /// Get the latest final executed operations involving an address, most recent first
+    fn get_address_history(&self, address: &Address, max_count: usize) -> Vec<AddressHistoryEntry> {
+        self.execution_state
+            .read()
+            .get_address_history(address, max_count)
+    }
+
+    /// Get the messages of the final asynchronous pool matching a filter, in priority order
+    fn get_async_messages(
+        &self,
+        filter: &AsyncMessageFilter,
+        offset: usize,
+        max_count: usize,
+    ) -> (usize, Vec<AsyncMessageEntry>) {
+        self.execution_state
+            .read()
+            .get_async_messages(filter, offset, max_count)
+    }
+
+    /// Get at most `max_count` journaled final execution outputs, in slot order, starting at `start_slot`
+    fn get_final_execution_outputs(
+        &self,
+        start_slot: Slot,
+        max_count: usize,
+    ) -> Vec<ExecutionOutput> {
+        self.execution_state
+            .read()
+            .get_final_execution_outputs(start_slot, max_count)
+    }
+
+    /// Get the proof of inclusion (or exclusion) of a raw key in the final state.
+    /// Returns None if the node does not maintain the state tree.
+    fn get_state_proof(&self, key: &[u8]) -> Option<StateProof> {
+        self.execution_state.read().get_state_proof(key)
+    }
+
+    /// Get the traces of executed operations and asynchronous messages
+    fn get_execution_traces(&self, ids: &[ExecutionTraceId]) -> Vec<Option<ExecutionTrace>> {
+        let execution_state = self.execution_state.read();
+        ids.iter()
+            .map(|id| execution_state.get_execution_trace(id))
+            .collect()
+    }
+
     /// Get execution statistics
     fn get_stats(&self) -> ExecutionStats {
         self.execution_state.read().get_stats()
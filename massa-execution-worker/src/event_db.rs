@@ -0,0 +1,330 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module implements a persistent store for final execution events.
+//!
+//! Events are stored in a RocksDB instance, ordered by slot and index in slot,
+//! with secondary indexes on the emitter address, the original caller address and the origin operation id.
+//! Events older than a configurable number of periods are pruned.
+
+use massa_execution_exports::{event_matches_filter, EventStore};
+use massa_models::address::{Address, AddressSerializer};
+use massa_models::execution::EventFilter;
+use massa_models::operation::{OperationId, OperationIdSerializer};
+use massa_models::output_event::SCOutputEvent;
+use massa_models::slot::{Slot, SLOT_KEY_SIZE};
+use massa_serialization::Serializer;
+use rocksdb::{ColumnFamilyDescriptor, Direction, IteratorMode, Options, WriteBatch, DB};
+use std::path::PathBuf;
+
+const OPEN_ERROR: &str = "critical: event store rocksdb open operation failed";
+const CRUD_ERROR: &str = "critical: event store rocksdb crud operation failed";
+const CF_ERROR: &str = "critical: event store rocksdb column family operation failed";
+const EVENT_SER_ERROR: &str = "critical: event serialization failed";
+const EVENT_DESER_ERROR: &str = "critical: event deserialization failed";
+const KEY_SER_ERROR: &str = "critical: event index key serialization failed";
+
+const EVENTS_CF: &str = "events";
+const EMITTER_INDEX_CF: &str = "events_by_emitter";
+const CALLER_INDEX_CF: &str = "events_by_caller";
+const OPERATION_INDEX_CF: &str = "events_by_operation";
+
+/// Size of the primary key of an event: slot key followed by the index of the event in its slot
+const EVENT_KEY_SIZE: usize = SLOT_KEY_SIZE + 8;
+
+/// Primary key of an event, sortable by (slot, index in slot)
+fn event_key(event: &SCOutputEvent) -> [u8; EVENT_KEY_SIZE] {
+    let mut key = [0u8; EVENT_KEY_SIZE];
+    key[..SLOT_KEY_SIZE].copy_from_slice(&event.context.slot.to_bytes_key());
+    key[SLOT_KEY_SIZE..].copy_from_slice(&event.context.index_in_slot.to_be_bytes());
+    key
+}
+
+/// Extracts the slot from an event primary key
+fn slot_from_event_key(key: &[u8]) -> Slot {
+    let slot_key: &[u8; SLOT_KEY_SIZE] = key[..SLOT_KEY_SIZE]
+        .try_into()
+        .expect("critical: invalid event key length");
+    Slot::from_bytes_key(slot_key)
+}
+
+/// Which column family is used to iterate over the events matching a filter
+enum IndexSelection {
+    Emitter(Address),
+    Caller(Address),
+    Operation(OperationId),
+    Slot,
+}
+
+/// Persistent store for final execution events
+pub(crate) struct EventDB {
+    /// RocksDB database
+    db: DB,
+    /// Number of periods during which final events are kept
+    retention_periods: u64,
+    /// Address serializer used to build index keys
+    address_serializer: AddressSerializer,
+    /// Operation id serializer used to build index keys
+    operation_id_serializer: OperationIdSerializer,
+}
+
+impl EventDB {
+    /// Open (or create) a persistent event store
+    ///
+    /// # Arguments
+    /// * `path`: where to store the db
+    /// * `retention_periods`: number of periods during which final events are kept
+    pub fn new(path: PathBuf, retention_periods: u64) -> Self {
+        let mut db_opts = Options::default();
+        db_opts.create_if_missing(true);
+        db_opts.create_missing_column_families(true);
+
+        let db = DB::open_cf_descriptors(
+            &db_opts,
+            path,
+            vec![
+                ColumnFamilyDescriptor::new(EVENTS_CF, Options::default()),
+                ColumnFamilyDescriptor::new(EMITTER_INDEX_CF, Options::default()),
+                ColumnFamilyDescriptor::new(CALLER_INDEX_CF, Options::default()),
+                ColumnFamilyDescriptor::new(OPERATION_INDEX_CF, Options::default()),
+            ],
+        )
+        .expect(OPEN_ERROR);
+
+        Self {
+            db,
+            retention_periods,
+            address_serializer: AddressSerializer::new(),
+            operation_id_serializer: OperationIdSerializer::new(),
+        }
+    }
+
+    /// Index key prefix for an address
+    fn address_prefix(&self, address: &Address) -> Vec<u8> {
+        let mut prefix = Vec::new();
+        self.address_serializer
+            .serialize(address, &mut prefix)
+            .expect(KEY_SER_ERROR);
+        prefix
+    }
+
+    /// Index key prefix for an operation id
+    fn operation_prefix(&self, operation_id: &OperationId) -> Vec<u8> {
+        let mut prefix = Vec::new();
+        self.operation_id_serializer
+            .serialize(operation_id, &mut prefix)
+            .expect(KEY_SER_ERROR);
+        prefix
+    }
+
+    /// Secondary index entries (column family, key) of an event
+    fn index_keys(&self, event: &SCOutputEvent) -> Vec<(&'static str, Vec<u8>)> {
+        let key = event_key(event);
+        let mut index_keys = Vec::with_capacity(3);
+        if let Some(emitter) = event.context.call_stack.front() {
+            index_keys.push((
+                EMITTER_INDEX_CF,
+                [self.address_prefix(emitter).as_slice(), &key].concat(),
+            ));
+        }
+        if let Some(caller) = event.context.call_stack.back() {
+            index_keys.push((
+                CALLER_INDEX_CF,
+                [self.address_prefix(caller).as_slice(), &key].concat(),
+            ));
+        }
+        if let Some(operation_id) = event.context.origin_operation_id {
+            index_keys.push((
+                OPERATION_INDEX_CF,
+                [self.operation_prefix(&operation_id).as_slice(), &key].concat(),
+            ));
+        }
+        index_keys
+    }
+
+    /// Persist final events along with their index entries
+    pub fn insert_events(&self, events: &EventStore) {
+        let mut batch = WriteBatch::default();
+        let events_handle = self.db.cf_handle(EVENTS_CF).expect(CF_ERROR);
+        for event in events.0.iter() {
+            let value = serde_json::to_vec(event).expect(EVENT_SER_ERROR);
+            batch.put_cf(events_handle, event_key(event), value);
+            for (cf, index_key) in self.index_keys(event) {
+                batch.put_cf(self.db.cf_handle(cf).expect(CF_ERROR), index_key, b"");
+            }
+        }
+        self.db.write(batch).expect(CRUD_ERROR);
+    }
+
+    /// Remove the events that are older than the retention window relative to `final_slot`
+    pub fn prune(&self, final_slot: Slot) {
+        let min_period = final_slot.period.saturating_sub(self.retention_periods);
+        if min_period == 0 {
+            return;
+        }
+        let events_handle = self.db.cf_handle(EVENTS_CF).expect(CF_ERROR);
+        let mut batch = WriteBatch::default();
+        for (key, value) in self
+            .db
+            .iterator_cf(events_handle, IteratorMode::Start)
+            .flatten()
+        {
+            if slot_from_event_key(&key).period >= min_period {
+                break;
+            }
+            let event: SCOutputEvent = serde_json::from_slice(&value).expect(EVENT_DESER_ERROR);
+            for (cf, index_key) in self.index_keys(&event) {
+                batch.delete_cf(self.db.cf_handle(cf).expect(CF_ERROR), index_key);
+            }
+            batch.delete_cf(events_handle, key);
+        }
+        if !batch.is_empty() {
+            self.db.write(batch).expect(CRUD_ERROR);
+        }
+    }
+
+    /// Get an event from its primary key
+    fn get_event(&self, key: &[u8]) -> Option<SCOutputEvent> {
+        let events_handle = self.db.cf_handle(EVENTS_CF).expect(CF_ERROR);
+        self.db
+            .get_cf(events_handle, key)
+            .expect(CRUD_ERROR)
+            .map(|value| serde_json::from_slice(&value).expect(EVENT_DESER_ERROR))
+    }
+
+    /// Get the final events matching a filter, ordered by slot and index in slot.
+    ///
+    /// The most selective available index is used (operation id, then emitter, then caller)
+    /// and events are lazily read from disk, so that pagination does not load the whole store.
+    /// Pagination parameters of the filter are not applied here.
+    pub fn get_filtered_sc_output_events<'a>(
+        &'a self,
+        filter: &'a EventFilter,
+    ) -> Box<dyn Iterator<Item = SCOutputEvent> + 'a> {
+        if filter.is_final == Some(false) {
+            return Box::new(std::iter::empty());
+        }
+
+        let start_key = filter
+            .start
+            .map(|slot| slot.to_bytes_key().to_vec())
+            .unwrap_or_default();
+        let end = filter.end;
+
+        let selection = match (
+            filter.original_operation_id,
+            filter.emitter_address,
+            filter.original_caller_address,
+        ) {
+            (Some(operation_id), _, _) => IndexSelection::Operation(operation_id),
+            (None, Some(emitter), _) => IndexSelection::Emitter(emitter),
+            (None, None, Some(caller)) => IndexSelection::Caller(caller),
+            (None, None, None) => IndexSelection::Slot,
+        };
+
+        let (cf, prefix) = match selection {
+            IndexSelection::Operation(operation_id) => {
+                (OPERATION_INDEX_CF, self.operation_prefix(&operation_id))
+            }
+            IndexSelection::Emitter(address) => (EMITTER_INDEX_CF, self.address_prefix(&address)),
+            IndexSelection::Caller(address) => (CALLER_INDEX_CF, self.address_prefix(&address)),
+            IndexSelection::Slot => {
+                let events_handle = self.db.cf_handle(EVENTS_CF).expect(CF_ERROR);
+                return Box::new(
+                    self.db
+                        .iterator_cf(
+                            events_handle,
+                            IteratorMode::From(&start_key, Direction::Forward),
+                        )
+                        .flatten()
+                        .take_while(move |(key, _)| {
+                            end.map_or(true, |end| slot_from_event_key(key) < end)
+                        })
+                        .map(|(_, value)| {
+                            serde_json::from_slice::<SCOutputEvent>(&value)
+                                .expect(EVENT_DESER_ERROR)
+                        })
+                        .filter(move |event| event_matches_filter(event, filter)),
+                );
+            }
+        };
+
+        let handle = self.db.cf_handle(cf).expect(CF_ERROR);
+        let from_key = [prefix.as_slice(), &start_key].concat();
+        Box::new(
+            self.db
+                .iterator_cf(handle, IteratorMode::From(&from_key, Direction::Forward))
+                .flatten()
+                .take_while(move |(key, _)| key.starts_with(&prefix))
+                .map(move |(key, _)| key[key.len() - EVENT_KEY_SIZE..].to_vec())
+                .take_while(move |key| end.map_or(true, |end| slot_from_event_key(key) < end))
+                .filter_map(move |key| self.get_event(&key))
+                .filter(move |event| event_matches_filter(event, filter)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_models::output_event::EventExecutionContext;
+    use std::collections::VecDeque;
+    use std::str::FromStr;
+    use tempfile::TempDir;
+
+    fn event(slot: Slot, index_in_slot: u64, call_stack: Vec<Address>) -> SCOutputEvent {
+        SCOutputEvent {
+            context: EventExecutionContext {
+                slot,
+                block: None,
+                read_only: false,
+                index_in_slot,
+                call_stack: call_stack.into_iter().collect(),
+                origin_operation_id: None,
+                is_final: true,
+                is_error: false,
+            },
+            data: format!("{}-{}", slot, index_in_slot),
+        }
+    }
+
+    #[test]
+    fn test_event_db_index_and_prune() {
+        let dir = TempDir::new().unwrap();
+        let db = EventDB::new(dir.path().to_path_buf(), 10);
+        let addr_1 =
+            Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+        let addr_2 =
+            Address::from_str("AU12hgh5ULW9o8fJE9muLNXhQENaUUswQbxPyDSq8ridnDGu5gRiJ").unwrap();
+
+        let mut store = EventStore(VecDeque::new());
+        for period in 1..=20 {
+            store.push(event(Slot::new(period, 0), 0, vec![addr_1]));
+            store.push(event(Slot::new(period, 0), 1, vec![addr_2, addr_1]));
+        }
+        db.insert_events(&store);
+
+        let filter = EventFilter {
+            emitter_address: Some(addr_2),
+            start: Some(Slot::new(5, 0)),
+            end: Some(Slot::new(8, 0)),
+            ..Default::default()
+        };
+        let events: Vec<_> = db.get_filtered_sc_output_events(&filter).collect();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].data, "(period: 5, thread: 0)-1");
+
+        let filter = EventFilter {
+            original_caller_address: Some(addr_1),
+            ..Default::default()
+        };
+        assert_eq!(db.get_filtered_sc_output_events(&filter).count(), 40);
+
+        db.prune(Slot::new(20, 0));
+        assert_eq!(
+            db.get_filtered_sc_output_events(&EventFilter::default())
+                .count(),
+            22
+        );
+        assert_eq!(db.get_filtered_sc_output_events(&filter).count(), 22);
+    }
+}
@@ -9,8 +9,13 @@
 //! * the output of the execution is extracted from the context
 
 use crate::active_history::{ActiveHistory, HistorySearchResult};
+use crate::address_history_db::AddressHistoryDB;
+use crate::async_message_status::{get_async_message_statuses, matches_async_message_filter};
 use crate::context::{ExecutionContext, ExecutionContextSnapshot};
+use crate::event_db::EventDB;
+use crate::historical_ledger::HistoricalLedger;
 use crate::interface_impl::InterfaceImpl;
+use crate::output_journal::OutputJournal;
 use crate::stats::ExecutionStatsCounter;
 use massa_async_pool::AsyncMessage;
 use massa_execution_exports::{
@@ -19,32 +24,39 @@ use massa_execution_exports::{
     ExecutionStackElement, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
     ReadOnlyExecutionTarget, SlotExecutionOutput,
 };
-use massa_final_state::FinalState;
+use massa_final_state::{FinalState, StateChanges};
+use massa_hash::Hash;
 use massa_ledger_exports::{SetOrDelete, SetUpdateOrDelete};
 use massa_metrics::MassaMetrics;
 use massa_models::address::ExecutionAddressCycleInfo;
 use massa_models::bytecode::Bytecode;
 use massa_models::datastore::get_prefix_bounds;
 use massa_models::denunciation::{Denunciation, DenunciationIndex};
-use massa_models::execution::EventFilter;
+use massa_models::execution::{
+    AddressHistoryEntry, AsyncMessageEntry, AsyncMessageFilter, EventFilter, ExecutionTrace,
+    ExecutionTraceId,
+};
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
+use massa_models::state_proof::StateProof;
 use massa_models::stats::ExecutionStats;
 use massa_models::timeslots::get_block_slot_timestamp;
 use massa_models::{
     address::Address,
     block_id::BlockId,
-    operation::{OperationId, OperationType, SecureShareOperation},
+    operation::{Operation, OperationId, OperationSerializer, OperationType, SecureShareOperation},
+    secure_share::Id,
 };
 use massa_models::{amount::Amount, slot::Slot};
 use massa_module_cache::config::ModuleCacheConfig;
 use massa_module_cache::controller::ModuleCache;
 use massa_pos_exports::SelectorController;
 use massa_sc_runtime::{Interface, Response, VMError};
+use massa_serialization::Serializer;
 use massa_versioning::versioning::MipStore;
 use massa_wallet::Wallet;
 use parking_lot::{Mutex, RwLock};
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::sync::Arc;
 use tracing::{debug, info, trace, warn};
 
@@ -72,6 +84,16 @@ pub(crate) struct ExecutionState {
     pub final_cursor: Slot,
     // store containing execution events that became final
     final_events: EventStore,
+    // optional persistent store for final execution events, replacing `final_events` when enabled
+    final_events_db: Option<EventDB>,
+    // optional persistent per-address history of final executed operations
+    address_history_db: Option<AddressHistoryDB>,
+    // optional persistent journal of the final execution outputs, replayed to the stream consumers
+    output_journal: Option<OutputJournal>,
+    // reverse ledger diffs of the latest final slots, to read the final ledger at a past final slot
+    historical_ledger: HistoricalLedger,
+    // traces of the final executions of operations and async messages, oldest first (empty if executions are not traced)
+    final_traces: VecDeque<ExecutionTrace>,
     // final state with atomic R/W access
     final_state: Arc<RwLock<FinalState>>,
     // execution context (see documentation in context.rs)
@@ -161,6 +183,26 @@ impl ExecutionState {
             active_history,
             // empty final event store: it is not recovered through bootstrap
             final_events: Default::default(),
+            final_events_db: config
+                .event_store_path
+                .clone()
+                .map(|path| EventDB::new(path, config.event_store_retention_periods)),
+            address_history_db: config
+                .address_history_path
+                .clone()
+                .map(AddressHistoryDB::new),
+            output_journal: config.output_journal_path.clone().map(|path| {
+                OutputJournal::new(
+                    path,
+                    config.thread_count,
+                    config.output_journal_retention_periods,
+                )
+            }),
+            historical_ledger: HistoricalLedger::new(
+                config.thread_count,
+                config.historical_ledger_periods,
+            ),
+            final_traces: Default::default(),
             // no active slots executed yet: set active_cursor to the last final block
             active_cursor: last_final_slot,
             final_cursor: last_final_slot,
@@ -212,7 +254,43 @@ impl ExecutionState {
         // as it will also write the MIP store on disk
         self.update_versioning_stats(&exec_out.block_info, &exec_out.slot);
 
+        // record the executed operations in the history of the addresses they involve
+        if let (Some(address_history_db), Some(block_info)) =
+            (&self.address_history_db, &exec_out.block_info)
+        {
+            address_history_db.insert_executed_operations(
+                &exec_out.slot,
+                block_info,
+                &exec_out.state_changes.executed_ops_changes,
+            );
+        }
+
+        // keep the traces of the final executions
+        let traces = std::mem::take(&mut exec_out.traces);
+        if let Some(max_final_execution_traces) = self.config.max_final_execution_traces {
+            self.final_traces.extend(traces);
+            while self.final_traces.len() > max_final_execution_traces {
+                self.final_traces.pop_front();
+            }
+        }
+
         let exec_out_2 = exec_out.clone();
+
+        // journal the output before broadcasting it, so that stream consumers can resume from any broadcast slot
+        if let Some(output_journal) = &self.output_journal {
+            output_journal.insert(&exec_out_2);
+            output_journal.prune(exec_out_2.slot);
+        }
+
+        // keep the reverse ledger diff of the slot, computed before the final ledger is modified
+        if self.historical_ledger.is_enabled() {
+            let undo_changes = HistoricalLedger::compute_undo_changes(
+                &*self.final_state.read().ledger,
+                &exec_out.state_changes.ledger_changes,
+            );
+            self.historical_ledger.push(exec_out.slot, undo_changes);
+        }
+
         // apply state changes to the final ledger
         self.final_state
             .write()
@@ -229,8 +307,13 @@ impl ExecutionState {
 
         // append generated events to the final event store
         exec_out.events.finalize();
-        self.final_events.extend(exec_out.events);
-        self.final_events.prune(self.config.max_final_events);
+        if let Some(final_events_db) = &self.final_events_db {
+            final_events_db.insert_events(&exec_out.events);
+            final_events_db.prune(exec_out.slot);
+        } else {
+            self.final_events.extend(exec_out.events);
+            self.final_events.prune(self.config.max_final_events);
+        }
 
         // update the prometheus metrics
         self.massa_metrics
@@ -301,15 +384,15 @@ impl ExecutionState {
     /// - if not yet executed then transfer fee and add the operation to the context then return a context snapshot
     ///
     /// # Arguments
+    /// * `operation_id`: id of the operation
     /// * `operation`: operation to be schedule
     /// * `sender_addr`: sender address for the operation (for fee transfer)
     fn prepare_operation_for_execution(
         &self,
-        operation: &SecureShareOperation,
+        operation_id: OperationId,
+        operation: &Operation,
         sender_addr: Address,
     ) -> Result<ExecutionContextSnapshot, ExecutionError> {
-        let operation_id = operation.id;
-
         // lock execution context
         let mut context = context_guard!(self);
 
@@ -331,9 +414,7 @@ impl ExecutionState {
         );
 
         // debit the fee from the operation sender
-        if let Err(err) =
-            context.transfer_coins(Some(sender_addr), None, operation.content.fee, false)
-        {
+        if let Err(err) = context.transfer_coins(Some(sender_addr), None, operation.fee, false) {
             let error = format!("could not spend fees: {}", err);
             let event = context.event_create(error.clone(), true);
             context.event_emit(event);
@@ -350,11 +431,24 @@ impl ExecutionState {
         context.max_gas = operation.get_gas_usage();
 
         // set the creator address
-        context.creator_address = Some(operation.content_creator_address);
+        context.creator_address = Some(sender_addr);
 
         // set the context origin operation ID
         context.origin_operation_id = Some(operation_id);
 
+        // trace the execution of the operation, starting with a call to its target if any
+        let (trace_address, trace_coins) = match &operation.op {
+            OperationType::CallSC {
+                target_addr, coins, ..
+            } => (*target_addr, *coins),
+            _ => (sender_addr, Amount::zero()),
+        };
+        context.trace_start(
+            ExecutionTraceId::Operation(operation_id),
+            trace_address,
+            trace_coins,
+        );
+
         Ok(context_snapshot)
     }
 
@@ -366,13 +460,40 @@ impl ExecutionState {
     /// * `block_slot`: slot of the block in which the op is included
     /// * `remaining_block_gas`: mutable reference towards the remaining gas in the block
     /// * `block_credits`: mutable reference towards the total block reward/fee credits
+    ///
+    /// # Returns
+    /// An error if the operation could not be included in the block.
+    /// Otherwise, the outcome of its execution: the gas it used, or the error that made it fail.
     pub fn execute_operation(
         &self,
         operation: &SecureShareOperation,
         block_slot: Slot,
         remaining_block_gas: &mut u64,
         block_credits: &mut Amount,
-    ) -> Result<(), ExecutionError> {
+    ) -> Result<Result<u64, ExecutionError>, ExecutionError> {
+        self.execute_operation_content(
+            operation.id,
+            &operation.content,
+            operation.content_creator_address,
+            block_slot,
+            remaining_block_gas,
+            block_credits,
+        )
+    }
+
+    /// Execute the content of an operation sent by `sender_addr` in the context of a block.
+    /// The signature of the operation is not involved: it is checked before the operation reaches execution.
+    ///
+    /// See `execute_operation` for the arguments and the returned value.
+    fn execute_operation_content(
+        &self,
+        operation_id: OperationId,
+        operation: &Operation,
+        sender_addr: Address,
+        block_slot: Slot,
+        remaining_block_gas: &mut u64,
+        block_credits: &mut Amount,
+    ) -> Result<Result<u64, ExecutionError>, ExecutionError> {
         // check validity period
         if !(operation
             .get_validity_range(self.config.operation_validity_period)
@@ -389,9 +510,6 @@ impl ExecutionState {
             )
         })?;
 
-        // get the operation's sender address
-        let sender_addr = operation.content_creator_address;
-
         // get the thread to which the operation belongs
         let op_thread = sender_addr.get_thread(self.config.thread_count);
 
@@ -402,13 +520,11 @@ impl ExecutionState {
             ));
         }
 
-        // get operation ID
-        let operation_id = operation.id;
-
         // Add fee from operation.
-        let new_block_credits = block_credits.saturating_add(operation.content.fee);
+        let new_block_credits = block_credits.saturating_add(operation.fee);
 
-        let context_snapshot = self.prepare_operation_for_execution(operation, sender_addr)?;
+        let context_snapshot =
+            self.prepare_operation_for_execution(operation_id, operation, sender_addr)?;
 
         // update block gas
         *remaining_block_gas = new_remaining_block_gas;
@@ -417,22 +533,20 @@ impl ExecutionState {
         *block_credits = new_block_credits;
 
         // Call the execution process specific to the operation type.
-        let mut execution_result = match &operation.content.op {
+        let mut execution_result = match &operation.op {
             OperationType::ExecuteSC { .. } => {
-                self.execute_executesc_op(&operation.content.op, sender_addr)
-            }
-            OperationType::CallSC { .. } => {
-                self.execute_callsc_op(&operation.content.op, sender_addr)
-            }
-            OperationType::RollBuy { .. } => {
-                self.execute_roll_buy_op(&operation.content.op, sender_addr)
-            }
-            OperationType::RollSell { .. } => {
-                self.execute_roll_sell_op(&operation.content.op, sender_addr)
-            }
-            OperationType::Transaction { .. } => {
-                self.execute_transaction_op(&operation.content.op, sender_addr)
+                self.execute_executesc_op(&operation.op, sender_addr)
             }
+            OperationType::CallSC { .. } => self.execute_callsc_op(&operation.op, sender_addr),
+            OperationType::RollBuy { .. } => self
+                .execute_roll_buy_op(&operation.op, sender_addr)
+                .map(|_| 0),
+            OperationType::RollSell { .. } => self
+                .execute_roll_sell_op(&operation.op, sender_addr)
+                .map(|_| 0),
+            OperationType::Transaction { .. } => self
+                .execute_transaction_op(&operation.op, sender_addr)
+                .map(|_| 0),
         };
 
         {
@@ -456,33 +570,38 @@ impl ExecutionState {
 
             // check execution results
             match execution_result {
-                Ok(_) => {
+                Ok(gas_used) => {
                     context.insert_executed_op(
                         operation_id,
                         true,
-                        Slot::new(operation.content.expire_period, op_thread),
+                        Slot::new(operation.expire_period, op_thread),
                     );
+                    context.trace_finish(gas_used, None);
+                    Ok(Ok(gas_used))
                 }
                 Err(err) => {
                     // an error occurred: emit error event and reset context to snapshot
-                    let err = ExecutionError::RuntimeError(format!(
+                    let error_message = format!(
                         "runtime error when executing operation {}: {}",
                         operation_id, &err
-                    ));
-                    debug!("{}", &err);
-                    context.reset_to_snapshot(context_snapshot, err);
+                    );
+                    debug!("{}", &error_message);
+                    context.reset_to_snapshot(
+                        context_snapshot,
+                        ExecutionError::RuntimeError(error_message.clone()),
+                    );
 
                     // Insert op AFTER the context has been restored (otherwise it would be overwritten)
                     context.insert_executed_op(
                         operation_id,
                         false,
-                        Slot::new(operation.content.expire_period, op_thread),
-                    )
+                        Slot::new(operation.expire_period, op_thread),
+                    );
+                    context.trace_finish(op_gas, Some(error_message.clone()));
+                    Ok(Err(ExecutionError::RuntimeError(error_message)))
                 }
             }
         }
-
-        Ok(())
     }
 
     /// Execute a denunciation in the context of a block.
@@ -772,11 +891,14 @@ impl ExecutionState {
     /// # Arguments
     /// * `operation`: the `WrappedOperation` to process, must be an `ExecuteSC`
     /// * `sender_addr`: address of the sender
+    ///
+    /// # Returns
+    /// The gas used by the execution
     pub fn execute_executesc_op(
         &self,
         operation: &OperationType,
         sender_addr: Address,
-    ) -> Result<(), ExecutionError> {
+    ) -> Result<u64, ExecutionError> {
         // process ExecuteSC operations only
         let (bytecode, max_gas, datastore) = match &operation {
             OperationType::ExecuteSC {
@@ -816,7 +938,7 @@ impl ExecutionState {
                 "not enough gas to pay for singlepass compilation".to_string(),
             ))?;
         // run the VM
-        massa_sc_runtime::run_main(
+        let response = massa_sc_runtime::run_main(
             &*self.execution_interface,
             module,
             remaining_gas,
@@ -827,7 +949,7 @@ impl ExecutionState {
             error,
         })?;
 
-        Ok(())
+        Ok(max_gas.saturating_sub(response.remaining_gas))
     }
 
     /// Execute an operation of type `CallSC`
@@ -838,11 +960,14 @@ impl ExecutionState {
     /// * `block_creator_addr`: address of the block creator
     /// * `operation_id`: ID of the operation
     /// * `sender_addr`: address of the sender
+    ///
+    /// # Returns
+    /// The gas used by the execution
     pub fn execute_callsc_op(
         &self,
         operation: &OperationType,
         sender_addr: Address,
-    ) -> Result<(), ExecutionError> {
+    ) -> Result<u64, ExecutionError> {
         // process CallSC operations only
         let (max_gas, target_addr, target_func, param, coins) = match &operation {
             OperationType::CallSC {
@@ -899,7 +1024,7 @@ impl ExecutionState {
 
             // quit if there is no function to be called
             if target_func.is_empty() {
-                return Ok(());
+                return Ok(0);
             }
 
             // Load bytecode. Assume empty bytecode if not found.
@@ -926,11 +1051,11 @@ impl ExecutionState {
             }
             _ => (),
         }
-        response.map_err(|error| ExecutionError::VMError {
+        let response = response.map_err(|error| ExecutionError::VMError {
             context: "CallSC".to_string(),
             error,
         })?;
-        Ok(())
+        Ok(max_gas.saturating_sub(response.remaining_gas))
     }
 
     /// Tries to execute an asynchronous message
@@ -966,6 +1091,14 @@ impl ExecutionState {
                     operation_datastore: None,
                 },
             ];
+            context.trace_start(
+                ExecutionTraceId::AsyncMessage {
+                    emission_slot: message.emission_slot,
+                    emission_index: message.emission_index,
+                },
+                message.destination,
+                message.coins,
+            );
 
             // if the target address is not SC: fail
             if !matches!(message.destination, Address::SC(..)) {
@@ -974,6 +1107,7 @@ impl ExecutionState {
                 );
                 context.reset_to_snapshot(context_snapshot, err.clone());
                 context.cancel_async_message(&message);
+                context.trace_finish(0, Some(err.to_string()));
                 return Err(err);
             }
 
@@ -984,6 +1118,7 @@ impl ExecutionState {
                     let err = ExecutionError::RuntimeError("no target bytecode found".into());
                     context.reset_to_snapshot(context_snapshot, err.clone());
                     context.cancel_async_message(&message);
+                    context.trace_finish(0, Some(err.to_string()));
                     return Err(err);
                 }
             };
@@ -999,6 +1134,7 @@ impl ExecutionState {
                 ));
                 context.reset_to_snapshot(context_snapshot, err.clone());
                 context.cancel_async_message(&message);
+                context.trace_finish(0, Some(err.to_string()));
                 return Err(err);
             }
 
@@ -1020,10 +1156,16 @@ impl ExecutionState {
             self.config.gas_costs.clone(),
         );
         match response {
-            Ok(Response { init_gas_cost, .. }) => {
+            Ok(Response {
+                init_gas_cost,
+                remaining_gas,
+                ..
+            }) => {
                 self.module_cache
                     .write()
                     .set_init_cost(&bytecode, init_gas_cost);
+                context_guard!(self)
+                    .trace_finish(message.max_gas.saturating_sub(remaining_gas), None);
                 Ok(())
             }
             Err(error) => {
@@ -1040,6 +1182,7 @@ impl ExecutionState {
                 let mut context = context_guard!(self);
                 context.reset_to_snapshot(context_snapshot, err.clone());
                 context.cancel_async_message(&message);
+                context.trace_finish(message.max_gas, Some(err.to_string()));
                 Err(err)
             }
         }
@@ -1103,12 +1246,6 @@ impl ExecutionState {
                 .expect("Missing block in storage.")
                 .clone();
 
-            block_info = Some(ExecutedBlockInfo {
-                block_id: *block_id,
-                current_version: stored_block.content.header.content.current_version,
-                announced_version: stored_block.content.header.content.announced_version,
-            });
-
             // gather all operations
             let operations = {
                 let ops = block_store.read_operations();
@@ -1124,6 +1261,20 @@ impl ExecutionState {
                     .collect::<Vec<_>>()
             };
 
+            block_info = Some(ExecutedBlockInfo {
+                block_id: *block_id,
+                current_version: stored_block.content.header.content.current_version,
+                announced_version: stored_block.content.header.content.announced_version,
+                operations_involved_addresses: if self.config.address_history_path.is_some() {
+                    operations
+                        .iter()
+                        .map(|op| (op.id, op.get_ledger_involved_addresses()))
+                        .collect()
+                } else {
+                    Vec::new()
+                },
+            });
+
             debug!("executing {} operations at slot {}", operations.len(), slot);
 
             // gather all available endorsement creators and target blocks
@@ -1148,6 +1299,8 @@ impl ExecutionState {
             // Try executing the operations of this block in the order in which they appear in the block.
             // Errors are logged but do not interrupt the execution of the slot.
             for operation in operations.into_iter() {
+                // A failed execution is already recorded by `execute_operation`:
+                // the fee is spent, the error event emitted and the operation marked as failed.
                 if let Err(err) = self.execute_operation(
                     &operation,
                     stored_block.content.header.content.slot,
@@ -1394,17 +1547,49 @@ impl ExecutionState {
             )));
         }
 
-        // set the execution slot to be the one after the latest executed active or final slot
-        let slot = if req.is_final {
-            self.final_cursor
-                .get_next_slot(self.config.thread_count)
-                .expect("slot overflow in readonly execution from final slot")
+        // set the execution slot to be the one after the requested past final slot,
+        // or after the latest executed active or final slot
+        let (mut slot, active_history) = if let Some(at_slot) = req.at_slot {
+            let active_history = self
+                .get_historical_active_history(&at_slot)
+                .ok_or_else(|| {
+                    ExecutionError::RuntimeError(format!(
+                        "slot {} is not within the historical ledger window",
+                        at_slot
+                    ))
+                })?;
+            (
+                at_slot
+                    .get_next_slot(self.config.thread_count)
+                    .expect("slot overflow in readonly execution from past final slot"),
+                active_history,
+            )
+        } else if req.is_final {
+            (
+                self.final_cursor
+                    .get_next_slot(self.config.thread_count)
+                    .expect("slot overflow in readonly execution from final slot"),
+                self.active_history.clone(),
+            )
         } else {
-            self.active_cursor
-                .get_next_slot(self.config.thread_count)
-                .expect("slot overflow in readonly execution from active slot")
+            (
+                self.active_cursor
+                    .get_next_slot(self.config.thread_count)
+                    .expect("slot overflow in readonly execution from active slot"),
+                self.active_history.clone(),
+            )
         };
 
+        // an operation can only be executed in a block of the thread of its sender
+        if let ReadOnlyExecutionTarget::Operation { sender_addr, .. } = &req.target {
+            let op_thread = sender_addr.get_thread(self.config.thread_count);
+            while slot.thread != op_thread {
+                slot = slot
+                    .get_next_slot(self.config.thread_count)
+                    .expect("slot overflow in readonly operation execution");
+            }
+        }
+
         // create a readonly execution context
         let execution_context = ExecutionContext::readonly(
             self.config.clone(),
@@ -1412,13 +1597,14 @@ impl ExecutionState {
             req.max_gas,
             req.call_stack,
             self.final_state.clone(),
-            self.active_history.clone(),
+            active_history.clone(),
             self.module_cache.clone(),
             self.mip_store.clone(),
         );
 
         // run the interpreter according to the target type
-        let exec_response = match req.target {
+        let mut execution_error = None;
+        let (gas_cost, call_result) = match req.target {
             ReadOnlyExecutionTarget::BytecodeExecution(bytecode) => {
                 // set the execution context
                 *context_guard!(self) = execution_context;
@@ -1429,7 +1615,7 @@ impl ExecutionState {
                     .read()
                     .load_tmp_module(&bytecode, req.max_gas)?;
                 // run the VM
-                massa_sc_runtime::run_main(
+                let exec_response = massa_sc_runtime::run_main(
                     &*self.execution_interface,
                     module,
                     req.max_gas,
@@ -1438,7 +1624,11 @@ impl ExecutionState {
                 .map_err(|error| ExecutionError::VMError {
                     context: "ReadOnlyExecutionTarget::BytecodeExecution".to_string(),
                     error,
-                })?
+                })?;
+                (
+                    req.max_gas.saturating_sub(exec_response.remaining_gas),
+                    exec_response.ret,
+                )
             }
             ReadOnlyExecutionTarget::FunctionCall {
                 target_addr,
@@ -1477,22 +1667,157 @@ impl ExecutionState {
                     }
                     _ => (),
                 }
-                response.map_err(|error| ExecutionError::VMError {
+                let exec_response = response.map_err(|error| ExecutionError::VMError {
                     context: "ReadOnlyExecutionTarget::FunctionCall".to_string(),
                     error,
-                })?
+                })?;
+                (
+                    req.max_gas.saturating_sub(exec_response.remaining_gas),
+                    exec_response.ret,
+                )
+            }
+            ReadOnlyExecutionTarget::Operation {
+                sender_addr,
+                fee,
+                op,
+            } => {
+                // set the execution context
+                *context_guard!(self) = execution_context;
+
+                let operation = Operation {
+                    fee,
+                    expire_period: slot.period,
+                    op,
+                };
+
+                // check if the operation max gas is above the read-only threshold
+                if operation.get_gas_usage() > self.config.max_read_only_gas {
+                    return Err(ExecutionError::TooMuchGas(format!(
+                        "execution gas for read-only operation is {} which is above the maximum allowed {}",
+                        operation.get_gas_usage(), self.config.max_read_only_gas
+                    )));
+                }
+
+                // the operation is not signed: identify it by its sender and content
+                let mut operation_serialized = Vec::new();
+                OperationSerializer::new()
+                    .serialize(&operation, &mut operation_serialized)
+                    .map_err(|err| {
+                        ExecutionError::RuntimeError(format!(
+                            "could not serialize the operation to execute: {}",
+                            err
+                        ))
+                    })?;
+                let operation_id = OperationId::new(Hash::compute_from_tuple(&[
+                    &sender_addr.to_prefixed_bytes(),
+                    &operation_serialized,
+                ]));
+
+                // execute the operation alone in a block.
+                // An operation that cannot be included is an error,
+                // while a failed execution is a result of the simulation, with its fee and events.
+                let mut remaining_block_gas = self.config.max_gas_per_block;
+                let mut block_credits = Amount::zero();
+                match self.execute_operation_content(
+                    operation_id,
+                    &operation,
+                    sender_addr,
+                    slot,
+                    &mut remaining_block_gas,
+                    &mut block_credits,
+                )? {
+                    Ok(gas_used) => (gas_used, Vec::new()),
+                    Err(err) => {
+                        execution_error = Some(err);
+                        (operation.get_gas_usage(), Vec::new())
+                    }
+                }
             }
         };
 
-        // return the execution output
+        // settle the execution and compare the modified balances with the ones before the execution
         let execution_output = context_guard!(self).settle_slot(None);
+        let initial_context = ExecutionContext::readonly(
+            self.config.clone(),
+            slot,
+            0,
+            Vec::new(),
+            self.final_state.clone(),
+            active_history,
+            self.module_cache.clone(),
+            self.mip_store.clone(),
+        );
+        let mut balance_changes = BTreeMap::new();
+        for address in execution_output.state_changes.ledger_changes.0.keys() {
+            let initial_balance = initial_context
+                .get_balance(address)
+                .unwrap_or_else(Amount::zero);
+            let final_balance = execution_output
+                .state_changes
+                .ledger_changes
+                .get_balance_or_else(address, || Some(initial_balance))
+                .unwrap_or_else(Amount::zero);
+            if final_balance != initial_balance {
+                balance_changes.insert(*address, (initial_balance, final_balance));
+            }
+        }
+
+        // return the execution output
         Ok(ReadOnlyExecutionOutput {
             out: execution_output,
-            gas_cost: req.max_gas.saturating_sub(exec_response.remaining_gas),
-            call_result: exec_response.ret,
+            gas_cost,
+            call_result,
+            balance_changes,
+            execution_error,
         })
     }
 
+    /// Builds a history holding the reverse ledger diff that brings the final ledger back to its state at a past final slot.
+    /// Only the ledger is read at that slot through this history, the rest of the final state is left as is.
+    ///
+    /// Returns None if the slot is not within the historical ledger window.
+    fn get_historical_active_history(&self, slot: &Slot) -> Option<Arc<RwLock<ActiveHistory>>> {
+        let ledger_changes = self
+            .historical_ledger
+            .get_undo_changes(slot, &self.final_cursor)?;
+        let output = ExecutionOutput {
+            slot: *slot,
+            block_info: None,
+            state_changes: StateChanges {
+                ledger_changes,
+                ..Default::default()
+            },
+            events: Default::default(),
+            traces: Vec::new(),
+        };
+        Some(Arc::new(RwLock::new(ActiveHistory(VecDeque::from([
+            output,
+        ])))))
+    }
+
+    /// Creates a read-only context reading the final ledger at a past final slot
+    ///
+    /// Returns None if the slot is not within the historical ledger window.
+    pub(crate) fn get_historical_ledger_context(&self, slot: &Slot) -> Option<ExecutionContext> {
+        let active_history = self.get_historical_active_history(slot)?;
+        Some(ExecutionContext::readonly(
+            self.config.clone(),
+            slot.get_next_slot(self.config.thread_count)
+                .expect("slot overflow in historical ledger context"),
+            0,
+            Vec::new(),
+            self.final_state.clone(),
+            active_history,
+            self.module_cache.clone(),
+            self.mip_store.clone(),
+        ))
+    }
+
+    /// Gets the highest gas limit of an operation executed in read-only mode
+    pub fn get_max_readonly_operation_gas(&self) -> u64 {
+        std::cmp::min(self.config.max_read_only_gas, self.config.max_gas_per_block)
+    }
+
     /// Gets a balance both at the latest final and candidate executed slots
     pub fn get_final_and_candidate_balance(
         &self,
@@ -1633,6 +1958,117 @@ impl ExecutionState {
             .get_all_active_rolls(cycle)
     }
 
+    /// Get at most `max_count` of the latest final executed operations involving an address, most recent first.
+    /// Returns an empty list if the address history is not recorded.
+    pub fn get_address_history(
+        &self,
+        address: &Address,
+        max_count: usize,
+    ) -> Vec<AddressHistoryEntry> {
+        self.address_history_db
+            .as_ref()
+            .map(|address_history_db| address_history_db.get_address_history(address, max_count))
+            .unwrap_or_default()
+    }
+
+    /// Get the messages of the final asynchronous pool matching a filter, in priority order,
+    /// with their status at the slot following the last final slot.
+    ///
+    /// Returns the number of matching messages, and at most `max_count` of them starting at `offset`.
+    pub fn get_async_messages(
+        &self,
+        filter: &AsyncMessageFilter,
+        offset: usize,
+        max_count: usize,
+    ) -> (usize, Vec<AsyncMessageEntry>) {
+        let slot = self
+            .final_cursor
+            .get_next_slot(self.config.thread_count)
+            .expect("final slot overflow");
+        let final_state = self.final_state.read();
+        let matching_messages: Vec<_> = get_async_message_statuses(
+            final_state.async_pool.message_info_cache.iter(),
+            slot,
+            self.config.max_async_gas,
+        )
+        .into_iter()
+        .filter(|(message_id, message_info, _)| {
+            matches_async_message_filter(filter, message_id, message_info)
+        })
+        .collect();
+
+        // only the requested messages are read from the database
+        let entries = matching_messages
+            .iter()
+            .skip(offset)
+            .take(max_count)
+            .filter_map(|(message_id, _, (status, execution_rank))| {
+                let message = final_state.async_pool.fetch_message(message_id)?;
+                Some(AsyncMessageEntry {
+                    emission_slot: message.emission_slot,
+                    emission_index: message.emission_index,
+                    sender: message.sender,
+                    destination: message.destination,
+                    handler: message.handler,
+                    max_gas: message.max_gas,
+                    fee: message.fee,
+                    coins: message.coins,
+                    validity_start: message.validity_start,
+                    validity_end: message.validity_end,
+                    has_trigger: message.trigger.is_some(),
+                    can_be_executed: message.can_be_executed,
+                    status: *status,
+                    execution_rank: *execution_rank,
+                })
+            })
+            .collect();
+        (matching_messages.len(), entries)
+    }
+
+    /// Get at most `max_count` journaled final execution outputs, in slot order, starting at `start_slot`.
+    /// Returns an empty list if the outputs are not journaled.
+    pub fn get_final_execution_outputs(
+        &self,
+        start_slot: Slot,
+        max_count: usize,
+    ) -> Vec<ExecutionOutput> {
+        self.output_journal
+            .as_ref()
+            .map(|output_journal| output_journal.get_outputs(start_slot, max_count))
+            .unwrap_or_default()
+    }
+
+    /// Get the proof of inclusion (or exclusion) of a raw key in the final state.
+    /// Returns None if the node does not maintain the state tree.
+    pub fn get_state_proof(&self, key: &[u8]) -> Option<StateProof> {
+        self.final_state.read().db.read().get_state_proof(key)
+    }
+
+    /// Get the trace of an executed operation or asynchronous message,
+    /// looking first at the candidate executions (most recent first), then at the final ones.
+    pub fn get_execution_trace(&self, id: &ExecutionTraceId) -> Option<ExecutionTrace> {
+        let active_trace = self
+            .active_history
+            .read()
+            .0
+            .iter()
+            .rev()
+            .find_map(|exec_out| {
+                exec_out
+                    .traces
+                    .iter()
+                    .find(|trace| &trace.id == id)
+                    .cloned()
+            });
+        active_trace.or_else(|| {
+            self.final_traces
+                .iter()
+                .rev()
+                .find(|trace| &trace.id == id)
+                .cloned()
+        })
+    }
+
     /// Gets execution events optionally filtered by:
     /// * start slot
     /// * end slot
@@ -1640,33 +2076,41 @@ impl ExecutionState {
     /// * original caller address
     /// * operation id
     /// * event state (final, candidate or both)
+    ///
+    /// Final events come first, followed by candidate events.
+    /// The `offset` and `limit` pagination parameters of the filter are applied to the whole result.
     pub fn get_filtered_sc_output_event(&self, filter: EventFilter) -> Vec<SCOutputEvent> {
-        match filter.is_final {
-            Some(true) => self
-                .final_events
-                .get_filtered_sc_output_events(&filter)
-                .into_iter()
-                .collect(),
-            Some(false) => self
+        let offset = filter.offset.unwrap_or(0);
+        let limit = filter.limit.unwrap_or(usize::MAX);
+
+        let final_events: Box<dyn Iterator<Item = SCOutputEvent> + '_> = match filter.is_final {
+            Some(false) => Box::new(std::iter::empty()),
+            _ => match &self.final_events_db {
+                Some(final_events_db) => final_events_db.get_filtered_sc_output_events(&filter),
+                None => Box::new(
+                    self.final_events
+                        .get_filtered_sc_output_events(&filter)
+                        .into_iter(),
+                ),
+            },
+        };
+
+        let candidate_events: Vec<SCOutputEvent> = match filter.is_final {
+            Some(true) => Vec::new(),
+            _ => self
                 .active_history
                 .read()
                 .0
                 .iter()
                 .flat_map(|item| item.events.get_filtered_sc_output_events(&filter))
                 .collect(),
-            None => self
-                .final_events
-                .get_filtered_sc_output_events(&filter)
-                .into_iter()
-                .chain(
-                    self.active_history
-                        .read()
-                        .0
-                        .iter()
-                        .flat_map(|item| item.events.get_filtered_sc_output_events(&filter)),
-                )
-                .collect(),
-        }
+        };
+
+        final_events
+            .chain(candidate_events)
+            .skip(offset)
+            .take(limit)
+            .collect()
     }
 
     /// Check if a denunciation has been executed given a `DenunciationIndex`
@@ -0,0 +1,314 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module keeps the reverse ledger diffs of the latest final slots in memory.
+//!
+//! The reverse diff of a final slot is computed from the final ledger just before the slot is finalized:
+//! applying it to the ledger after that slot gives back the ledger before it.
+//! Composing the reverse diffs of the slots following a past final slot gives the ledger changes
+//! that bring the current final ledger back to its state at that slot,
+//! which allows read-only executions and state queries to be pinned to a past final slot.
+//! Only the ledger is covered: the other parts of the final state are always read at the current final slot.
+//! Diffs older than a configurable number of periods are pruned, and they are lost on restart.
+
+use massa_ledger_exports::{
+    Applicable, LedgerChanges, LedgerController, LedgerEntry, LedgerEntryUpdate, SetOrDelete,
+    SetOrKeep, SetUpdateOrDelete,
+};
+use massa_models::slot::Slot;
+use std::collections::VecDeque;
+
+/// Reverse ledger diffs of the latest final slots
+pub(crate) struct HistoricalLedger {
+    /// Number of threads
+    thread_count: u8,
+    /// Number of periods during which reverse diffs are kept. Zero disables the history.
+    retention_periods: u64,
+    /// Reverse ledger diffs of consecutive final slots, oldest first
+    undo_changes: VecDeque<(Slot, LedgerChanges)>,
+}
+
+impl HistoricalLedger {
+    /// Create an empty historical ledger
+    ///
+    /// # Arguments
+    /// * `thread_count`: number of threads
+    /// * `retention_periods`: number of periods during which reverse diffs are kept, zero to disable
+    pub fn new(thread_count: u8, retention_periods: u64) -> Self {
+        Self {
+            thread_count,
+            retention_periods,
+            undo_changes: VecDeque::new(),
+        }
+    }
+
+    /// Whether reverse diffs are kept at all
+    pub fn is_enabled(&self) -> bool {
+        self.retention_periods > 0
+    }
+
+    /// Compute the reverse of the ledger changes of a slot, before they are applied to the final ledger
+    ///
+    /// # Arguments
+    /// * `ledger`: final ledger, not yet modified by `changes`
+    /// * `changes`: ledger changes about to be applied to the final ledger
+    pub fn compute_undo_changes(
+        ledger: &dyn LedgerController,
+        changes: &LedgerChanges,
+    ) -> LedgerChanges {
+        let mut undo_changes = LedgerChanges::default();
+        for (addr, change) in changes.0.iter() {
+            // an address absent before the slot is simply deleted back
+            let Some(balance) = ledger.get_balance(addr) else {
+                undo_changes.0.insert(*addr, SetUpdateOrDelete::Delete);
+                continue;
+            };
+            let undo_change = match change {
+                SetUpdateOrDelete::Update(update) => {
+                    let balance = match update.balance {
+                        SetOrKeep::Set(_) => SetOrKeep::Set(balance),
+                        SetOrKeep::Keep => SetOrKeep::Keep,
+                    };
+                    let bytecode = match update.bytecode {
+                        SetOrKeep::Set(_) => {
+                            SetOrKeep::Set(ledger.get_bytecode(addr).unwrap_or_default())
+                        }
+                        SetOrKeep::Keep => SetOrKeep::Keep,
+                    };
+                    let datastore = update
+                        .datastore
+                        .keys()
+                        .map(|key| {
+                            let value = match ledger.get_data_entry(addr, key) {
+                                Some(value) => SetOrDelete::Set(value),
+                                None => SetOrDelete::Delete,
+                            };
+                            (key.clone(), value)
+                        })
+                        .collect();
+                    SetUpdateOrDelete::Update(LedgerEntryUpdate {
+                        balance,
+                        bytecode,
+                        datastore,
+                    })
+                }
+                // The whole entry is replaced or deleted: restore it entirely.
+                // Executions only set entire entries when creating addresses, so this is rare.
+                SetUpdateOrDelete::Set(_) | SetUpdateOrDelete::Delete => {
+                    let datastore = ledger
+                        .get_datastore_keys(addr, &[])
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter_map(|key| {
+                            let value = ledger.get_data_entry(addr, &key)?;
+                            Some((key, value))
+                        })
+                        .collect();
+                    SetUpdateOrDelete::Set(LedgerEntry {
+                        balance,
+                        bytecode: ledger.get_bytecode(addr).unwrap_or_default(),
+                        datastore,
+                    })
+                }
+            };
+            undo_changes.0.insert(*addr, undo_change);
+        }
+        undo_changes
+    }
+
+    /// Record the reverse ledger diff of a newly finalized slot, and prune the diffs that left the retention window
+    pub fn push(&mut self, slot: Slot, undo_changes: LedgerChanges) {
+        self.undo_changes.push_back((slot, undo_changes));
+        let min_period = slot.period.saturating_sub(self.retention_periods);
+        while let Some((oldest_slot, _)) = self.undo_changes.front() {
+            if oldest_slot.period >= min_period {
+                break;
+            }
+            self.undo_changes.pop_front();
+        }
+    }
+
+    /// Get the ledger changes that bring the final ledger at `final_slot` back to its state at `slot`.
+    ///
+    /// Returns None if `slot` is after `final_slot` or if it left the retention window.
+    pub fn get_undo_changes(&self, slot: &Slot, final_slot: &Slot) -> Option<LedgerChanges> {
+        if slot > final_slot {
+            return None;
+        }
+        if slot == final_slot {
+            return Some(LedgerChanges::default());
+        }
+
+        // the diffs of all the slots following `slot` are needed
+        let (oldest_slot, _) = self.undo_changes.front()?;
+        let next_slot = slot.get_next_slot(self.thread_count).ok()?;
+        if *oldest_slot > next_slot {
+            return None;
+        }
+
+        // undo the latest slots first
+        let mut undo_changes = LedgerChanges::default();
+        for (_, slot_undo_changes) in self
+            .undo_changes
+            .iter()
+            .rev()
+            .take_while(|(undo_slot, _)| undo_slot > slot)
+        {
+            undo_changes.apply(slot_undo_changes.clone());
+        }
+        Some(undo_changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_db_exports::{DBBatch, MassaDBConfig, MassaDBController};
+    use massa_db_worker::MassaDB;
+    use massa_ledger_exports::LedgerConfig;
+    use massa_ledger_worker::FinalLedger;
+    use massa_models::address::Address;
+    use massa_models::amount::Amount;
+    use massa_models::bytecode::Bytecode;
+    use massa_models::config::THREAD_COUNT;
+    use parking_lot::RwLock;
+    use std::collections::{BTreeMap, HashMap};
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    fn set_balance(addr: Address, balance: u64) -> LedgerChanges {
+        let mut changes = LedgerChanges::default();
+        changes.0.insert(
+            addr,
+            SetUpdateOrDelete::Update(LedgerEntryUpdate {
+                balance: SetOrKeep::Set(Amount::from_raw(balance)),
+                ..Default::default()
+            }),
+        );
+        changes
+    }
+
+    #[test]
+    fn test_historical_ledger_window() {
+        let addr =
+            Address::from_str("AU12htxRWiEm8jDJpJptr6cwEhWNcCSFWstN1MLSa96DDkVM9Y42G").unwrap();
+        let mut history = HistoricalLedger::new(THREAD_COUNT, 3);
+
+        // the balance before slot (p, 0) is p
+        for period in 1..=10 {
+            history.push(Slot::new(period, 0), set_balance(addr, period));
+            for thread in 1..THREAD_COUNT {
+                history.push(Slot::new(period, thread), LedgerChanges::default());
+            }
+        }
+        let final_slot = Slot::new(10, THREAD_COUNT - 1);
+
+        assert_eq!(
+            history.get_undo_changes(&final_slot, &final_slot),
+            Some(LedgerChanges::default())
+        );
+        assert!(history
+            .get_undo_changes(&Slot::new(11, 0), &final_slot)
+            .is_none());
+        assert_eq!(
+            history
+                .get_undo_changes(&Slot::new(8, 5), &final_slot)
+                .unwrap()
+                .get_balance_or_else(&addr, || None),
+            Some(Amount::from_raw(9))
+        );
+        assert_eq!(
+            history
+                .get_undo_changes(&Slot::new(6, THREAD_COUNT - 1), &final_slot)
+                .unwrap()
+                .get_balance_or_else(&addr, || None),
+            Some(Amount::from_raw(7))
+        );
+        assert!(history
+            .get_undo_changes(&Slot::new(6, THREAD_COUNT - 2), &final_slot)
+            .is_none());
+    }
+
+    #[test]
+    fn test_compute_undo_changes() {
+        let updated_addr =
+            Address::from_str("AU12htxRWiEm8jDJpJptr6cwEhWNcCSFWstN1MLSa96DDkVM9Y42G").unwrap();
+        let deleted_addr =
+            Address::from_str("AU12hgh5ULW9o8fJE9muLNXhQENaUUswQbxPyDSq8ridnDGu5gRiJ").unwrap();
+        let new_addr =
+            Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+        let updated_entry = LedgerEntry {
+            balance: Amount::from_raw(10),
+            bytecode: Bytecode(vec![1]),
+            datastore: BTreeMap::from([(b"a".to_vec(), b"1".to_vec())]),
+        };
+        let deleted_entry = LedgerEntry {
+            balance: Amount::from_raw(20),
+            bytecode: Bytecode(vec![2]),
+            datastore: BTreeMap::from([(b"b".to_vec(), b"2".to_vec())]),
+        };
+
+        // final ledger before the slot
+        let (ledger_config, _keep_file, keep_dir) = LedgerConfig::sample(&HashMap::new());
+        let db_config = MassaDBConfig {
+            path: keep_dir.path().to_path_buf(),
+            max_history_length: 10,
+            max_new_elements: 100,
+            thread_count: THREAD_COUNT,
+            enable_state_tree: false,
+        };
+        let db = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+        ));
+        let mut ledger = FinalLedger::new(ledger_config, db.clone());
+        ledger.load_initial_ledger().unwrap();
+        let mut initial_changes = LedgerChanges::default();
+        initial_changes
+            .0
+            .insert(updated_addr, SetUpdateOrDelete::Set(updated_entry));
+        initial_changes
+            .0
+            .insert(deleted_addr, SetUpdateOrDelete::Set(deleted_entry.clone()));
+        let mut batch = DBBatch::new();
+        ledger.apply_changes_to_batch(initial_changes, &mut batch);
+        db.write()
+            .write_batch(batch, Default::default(), Some(Slot::new(1, 0)));
+
+        // changes of the slot
+        let mut changes = LedgerChanges::default();
+        changes.0.insert(
+            updated_addr,
+            SetUpdateOrDelete::Update(LedgerEntryUpdate {
+                balance: SetOrKeep::Set(Amount::from_raw(5)),
+                bytecode: SetOrKeep::Keep,
+                datastore: BTreeMap::from([
+                    (b"a".to_vec(), SetOrDelete::Set(b"x".to_vec())),
+                    (b"c".to_vec(), SetOrDelete::Set(b"y".to_vec())),
+                ]),
+            }),
+        );
+        changes.0.insert(deleted_addr, SetUpdateOrDelete::Delete);
+        changes
+            .0
+            .insert(new_addr, SetUpdateOrDelete::Set(LedgerEntry::default()));
+
+        // the undo changes restore the previous values and remove what was added
+        let undo_changes = HistoricalLedger::compute_undo_changes(&ledger, &changes);
+        let mut expected = LedgerChanges::default();
+        expected.0.insert(
+            updated_addr,
+            SetUpdateOrDelete::Update(LedgerEntryUpdate {
+                balance: SetOrKeep::Set(Amount::from_raw(10)),
+                bytecode: SetOrKeep::Keep,
+                datastore: BTreeMap::from([
+                    (b"a".to_vec(), SetOrDelete::Set(b"1".to_vec())),
+                    (b"c".to_vec(), SetOrDelete::Delete),
+                ]),
+            }),
+        );
+        expected
+            .0
+            .insert(deleted_addr, SetUpdateOrDelete::Set(deleted_entry));
+        expected.0.insert(new_addr, SetUpdateOrDelete::Delete);
+        assert_eq!(undo_changes, expected);
+    }
+}
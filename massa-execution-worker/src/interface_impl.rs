@@ -254,6 +254,7 @@ impl Interface for InterfaceImpl {
             owned_addresses: vec![to_address],
             operation_datastore: None,
         });
+        context.trace_call_start(to_address, coins);
 
         // return the target bytecode
         Ok(bytecode.0)
@@ -267,6 +268,7 @@ impl Interface for InterfaceImpl {
         if context.stack.pop().is_none() {
             bail!("call stack out of bounds")
         }
+        context.trace_call_end();
 
         Ok(())
     }
@@ -403,7 +405,7 @@ impl Interface for InterfaceImpl {
     ///
     /// [DeprecatedByNewRuntime] Replaced by `raw_get_data_wasmv1`
     fn raw_get_data(&self, key: &[u8]) -> Result<Vec<u8>> {
-        let context = context_guard!(self);
+        let mut context = context_guard!(self);
         let addr = context.get_current_address()?;
         match context.get_data_entry(&addr, key) {
             Some(value) => Ok(value),
@@ -423,7 +425,7 @@ impl Interface for InterfaceImpl {
     /// [DeprecatedByNewRuntime] Replaced by `raw_get_data_wasmv1`
     fn raw_get_data_for(&self, address: &str, key: &[u8]) -> Result<Vec<u8>> {
         let addr = &massa_models::address::Address::from_str(address)?;
-        let context = context_guard!(self);
+        let mut context = context_guard!(self);
         match context.get_data_entry(addr, key) {
             Some(value) => Ok(value),
             _ => bail!("data entry not found"),
@@ -439,7 +441,7 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// The datastore value matching the provided key, if found, otherwise an error.
     fn get_ds_value_wasmv1(&self, key: &[u8], address: Option<String>) -> Result<Vec<u8>> {
-        let context = context_guard!(self);
+        let mut context = context_guard!(self);
         let address = get_address_from_opt_or_context(&context, address)?;
 
         match context.get_data_entry(&address, key) {
@@ -1338,6 +1340,7 @@ impl Interface for InterfaceImpl {
             owned_addresses: vec![to_address],
             operation_datastore: None,
         });
+        context.trace_call_start(to_address, coins);
 
         // return the target bytecode
         Ok(bytecode.0)
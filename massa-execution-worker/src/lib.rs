@@ -75,15 +75,32 @@
 //!
 //! ## `stats.rs`
 //! Defines a structure that gathers execution statistics.
+//!
+//! ## `event_db.rs`
+//! A persistent store for final execution events, indexed by emitter, caller, operation and slot.
+//!
+//! ## `address_history_db.rs`
+//! A persistent per-address history of final executed operations and their outcome.
+//!
+//! ## `output_journal.rs`
+//! A persistent journal of the final execution outputs, replayed to the consumers of the execution output streams.
+//!
+//! ## `historical_ledger.rs`
+//! Reverse ledger diffs of the latest final slots, used to execute read-only requests and queries at a past final slot.
 
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
 
 mod active_history;
+mod address_history_db;
+mod async_message_status;
 mod context;
 mod controller;
+mod event_db;
 mod execution;
+mod historical_ledger;
 mod interface_impl;
+mod output_journal;
 mod request_queue;
 mod slot_sequencer;
 mod speculative_async_pool;
@@ -0,0 +1,212 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module implements a persistent journal of the final execution outputs.
+//!
+//! Each final slot output is stored under its slot key, so that the outputs can be replayed in slot order
+//! to the consumers of the execution output streams that reconnect after missing some slots.
+//! State changes are stored in their binary form, followed by the block info and the events in JSON.
+//! Outputs older than a configurable number of periods are pruned.
+
+use massa_execution_exports::{EventStore, ExecutedBlockInfo, ExecutionOutput};
+use massa_final_state::{StateChangesDeserializer, StateChangesSerializer};
+use massa_models::block_id::BlockId;
+use massa_models::config::{
+    ENDORSEMENT_COUNT, MAX_ASYNC_MESSAGE_DATA, MAX_ASYNC_POOL_LENGTH, MAX_DATASTORE_ENTRY_COUNT,
+    MAX_DATASTORE_KEY_LENGTH, MAX_DATASTORE_VALUE_LENGTH, MAX_DEFERRED_CREDITS_LENGTH,
+    MAX_DENUNCIATION_CHANGES_LENGTH, MAX_EXECUTED_OPS_CHANGES_LENGTH, MAX_LEDGER_CHANGES_COUNT,
+    MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH,
+};
+use massa_models::output_event::SCOutputEvent;
+use massa_models::slot::{Slot, SLOT_KEY_SIZE};
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
+use rocksdb::{Direction, IteratorMode, WriteBatch, DB};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+const OPEN_ERROR: &str = "critical: output journal rocksdb open operation failed";
+const CRUD_ERROR: &str = "critical: output journal rocksdb crud operation failed";
+const OUTPUT_SER_ERROR: &str = "critical: execution output serialization failed";
+const OUTPUT_DESER_ERROR: &str = "critical: execution output deserialization failed";
+
+/// Block id, current version and announced version of the executed block, if any
+type JournalBlockInfo = Option<(BlockId, u32, Option<u32>)>;
+
+/// Extracts the slot from a journal key
+fn slot_from_key(key: &[u8]) -> Slot {
+    let slot_key: &[u8; SLOT_KEY_SIZE] = key
+        .try_into()
+        .expect("critical: invalid output journal key length");
+    Slot::from_bytes_key(slot_key)
+}
+
+/// Persistent journal of the final execution outputs
+pub(crate) struct OutputJournal {
+    /// RocksDB database
+    db: DB,
+    /// Number of periods during which final outputs are kept
+    retention_periods: u64,
+    /// State changes serializer
+    state_changes_serializer: StateChangesSerializer,
+    /// State changes deserializer
+    state_changes_deserializer: StateChangesDeserializer,
+}
+
+impl OutputJournal {
+    /// Open (or create) the output journal
+    ///
+    /// # Arguments
+    /// * `path`: where to store the db
+    /// * `thread_count`: number of threads
+    /// * `retention_periods`: number of periods during which final outputs are kept
+    pub fn new(path: PathBuf, thread_count: u8, retention_periods: u64) -> Self {
+        Self {
+            db: DB::open_default(path).expect(OPEN_ERROR),
+            retention_periods,
+            state_changes_serializer: StateChangesSerializer::new(),
+            state_changes_deserializer: StateChangesDeserializer::new(
+                thread_count,
+                MAX_ASYNC_POOL_LENGTH,
+                MAX_ASYNC_MESSAGE_DATA,
+                MAX_LEDGER_CHANGES_COUNT,
+                MAX_DATASTORE_KEY_LENGTH,
+                MAX_DATASTORE_VALUE_LENGTH,
+                MAX_DATASTORE_ENTRY_COUNT,
+                MAX_ROLLS_COUNT_LENGTH,
+                MAX_PRODUCTION_STATS_LENGTH,
+                MAX_DEFERRED_CREDITS_LENGTH,
+                MAX_EXECUTED_OPS_CHANGES_LENGTH,
+                ENDORSEMENT_COUNT,
+                MAX_DENUNCIATION_CHANGES_LENGTH,
+            ),
+        }
+    }
+
+    /// Persist the output of a final slot.
+    /// Traces and involved addresses are not journaled.
+    pub fn insert(&self, output: &ExecutionOutput) {
+        let mut value = Vec::new();
+        self.state_changes_serializer
+            .serialize(&output.state_changes, &mut value)
+            .expect(OUTPUT_SER_ERROR);
+        let block_info: JournalBlockInfo = output
+            .block_info
+            .as_ref()
+            .map(|info| (info.block_id, info.current_version, info.announced_version));
+        serde_json::to_writer(&mut value, &(block_info, &output.events.0)).expect(OUTPUT_SER_ERROR);
+        self.db
+            .put(output.slot.to_bytes_key(), value)
+            .expect(CRUD_ERROR);
+    }
+
+    /// Remove the outputs that are older than the retention window relative to `final_slot`
+    pub fn prune(&self, final_slot: Slot) {
+        let min_period = final_slot.period.saturating_sub(self.retention_periods);
+        if min_period == 0 {
+            return;
+        }
+        let mut batch = WriteBatch::default();
+        for (key, _) in self.db.iterator(IteratorMode::Start).flatten() {
+            if slot_from_key(&key).period >= min_period {
+                break;
+            }
+            batch.delete(key);
+        }
+        if !batch.is_empty() {
+            self.db.write(batch).expect(CRUD_ERROR);
+        }
+    }
+
+    /// Decode a journaled output
+    fn decode(&self, key: &[u8], value: &[u8]) -> ExecutionOutput {
+        let (rest, state_changes) = self
+            .state_changes_deserializer
+            .deserialize::<DeserializeError>(value)
+            .expect(OUTPUT_DESER_ERROR);
+        let (block_info, events): (JournalBlockInfo, VecDeque<SCOutputEvent>) =
+            serde_json::from_slice(rest).expect(OUTPUT_DESER_ERROR);
+        ExecutionOutput {
+            slot: slot_from_key(key),
+            block_info: block_info.map(|(block_id, current_version, announced_version)| {
+                ExecutedBlockInfo {
+                    block_id,
+                    current_version,
+                    announced_version,
+                    operations_involved_addresses: Vec::new(),
+                }
+            }),
+            state_changes,
+            events: EventStore(events),
+            traces: Vec::new(),
+        }
+    }
+
+    /// Get at most `max_count` journaled outputs, in slot order, starting at `start_slot` (inclusive).
+    /// The returned slots are not necessarily consecutive if some outputs were not journaled or were pruned.
+    pub fn get_outputs(&self, start_slot: Slot, max_count: usize) -> Vec<ExecutionOutput> {
+        self.db
+            .iterator(IteratorMode::From(
+                &start_slot.to_bytes_key(),
+                Direction::Forward,
+            ))
+            .flatten()
+            .take(max_count)
+            .map(|(key, value)| self.decode(&key, &value))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_final_state::StateChanges;
+    use massa_models::config::THREAD_COUNT;
+    use massa_models::output_event::EventExecutionContext;
+    use tempfile::TempDir;
+
+    fn output(slot: Slot) -> ExecutionOutput {
+        let event = SCOutputEvent {
+            context: EventExecutionContext {
+                slot,
+                block: None,
+                read_only: false,
+                index_in_slot: 0,
+                call_stack: VecDeque::new(),
+                origin_operation_id: None,
+                is_final: true,
+                is_error: false,
+            },
+            data: slot.to_string(),
+        };
+        ExecutionOutput {
+            slot,
+            block_info: None,
+            state_changes: StateChanges::default(),
+            events: EventStore(vec![event].into()),
+            traces: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_output_journal_replay_and_prune() {
+        let dir = TempDir::new().unwrap();
+        let journal = OutputJournal::new(dir.path().to_path_buf(), THREAD_COUNT, 10);
+        for period in 1..=20 {
+            for thread in 0..2 {
+                journal.insert(&output(Slot::new(period, thread)));
+            }
+        }
+
+        let outputs = journal.get_outputs(Slot::new(5, 1), 3);
+        assert_eq!(
+            outputs.iter().map(|o| o.slot).collect::<Vec<_>>(),
+            vec![Slot::new(5, 1), Slot::new(6, 0), Slot::new(6, 1)]
+        );
+        assert_eq!(outputs[0].events.0[0].data, Slot::new(5, 1).to_string());
+        assert!(outputs[0].block_info.is_none());
+
+        journal.prune(Slot::new(20, 1));
+        let outputs = journal.get_outputs(Slot::new(0, 0), usize::MAX);
+        assert_eq!(outputs.len(), 22);
+        assert_eq!(outputs[0].slot, Slot::new(10, 0));
+    }
+}
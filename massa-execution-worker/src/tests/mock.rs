@@ -84,6 +84,7 @@ pub fn get_sample_state(
         max_history_length: 10,
         max_new_elements: 100,
         thread_count: THREAD_COUNT,
+        enable_state_tree: false,
     };
     let db = Arc::new(RwLock::new(
         Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
@@ -2,6 +2,7 @@
 #[cfg(test)]
 mod tests {
     use crate::active_history::ActiveHistory;
+    use crate::controller::search_min_gas_limit;
     use crate::speculative_async_pool::SpeculativeAsyncPool;
     use crate::start_execution_worker;
     use crate::tests::mock::{create_block, get_random_address_full, get_sample_state};
@@ -9,7 +10,8 @@ mod tests {
     use massa_db_exports::DBBatch;
     use massa_execution_exports::{
         ExecutionBlockMetadata, ExecutionChannels, ExecutionConfig, ExecutionController,
-        ExecutionError, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+        ExecutionError, ExecutionQueryError, ExecutionQueryRequest, ExecutionQueryRequestItem,
+        ExecutionQueryResponseItem, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
     };
     use massa_hash::Hash;
     use massa_metrics::MassaMetrics;
@@ -27,7 +29,7 @@ mod tests {
         block_id::BlockId,
         datastore::Datastore,
         denunciation::Denunciation,
-        execution::EventFilter,
+        execution::{EventFilter, ExecutionTraceId, ExecutionTraceStep},
         operation::{Operation, OperationSerializer, OperationType, SecureShareOperation},
         secure_share::SecureShareContent,
     };
@@ -180,6 +182,7 @@ mod tests {
                     include_bytes!("./wasm/event_test.wasm").to_vec(),
                 ),
                 is_final: true,
+                at_slot: None,
             })
             .expect("readonly execution failed");
 
@@ -195,6 +198,7 @@ mod tests {
                     include_bytes!("./wasm/event_test.wasm").to_vec(),
                 ),
                 is_final: false,
+                at_slot: None,
             })
             .expect("readonly execution failed");
 
@@ -203,6 +207,290 @@ mod tests {
         manager.stop();
     }
 
+    #[test]
+    #[serial]
+    fn test_readonly_operation_simulation() {
+        // setup the period duration
+        let exec_cfg = ExecutionConfig {
+            t0: MassaTime::from_millis(100),
+            cursor_delay: MassaTime::from_millis(0),
+            ..ExecutionConfig::default()
+        };
+        // init the MIP store
+        let mip_stats_config = MipStatsConfig {
+            block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+            warn_announced_version_ratio: Ratio::new_raw(30, 100),
+        };
+        let mip_store = MipStore::try_from(([], mip_stats_config)).unwrap();
+        // get a sample final state
+        let (sample_state, _keep_file, _keep_dir) = get_sample_state(0).unwrap();
+        // init the storage
+        let storage = Storage::create_root();
+
+        let slot_execution_output_sender = broadcast::channel(5000).0;
+
+        let channels = ExecutionChannels {
+            slot_execution_output_sender,
+        };
+
+        // start the execution worker
+        let (mut manager, controller) = start_execution_worker(
+            exec_cfg.clone(),
+            sample_state.clone(),
+            sample_state.read().pos_state.selector.clone(),
+            mip_store,
+            channels,
+            Arc::new(RwLock::new(create_test_wallet(Some(PreHashMap::default())))),
+            MassaMetrics::new(
+                false,
+                "0.0.0.0:9898".parse().unwrap(),
+                32,
+                std::time::Duration::from_secs(5),
+            )
+            .0,
+        );
+        // initialize the execution system with genesis blocks
+        init_execution_worker(&exec_cfg, &storage, controller.clone());
+
+        let sender_address =
+            Address::from_public_key(&KeyPair::from_str(TEST_SK_1).unwrap().get_public_key());
+        let sender_balance = sample_state
+            .read()
+            .ledger
+            .get_balance(&sender_address)
+            .unwrap();
+        let recipient_address = get_random_address();
+
+        // simulate a transaction: the fee and the amount are debited from the sender
+        let res = controller
+            .execute_readonly_request(ReadOnlyExecutionRequest {
+                max_gas: 0,
+                call_stack: vec![],
+                target: ReadOnlyExecutionTarget::Operation {
+                    sender_addr: sender_address,
+                    fee: Amount::from_str("1").unwrap(),
+                    op: OperationType::Transaction {
+                        recipient_address,
+                        amount: Amount::from_str("100").unwrap(),
+                    },
+                },
+                is_final: true,
+                at_slot: None,
+            })
+            .expect("operation simulation failed");
+        assert_eq!(
+            res.out.slot.thread,
+            sender_address.get_thread(exec_cfg.thread_count)
+        );
+        assert_eq!(res.gas_cost, 0);
+        assert_eq!(
+            res.balance_changes.get(&sender_address),
+            Some(&(
+                sender_balance,
+                sender_balance.saturating_sub(Amount::from_str("101").unwrap())
+            ))
+        );
+        assert_eq!(
+            res.balance_changes.get(&recipient_address),
+            Some(&(
+                Amount::zero(),
+                Amount::from_str("100")
+                    .unwrap()
+                    .saturating_sub(LEDGER_ENTRY_BASE_COST)
+            ))
+        );
+
+        // the simulation does not change the state
+        assert_eq!(
+            sample_state.read().ledger.get_balance(&recipient_address),
+            None
+        );
+
+        // simulate a transaction that the sender cannot afford:
+        // its execution fails, but the fee is paid and the error is reported with its event
+        let res = controller
+            .execute_readonly_request(ReadOnlyExecutionRequest {
+                max_gas: 0,
+                call_stack: vec![],
+                target: ReadOnlyExecutionTarget::Operation {
+                    sender_addr: sender_address,
+                    fee: Amount::from_str("1").unwrap(),
+                    op: OperationType::Transaction {
+                        recipient_address,
+                        amount: sender_balance,
+                    },
+                },
+                is_final: true,
+                at_slot: None,
+            })
+            .expect("operation simulation failed");
+        assert!(res.execution_error.is_some());
+        assert_eq!(
+            res.balance_changes.get(&sender_address),
+            Some(&(
+                sender_balance,
+                sender_balance.saturating_sub(Amount::from_str("1").unwrap())
+            ))
+        );
+        assert!(res.balance_changes.get(&recipient_address).is_none());
+        assert!(res.out.events.0.iter().any(|event| event.context.is_error));
+
+        // a simulated operation whose fee cannot be paid cannot be included in a block
+        let res = controller.execute_readonly_request(ReadOnlyExecutionRequest {
+            max_gas: 0,
+            call_stack: vec![],
+            target: ReadOnlyExecutionTarget::Operation {
+                sender_addr: sender_address,
+                fee: sender_balance.saturating_add(Amount::from_str("1").unwrap()),
+                op: OperationType::Transaction {
+                    recipient_address,
+                    amount: Amount::zero(),
+                },
+            },
+            is_final: true,
+            at_slot: None,
+        });
+        assert!(res.is_err());
+
+        manager.stop();
+    }
+
+    #[test]
+    fn test_search_min_gas_limit() {
+        // an execution using `used` gas that only succeeds with at least `needed` gas
+        let execution = |used: u64, needed: u64| {
+            move |gas_limit: u64| -> Result<Result<u64, ExecutionError>, ExecutionError> {
+                if gas_limit >= needed {
+                    Ok(Ok(used))
+                } else {
+                    Ok(Err(ExecutionError::NotEnoughGas("out of gas".to_string())))
+                }
+            }
+        };
+
+        // the gas used is enough
+        assert_eq!(
+            search_min_gas_limit(1000, execution(100, 100)).unwrap(),
+            100
+        );
+        // more gas is needed than what is used, e.g. for a nested call
+        assert_eq!(
+            search_min_gas_limit(1000, execution(100, 357)).unwrap(),
+            357
+        );
+        assert_eq!(
+            search_min_gas_limit(1000, execution(100, 1000)).unwrap(),
+            1000
+        );
+        // the execution fails with the highest gas limit
+        assert!(search_min_gas_limit(1000, execution(100, 1001)).is_err());
+
+        // an error of the execution itself interrupts the search
+        let mut tries = 0;
+        let res = search_min_gas_limit(1000, |gas_limit| {
+            tries += 1;
+            if gas_limit == 1000 {
+                Ok(Ok(10))
+            } else {
+                Err(ExecutionError::ChannelError("stopped".to_string()))
+            }
+        });
+        assert!(matches!(res, Err(ExecutionError::ChannelError(_))));
+        assert_eq!(tries, 2);
+    }
+
+    #[test]
+    #[serial]
+    fn test_estimate_gas() {
+        // setup the period duration
+        let exec_cfg = ExecutionConfig {
+            t0: MassaTime::from_millis(100),
+            cursor_delay: MassaTime::from_millis(0),
+            ..ExecutionConfig::default()
+        };
+        // init the MIP store
+        let mip_stats_config = MipStatsConfig {
+            block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+            warn_announced_version_ratio: Ratio::new_raw(30, 100),
+        };
+        let mip_store = MipStore::try_from(([], mip_stats_config)).unwrap();
+        // get a sample final state
+        let (sample_state, _keep_file, _keep_dir) = get_sample_state(0).unwrap();
+        // init the storage
+        let storage = Storage::create_root();
+
+        let slot_execution_output_sender = broadcast::channel(5000).0;
+
+        let channels = ExecutionChannels {
+            slot_execution_output_sender,
+        };
+
+        // start the execution worker
+        let (mut manager, controller) = start_execution_worker(
+            exec_cfg.clone(),
+            sample_state.clone(),
+            sample_state.read().pos_state.selector.clone(),
+            mip_store,
+            channels,
+            Arc::new(RwLock::new(create_test_wallet(Some(PreHashMap::default())))),
+            MassaMetrics::new(
+                false,
+                "0.0.0.0:9898".parse().unwrap(),
+                32,
+                std::time::Duration::from_secs(5),
+            )
+            .0,
+        );
+        // initialize the execution system with genesis blocks
+        init_execution_worker(&exec_cfg, &storage, controller.clone());
+
+        let sender_address =
+            Address::from_public_key(&KeyPair::from_str(TEST_SK_1).unwrap().get_public_key());
+        let execute_sc = |max_gas: u64| OperationType::ExecuteSC {
+            data: include_bytes!("./wasm/event_test.wasm").to_vec(),
+            max_gas,
+            max_coins: Amount::zero(),
+            datastore: BTreeMap::default(),
+        };
+        let simulate = |max_gas: u64| {
+            controller
+                .execute_readonly_request(ReadOnlyExecutionRequest {
+                    max_gas,
+                    call_stack: vec![],
+                    target: ReadOnlyExecutionTarget::Operation {
+                        sender_addr: sender_address,
+                        fee: Amount::zero(),
+                        op: execute_sc(max_gas),
+                    },
+                    is_final: false,
+                    at_slot: None,
+                })
+                .expect("operation simulation failed")
+        };
+
+        // the estimated gas is the lowest limit with which the operation succeeds
+        let estimation = controller
+            .estimate_gas(sender_address, execute_sc(0))
+            .expect("gas estimation failed");
+        assert!(estimation.min_gas > 0);
+        assert!(estimation.recommended_gas >= estimation.min_gas);
+        assert!(simulate(estimation.min_gas).execution_error.is_none());
+        assert!(simulate(estimation.min_gas - 1).execution_error.is_some());
+
+        // only smart contract operations use gas
+        assert!(controller
+            .estimate_gas(
+                sender_address,
+                OperationType::Transaction {
+                    recipient_address: get_random_address(),
+                    amount: Amount::zero(),
+                },
+            )
+            .is_err());
+
+        manager.stop();
+    }
+
     /// generate a random address
     fn get_random_address() -> Address {
         let kp = KeyPair::generate(0).unwrap();
@@ -1387,6 +1675,179 @@ mod tests {
         manager.stop();
     }
 
+    #[test]
+    #[serial]
+    pub fn historical_ledger_reads() {
+        // setup the period duration and keep the reverse ledger diffs
+        let exec_cfg = ExecutionConfig {
+            t0: MassaTime::from_millis(100),
+            cursor_delay: MassaTime::from_millis(0),
+            historical_ledger_periods: 10,
+            ..ExecutionConfig::default()
+        };
+        // get a sample final state
+        let (sample_state, _keep_file, _keep_dir) = get_sample_state(0).unwrap();
+
+        // init the MIP store
+        let mip_stats_config = MipStatsConfig {
+            block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+            warn_announced_version_ratio: Ratio::new_raw(30, 100),
+        };
+        let mip_store = MipStore::try_from(([], mip_stats_config)).unwrap();
+
+        // init the storage
+        let mut storage = Storage::create_root();
+
+        let slot_execution_output_sender = broadcast::channel(5000).0;
+
+        let channels = ExecutionChannels {
+            slot_execution_output_sender,
+        };
+
+        // start the execution worker
+        let (mut manager, controller) = start_execution_worker(
+            exec_cfg.clone(),
+            sample_state.clone(),
+            sample_state.read().pos_state.selector.clone(),
+            mip_store,
+            channels,
+            Arc::new(RwLock::new(create_test_wallet(Some(PreHashMap::default())))),
+            MassaMetrics::new(
+                false,
+                "0.0.0.0:9898".parse().unwrap(),
+                32,
+                std::time::Duration::from_secs(5),
+            )
+            .0,
+        );
+        // initialize the execution system with genesis blocks
+        init_execution_worker(&exec_cfg, &storage, controller.clone());
+
+        let sender_keypair = KeyPair::from_str(TEST_SK_1).unwrap();
+        let sender_address = Address::from_public_key(&sender_keypair.get_public_key());
+        let sender_balance = sample_state
+            .read()
+            .ledger
+            .get_balance(&sender_address)
+            .unwrap();
+        let (recipient_address, _keypair) = get_random_address_full();
+        let past_slot = Slot::new(0, exec_cfg.thread_count - 1);
+
+        // finalize a block transferring coins from the sender to the recipient
+        let operation = Operation::new_verifiable(
+            Operation {
+                fee: Amount::zero(),
+                expire_period: 10,
+                op: OperationType::Transaction {
+                    recipient_address,
+                    amount: Amount::from_str("100").unwrap(),
+                },
+            },
+            OperationSerializer::new(),
+            &sender_keypair,
+        )
+        .unwrap();
+        storage.store_operations(vec![operation.clone()]);
+        let block = create_block(
+            KeyPair::generate(0).unwrap(),
+            vec![operation],
+            vec![],
+            Slot::new(1, 0),
+        )
+        .unwrap();
+        storage.store_block(block.clone());
+        let mut finalized_blocks: HashMap<Slot, BlockId> = Default::default();
+        finalized_blocks.insert(block.content.header.content.slot, block.id);
+        let mut block_metadata: PreHashMap<BlockId, ExecutionBlockMetadata> = Default::default();
+        block_metadata.insert(
+            block.id,
+            ExecutionBlockMetadata {
+                same_thread_parent_creator: Some(get_random_address()),
+                storage: Some(storage),
+            },
+        );
+        controller.update_blockclique_status(
+            finalized_blocks,
+            Default::default(),
+            block_metadata.clone(),
+        );
+        std::thread::sleep(Duration::from_millis(10));
+        assert_eq!(
+            sample_state.read().ledger.get_balance(&sender_address),
+            Some(sender_balance.saturating_sub(Amount::from_str("100").unwrap()))
+        );
+
+        // the final ledger items are read as they were before the transfer
+        let response = controller.query_state(ExecutionQueryRequest {
+            requests: vec![
+                ExecutionQueryRequestItem::AddressBalanceFinal(sender_address),
+                ExecutionQueryRequestItem::AddressExistsFinal(recipient_address),
+                ExecutionQueryRequestItem::AddressRollsFinal(sender_address),
+            ],
+            at_slot: Some(past_slot),
+        });
+        assert!(matches!(
+            response.responses[0],
+            Ok(ExecutionQueryResponseItem::Amount(balance)) if balance == sender_balance
+        ));
+        assert!(matches!(
+            response.responses[1],
+            Ok(ExecutionQueryResponseItem::Boolean(false))
+        ));
+        assert!(matches!(
+            response.responses[2],
+            Err(ExecutionQueryError::Unsupported(_))
+        ));
+
+        // a read-only execution starts from the ledger at the past slot
+        let simulate_at = |at_slot: Option<Slot>| {
+            controller.execute_readonly_request(ReadOnlyExecutionRequest {
+                max_gas: 0,
+                call_stack: vec![],
+                target: ReadOnlyExecutionTarget::Operation {
+                    sender_addr: sender_address,
+                    fee: Amount::zero(),
+                    op: OperationType::Transaction {
+                        recipient_address,
+                        amount: Amount::from_str("1").unwrap(),
+                    },
+                },
+                is_final: true,
+                at_slot,
+            })
+        };
+        let res = simulate_at(Some(past_slot)).expect("historical simulation failed");
+        assert_eq!(
+            res.balance_changes
+                .get(&sender_address)
+                .map(|(before, _)| *before),
+            Some(sender_balance)
+        );
+        let res = simulate_at(None).expect("simulation failed");
+        assert_eq!(
+            res.balance_changes
+                .get(&sender_address)
+                .map(|(before, _)| *before),
+            Some(sender_balance.saturating_sub(Amount::from_str("100").unwrap()))
+        );
+
+        // slots out of the retained window are refused
+        assert!(simulate_at(Some(Slot::new(0, 0))).is_err());
+        let response = controller.query_state(ExecutionQueryRequest {
+            requests: vec![ExecutionQueryRequestItem::AddressBalanceFinal(
+                sender_address,
+            )],
+            at_slot: Some(Slot::new(0, 0)),
+        });
+        assert!(matches!(
+            response.responses[0],
+            Err(ExecutionQueryError::NotFound(_))
+        ));
+
+        // stop the execution controller
+        manager.stop();
+    }
+
     #[test]
     #[serial]
     pub fn roll_buy() {
@@ -2527,6 +2988,149 @@ mod tests {
         manager.stop();
     }
 
+    /// Check that the datastore accesses and the events of a traced operation are recorded in its trace
+    #[test]
+    #[serial]
+    fn execution_traces() {
+        // setup the period duration and enable the execution traces
+        let exec_cfg = ExecutionConfig {
+            t0: MassaTime::from_millis(100),
+            cursor_delay: MassaTime::from_millis(0),
+            max_async_gas: 100_000,
+            max_final_execution_traces: Some(10),
+            ..ExecutionConfig::default()
+        };
+        // get a sample final state
+        let (sample_state, _keep_file, _keep_dir) = get_sample_state(0).unwrap();
+
+        // init the MIP store
+        let mip_stats_config = MipStatsConfig {
+            block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+            warn_announced_version_ratio: Ratio::new_raw(30, 100),
+        };
+        let mip_store = MipStore::try_from(([], mip_stats_config)).unwrap();
+
+        // init the storage
+        let mut storage = Storage::create_root();
+
+        let slot_execution_output_sender = broadcast::channel(5000).0;
+
+        let channels = ExecutionChannels {
+            slot_execution_output_sender,
+        };
+
+        // start the execution worker
+        let (mut manager, controller) = start_execution_worker(
+            exec_cfg.clone(),
+            sample_state.clone(),
+            sample_state.read().pos_state.selector.clone(),
+            mip_store,
+            channels,
+            Arc::new(RwLock::new(create_test_wallet(Some(PreHashMap::default())))),
+            MassaMetrics::new(
+                false,
+                "0.0.0.0:9898".parse().unwrap(),
+                32,
+                std::time::Duration::from_secs(5),
+            )
+            .0,
+        );
+        // initialize the execution system with genesis blocks
+        init_execution_worker(&exec_cfg, &storage, controller.clone());
+
+        // keypair associated to thread 0
+        let keypair = KeyPair::from_str(TEST_SK_1).unwrap();
+        let address = Address::from_public_key(&keypair.get_public_key());
+
+        // load bytecode
+        // you can check the source code of the following wasm file in massa-unit-tests-src
+        let bytecode = include_bytes!("./wasm/datastore_manipulations.wasm");
+        // create the block containing the smart contract execution operation
+        let operation =
+            create_execute_sc_operation(&keypair, bytecode, BTreeMap::default()).unwrap();
+        let operation_id = operation.id;
+        storage.store_operations(vec![operation.clone()]);
+        let block = create_block(
+            KeyPair::generate(0).unwrap(),
+            vec![operation],
+            vec![],
+            Slot::new(1, 0),
+        )
+        .unwrap();
+        // store the block in storage
+        storage.store_block(block.clone());
+        // set our block as a final block
+        let mut finalized_blocks: HashMap<Slot, BlockId> = Default::default();
+        finalized_blocks.insert(block.content.header.content.slot, block.id);
+        let block_store = vec![(
+            block.id,
+            ExecutionBlockMetadata {
+                storage: Some(storage.clone()),
+                same_thread_parent_creator: Some(get_random_address()),
+            },
+        )]
+        .into_iter()
+        .collect();
+        controller.update_blockclique_status(finalized_blocks, Default::default(), block_store);
+        std::thread::sleep(
+            exec_cfg
+                .t0
+                .saturating_add(MassaTime::from_millis(50))
+                .into(),
+        );
+
+        // an operation that was never executed has no trace
+        let unknown_operation_id = create_execute_sc_operation(
+            &KeyPair::generate(0).unwrap(),
+            bytecode,
+            BTreeMap::default(),
+        )
+        .unwrap()
+        .id;
+        let traces = controller.get_execution_traces(&[
+            ExecutionTraceId::Operation(operation_id),
+            ExecutionTraceId::Operation(unknown_operation_id),
+        ]);
+        assert!(traces[1].is_none());
+
+        // the trace of the final operation was kept
+        let trace = traces[0].clone().expect("the operation was not traced");
+        assert_eq!(trace.slot, Slot::new(1, 0));
+        assert!(trace.error.is_none(), "{:?}", trace.error);
+        assert_eq!(trace.call.address, address);
+        assert!(trace.call.gas_used.is_some());
+
+        // every event and datastore write of the execution is in the trace
+        let events = controller.get_filtered_sc_output_event(EventFilter::default());
+        let traced_events: Vec<_> = trace
+            .call
+            .steps
+            .iter()
+            .filter_map(|step| match step {
+                ExecutionTraceStep::Event(event) => Some(event.data.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            traced_events,
+            events
+                .into_iter()
+                .map(|event| event.data)
+                .collect::<Vec<_>>()
+        );
+        assert!(trace.call.steps.iter().any(|step| matches!(
+            step,
+            ExecutionTraceStep::DatastoreWrite {
+                address: write_address,
+                success: true,
+                ..
+            } if *write_address == address
+        )));
+
+        // stop the execution controller
+        manager.stop();
+    }
+
     /// This test checks causes a history rewrite in slot sequencing and ensures that emitted events match
     #[test]
     #[serial]
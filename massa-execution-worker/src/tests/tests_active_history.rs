@@ -58,6 +58,7 @@ mod tests {
                 execution_trail_hash_change: Default::default(),
             },
             events: Default::default(),
+            traces: Default::default(),
         };
 
         let active_history = ActiveHistory {
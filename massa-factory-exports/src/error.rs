@@ -10,4 +10,6 @@ pub type FactoryResult<T, E = FactoryError> = core::result::Result<T, E>;
 pub enum FactoryError {
     /// Generic error: {0}
     GenericError(String),
+    /// Slashing protection error: {0}
+    SlashingProtectionError(String),
 }
@@ -1,5 +1,6 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::SlashingProtectionDB;
 use massa_channel::receiver::MassaReceiver;
 use massa_factory_exports::{FactoryChannels, FactoryConfig};
 use massa_models::{
@@ -7,6 +8,7 @@ use massa_models::{
     block_header::{BlockHeader, BlockHeaderSerializer, SecuredHeader},
     block_id::BlockId,
     endorsement::SecureShareEndorsement,
+    error::ModelsError,
     operation::{compute_operations_hash, OperationIdSerializer},
     secure_share::SecureShareContent,
     slot::Slot,
@@ -14,19 +16,19 @@ use massa_models::{
 };
 use massa_time::MassaTime;
 use massa_versioning::versioning::MipStore;
-use massa_wallet::Wallet;
-use parking_lot::RwLock;
+use massa_wallet::Signer;
 use std::{sync::Arc, thread, time::Instant};
 use tracing::{info, warn};
 
 /// Structure gathering all elements needed by the factory thread
 pub(crate) struct BlockFactoryWorker {
     cfg: FactoryConfig,
-    wallet: Arc<RwLock<Wallet>>,
+    signer: Arc<dyn Signer>,
     channels: FactoryChannels,
     factory_receiver: MassaReceiver<()>,
     mip_store: MipStore,
     op_id_serializer: OperationIdSerializer,
+    slashing_protection: Arc<SlashingProtectionDB>,
 }
 
 impl BlockFactoryWorker {
@@ -34,21 +36,23 @@ impl BlockFactoryWorker {
     /// needed by the factory worker thread.
     pub(crate) fn spawn(
         cfg: FactoryConfig,
-        wallet: Arc<RwLock<Wallet>>,
+        signer: Arc<dyn Signer>,
         channels: FactoryChannels,
         factory_receiver: MassaReceiver<()>,
         mip_store: MipStore,
+        slashing_protection: Arc<SlashingProtectionDB>,
     ) -> thread::JoinHandle<()> {
         thread::Builder::new()
             .name("block-factory".into())
             .spawn(|| {
                 let mut this = Self {
                     cfg,
-                    wallet,
+                    signer,
                     channels,
                     factory_receiver,
                     mip_store,
                     op_id_serializer: OperationIdSerializer::new(),
+                    slashing_protection,
                 };
                 this.run();
             })
@@ -134,25 +138,30 @@ impl BlockFactoryWorker {
             }
         };
 
-        // check if the block producer address is handled by the wallet
-        let block_producer_keypair_ref = self.wallet.read();
-        let block_producer_keypair = if let Some(kp) =
-            block_producer_keypair_ref.find_associated_keypair(&block_producer_addr)
-        {
+        // check if the block producer address is handled by the signer
+        let block_producer_public_key = match self.signer.get_public_key(&block_producer_addr) {
             // the selected block producer is managed locally => continue to attempt block production
-            kp
-        } else {
+            Ok(Some(public_key)) => public_key,
             // the selected block producer is not managed locally => quit
-            return;
+            Ok(None) => return,
+            Err(err) => {
+                warn!(
+                    "block factory could not get the public key of address {} for slot {}: {}",
+                    block_producer_addr, slot, err
+                );
+                return;
+            }
         };
         let mut block_storage = self.channels.storage.clone_without_refs();
         {
+            // a block from this address at this slot may have been produced by another node using the same keys
             let block_lock = block_storage.read_blocks();
             if let Some(block_ids) = block_lock.get_blocks_by_slot(&slot) {
                 for block_id in block_ids {
                     if let Some(block) = block_lock.get(block_id) {
                         if block.content_creator_address == block_producer_addr {
-                            panic!("You already created a block for slot {} with address {}, node is stopping to prevent you from losing all your stake due to double staking protection", slot, block_producer_addr);
+                            warn!("block factory refused to produce a block at slot {} with address {}: a block from this address at this slot is already known, make sure the same keys are not staking on another node", slot, block_producer_addr);
+                            return;
                         }
                     }
                 }
@@ -208,35 +217,75 @@ impl BlockFactoryWorker {
 
         block_storage.extend(op_storage);
 
+        // never sign twice at the same slot, even across restarts
+        if !self
+            .slashing_protection
+            .try_record_block(&block_producer_addr, &slot)
+        {
+            warn!(
+                "block factory refused to produce a block at slot {} with address {}: already signed according to the slashing protection database",
+                slot, block_producer_addr
+            );
+            return;
+        }
+
         // create header
         let current_version = self.mip_store.get_network_version_current();
         let announced_version = self.mip_store.get_network_version_to_announce();
-        let header: SecuredHeader = BlockHeader::new_verifiable::<BlockHeaderSerializer, BlockId>(
-            BlockHeader {
-                current_version,
-                announced_version,
-                slot,
-                parents: parents.into_iter().map(|(id, _period)| id).collect(),
-                operation_merkle_root: compute_operations_hash(&op_ids, &self.op_id_serializer),
-                endorsements,
-                denunciations: self.channels.pool.get_block_denunciations(&slot),
-            },
-            BlockHeaderSerializer::new(), // TODO reuse self.block_header_serializer
-            block_producer_keypair,
-        )
-        .expect("error while producing block header");
+        let header: SecuredHeader =
+            match BlockHeader::new_verifiable_with_signer::<BlockHeaderSerializer, BlockId>(
+                BlockHeader {
+                    current_version,
+                    announced_version,
+                    slot,
+                    parents: parents.into_iter().map(|(id, _period)| id).collect(),
+                    operation_merkle_root: compute_operations_hash(&op_ids, &self.op_id_serializer),
+                    endorsements,
+                    denunciations: self.channels.pool.get_block_denunciations(&slot),
+                },
+                BlockHeaderSerializer::new(), // TODO reuse self.block_header_serializer
+                &block_producer_public_key,
+                |hash| {
+                    self.signer
+                        .sign_hash(&block_producer_addr, hash)
+                        .map_err(|err| ModelsError::SignerError(err.to_string()))
+                },
+            ) {
+                Ok(header) => header,
+                Err(err) => {
+                    warn!(
+                    "block factory could not sign the block header at slot {} with address {}: {}",
+                    slot, block_producer_addr, err
+                );
+                    return;
+                }
+            };
         // create block
         let block_ = Block {
             header,
             operations: op_ids.into_iter().collect(),
         };
 
-        let block = Block::new_verifiable(
+        // the block carries the signature of its header: the signer is not called again
+        let block = match Block::new_verifiable_with_signer(
             block_,
             BlockSerializer::new(), // TODO reuse self.block_serializer
-            block_producer_keypair,
-        )
-        .expect("error while producing block");
+            &block_producer_public_key,
+            |_| {
+                Err(ModelsError::SignerError(
+                    "a block is signed through its header".to_string(),
+                ))
+            },
+        ) {
+            Ok(block) => block,
+            Err(err) => {
+                warn!(
+                    "block factory could not produce the block at slot {} with address {}: {}",
+                    slot, block_producer_addr, err
+                );
+                return;
+            }
+        };
         let block_id = block.id;
         // store block in storage
         block_storage.store_block(block);
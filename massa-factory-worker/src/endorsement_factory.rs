@@ -1,29 +1,32 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::SlashingProtectionDB;
 use massa_channel::receiver::MassaReceiver;
 use massa_factory_exports::{FactoryChannels, FactoryConfig};
 use massa_models::{
+    address::Address,
     block_id::BlockId,
     endorsement::{Endorsement, EndorsementSerializer, SecureShareEndorsement},
+    error::ModelsError,
     secure_share::SecureShareContent,
     slot::Slot,
     timeslots::{get_block_slot_timestamp, get_closest_slot_to_timestamp},
 };
-use massa_signature::KeyPair;
+use massa_signature::PublicKey;
 use massa_time::MassaTime;
-use massa_wallet::Wallet;
-use parking_lot::RwLock;
+use massa_wallet::Signer;
 use std::{sync::Arc, thread, time::Instant};
 use tracing::{debug, warn};
 
 /// Structure gathering all elements needed by the factory thread
 pub(crate) struct EndorsementFactoryWorker {
     cfg: FactoryConfig,
-    wallet: Arc<RwLock<Wallet>>,
+    signer: Arc<dyn Signer>,
     channels: FactoryChannels,
     factory_receiver: MassaReceiver<()>,
     half_t0: MassaTime,
     endorsement_serializer: EndorsementSerializer,
+    slashing_protection: Arc<SlashingProtectionDB>,
 }
 
 impl EndorsementFactoryWorker {
@@ -31,9 +34,10 @@ impl EndorsementFactoryWorker {
     /// needed by the factory worker thread.
     pub(crate) fn spawn(
         cfg: FactoryConfig,
-        wallet: Arc<RwLock<Wallet>>,
+        signer: Arc<dyn Signer>,
         channels: FactoryChannels,
         factory_receiver: MassaReceiver<()>,
+        slashing_protection: Arc<SlashingProtectionDB>,
     ) -> thread::JoinHandle<()> {
         thread::Builder::new()
             .name("endorsement-factory".into())
@@ -44,10 +48,11 @@ impl EndorsementFactoryWorker {
                         .checked_div_u64(2)
                         .expect("could not compute half_t0"),
                     cfg,
-                    wallet,
+                    signer,
                     channels,
                     factory_receiver,
                     endorsement_serializer: EndorsementSerializer::new(),
+                    slashing_protection,
                 };
                 this.run();
             })
@@ -134,22 +139,24 @@ impl EndorsementFactoryWorker {
             }
         };
 
-        // get creators if they are managed by our wallet
-        let mut producers_indices: Vec<(KeyPair, usize)> = Vec::new();
-        {
-            let wallet = self.wallet.read();
-            for (index, producer_addr) in producer_addrs.into_iter().enumerate() {
-                // check if the block producer address is handled by the wallet
-                let producer_keypair =
-                    if let Some(kp) = wallet.find_associated_keypair(&producer_addr) {
-                        // the selected block producer is managed locally => continue to attempt endorsement production
-                        kp.clone()
-                    } else {
-                        // the selected block producer is not managed locally => continue
-                        continue;
-                    };
-                producers_indices.push((producer_keypair, index));
-            }
+        // get creators if they are managed by our signer
+        let mut producers_indices: Vec<(Address, PublicKey, usize)> = Vec::new();
+        for (index, producer_addr) in producer_addrs.into_iter().enumerate() {
+            // check if the endorsement producer address is handled by the signer
+            let producer_public_key = match self.signer.get_public_key(&producer_addr) {
+                // the selected endorsement producer is managed locally => continue to attempt endorsement production
+                Ok(Some(public_key)) => public_key,
+                // the selected endorsement producer is not managed locally => continue
+                Ok(None) => continue,
+                Err(err) => {
+                    warn!(
+                        "endorsement factory could not get the public key of address {} for slot {}: {}",
+                        producer_addr, slot, err
+                    );
+                    continue;
+                }
+            };
+            producers_indices.push((producer_addr, producer_public_key, index));
         }
 
         // quit if there is nothing to produce
@@ -177,17 +184,42 @@ impl EndorsementFactoryWorker {
         // produce endorsements
         let mut endorsements: Vec<SecureShareEndorsement> =
             Vec::with_capacity(producers_indices.len());
-        for (keypair, index) in producers_indices {
-            let endorsement = Endorsement::new_verifiable(
+        for (producer_addr, public_key, index) in producers_indices {
+            // never sign twice the same endorsement, even across restarts
+            if !self
+                .slashing_protection
+                .try_record_endorsement(&producer_addr, &slot, index as u32)
+            {
+                warn!(
+                    "endorsement factory refused to endorse again at slot {} index {} with address {}: already signed according to the slashing protection database",
+                    slot, index, producer_addr
+                );
+                continue;
+            }
+
+            let endorsement = match Endorsement::new_verifiable_with_signer(
                 Endorsement {
                     slot,
                     index: index as u32,
                     endorsed_block,
                 },
                 self.endorsement_serializer.clone(),
-                &keypair,
-            )
-            .expect("could not create endorsement");
+                &public_key,
+                |hash| {
+                    self.signer
+                        .sign_hash(&producer_addr, hash)
+                        .map_err(|err| ModelsError::SignerError(err.to_string()))
+                },
+            ) {
+                Ok(endorsement) => endorsement,
+                Err(err) => {
+                    warn!(
+                        "endorsement factory could not sign the endorsement at slot {} index {} with address {}: {}",
+                        slot, index, producer_addr, err
+                    );
+                    continue;
+                }
+            };
 
             // log endorsement creation
             debug!(
@@ -4,8 +4,12 @@ mod block_factory;
 mod endorsement_factory;
 mod manager;
 mod run;
+mod slashing_protection;
 
 pub use run::start_factory;
+pub use slashing_protection::{
+    SignedBlockRecord, SignedEndorsementRecord, SlashingProtectionDB, SlashingProtectionInterchange,
+};
 
 #[cfg(test)]
 mod tests;
@@ -2,30 +2,31 @@
 
 use massa_channel::MassaChannel;
 use massa_versioning::versioning::MipStore;
-use parking_lot::RwLock;
 use std::sync::Arc;
 
 use crate::{
     block_factory::BlockFactoryWorker, endorsement_factory::EndorsementFactoryWorker,
-    manager::FactoryManagerImpl,
+    manager::FactoryManagerImpl, SlashingProtectionDB,
 };
 use massa_factory_exports::{FactoryChannels, FactoryConfig, FactoryManager};
-use massa_wallet::Wallet;
+use massa_wallet::Signer;
 
 /// Start factory
 ///
 /// # Arguments
 /// * `cfg`: factory configuration
-/// * `wallet`: atomic reference to the node wallet
+/// * `signer`: provides the public keys and signatures of the staking addresses
 /// * `channels`: channels to communicate with other modules
+/// * `slashing_protection`: record of the slots already signed by the staking addresses
 ///
 /// # Return value
 /// Returns a factory manager allowing to stop the workers cleanly.
 pub fn start_factory(
     cfg: FactoryConfig,
-    wallet: Arc<RwLock<Wallet>>,
+    signer: Arc<dyn Signer>,
     channels: FactoryChannels,
     mip_store: MipStore,
+    slashing_protection: Arc<SlashingProtectionDB>,
 ) -> Box<dyn FactoryManager> {
     // create block factory channel
     let (block_worker_tx, block_worker_rx) =
@@ -38,15 +39,21 @@ pub fn start_factory(
     // start block factory worker
     let block_worker_handle = BlockFactoryWorker::spawn(
         cfg.clone(),
-        wallet.clone(),
+        signer.clone(),
         channels.clone(),
         block_worker_rx,
         mip_store,
+        slashing_protection.clone(),
     );
 
     // start endorsement factory worker
-    let endorsement_worker_handle =
-        EndorsementFactoryWorker::spawn(cfg, wallet, channels, endorsement_worker_rx);
+    let endorsement_worker_handle = EndorsementFactoryWorker::spawn(
+        cfg,
+        signer,
+        channels,
+        endorsement_worker_rx,
+        slashing_protection,
+    );
 
     // create factory manager
     let manager = FactoryManagerImpl {
@@ -0,0 +1,220 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Persistent slashing protection for the block and endorsement factories.
+//!
+//! Every slot signed by a staking address is recorded on disk before the signature is made,
+//! so that the node never signs twice at the same position, even after a restart.
+//! The records can be exported to (and imported from) a JSON interchange file
+//! to move staking keys from one host to another.
+
+use massa_factory_exports::{FactoryError, FactoryResult};
+use massa_models::{address::Address, slot::Slot};
+use parking_lot::Mutex;
+use rocksdb::{ColumnFamilyDescriptor, IteratorMode, Options, WriteOptions, DB};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const BLOCKS_CF: &str = "blocks";
+const ENDORSEMENTS_CF: &str = "endorsements";
+
+const OPEN_ERROR: &str = "critical: slashing protection rocksdb open operation failed";
+const CRUD_ERROR: &str = "critical: slashing protection rocksdb crud operation failed";
+const CF_ERROR: &str = "critical: slashing protection rocksdb column family operation failed";
+const RECORD_SER_ERROR: &str = "critical: slashing protection record serialization failed";
+const RECORD_DESER_ERROR: &str = "critical: slashing protection record deserialization failed";
+
+/// A block signed by a staking address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedBlockRecord {
+    /// block creator
+    pub address: Address,
+    /// slot of the block
+    pub slot: Slot,
+}
+
+/// An endorsement signed by a staking address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedEndorsementRecord {
+    /// endorsement creator
+    pub address: Address,
+    /// slot of the endorsement
+    pub slot: Slot,
+    /// index of the endorsement in the block
+    pub index: u32,
+}
+
+/// Content of a slashing protection interchange file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlashingProtectionInterchange {
+    /// signed blocks
+    pub blocks: Vec<SignedBlockRecord>,
+    /// signed endorsements
+    pub endorsements: Vec<SignedEndorsementRecord>,
+}
+
+/// On-disk record of the slots signed by the staking addresses
+pub struct SlashingProtectionDB {
+    /// RocksDB database
+    db: DB,
+    /// ensures that checking and recording a signature is atomic
+    record_lock: Mutex<()>,
+}
+
+impl SlashingProtectionDB {
+    /// Open (or create) the slashing protection database
+    ///
+    /// # Arguments
+    /// * path: where to store the db
+    pub fn new(path: PathBuf) -> Self {
+        let mut db_opts = Options::default();
+        db_opts.create_if_missing(true);
+        db_opts.create_missing_column_families(true);
+
+        let db = DB::open_cf_descriptors(
+            &db_opts,
+            path,
+            vec![
+                ColumnFamilyDescriptor::new(BLOCKS_CF, Options::default()),
+                ColumnFamilyDescriptor::new(ENDORSEMENTS_CF, Options::default()),
+            ],
+        )
+        .expect(OPEN_ERROR);
+
+        Self {
+            db,
+            record_lock: Mutex::new(()),
+        }
+    }
+
+    fn block_key(address: &Address, slot: &Slot) -> Vec<u8> {
+        let mut key = address.to_prefixed_bytes();
+        key.extend(slot.to_bytes_key());
+        key
+    }
+
+    fn endorsement_key(address: &Address, slot: &Slot, index: u32) -> Vec<u8> {
+        let mut key = Self::block_key(address, slot);
+        key.extend(index.to_be_bytes());
+        key
+    }
+
+    /// Record a signature under a key, unless there is already one.
+    /// The record is synced to disk before returning.
+    ///
+    /// # Return value
+    /// Returns `true` if the signature was recorded and can be made, `false` if it was already made.
+    fn try_record(&self, handle_str: &str, key: Vec<u8>, value: Vec<u8>) -> bool {
+        let _guard = self.record_lock.lock();
+        let handle = self.db.cf_handle(handle_str).expect(CF_ERROR);
+        if self.db.get_cf(handle, &key).expect(CRUD_ERROR).is_some() {
+            return false;
+        }
+        let mut write_opts = WriteOptions::default();
+        write_opts.set_sync(true);
+        self.db
+            .put_cf_opt(handle, key, value, &write_opts)
+            .expect(CRUD_ERROR);
+        true
+    }
+
+    /// Record that `address` is about to sign a block at `slot`.
+    ///
+    /// # Return value
+    /// Returns `false` if this address already signed a block at that slot: the block must not be produced.
+    pub fn try_record_block(&self, address: &Address, slot: &Slot) -> bool {
+        let record = SignedBlockRecord {
+            address: *address,
+            slot: *slot,
+        };
+        self.try_record(
+            BLOCKS_CF,
+            Self::block_key(address, slot),
+            serde_json::to_vec(&record).expect(RECORD_SER_ERROR),
+        )
+    }
+
+    /// Record that `address` is about to sign the endorsement of index `index` at `slot`.
+    ///
+    /// # Return value
+    /// Returns `false` if this address already signed that endorsement: it must not be produced.
+    pub fn try_record_endorsement(&self, address: &Address, slot: &Slot, index: u32) -> bool {
+        let record = SignedEndorsementRecord {
+            address: *address,
+            slot: *slot,
+            index,
+        };
+        self.try_record(
+            ENDORSEMENTS_CF,
+            Self::endorsement_key(address, slot, index),
+            serde_json::to_vec(&record).expect(RECORD_SER_ERROR),
+        )
+    }
+
+    /// Get all the records of the database
+    pub fn get_interchange(&self) -> SlashingProtectionInterchange {
+        let handle_blocks = self.db.cf_handle(BLOCKS_CF).expect(CF_ERROR);
+        let handle_endorsements = self.db.cf_handle(ENDORSEMENTS_CF).expect(CF_ERROR);
+        SlashingProtectionInterchange {
+            blocks: self
+                .db
+                .iterator_cf(handle_blocks, IteratorMode::Start)
+                .map(|item| item.expect(CRUD_ERROR))
+                .map(|(_, value)| serde_json::from_slice(&value).expect(RECORD_DESER_ERROR))
+                .collect(),
+            endorsements: self
+                .db
+                .iterator_cf(handle_endorsements, IteratorMode::Start)
+                .map(|item| item.expect(CRUD_ERROR))
+                .map(|(_, value)| serde_json::from_slice(&value).expect(RECORD_DESER_ERROR))
+                .collect(),
+        }
+    }
+
+    /// Add records to the database. Existing records are kept.
+    pub fn merge_interchange(&self, interchange: &SlashingProtectionInterchange) {
+        for record in &interchange.blocks {
+            self.try_record_block(&record.address, &record.slot);
+        }
+        for record in &interchange.endorsements {
+            self.try_record_endorsement(&record.address, &record.slot, record.index);
+        }
+    }
+
+    /// Export all the records to a JSON interchange file
+    pub fn export(&self, path: &Path) -> FactoryResult<()> {
+        let content = serde_json::to_string_pretty(&self.get_interchange()).map_err(|err| {
+            FactoryError::SlashingProtectionError(format!(
+                "could not serialize slashing protection records: {}",
+                err
+            ))
+        })?;
+        std::fs::write(path, content).map_err(|err| {
+            FactoryError::SlashingProtectionError(format!(
+                "could not write slashing protection file {}: {}",
+                path.display(),
+                err
+            ))
+        })
+    }
+
+    /// Import the records of a JSON interchange file. Existing records are kept.
+    pub fn import(&self, path: &Path) -> FactoryResult<()> {
+        let content = std::fs::read(path).map_err(|err| {
+            FactoryError::SlashingProtectionError(format!(
+                "could not read slashing protection file {}: {}",
+                path.display(),
+                err
+            ))
+        })?;
+        let interchange: SlashingProtectionInterchange =
+            serde_json::from_slice(&content).map_err(|err| {
+                FactoryError::SlashingProtectionError(format!(
+                    "could not parse slashing protection file {}: {}",
+                    path.display(),
+                    err
+                ))
+            })?;
+        self.merge_interchange(&interchange);
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 mod scenarios;
+mod slashing_protection;
 mod tools;
 
 pub use tools::*;
@@ -0,0 +1,36 @@
+use crate::{SlashingProtectionDB, SlashingProtectionInterchange};
+use massa_models::{address::Address, slot::Slot};
+use massa_signature::KeyPair;
+use tempfile::TempDir;
+
+/// Checks that a slot can only be signed once, and that the records survive an export / import.
+#[test]
+fn slashing_protection_records_and_interchange() {
+    let keypair = KeyPair::generate(0).unwrap();
+    let address = Address::from_public_key(&keypair.get_public_key());
+    let slot = Slot::new(10, 3);
+
+    let source_dir = TempDir::new().unwrap();
+    let source = SlashingProtectionDB::new(source_dir.path().to_path_buf());
+    assert!(source.try_record_block(&address, &slot));
+    assert!(!source.try_record_block(&address, &slot));
+    assert!(source.try_record_endorsement(&address, &slot, 2));
+    assert!(!source.try_record_endorsement(&address, &slot, 2));
+    assert!(source.try_record_endorsement(&address, &slot, 5));
+
+    let export_dir = TempDir::new().unwrap();
+    let export_path = export_dir.path().join("slashing_protection.json");
+    source.export(&export_path).unwrap();
+
+    let target_dir = TempDir::new().unwrap();
+    let target = SlashingProtectionDB::new(target_dir.path().to_path_buf());
+    assert_eq!(
+        target.get_interchange(),
+        SlashingProtectionInterchange::default()
+    );
+    target.import(&export_path).unwrap();
+    assert_eq!(target.get_interchange(), source.get_interchange());
+    assert!(!target.try_record_block(&address, &slot));
+    assert!(!target.try_record_endorsement(&address, &slot, 5));
+    assert!(target.try_record_block(&address, &Slot::new(10, 4)));
+}
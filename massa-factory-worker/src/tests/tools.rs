@@ -8,6 +8,7 @@ use massa_versioning::versioning::MipStore;
 use num::rational::Ratio;
 use parking_lot::RwLock;
 use std::{sync::Arc, thread::sleep, time::Duration};
+use tempfile::TempDir;
 
 use massa_factory_exports::{
     test_exports::create_empty_block, FactoryChannels, FactoryConfig, FactoryManager,
@@ -29,8 +30,8 @@ use massa_signature::KeyPair;
 use massa_storage::Storage;
 use massa_time::MassaTime;
 
-use crate::start_factory;
-use massa_wallet::test_exports::create_test_wallet;
+use crate::{start_factory, SlashingProtectionDB};
+use massa_wallet::{test_exports::create_test_wallet, WalletSigner};
 
 /// This structure store all information and links to creates tests for the factory.
 /// The factory will ask that to the the pool, consensus and factory and then will send the block to the consensus.
@@ -46,6 +47,7 @@ pub struct TestFactory {
     genesis_blocks: Vec<(BlockId, u64)>,
     pub(crate) storage: Storage,
     keypair: KeyPair,
+    _slashing_protection_dir: TempDir,
 }
 
 impl TestFactory {
@@ -90,9 +92,13 @@ impl TestFactory {
             MipStore::try_from(([], mip_stats_config)).expect("Cannot create an empty MIP store");
 
         let wallet = create_test_wallet(Some(accounts));
+        let slashing_protection_dir = TempDir::new().expect("cannot create temp directory");
+        let slashing_protection = Arc::new(SlashingProtectionDB::new(
+            slashing_protection_dir.path().to_path_buf(),
+        ));
         let factory_manager = start_factory(
             factory_config.clone(),
-            Arc::new(RwLock::new(wallet)),
+            Arc::new(WalletSigner::new(Arc::new(RwLock::new(wallet)))),
             FactoryChannels {
                 selector: selector_controller.clone(),
                 consensus: consensus_controller,
@@ -101,6 +107,7 @@ impl TestFactory {
                 storage: storage.clone_without_refs(),
             },
             mip_store,
+            slashing_protection,
         );
 
         TestFactory {
@@ -112,6 +119,7 @@ impl TestFactory {
             genesis_blocks,
             storage,
             keypair: default_keypair.clone(),
+            _slashing_protection_dir: slashing_protection_dir,
         }
     }
 
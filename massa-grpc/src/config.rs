@@ -115,6 +115,8 @@ pub struct GrpcConfig {
     pub max_operation_ids_per_request: u32,
     /// max number of filters that can be included in a single request
     pub max_filters_per_request: u32,
+    /// max number of smart contract execution events returned by a single request
+    pub max_events_per_request: u32,
     /// certificate authority root path
     pub certificate_authority_root_path: PathBuf,
     /// server certificate path
@@ -1,6 +1,5 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
-use std::net::IpAddr;
 use std::str::FromStr;
 
 use crate::error::GrpcError;
@@ -8,6 +7,7 @@ use crate::server::MassaPrivateGrpc;
 use massa_execution_exports::ExecutionQueryRequest;
 use massa_hash::Hash;
 use massa_models::config::CompactConfig;
+use massa_models::ip_list::{IpListEntry, IpNetwork};
 use massa_models::node::NodeId;
 use massa_models::slot::Slot;
 use massa_models::timeslots::get_latest_block_slot_at_timestamp;
@@ -19,7 +19,7 @@ use massa_time::MassaTime;
 use tracing::warn;
 // use massa_proto_rs::massa::model::v1 "add_to_bootstrap_blacklist"as grpc_model;
 
-/// Add IP addresses to node bootstrap blacklist
+/// Add IP addresses or CIDR ranges to node bootstrap blacklist
 pub(crate) fn add_to_bootstrap_blacklist(
     grpc: &MassaPrivateGrpc,
     request: tonic::Request<grpc_api::AddToBootstrapBlacklistRequest>,
@@ -29,8 +29,8 @@ pub(crate) fn add_to_bootstrap_blacklist(
     let ips = inner_req
         .ips
         .into_iter()
-        .filter_map(|ip| match IpAddr::from_str(&ip) {
-            Ok(ip_addr) => Some(ip_addr),
+        .filter_map(|ip| match IpNetwork::from_str(&ip) {
+            Ok(network) => Some(IpListEntry::from(network)),
             Err(e) => {
                 warn!("error when parsing address : {}", e);
                 None
@@ -39,14 +39,14 @@ pub(crate) fn add_to_bootstrap_blacklist(
         .collect();
 
     if let Some(bs_list) = &grpc.bs_white_black_list {
-        if let Err(e) = bs_list.add_ips_to_blacklist(ips) {
+        if let Err(e) = bs_list.add_to_blacklist(ips) {
             warn!("error when adding ips to bootstrap blacklist : {}", e)
         }
     }
 
     Ok(grpc_api::AddToBootstrapBlacklistResponse {})
 }
-/// Add IP addresses to node bootstrap whitelist
+/// Add IP addresses or CIDR ranges to node bootstrap whitelist
 pub(crate) fn add_to_bootstrap_whitelist(
     grpc: &MassaPrivateGrpc,
     request: tonic::Request<grpc_api::AddToBootstrapWhitelistRequest>,
@@ -56,8 +56,8 @@ pub(crate) fn add_to_bootstrap_whitelist(
     let ips = inner_req
         .ips
         .into_iter()
-        .filter_map(|ip| match IpAddr::from_str(&ip) {
-            Ok(ip_addr) => Some(ip_addr),
+        .filter_map(|ip| match IpNetwork::from_str(&ip) {
+            Ok(network) => Some(IpListEntry::from(network)),
             Err(e) => {
                 warn!("error when parsing address : {}", e);
                 None
@@ -66,7 +66,7 @@ pub(crate) fn add_to_bootstrap_whitelist(
         .collect();
 
     if let Some(bs_list) = &grpc.bs_white_black_list {
-        if let Err(e) = bs_list.add_ips_to_whitelist(ips) {
+        if let Err(e) = bs_list.add_to_whitelist(ips) {
             warn!("error when adding ips to bootstrap whitelist : {}", e)
         }
     }
@@ -141,7 +141,7 @@ pub(crate) fn ban_nodes_by_ids(
         })
         .collect::<Result<Vec<_>, _>>()?;
 
-    grpc.protocol_controller.ban_peers(peer_ids)?;
+    grpc.protocol_controller.ban_peers(peer_ids, None, None)?;
 
     Ok(grpc_api::BanNodesByIdsResponse {})
 }
@@ -154,7 +154,7 @@ pub(crate) fn ban_nodes_by_ips(
     Err(GrpcError::Unimplemented("ban_nodes_by_ips".to_string()))
 }
 
-/// Get node bootstrap blacklist IP addresses
+/// Get node bootstrap blacklist IP addresses and CIDR ranges
 pub(crate) fn get_bootstrap_blacklist(
     grpc: &MassaPrivateGrpc,
     _request: tonic::Request<grpc_api::GetBootstrapBlacklistRequest>,
@@ -165,14 +165,14 @@ pub(crate) fn get_bootstrap_blacklist(
                 .get_black_list()
                 .unwrap_or_default()
                 .into_iter()
-                .map(|ip| ip.to_string())
+                .map(|entry| entry.network.to_string())
                 .collect(),
             None => Vec::new(),
         }
     };
     Ok(grpc_api::GetBootstrapBlacklistResponse { ips: list })
 }
-/// Get node bootstrap whitelist IP addresses
+/// Get node bootstrap whitelist IP addresses and CIDR ranges
 pub(crate) fn get_bootstrap_whitelist(
     grpc: &MassaPrivateGrpc,
     _request: tonic::Request<grpc_api::GetBootstrapWhitelistRequest>,
@@ -183,7 +183,7 @@ pub(crate) fn get_bootstrap_whitelist(
                 .get_white_list()
                 .unwrap_or_default()
                 .into_iter()
-                .map(|ip| ip.to_string())
+                .map(|entry| entry.network.to_string())
                 .collect(),
             None => Vec::new(),
         }
@@ -279,7 +279,10 @@ pub(crate) fn get_node_status(
             })?
     };
     let next_cycle_time = current_cycle_time.checked_add(cycle_duration)?;
-    let empty_request = ExecutionQueryRequest { requests: vec![] };
+    let empty_request = ExecutionQueryRequest {
+        requests: vec![],
+        at_slot: None,
+    };
     let state = grpc.execution_controller.query_state(empty_request);
     let node_ip = grpc
         .protocol_config
@@ -317,7 +320,7 @@ pub(crate) fn get_peers_whitelist(
 ) -> Result<grpc_api::GetPeersWhitelistResponse, GrpcError> {
     Err(GrpcError::Unimplemented("get_peers_whitelist".to_string()))
 }
-/// Remove from bootstrap blacklist given IP addresses
+/// Remove from bootstrap blacklist given IP addresses or CIDR ranges
 pub(crate) fn remove_from_bootstrap_blacklist(
     grpc: &MassaPrivateGrpc,
     request: tonic::Request<grpc_api::RemoveFromBootstrapBlacklistRequest>,
@@ -326,8 +329,8 @@ pub(crate) fn remove_from_bootstrap_blacklist(
     let ips = inner_req
         .ips
         .into_iter()
-        .filter_map(|ip| match IpAddr::from_str(&ip) {
-            Ok(ip_addr) => Some(ip_addr),
+        .filter_map(|ip| match IpNetwork::from_str(&ip) {
+            Ok(network) => Some(network),
             Err(e) => {
                 warn!("error when parsing address : {}", e);
                 None
@@ -336,14 +339,14 @@ pub(crate) fn remove_from_bootstrap_blacklist(
         .collect();
 
     if let Some(bs_list) = &grpc.bs_white_black_list {
-        if let Err(e) = bs_list.remove_ips_from_blacklist(ips) {
+        if let Err(e) = bs_list.remove_from_blacklist(ips) {
             warn!("error when removing ips to bootstrap blacklist : {}", e)
         }
     }
 
     Ok(grpc_api::RemoveFromBootstrapBlacklistResponse {})
 }
-/// Remove from bootstrap whitelist given IP addresses
+/// Remove from bootstrap whitelist given IP addresses or CIDR ranges
 pub(crate) fn remove_from_bootstrap_whitelist(
     grpc: &MassaPrivateGrpc,
     request: tonic::Request<grpc_api::RemoveFromBootstrapWhitelistRequest>,
@@ -352,8 +355,8 @@ pub(crate) fn remove_from_bootstrap_whitelist(
     let ips = inner_req
         .ips
         .into_iter()
-        .filter_map(|ip| match IpAddr::from_str(&ip) {
-            Ok(ip_addr) => Some(ip_addr),
+        .filter_map(|ip| match IpNetwork::from_str(&ip) {
+            Ok(network) => Some(network),
             Err(e) => {
                 warn!("error when parsing address : {}", e);
                 None
@@ -362,7 +365,7 @@ pub(crate) fn remove_from_bootstrap_whitelist(
         .collect();
 
     if let Some(bs_list) = &grpc.bs_white_black_list {
-        if let Err(e) = bs_list.remove_ips_from_whitelist(ips) {
+        if let Err(e) = bs_list.remove_from_whitelist(ips) {
             warn!("error when removing ips to bootstrap whitelist : {}", e)
         }
     }
@@ -114,6 +114,7 @@ pub(crate) fn execute_read_only_call(
         call_stack,
         target,
         is_final: call.is_final,
+        at_slot: None,
     };
 
     let output = grpc
@@ -160,33 +161,47 @@ pub(crate) fn get_blocks(
         })
         .collect::<Result<_, _>>()?;
 
-    let read_blocks = grpc.storage.read_blocks();
-    let blocks = block_ids
-        .into_iter()
-        .filter_map(|id| {
-            let content = if let Some(wrapped_block) = read_blocks.get(&id) {
-                wrapped_block.content.clone()
-            } else {
-                return None;
-            };
-
-            Some(content)
-        })
-        .collect::<Vec<Block>>();
+    // (block, is the block coming from the archive)
+    let blocks = {
+        let read_blocks = grpc.storage.read_blocks();
+        block_ids
+            .into_iter()
+            .filter_map(|id| {
+                if let Some(wrapped_block) = read_blocks.get(&id) {
+                    Some((wrapped_block.content.clone(), false))
+                } else {
+                    // the block is not in memory anymore: look for it in the archive
+                    grpc.consensus_channels
+                        .archive
+                        .as_ref()?
+                        .get_block(&id)
+                        .map(|archived_block| (archived_block.content, true))
+                }
+            })
+            .collect::<Vec<(Block, bool)>>()
+    };
 
     let block_ids = blocks
         .iter()
-        .map(|block| block.header.id)
+        .map(|(block, _)| block.header.id)
         .collect::<Vec<BlockId>>();
 
     let blocks_status = grpc.consensus_controller.get_block_statuses(&block_ids);
 
     let result = blocks
-        .iter()
+        .into_iter()
         .zip(blocks_status)
-        .map(|(block, block_graph_status)| grpc_model::BlockWrapper {
-            block: Some(block.clone().into()),
-            status: block_graph_status.into(),
+        .map(|((block, archived), block_graph_status)| {
+            // archived blocks are final
+            let status = if archived {
+                BlockGraphStatus::Final
+            } else {
+                block_graph_status
+            };
+            grpc_model::BlockWrapper {
+                block: Some(block.into()),
+                status: status.into(),
+            }
         })
         .collect();
 
@@ -485,18 +500,30 @@ pub(crate) fn get_operations(
     let read_blocks = grpc.storage.read_blocks();
     let read_ops = grpc.storage.read_operations();
 
-    // Get the operations and the list of blocks that contain them from storage
-    let storage_info: Vec<(&SecureShareOperation, HashSet<BlockId>)> = operation_ids
+    // Get the operations and the list of blocks that contain them from storage,
+    // falling back to the archive (if enabled) for the operations that were pruned
+    let storage_info: Vec<(SecureShareOperation, HashSet<BlockId>)> = operation_ids
         .into_iter()
         .filter_map(|ope_id| {
-            read_ops.get(&ope_id).map(|secure_share| {
-                let block_ids = read_blocks
-                    .get_blocks_by_operation(&ope_id)
-                    .map(|hashset| hashset.iter().cloned().collect::<HashSet<BlockId>>())
-                    .unwrap_or_default();
+            read_ops
+                .get(&ope_id)
+                .map(|secure_share| {
+                    let block_ids = read_blocks
+                        .get_blocks_by_operation(&ope_id)
+                        .map(|hashset| hashset.iter().cloned().collect::<HashSet<BlockId>>())
+                        .unwrap_or_default();
 
-                (secure_share, block_ids)
-            })
+                    (secure_share.clone(), block_ids)
+                })
+                .or_else(|| {
+                    grpc.consensus_channels
+                        .archive
+                        .as_ref()?
+                        .get_operation(&ope_id)
+                        .map(|(secure_share, block_ids)| {
+                            (secure_share, block_ids.into_iter().collect())
+                        })
+                })
         })
         .collect();
 
@@ -508,7 +535,7 @@ pub(crate) fn get_operations(
                 thread: secure_share
                     .content_creator_address
                     .get_thread(grpc.grpc_config.thread_count) as u32,
-                operation: Some((*secure_share).clone().into()),
+                operation: Some(secure_share.into()),
                 block_ids: block_ids.into_iter().map(|id| id.to_string()).collect(),
             }
         })
@@ -524,7 +551,18 @@ pub(crate) fn get_sc_execution_events(
     grpc: &MassaPublicGrpc,
     request: tonic::Request<grpc_api::GetScExecutionEventsRequest>,
 ) -> Result<grpc_api::GetScExecutionEventsResponse, GrpcError> {
-    let event_filter = to_event_filter(request.into_inner().filters)?;
+    let inner_req = request.into_inner();
+    if inner_req.filters.len() as u32 > grpc.grpc_config.max_filters_per_request {
+        return Err(GrpcError::InvalidArgument(format!(
+            "too many filters received. Only a maximum of {} filters are accepted per request",
+            grpc.grpc_config.max_filters_per_request
+        )));
+    }
+
+    let event_filter = to_event_filter(
+        inner_req.filters,
+        grpc.grpc_config.max_events_per_request as usize,
+    )?;
     let events: Vec<grpc_model::ScExecutionEvent> = grpc
         .execution_controller
         .get_filtered_sc_output_event(event_filter)
@@ -694,7 +732,10 @@ pub(crate) fn get_status(
     };
     let next_cycle_time = current_cycle_time.checked_add(cycle_duration)?;
     //TODO to be enhanced
-    let empty_request = ExecutionQueryRequest { requests: vec![] };
+    let empty_request = ExecutionQueryRequest {
+        requests: vec![],
+        at_slot: None,
+    };
     let state = grpc.execution_controller.query_state(empty_request);
 
     let status = grpc_model::PublicStatus {
@@ -744,12 +785,15 @@ pub(crate) fn query_state(
         .into_inner()
         .queries
         .into_iter()
-        .map(to_querystate_filter)
+        .map(|query| to_querystate_filter(query, grpc.grpc_config.max_events_per_request as usize))
         .collect::<Result<Vec<_>, _>>()?;
 
     let response = grpc
         .execution_controller
-        .query_state(ExecutionQueryRequest { requests: queries });
+        .query_state(ExecutionQueryRequest {
+            requests: queries,
+            at_slot: None,
+        });
 
     Ok(grpc_api::QueryStateResponse {
         final_cursor: Some(response.final_cursor.into()),
@@ -840,12 +884,15 @@ pub(crate) fn search_blocks(
         return Err(GrpcError::InvalidArgument("no filter provided".to_string()));
     }
 
+    let archive = grpc.consensus_channels.archive.as_ref();
     let mut res: Option<PreHashSet<BlockId>> = None;
 
     // filter by block ids
     if let Some(mut b_ids) = block_ids_filter {
         let read_lock = grpc.storage.read_blocks();
-        b_ids.retain(|id: &BlockId| read_lock.contains(id));
+        b_ids.retain(|id: &BlockId| {
+            read_lock.contains(id) || archive.map_or(false, |archive| archive.contains_block(id))
+        });
 
         res = Some(b_ids);
     }
@@ -859,6 +906,12 @@ pub(crate) fn search_blocks(
                 if let Some(addr_b_ids) = read_lock.get_blocks_created_by(&addr) {
                     b_ids.extend(addr_b_ids.clone());
                 }
+                if let Some(archive) = archive {
+                    b_ids.extend(archive.get_block_ids_by_creator(
+                        &addr,
+                        grpc.grpc_config.max_block_ids_per_request as usize,
+                    ));
+                }
             }
 
             b_ids
@@ -885,8 +938,14 @@ pub(crate) fn search_blocks(
         end_slot = end_slot.max(start_slot);
 
         let read_lock = grpc.storage.read_blocks();
-        let b_ids: PreHashSet<BlockId> =
+        let mut b_ids: PreHashSet<BlockId> =
             read_lock.aggregate_blocks_by_slot_range(start_slot..end_slot);
+        if let Some(archive) = archive {
+            b_ids.extend(archive.get_block_ids_by_slot_range(
+                start_slot..end_slot,
+                grpc.grpc_config.max_block_ids_per_request as usize,
+            ));
+        }
 
         if let Some(block_ids) = res.as_mut() {
             block_ids.retain(|id: &BlockId| b_ids.contains(id));
@@ -905,12 +964,21 @@ pub(crate) fn search_blocks(
 
     let blocks_status = grpc.consensus_controller.get_block_statuses(&block_ids);
 
+    let read_lock = grpc.storage.read_blocks();
     let result = block_ids
         .iter()
         .zip(blocks_status)
-        .map(|(block_id, block_graph_status)| grpc_model::BlockInfo {
-            block_id: block_id.to_string(),
-            status: block_graph_status.into(),
+        .map(|(block_id, block_graph_status)| {
+            // blocks that are only found in the archive are final
+            let status = if read_lock.contains(block_id) {
+                block_graph_status
+            } else {
+                BlockGraphStatus::Final
+            };
+            grpc_model::BlockInfo {
+                block_id: block_id.to_string(),
+                status: status.into(),
+            }
         })
         .collect();
 
@@ -995,12 +1063,18 @@ pub(crate) fn search_endorsements(
         return Err(GrpcError::InvalidArgument("no filter provided".to_string()));
     }
 
+    let archive = grpc.consensus_channels.archive.as_ref();
     let mut eds_ids: Option<PreHashSet<EndorsementId>> = None;
 
     // filter by endorsement ids
     if let Some(mut e_ids) = endorsement_ids_filter {
         let read_lock = grpc.storage.read_endorsements();
-        e_ids.retain(|id: &EndorsementId| read_lock.contains(id));
+        e_ids.retain(|id: &EndorsementId| {
+            read_lock.contains(id)
+                || archive.map_or(false, |archive| {
+                    !archive.get_endorsement_block_ids(id).is_empty()
+                })
+        });
         eds_ids = Some(e_ids);
     }
 
@@ -1013,6 +1087,12 @@ pub(crate) fn search_endorsements(
                 if let Some(addr_e_ids) = read_lock.get_endorsements_created_by(&addr) {
                     e_ids.extend(addr_e_ids.clone());
                 }
+                if let Some(archive) = archive {
+                    e_ids.extend(archive.get_endorsement_ids_by_creator(
+                        &addr,
+                        grpc.grpc_config.max_endorsement_ids_per_request as usize,
+                    ));
+                }
             }
 
             e_ids
@@ -1039,6 +1119,18 @@ pub(crate) fn search_endorsements(
                     .map(|wrapped_endorsement| wrapped_endorsement.id)
                     .collect();
                 e_ids.extend(&b_endorsements);
+            } else if let Some(archived_block) =
+                archive.and_then(|archive| archive.get_block(&block_id))
+            {
+                e_ids.extend(
+                    archived_block
+                        .content
+                        .header
+                        .content
+                        .endorsements
+                        .iter()
+                        .map(|wrapped_endorsement| wrapped_endorsement.id),
+                );
             }
         }
 
@@ -1049,19 +1141,19 @@ pub(crate) fn search_endorsements(
         }
     }
 
-    let storage_info: Vec<(EndorsementId, PreHashSet<BlockId>)> = {
+    // (endorsement id, including blocks, are the blocks coming from the archive)
+    let storage_info: Vec<(EndorsementId, PreHashSet<BlockId>, bool)> = {
         let read_blocks_lock = grpc.storage.read_blocks();
         if let Some(endorsement_ids) = eds_ids {
             endorsement_ids
                 .into_iter()
-                .map(|id| {
-                    let block_ids = read_blocks_lock
-                        .get_blocks_by_endorsement(&id)
-                        .cloned()
-                        .unwrap_or_default();
-
-                    (id, block_ids)
-                })
+                .map(
+                    |id| match (read_blocks_lock.get_blocks_by_endorsement(&id), archive) {
+                        (Some(block_ids), _) => (id, block_ids.clone(), false),
+                        (None, Some(archive)) => (id, archive.get_endorsement_block_ids(&id), true),
+                        (None, None) => (id, PreHashSet::default(), false),
+                    },
+                )
                 .collect()
         } else {
             return Ok(grpc_api::SearchEndorsementsResponse {
@@ -1070,8 +1162,8 @@ pub(crate) fn search_endorsements(
         }
     };
 
-    // keep only the endorsements found in storage
-    let e_ids: Vec<EndorsementId> = storage_info.iter().map(|(ed, _)| *ed).collect();
+    // keep only the endorsements found in storage or in the archive
+    let e_ids: Vec<EndorsementId> = storage_info.iter().map(|(ed, _, _)| *ed).collect();
 
     // ask pool whether it carries the endorsements
     let in_pool = grpc.pool_controller.contains_endorsements(&e_ids);
@@ -1082,7 +1174,7 @@ pub(crate) fn search_endorsements(
     let is_final: Vec<bool> = {
         let involved_blocks: Vec<BlockId> = storage_info
             .iter()
-            .flat_map(|(_ed, bs)| bs.iter())
+            .flat_map(|(_ed, bs, _archived)| bs.iter())
             .unique()
             .cloned()
             .collect();
@@ -1095,9 +1187,12 @@ pub(crate) fn search_endorsements(
             .collect();
         storage_info
             .iter()
-            .map(|(_ed, bs)| {
-                bs.iter()
-                    .any(|b| block_statuses.get(b) == Some(&BlockGraphStatus::Final))
+            .map(|(_ed, bs, archived)| {
+                // archived endorsements are included in final blocks
+                *archived
+                    || bs
+                        .iter()
+                        .any(|b| block_statuses.get(b) == Some(&BlockGraphStatus::Final))
             })
             .collect()
     };
@@ -1110,7 +1205,7 @@ pub(crate) fn search_endorsements(
         is_final.into_iter()
     );
 
-    for ((e_id, in_blocks), in_pool, is_final) in zipped_iterator {
+    for ((e_id, in_blocks, _archived), in_pool, is_final) in zipped_iterator {
         res.push(grpc_model::EndorsementInfo {
             in_pool,
             is_final,
@@ -1185,12 +1280,18 @@ pub(crate) fn search_operations(
         return Err(GrpcError::InvalidArgument("no filter provided".to_string()));
     }
 
+    let archive = grpc.consensus_channels.archive.as_ref();
     let mut ops_ids: Option<PreHashSet<OperationId>> = None;
 
     // filter by operation ids
     if let Some(mut o_ids) = operation_ids_filter {
         let read_lock = grpc.storage.read_operations();
-        o_ids.retain(|id: &OperationId| read_lock.contains(id));
+        o_ids.retain(|id: &OperationId| {
+            read_lock.contains(id)
+                || archive.map_or(false, |archive| {
+                    !archive.get_operation_block_ids(id).is_empty()
+                })
+        });
         ops_ids = Some(o_ids);
     }
 
@@ -1203,6 +1304,12 @@ pub(crate) fn search_operations(
                 if let Some(addr_o_ids) = read_lock.get_operations_created_by(&addr) {
                     o_ids.extend(addr_o_ids.clone());
                 }
+                if let Some(archive) = archive {
+                    o_ids.extend(archive.get_operation_ids_by_creator(
+                        &addr,
+                        grpc.grpc_config.max_operation_ids_per_request as usize,
+                    ));
+                }
             }
 
             o_ids
@@ -1215,20 +1322,30 @@ pub(crate) fn search_operations(
     }
 
     let operations: Vec<grpc_model::OperationInfo> = if let Some(operation_ids) = ops_ids {
-        // Get the operations and the list of blocks that contain them from storage
+        // Get the operations and the list of blocks that contain them from storage,
+        // falling back to the archive (if enabled) for the operations that were pruned
         let read_ops_lock = grpc.storage.read_operations();
         let read_blocks_lock = grpc.storage.read_blocks();
-        let storage_info: Vec<(&SecureShareOperation, HashSet<BlockId>)> = operation_ids
+        let storage_info: Vec<(SecureShareOperation, HashSet<BlockId>)> = operation_ids
             .into_iter()
             .filter_map(|ope_id| {
-                read_ops_lock.get(&ope_id).map(|secure_share| {
-                    let block_ids = read_blocks_lock
-                        .get_blocks_by_operation(&ope_id)
-                        .map(|hashset| hashset.iter().cloned().collect::<HashSet<BlockId>>())
-                        .unwrap_or_default();
-
-                    (secure_share, block_ids)
-                })
+                read_ops_lock
+                    .get(&ope_id)
+                    .map(|secure_share| {
+                        let block_ids = read_blocks_lock
+                            .get_blocks_by_operation(&ope_id)
+                            .map(|hashset| hashset.iter().cloned().collect::<HashSet<BlockId>>())
+                            .unwrap_or_default();
+
+                        (secure_share.clone(), block_ids)
+                    })
+                    .or_else(|| {
+                        archive?
+                            .get_operation(&ope_id)
+                            .map(|(secure_share, block_ids)| {
+                                (secure_share, block_ids.into_iter().collect())
+                            })
+                    })
             })
             .collect();
 
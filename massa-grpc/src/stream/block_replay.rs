@@ -0,0 +1,180 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::SlotRange;
+use massa_consensus_exports::ConsensusController;
+use massa_models::block_id::BlockId;
+use massa_models::prehash::PreHashSet;
+use massa_models::slot::Slot;
+use std::collections::{BTreeMap, HashSet};
+use tracing::log::error;
+
+/// Number of periods during which the blocks sent to a client are remembered, so that they are not replayed to it
+const SENT_BLOCKS_RETENTION_PERIODS: u64 = 32;
+
+/// Replay of the blocks retained by consensus to a client resuming a block stream from a start slot
+pub(crate) struct BlockReplay {
+    /// slot from which the client wants the blocks
+    start_slot: Slot,
+    /// latest slot of the blocks sent to the client, or `start_slot` if none was sent
+    cursor: Slot,
+    /// ids of the blocks recently sent to the client, by slot
+    sent_blocks: BTreeMap<Slot, PreHashSet<BlockId>>,
+}
+
+impl BlockReplay {
+    /// Blocks are replayed if the client asked for the blocks from a start slot.
+    /// If several slot ranges are given, the blocks are replayed from the latest start slot.
+    pub fn new(slot_ranges: &Option<HashSet<SlotRange>>) -> Option<Self> {
+        let start_slot = slot_ranges
+            .as_ref()?
+            .iter()
+            .filter_map(|slot_range| slot_range.start_slot)
+            .max()?;
+        Some(BlockReplay {
+            start_slot,
+            cursor: start_slot,
+            sent_blocks: BTreeMap::new(),
+        })
+    }
+
+    /// Oldest period of the blocks remembered as sent
+    fn oldest_remembered_period(&self) -> u64 {
+        self.cursor
+            .period
+            .saturating_sub(SENT_BLOCKS_RETENTION_PERIODS)
+    }
+
+    /// Record a block about to be sent to the client.
+    /// Returns false if it was already sent.
+    pub fn record_sent(&mut self, block_id: BlockId, slot: Slot) -> bool {
+        // too old to be remembered: it is not replayed either
+        if slot.period < self.oldest_remembered_period() {
+            return true;
+        }
+        if !self.sent_blocks.entry(slot).or_default().insert(block_id) {
+            return false;
+        }
+        if slot > self.cursor {
+            self.cursor = slot;
+            let oldest_slot = Slot::new(self.oldest_remembered_period(), 0);
+            self.sent_blocks = self.sent_blocks.split_off(&oldest_slot);
+        }
+        true
+    }
+
+    /// Get the ids of the retained blocks that were not sent to the client, in slot order.
+    /// Only the blocks from the start slot that are recent enough to be remembered as sent are returned.
+    ///
+    /// The boolean is true if blocks the client did not receive may no longer be retained:
+    /// this is the case when the oldest retained block is after the latest slot received by the client.
+    pub fn missed_blocks(
+        &self,
+        retained_blocks: impl Iterator<Item = (BlockId, Slot)>,
+    ) -> (bool, Vec<BlockId>) {
+        let from_slot = self
+            .start_slot
+            .max(Slot::new(self.oldest_remembered_period(), 0));
+        let mut oldest_retained_slot: Option<Slot> = None;
+        let mut missed_blocks = Vec::new();
+        for (block_id, slot) in retained_blocks {
+            oldest_retained_slot =
+                Some(oldest_retained_slot.map_or(slot, |oldest| oldest.min(slot)));
+            let sent = self
+                .sent_blocks
+                .get(&slot)
+                .map_or(false, |block_ids| block_ids.contains(&block_id));
+            if slot >= from_slot && !sent {
+                missed_blocks.push((slot, block_id));
+            }
+        }
+        missed_blocks.sort_unstable();
+        let gap = oldest_retained_slot.map_or(false, |oldest| oldest > self.cursor);
+        (
+            gap,
+            missed_blocks
+                .into_iter()
+                .map(|(_, block_id)| block_id)
+                .collect(),
+        )
+    }
+}
+
+/// Ids and slots of the active blocks retained by consensus
+pub(crate) fn retained_blocks(
+    consensus_controller: &dyn ConsensusController,
+) -> Vec<(BlockId, Slot)> {
+    match consensus_controller.get_block_graph_status(None, None) {
+        Ok(graph) => graph
+            .active_blocks
+            .iter()
+            .map(|(block_id, block)| (*block_id, block.header.content.slot))
+            .collect(),
+        Err(e) => {
+            error!("failed to get the blocks retained by consensus : {}", e);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_hash::Hash;
+
+    fn block_id(name: &str) -> BlockId {
+        BlockId::generate_from_hash(Hash::compute_from(name.as_bytes()))
+    }
+
+    fn slot_ranges(start_slot: Slot) -> Option<HashSet<SlotRange>> {
+        Some(HashSet::from([SlotRange {
+            start_slot: Some(start_slot),
+            end_slot: None,
+        }]))
+    }
+
+    #[test]
+    fn test_block_replay() {
+        assert!(BlockReplay::new(&None).is_none());
+        assert!(BlockReplay::new(&Some(HashSet::from([SlotRange {
+            start_slot: None,
+            end_slot: Some(Slot::new(10, 0)),
+        }])))
+        .is_none());
+
+        let (old, a, b, c) = (block_id("old"), block_id("a"), block_id("b"), block_id("c"));
+        let mut replay = BlockReplay::new(&slot_ranges(Slot::new(10, 0))).unwrap();
+
+        // the retained blocks from the start slot are replayed in slot order
+        let retained = vec![
+            (c, Slot::new(11, 0)),
+            (old, Slot::new(9, 0)),
+            (a, Slot::new(10, 0)),
+            (b, Slot::new(10, 1)),
+        ];
+        assert_eq!(
+            replay.missed_blocks(retained.clone().into_iter()),
+            (false, vec![a, b, c])
+        );
+
+        // the blocks already sent are neither replayed nor sent again
+        assert!(replay.record_sent(a, Slot::new(10, 0)));
+        assert!(replay.record_sent(c, Slot::new(11, 0)));
+        assert!(!replay.record_sent(a, Slot::new(10, 0)));
+        assert_eq!(replay.missed_blocks(retained.into_iter()), (false, vec![b]));
+
+        // the blocks following the latest one sent are no longer retained
+        assert_eq!(
+            replay.missed_blocks(vec![(block_id("d"), Slot::new(12, 0))].into_iter()),
+            (true, vec![block_id("d")])
+        );
+
+        // old blocks are forgotten
+        let recent_slot = Slot::new(11 + SENT_BLOCKS_RETENTION_PERIODS, 0);
+        assert!(replay.record_sent(block_id("recent"), recent_slot));
+        assert!(replay.sent_blocks.get(&Slot::new(10, 0)).is_none());
+        assert_eq!(
+            replay.missed_blocks(vec![(b, Slot::new(10, 1)), (c, Slot::new(11, 0))].into_iter()),
+            (false, vec![])
+        );
+    }
+}
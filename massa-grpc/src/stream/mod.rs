@@ -1,5 +1,7 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+/// replay of the blocks retained by consensus to the resumed block streams
+pub(crate) mod block_replay;
 /// stream new blocks
 pub mod new_blocks;
 /// stream new endorsements
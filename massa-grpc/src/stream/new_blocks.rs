@@ -3,6 +3,7 @@
 use crate::config::GrpcConfig;
 use crate::error::{match_for_io_error, GrpcError};
 use crate::server::MassaPublicGrpc;
+use crate::stream::block_replay::{retained_blocks, BlockReplay};
 use crate::SlotRange;
 use futures_util::StreamExt;
 use massa_models::address::Address;
@@ -15,6 +16,8 @@ use std::io::ErrorKind;
 use std::pin::Pin;
 use std::str::FromStr;
 use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc::Sender;
 use tonic::codegen::futures_core;
 use tonic::{Request, Streaming};
 use tracing::log::{error, warn};
@@ -67,12 +70,26 @@ pub(crate) async fn new_blocks(
                 }
             };
 
+            // If the client asked for the blocks from a start slot, replay the retained ones before going live
+            let mut replay = BlockReplay::new(&filters.slot_ranges);
+            if let Some(replay) = replay.as_mut() {
+                if !replay_blocks(&grpc, &filters, replay, &tx).await {
+                    return;
+                }
+            }
+
             loop {
                 select! {
                     // Receive a new block from the subscriber
                     event = subscriber.recv() => {
                         match event {
                             Ok(massa_block) => {
+                                // already replayed
+                                if let Some(replay) = replay.as_mut() {
+                                    if !replay.record_sent(massa_block.id, massa_block.content.header.content.slot) {
+                                        continue;
+                                    }
+                                }
                                 // Check if the block should be sent
                                 if !should_send(&massa_block, &filters, &grpc.grpc_config) {
                                     continue;
@@ -85,7 +102,15 @@ pub(crate) async fn new_blocks(
                                     break;
                                 }
                             },
-                            Err(e) => error!("error on receive new block : {}", e)
+                            Err(e) => {
+                                error!("error on receive new block : {}", e);
+                                // some blocks were missed: replay them if the client resumes the stream from a slot
+                                if let (RecvError::Lagged(_), Some(replay)) = (e, replay.as_mut()) {
+                                    if !replay_blocks(&grpc, &filters, replay, &tx).await {
+                                        break;
+                                    }
+                                }
+                            }
                         }
                     },
                     res = in_stream.next() => {
@@ -143,6 +168,48 @@ pub(crate) async fn new_blocks(
     Ok(Box::pin(out_stream) as NewBlocksStreamType)
 }
 
+/// Sends the blocks retained by consensus that the client resuming the stream did not receive, in slot order.
+/// They are preceded by a gap marker, a response without block, if some blocks may no longer be retained.
+///
+/// Returns false if the client is gone.
+async fn replay_blocks(
+    grpc: &MassaPublicGrpc,
+    filters: &Filter,
+    replay: &mut BlockReplay,
+    tx: &Sender<Result<grpc_api::NewBlocksResponse, tonic::Status>>,
+) -> bool {
+    let (gap, block_ids) =
+        replay.missed_blocks(retained_blocks(grpc.consensus_controller.as_ref()).into_iter());
+    if gap {
+        if let Err(e) = tx
+            .send(Ok(grpc_api::NewBlocksResponse { signed_block: None }))
+            .await
+        {
+            error!("failed to send new blocks gap marker : {}", e);
+            return false;
+        }
+    }
+    for block_id in block_ids {
+        let Some(block) = grpc.storage.read_blocks().get(&block_id).cloned() else {
+            continue;
+        };
+        replay.record_sent(block.id, block.content.header.content.slot);
+        if !should_send(&block, filters, &grpc.grpc_config) {
+            continue;
+        }
+        if let Err(e) = tx
+            .send(Ok(grpc_api::NewBlocksResponse {
+                signed_block: Some(block.into()),
+            }))
+            .await
+        {
+            error!("failed to send replayed block : {}", e);
+            return false;
+        }
+    }
+    true
+}
+
 // This function returns a filter from the request
 fn get_filter(
     request: grpc_api::NewBlocksRequest,
@@ -3,9 +3,11 @@
 use crate::config::GrpcConfig;
 use crate::error::{match_for_io_error, GrpcError};
 use crate::server::MassaPublicGrpc;
+use crate::stream::block_replay::{retained_blocks, BlockReplay};
 use crate::SlotRange;
 use futures_util::StreamExt;
 use massa_models::address::Address;
+use massa_models::block::FilledBlock;
 use massa_models::block_header::BlockHeader;
 use massa_models::block_id::BlockId;
 use massa_models::secure_share::SecureShare;
@@ -16,6 +18,8 @@ use std::io::ErrorKind;
 use std::pin::Pin;
 use std::str::FromStr;
 use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc::Sender;
 use tonic::codegen::futures_core;
 use tonic::{Request, Streaming};
 use tracing::log::{error, warn};
@@ -68,12 +72,27 @@ pub(crate) async fn new_filled_blocks(
                 }
             };
 
+            // If the client asked for the blocks from a start slot, replay the retained ones before going live
+            let mut replay = BlockReplay::new(&filters.slot_ranges);
+            if let Some(replay) = replay.as_mut() {
+                if !replay_filled_blocks(&grpc, &filters, replay, &tx).await {
+                    return;
+                }
+            }
+
             loop {
                 select! {
                     // Receive a new filled block from the subscriber
                      event = subscriber.recv() => {
                         match event {
                             Ok(massa_filled_block) => {
+                                // already replayed
+                                if let Some(replay) = replay.as_mut() {
+                                    let header = &massa_filled_block.header;
+                                    if !replay.record_sent(header.id, header.content.slot) {
+                                        continue;
+                                    }
+                                }
                                 // Check if the block should be sent
                                 if !should_send(&massa_filled_block.header, &filters, &grpc.grpc_config) {
                                     continue;
@@ -86,7 +105,15 @@ pub(crate) async fn new_filled_blocks(
                                     break;
                                 }
                             },
-                            Err(e) => error!("error on receive new filled block : {}", e)
+                            Err(e) => {
+                                error!("error on receive new filled block : {}", e);
+                                // some blocks were missed: replay them if the client resumes the stream from a slot
+                                if let (RecvError::Lagged(_), Some(replay)) = (e, replay.as_mut()) {
+                                    if !replay_filled_blocks(&grpc, &filters, replay, &tx).await {
+                                        break;
+                                    }
+                                }
+                            }
                         }
                     },
                 // Receive a new message from the in_stream
@@ -142,6 +169,61 @@ pub(crate) async fn new_filled_blocks(
     Ok(Box::pin(out_stream) as NewFilledBlocksStreamType)
 }
 
+/// Sends the blocks retained by consensus that the client resuming the stream did not receive, in slot order.
+/// They are preceded by a gap marker, a response without block, if some blocks may no longer be retained.
+///
+/// Returns false if the client is gone.
+async fn replay_filled_blocks(
+    grpc: &MassaPublicGrpc,
+    filters: &Filter,
+    replay: &mut BlockReplay,
+    tx: &Sender<Result<grpc_api::NewFilledBlocksResponse, tonic::Status>>,
+) -> bool {
+    let (gap, block_ids) =
+        replay.missed_blocks(retained_blocks(grpc.consensus_controller.as_ref()).into_iter());
+    if gap {
+        if let Err(e) = tx
+            .send(Ok(grpc_api::NewFilledBlocksResponse { filled_block: None }))
+            .await
+        {
+            error!("failed to send new filled blocks gap marker : {}", e);
+            return false;
+        }
+    }
+    for block_id in block_ids {
+        let filled_block = {
+            let read_blocks = grpc.storage.read_blocks();
+            let Some(block) = read_blocks.get(&block_id) else {
+                continue;
+            };
+            let read_operations = grpc.storage.read_operations();
+            FilledBlock {
+                header: block.content.header.clone(),
+                operations: block
+                    .content
+                    .operations
+                    .iter()
+                    .map(|operation_id| (*operation_id, read_operations.get(operation_id).cloned()))
+                    .collect(),
+            }
+        };
+        replay.record_sent(filled_block.header.id, filled_block.header.content.slot);
+        if !should_send(&filled_block.header, filters, &grpc.grpc_config) {
+            continue;
+        }
+        if let Err(e) = tx
+            .send(Ok(grpc_api::NewFilledBlocksResponse {
+                filled_block: Some(filled_block.into()),
+            }))
+            .await
+        {
+            error!("failed to send replayed filled block : {}", e);
+            return false;
+        }
+    }
+    true
+}
+
 // This function returns a filter from the request
 fn get_filter(
     request: grpc_api::NewFilledBlocksRequest,
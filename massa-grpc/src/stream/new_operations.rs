@@ -12,6 +12,8 @@ use std::collections::HashSet;
 use std::pin::Pin;
 use std::str::FromStr;
 use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc::Sender;
 use tonic::codegen::futures_core;
 use tonic::{Request, Streaming};
 use tracing::log::error;
@@ -65,6 +67,13 @@ pub(crate) async fn new_operations(
                 }
             };
 
+            // The operations watched by id that are already known are sent first,
+            // so that a client resuming the stream receives the ones it missed
+            let mut sent_operations = HashSet::new();
+            if !replay_watched_operations(&grpc, &filters, &mut sent_operations, &tx).await {
+                return;
+            }
+
             loop {
                 select! {
                     // Receive a new operation from the subscriber
@@ -75,6 +84,10 @@ pub(crate) async fn new_operations(
                                 if !should_send(&massa_operation, &filters) {
                                     continue;
                                 }
+                                // the watched operations are only sent once
+                                if filters.operation_ids.is_some() && !sent_operations.insert(massa_operation.id) {
+                                    continue;
+                                }
 
                                 // Send the new operation through the channel
                                 if let Err(e) = tx.send(Ok(grpc_api::NewOperationsResponse {signed_operation: Some(massa_operation.into())})).await {
@@ -82,7 +95,15 @@ pub(crate) async fn new_operations(
                                     break;
                                 }
                             },
-                            Err(e) => error!("{}", e)
+                            Err(e) => {
+                                error!("{}", e);
+                                // some operations were missed: send the watched ones that are known
+                                if let RecvError::Lagged(_) = e {
+                                    if !replay_watched_operations(&grpc, &filters, &mut sent_operations, &tx).await {
+                                        break;
+                                    }
+                                }
+                            }
                         }
                     },
                     // Receive a new message from the in_stream
@@ -103,6 +124,14 @@ pub(crate) async fn new_operations(
                                                 return;
                                             }
                                         };
+                                        // send the newly watched operations that are known
+                                        match &filters.operation_ids {
+                                            Some(operation_ids) => sent_operations.retain(|id| operation_ids.contains(id)),
+                                            None => sent_operations.clear(),
+                                        }
+                                        if !replay_watched_operations(&grpc, &filters, &mut sent_operations, &tx).await {
+                                            break;
+                                        }
                                     },
                                     Err(e) => {
                                         error!("{}", e);
@@ -127,6 +156,44 @@ pub(crate) async fn new_operations(
     Ok(Box::pin(out_stream) as NewOperationsStreamType)
 }
 
+/// Sends the operations watched by id that are known by the node and were not sent to the client yet.
+///
+/// Returns false if the client is gone.
+async fn replay_watched_operations(
+    grpc: &MassaPublicGrpc,
+    filters: &Filter,
+    sent_operations: &mut HashSet<OperationId>,
+    tx: &Sender<Result<grpc_api::NewOperationsResponse, tonic::Status>>,
+) -> bool {
+    let Some(operation_ids) = &filters.operation_ids else {
+        return true;
+    };
+    let operations: Vec<SecureShareOperation> = {
+        let read_operations = grpc.storage.read_operations();
+        operation_ids
+            .iter()
+            .filter(|operation_id| !sent_operations.contains(operation_id))
+            .filter_map(|operation_id| read_operations.get(operation_id).cloned())
+            .collect()
+    };
+    for operation in operations {
+        if !should_send(&operation, filters) {
+            continue;
+        }
+        sent_operations.insert(operation.id);
+        if let Err(e) = tx
+            .send(Ok(grpc_api::NewOperationsResponse {
+                signed_operation: Some(operation.into()),
+            }))
+            .await
+        {
+            error!("failed to send operation : {}", e);
+            return false;
+        }
+    }
+    true
+}
+
 // This function returns a filter from the request
 fn get_filter(
     request: NewOperationsRequest,
@@ -5,7 +5,7 @@ use crate::error::{match_for_io_error, GrpcError};
 use crate::server::MassaPublicGrpc;
 use crate::SlotRange;
 use futures_util::StreamExt;
-use massa_execution_exports::{ExecutionOutput, SlotExecutionOutput};
+use massa_execution_exports::{ExecutionController, ExecutionOutput, SlotExecutionOutput};
 use massa_models::slot::Slot;
 use massa_proto_rs::massa::api::v1::{self as grpc_api, NewSlotExecutionOutputsRequest};
 use massa_proto_rs::massa::model::v1::{self as grpc_model};
@@ -13,6 +13,7 @@ use std::collections::HashSet;
 use std::io::ErrorKind;
 use std::pin::Pin;
 use tokio::select;
+use tokio::sync::mpsc::Sender;
 use tonic::codegen::futures_core;
 use tonic::{Request, Streaming};
 use tracing::log::{error, warn};
@@ -27,6 +28,9 @@ pub type NewSlotExecutionOutputsStreamType = Pin<
     >,
 >;
 
+/// Maximum number of journaled final outputs read at once when replaying them
+const REPLAY_BATCH_SIZE: usize = 100;
+
 //TODO implement remaining sub filters
 // Type declaration for NewSlotExecutionOutputsFilter
 #[derive(Clone, Debug, Default)]
@@ -107,12 +111,39 @@ pub(crate) async fn new_slot_execution_outputs(
                 }
             };
 
+            // If the client asked for final outputs from a start slot, replay the journaled ones before going live.
+            // `cursor` is then the next final slot expected by the client.
+            let mut cursor = None;
+            if let Some(start_slot) = replay_start_slot(&filters) {
+                match replay_final_outputs(
+                    grpc.execution_controller.as_ref(),
+                    &grpc.grpc_config,
+                    &filters,
+                    start_slot,
+                    None,
+                    &tx,
+                )
+                .await
+                {
+                    Some(next_slot) => cursor = Some(next_slot),
+                    None => return,
+                }
+            }
+
             loop {
                 select! {
                     // Receive a new slot execution output from the subscriber
                     event = subscriber.recv() => {
                         match event {
                             Ok(massa_slot_execution_output) => {
+                                if let (Some(next_slot), SlotExecutionOutput::FinalizedSlot(exec_output)) = (cursor.as_mut(), &massa_slot_execution_output) {
+                                    match catch_up_final_output(grpc.execution_controller.as_ref(), &grpc.grpc_config, &filters, next_slot, exec_output.slot, &tx).await {
+                                        Some(true) => {},
+                                        // already replayed
+                                        Some(false) => continue,
+                                        None => break,
+                                    }
+                                }
                                 let slot_execution_output = filter_map(massa_slot_execution_output, &filters, &grpc.grpc_config);
                                 // Check if the slot execution output should be sent
                                 if let Some(slot_execution_output) = slot_execution_output {
@@ -186,6 +217,137 @@ pub(crate) async fn new_slot_execution_outputs(
     Ok(Box::pin(out_stream) as NewSlotExecutionOutputsStreamType)
 }
 
+/// Returns the slot from which the final outputs are replayed: the start of the slot range filter,
+/// if the client asked for one and did not exclude final outputs
+fn replay_start_slot(filters: &Filter) -> Option<Slot> {
+    if let Some(status_filter) = &filters.status_filter {
+        if !status_filter.contains(&(grpc_model::ExecutionOutputStatus::Final as i32)) {
+            return None;
+        }
+    }
+    filters
+        .slot_ranges_filter
+        .as_ref()?
+        .iter()
+        .filter_map(|slot_range| slot_range.start_slot)
+        .max()
+}
+
+/// Gap marker telling the client that the final outputs from `slot` (inclusive)
+/// up to the next final output it receives are no longer available.
+/// It is an output with an unspecified status, that only contains its first missing slot.
+fn gap_marker(slot: Slot) -> grpc_api::NewSlotExecutionOutputsResponse {
+    grpc_api::NewSlotExecutionOutputsResponse {
+        output: Some(grpc_model::SlotExecutionOutput {
+            status: grpc_model::ExecutionOutputStatus::Unspecified as i32,
+            execution_output: Some(grpc_model::ExecutionOutput {
+                slot: Some(slot.into()),
+                block_id: None,
+                events: Vec::new(),
+                state_changes: None,
+            }),
+        }),
+    }
+}
+
+/// Moves the cursor of a client replaying the final outputs after a live final output at `slot`.
+/// The journaled final outputs missed since `cursor` (e.g. because the subscriber lagged) are replayed first,
+/// and a gap marker is sent if some of them are no longer journaled.
+///
+/// Returns whether the live output must be sent (false if it was already replayed), or None if the client is gone.
+async fn catch_up_final_output(
+    execution_controller: &dyn ExecutionController,
+    grpc_config: &GrpcConfig,
+    filters: &Filter,
+    cursor: &mut Slot,
+    slot: Slot,
+    tx: &Sender<Result<grpc_api::NewSlotExecutionOutputsResponse, tonic::Status>>,
+) -> Option<bool> {
+    if slot < *cursor {
+        return Some(false);
+    }
+    if slot > *cursor {
+        let next_slot = replay_final_outputs(
+            execution_controller,
+            grpc_config,
+            filters,
+            *cursor,
+            Some(slot),
+            tx,
+        )
+        .await?;
+        if slot > next_slot {
+            if let Err(e) = tx.send(Ok(gap_marker(next_slot))).await {
+                error!("failed to send slot execution outputs gap marker : {}", e);
+                return None;
+            }
+        }
+    }
+    *cursor = match slot.get_next_slot(grpc_config.thread_count) {
+        Ok(next_slot) => next_slot,
+        Err(e) => {
+            error!("failed to get the slot following {} : {}", slot, e);
+            return None;
+        }
+    };
+    Some(true)
+}
+
+/// Sends the journaled final outputs from `cursor` (inclusive) up to `end` (exclusive),
+/// or up to the last journaled output if `end` is None.
+/// A gap marker is sent before an output if the outputs preceding it are not journaled.
+///
+/// Returns the next final slot expected by the client, or None if the client is gone.
+async fn replay_final_outputs(
+    execution_controller: &dyn ExecutionController,
+    grpc_config: &GrpcConfig,
+    filters: &Filter,
+    mut cursor: Slot,
+    end: Option<Slot>,
+    tx: &Sender<Result<grpc_api::NewSlotExecutionOutputsResponse, tonic::Status>>,
+) -> Option<Slot> {
+    loop {
+        let outputs = execution_controller.get_final_execution_outputs(cursor, REPLAY_BATCH_SIZE);
+        let batch_size = outputs.len();
+        for output in outputs {
+            if end.map_or(false, |end| output.slot >= end) {
+                return Some(cursor);
+            }
+            if output.slot > cursor {
+                if let Err(e) = tx.send(Ok(gap_marker(cursor))).await {
+                    error!("failed to send slot execution outputs gap marker : {}", e);
+                    return None;
+                }
+            }
+            cursor = match output.slot.get_next_slot(grpc_config.thread_count) {
+                Ok(next_slot) => next_slot,
+                Err(e) => {
+                    error!("failed to get the slot following {} : {}", output.slot, e);
+                    return None;
+                }
+            };
+            if let Some(slot_execution_output) = filter_map(
+                SlotExecutionOutput::FinalizedSlot(output),
+                filters,
+                grpc_config,
+            ) {
+                if let Err(e) = tx
+                    .send(Ok(grpc_api::NewSlotExecutionOutputsResponse {
+                        output: Some(slot_execution_output.into()),
+                    }))
+                    .await
+                {
+                    error!("failed to send replayed slot execution output : {}", e);
+                    return None;
+                }
+            }
+        }
+        if batch_size < REPLAY_BATCH_SIZE {
+            return Some(cursor);
+        }
+    }
+}
+
 // This function returns a filter from the request
 fn get_filter(
     request: NewSlotExecutionOutputsRequest,
@@ -430,3 +592,155 @@ fn filter_map_exec_output(
 
     Some(exec_output)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::tools::get_grpc_config;
+    use massa_execution_exports::MockExecutionController;
+    use massa_models::config::THREAD_COUNT;
+    use massa_signature::KeyPair;
+    use tokio::sync::mpsc::Receiver;
+
+    type Response = Result<grpc_api::NewSlotExecutionOutputsResponse, tonic::Status>;
+
+    /// Execution controller journaling the final outputs of (1, 0), (1, 1) and (1, 3)
+    fn get_execution_controller() -> MockExecutionController {
+        let journal = [Slot::new(1, 0), Slot::new(1, 1), Slot::new(1, 3)];
+        let mut execution_controller = MockExecutionController::new();
+        execution_controller
+            .expect_get_final_execution_outputs()
+            .returning(move |start_slot, max_count| {
+                journal
+                    .iter()
+                    .filter(|slot| **slot >= start_slot)
+                    .take(max_count)
+                    .map(|slot| ExecutionOutput {
+                        slot: *slot,
+                        block_info: None,
+                        state_changes: Default::default(),
+                        events: Default::default(),
+                        traces: Vec::new(),
+                    })
+                    .collect()
+            });
+        execution_controller
+    }
+
+    /// Status and slot of the outputs received by the client
+    fn received(rx: &mut Receiver<Response>) -> Vec<(grpc_model::ExecutionOutputStatus, Slot)> {
+        let mut received = Vec::new();
+        while let Ok(response) = rx.try_recv() {
+            let output = response.unwrap().output.unwrap();
+            let slot = output.execution_output.unwrap().slot.unwrap();
+            received.push((
+                grpc_model::ExecutionOutputStatus::from_i32(output.status).unwrap(),
+                Slot::new(slot.period, slot.thread as u8),
+            ));
+        }
+        received
+    }
+
+    #[tokio::test]
+    async fn test_replay_final_outputs() {
+        use grpc_model::ExecutionOutputStatus::{Final, Unspecified};
+        let execution_controller = get_execution_controller();
+        let grpc_config = get_grpc_config(&KeyPair::generate(0).unwrap());
+        let filters = Filter::default();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+
+        // the missing outputs are announced by a gap marker
+        let next_slot = replay_final_outputs(
+            &execution_controller,
+            &grpc_config,
+            &filters,
+            Slot::new(0, 5),
+            None,
+            &tx,
+        )
+        .await;
+        assert_eq!(next_slot, Some(Slot::new(1, 4)));
+        assert_eq!(
+            received(&mut rx),
+            vec![
+                (Unspecified, Slot::new(0, 5)),
+                (Final, Slot::new(1, 0)),
+                (Final, Slot::new(1, 1)),
+                (Unspecified, Slot::new(1, 2)),
+                (Final, Slot::new(1, 3)),
+            ]
+        );
+
+        // the replay stops before the end slot
+        let next_slot = replay_final_outputs(
+            &execution_controller,
+            &grpc_config,
+            &filters,
+            Slot::new(1, 1),
+            Some(Slot::new(1, 3)),
+            &tx,
+        )
+        .await;
+        assert_eq!(next_slot, Some(Slot::new(1, 2)));
+        assert_eq!(received(&mut rx), vec![(Final, Slot::new(1, 1))]);
+    }
+
+    #[tokio::test]
+    async fn test_catch_up_final_output() {
+        use grpc_model::ExecutionOutputStatus::{Final, Unspecified};
+        let execution_controller = get_execution_controller();
+        let grpc_config = get_grpc_config(&KeyPair::generate(0).unwrap());
+        let filters = Filter::default();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+        let mut cursor = Slot::new(1, 0);
+
+        // live outputs already replayed are skipped
+        let send = catch_up_final_output(
+            &execution_controller,
+            &grpc_config,
+            &filters,
+            &mut cursor,
+            Slot::new(0, THREAD_COUNT - 1),
+            &tx,
+        )
+        .await;
+        assert_eq!(send, Some(false));
+        assert_eq!(cursor, Slot::new(1, 0));
+
+        // the expected live output moves the cursor
+        let send = catch_up_final_output(
+            &execution_controller,
+            &grpc_config,
+            &filters,
+            &mut cursor,
+            Slot::new(1, 0),
+            &tx,
+        )
+        .await;
+        assert_eq!(send, Some(true));
+        assert_eq!(cursor, Slot::new(1, 1));
+        assert!(received(&mut rx).is_empty());
+
+        // the outputs missed before a live output are replayed first
+        let send = catch_up_final_output(
+            &execution_controller,
+            &grpc_config,
+            &filters,
+            &mut cursor,
+            Slot::new(1, 5),
+            &tx,
+        )
+        .await;
+        assert_eq!(send, Some(true));
+        assert_eq!(cursor, Slot::new(1, 6));
+        assert_eq!(
+            received(&mut rx),
+            vec![
+                (Final, Slot::new(1, 1)),
+                (Unspecified, Slot::new(1, 2)),
+                (Final, Slot::new(1, 3)),
+                (Unspecified, Slot::new(1, 4)),
+            ]
+        );
+    }
+}
@@ -101,9 +101,34 @@ pub(crate) async fn send_operations(
                             match verified_ops_res {
                                 // If all operations in the incoming message are valid, store and propagate them
                                 Ok(verified_ops) => {
+                                    let operations: Vec<SecureShareOperation> =
+                                        verified_ops.values().cloned().collect();
+                                    // If the pool refuses some operations, send their rejection reasons back to the client
+                                    let rejections: Vec<String> = pool_controller
+                                        .check_operations_admission(&operations)
+                                        .into_iter()
+                                        .zip(&operations)
+                                        .filter_map(|(admission, op)| {
+                                            admission
+                                                .err()
+                                                .map(|reason| format!("{}: {}", op.id, reason))
+                                        })
+                                        .collect();
+                                    if !rejections.is_empty() {
+                                        report_error(
+                                            tx.clone(),
+                                            tonic::Code::FailedPrecondition,
+                                            format!(
+                                                "operation(s) rejected by the pool: {}",
+                                                rejections.join(", ")
+                                            ),
+                                        )
+                                        .await;
+                                        continue;
+                                    }
+
                                     let mut operation_storage = storage.clone_without_refs();
-                                    operation_storage
-                                        .store_operations(verified_ops.values().cloned().collect());
+                                    operation_storage.store_operations(operations);
                                     // Add the received operations to the operations pool
                                     pool_controller.add_operations(operation_storage.clone());
 
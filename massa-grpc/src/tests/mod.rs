@@ -1,3 +1,4 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
 mod test;
+pub(crate) mod tools;
@@ -1,43 +1,47 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
-use crate::config::{GrpcConfig, ServiceName};
+use crate::public::{search_blocks, search_operations};
 use crate::server::MassaPublicGrpc;
+use crate::tests::tools::get_grpc_config;
+use massa_archive::{Archive, ArchiveConfig};
 use massa_channel::MassaChannel;
 use massa_consensus_exports::test_exports::MockConsensusControllerImpl;
 use massa_consensus_exports::ConsensusChannels;
 use massa_execution_exports::{test_exports::MockExecutionController, ExecutionChannels};
 use massa_models::{
-    config::{
-        ENDORSEMENT_COUNT, GENESIS_TIMESTAMP, MAX_DATASTORE_VALUE_LENGTH,
-        MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_ENDORSEMENTS_PER_MESSAGE, MAX_FUNCTION_NAME_LENGTH,
-        MAX_OPERATIONS_PER_BLOCK, MAX_OPERATIONS_PER_MESSAGE, MAX_OPERATION_DATASTORE_ENTRY_COUNT,
-        MAX_OPERATION_DATASTORE_KEY_LENGTH, MAX_OPERATION_DATASTORE_VALUE_LENGTH,
-        MAX_PARAMETERS_SIZE, MIP_STORE_STATS_BLOCK_CONSIDERED, PERIODS_PER_CYCLE, T0, THREAD_COUNT,
-        VERSION,
-    },
+    address::Address,
+    block::BlockGraphStatus,
+    config::{MIP_STORE_STATS_BLOCK_CONSIDERED, VERSION},
     node::NodeId,
+    slot::Slot,
 };
 use massa_pool_exports::test_exports::MockPoolController;
 use massa_pool_exports::PoolChannels;
 use massa_pos_exports::test_exports::MockSelectorController;
+use massa_proto_rs::massa::api::v1 as grpc_api;
 use massa_proto_rs::massa::api::v1::public_service_client::PublicServiceClient;
+use massa_proto_rs::massa::model::v1 as grpc_model;
+use massa_protocol_exports::test_exports::tools::{
+    create_block_with_operations, create_operation_with_expire_period,
+};
 use massa_protocol_exports::{MockProtocolController, ProtocolConfig};
 use massa_signature::KeyPair;
+use massa_storage::Storage;
 use massa_versioning::{
     keypair_factory::KeyPairFactory,
     versioning::{MipStatsConfig, MipStore},
 };
 use num::rational::Ratio;
-use std::{
-    net::{IpAddr, Ipv4Addr, SocketAddr},
-    path::PathBuf,
-};
+use std::sync::Arc;
+use tempfile::TempDir;
 
-#[tokio::test]
-async fn test_start_grpc_server() {
-    let consensus_controller = MockConsensusControllerImpl::new();
+/// Create the public gRPC service on top of mocked controllers
+fn create_public_grpc(
+    consensus_controller: MockConsensusControllerImpl,
+    archive: Option<Arc<Archive>>,
+) -> MassaPublicGrpc {
     let execution_ctrl = MockExecutionController::new_with_receiver();
-    let shared_storage: massa_storage::Storage = massa_storage::Storage::create_root();
+    let shared_storage = Storage::create_root();
     let selector_ctrl = MockSelectorController::new_with_receiver();
     let pool_ctrl = MockPoolController::new_with_receiver();
     let (consensus_event_sender, _consensus_event_receiver) =
@@ -52,73 +56,15 @@ async fn test_start_grpc_server() {
         block_sender: tokio::sync::broadcast::channel(100).0,
         block_header_sender: tokio::sync::broadcast::channel(100).0,
         filled_block_sender: tokio::sync::broadcast::channel(100).0,
+        archive,
+        archive_writer: None,
     };
 
     let endorsement_sender = tokio::sync::broadcast::channel(2000).0;
     let operation_sender = tokio::sync::broadcast::channel(5000).0;
     let slot_execution_output_sender = tokio::sync::broadcast::channel(5000).0;
     let keypair = KeyPair::generate(0).unwrap();
-    let grpc_config = GrpcConfig {
-        name: ServiceName::Public,
-        enabled: true,
-        accept_http1: true,
-        enable_cors: true,
-        enable_health: true,
-        enable_reflection: true,
-        enable_tls: false,
-        enable_mtls: false,
-        generate_self_signed_certificates: false,
-        subject_alt_names: vec![],
-        bind: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8888),
-        accept_compressed: None,
-        send_compressed: None,
-        max_decoding_message_size: 4194304,
-        max_encoding_message_size: 4194304,
-        concurrency_limit_per_connection: 0,
-        timeout: Default::default(),
-        initial_stream_window_size: None,
-        initial_connection_window_size: None,
-        max_concurrent_streams: None,
-        max_arguments: 128,
-        tcp_keepalive: None,
-        tcp_nodelay: false,
-        http2_keepalive_interval: None,
-        http2_keepalive_timeout: None,
-        http2_adaptive_window: None,
-        max_frame_size: None,
-        thread_count: THREAD_COUNT,
-        max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
-        endorsement_count: ENDORSEMENT_COUNT,
-        max_endorsements_per_message: MAX_ENDORSEMENTS_PER_MESSAGE,
-        max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
-        max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
-        max_datastore_entries_per_request: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
-        max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
-        max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
-        max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
-        max_parameter_size: MAX_PARAMETERS_SIZE,
-        max_operations_per_message: MAX_OPERATIONS_PER_MESSAGE,
-        genesis_timestamp: *GENESIS_TIMESTAMP,
-        t0: T0,
-        periods_per_cycle: PERIODS_PER_CYCLE,
-        keypair: keypair.clone(),
-        max_channel_size: 128,
-        draw_lookahead_period_count: 10,
-        last_start_period: 0,
-        max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
-        max_addresses_per_request: 50,
-        max_slot_ranges_per_request: 50,
-        max_block_ids_per_request: 50,
-        max_endorsement_ids_per_request: 100,
-        max_operation_ids_per_request: 250,
-        max_filters_per_request: 32,
-        server_certificate_path: PathBuf::default(),
-        server_private_key_path: PathBuf::default(),
-        certificate_authority_root_path: PathBuf::default(),
-        client_certificate_authority_root_path: PathBuf::default(),
-        client_certificate_path: PathBuf::default(),
-        client_private_key_path: PathBuf::default(),
-    };
+    let grpc_config = get_grpc_config(&keypair);
 
     let mip_stats_config = MipStatsConfig {
         block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
@@ -127,7 +73,7 @@ async fn test_start_grpc_server() {
 
     let mip_store = MipStore::try_from(([], mip_stats_config)).unwrap();
 
-    let service = MassaPublicGrpc {
+    MassaPublicGrpc {
         consensus_controller: Box::new(consensus_controller),
         consensus_channels,
         execution_controller: execution_ctrl.0.clone(),
@@ -137,6 +83,7 @@ async fn test_start_grpc_server() {
         pool_channels: PoolChannels {
             endorsement_sender,
             operation_sender,
+            operation_rejection_sender: tokio::sync::broadcast::channel(5000).0,
             selector: selector_ctrl.0.clone(),
             execution_controller: execution_ctrl.0.clone(),
         },
@@ -145,13 +92,17 @@ async fn test_start_grpc_server() {
         protocol_config: ProtocolConfig::default(),
         selector_controller: selector_ctrl.0,
         storage: shared_storage,
-        grpc_config: grpc_config.clone(),
+        grpc_config,
         version: *VERSION,
         node_id: NodeId::new(keypair.get_public_key()),
-        keypair_factory: KeyPairFactory {
-            mip_store: mip_store.clone(),
-        },
-    };
+        keypair_factory: KeyPairFactory { mip_store },
+    }
+}
+
+#[tokio::test]
+async fn test_start_grpc_server() {
+    let service = create_public_grpc(MockConsensusControllerImpl::new(), None);
+    let grpc_config = service.grpc_config.clone();
 
     let stop_handle = service.serve(&grpc_config).await.unwrap();
     // std::thread::sleep(Duration::from_millis(100));
@@ -165,3 +116,83 @@ async fn test_start_grpc_server() {
     let _res = PublicServiceClient::new(channel);
     stop_handle.stop();
 }
+
+#[test]
+fn test_search_falls_back_to_archive() {
+    let temp_dir = TempDir::new().unwrap();
+    // the test blocks only have 2 parents
+    let archive = Arc::new(Archive::new(ArchiveConfig {
+        path: temp_dir.path().to_path_buf(),
+        thread_count: 2,
+        endorsement_count: 16,
+        max_operations_per_block: 5000,
+        max_denunciations_per_block_header: 128,
+        max_datastore_value_length: 10_000_000,
+        max_function_name_length: u16::MAX,
+        max_parameter_size: 10_000_000,
+        max_op_datastore_entry_count: 128,
+        max_op_datastore_key_length: u8::MAX,
+        max_op_datastore_value_length: 10_000_000,
+    }));
+
+    // archive a final block that was then pruned from the node storage
+    let creator_keypair = KeyPair::generate(0).unwrap();
+    let sender_keypair = KeyPair::generate(0).unwrap();
+    let creator = Address::from_public_key(&creator_keypair.get_public_key());
+    let sender = Address::from_public_key(&sender_keypair.get_public_key());
+    let operation = create_operation_with_expire_period(&sender_keypair, 10);
+    let block =
+        create_block_with_operations(&creator_keypair, Slot::new(1, 0), vec![operation.clone()]);
+    let mut storage = Storage::create_root();
+    storage.store_operations(vec![operation.clone()]);
+    storage.store_block(block.clone());
+    archive.archive_final_block(&block.id, &storage);
+    drop(storage);
+
+    let mut consensus_controller = MockConsensusControllerImpl::new();
+    consensus_controller
+        .expect_get_block_statuses()
+        .returning(|ids| ids.iter().map(|_| BlockGraphStatus::NotFound).collect());
+    let service = create_public_grpc(consensus_controller, Some(archive));
+
+    // the operation is searched by its creator, not by the creator of the including block
+    let search_by = |address: &Address| grpc_api::SearchOperationsRequest {
+        filters: vec![grpc_api::SearchOperationsFilter {
+            filter: Some(grpc_api::search_operations_filter::Filter::Addresses(
+                grpc_model::Addresses {
+                    addresses: vec![address.to_string()],
+                },
+            )),
+        }],
+    };
+    let response = search_operations(&service, tonic::Request::new(search_by(&sender))).unwrap();
+    assert_eq!(response.operation_infos.len(), 1);
+    assert_eq!(response.operation_infos[0].id, operation.id.to_string());
+    assert_eq!(
+        response.operation_infos[0].block_ids,
+        vec![block.id.to_string()]
+    );
+    let response = search_operations(&service, tonic::Request::new(search_by(&creator))).unwrap();
+    assert!(response.operation_infos.is_empty());
+
+    // blocks found only in the archive are final
+    let response = search_blocks(
+        &service,
+        tonic::Request::new(grpc_api::SearchBlocksRequest {
+            filters: vec![grpc_api::SearchBlocksFilter {
+                filter: Some(grpc_api::search_blocks_filter::Filter::Addresses(
+                    grpc_model::Addresses {
+                        addresses: vec![creator.to_string()],
+                    },
+                )),
+            }],
+        }),
+    )
+    .unwrap();
+    assert_eq!(response.block_infos.len(), 1);
+    assert_eq!(response.block_infos[0].block_id, block.id.to_string());
+    assert_eq!(
+        response.block_infos[0].status,
+        i32::from(grpc_model::BlockStatus::Final)
+    );
+}
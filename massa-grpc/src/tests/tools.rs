@@ -0,0 +1,81 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::config::{GrpcConfig, ServiceName};
+use massa_models::config::{
+    ENDORSEMENT_COUNT, GENESIS_TIMESTAMP, MAX_DATASTORE_VALUE_LENGTH,
+    MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_ENDORSEMENTS_PER_MESSAGE, MAX_FUNCTION_NAME_LENGTH,
+    MAX_OPERATIONS_PER_BLOCK, MAX_OPERATIONS_PER_MESSAGE, MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+    MAX_OPERATION_DATASTORE_KEY_LENGTH, MAX_OPERATION_DATASTORE_VALUE_LENGTH, MAX_PARAMETERS_SIZE,
+    PERIODS_PER_CYCLE, T0, THREAD_COUNT,
+};
+use massa_signature::KeyPair;
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
+};
+
+/// Public gRPC configuration used by the tests
+pub(crate) fn get_grpc_config(keypair: &KeyPair) -> GrpcConfig {
+    GrpcConfig {
+        name: ServiceName::Public,
+        enabled: true,
+        accept_http1: true,
+        enable_cors: true,
+        enable_health: true,
+        enable_reflection: true,
+        enable_tls: false,
+        enable_mtls: false,
+        generate_self_signed_certificates: false,
+        subject_alt_names: vec![],
+        bind: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8888),
+        accept_compressed: None,
+        send_compressed: None,
+        max_decoding_message_size: 4194304,
+        max_encoding_message_size: 4194304,
+        concurrency_limit_per_connection: 0,
+        timeout: Default::default(),
+        initial_stream_window_size: None,
+        initial_connection_window_size: None,
+        max_concurrent_streams: None,
+        max_arguments: 128,
+        tcp_keepalive: None,
+        tcp_nodelay: false,
+        http2_keepalive_interval: None,
+        http2_keepalive_timeout: None,
+        http2_adaptive_window: None,
+        max_frame_size: None,
+        thread_count: THREAD_COUNT,
+        max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
+        endorsement_count: ENDORSEMENT_COUNT,
+        max_endorsements_per_message: MAX_ENDORSEMENTS_PER_MESSAGE,
+        max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+        max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+        max_datastore_entries_per_request: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+        max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
+        max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
+        max_parameter_size: MAX_PARAMETERS_SIZE,
+        max_operations_per_message: MAX_OPERATIONS_PER_MESSAGE,
+        genesis_timestamp: *GENESIS_TIMESTAMP,
+        t0: T0,
+        periods_per_cycle: PERIODS_PER_CYCLE,
+        keypair: keypair.clone(),
+        max_channel_size: 128,
+        draw_lookahead_period_count: 10,
+        last_start_period: 0,
+        max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+        max_addresses_per_request: 50,
+        max_slot_ranges_per_request: 50,
+        max_block_ids_per_request: 50,
+        max_endorsement_ids_per_request: 100,
+        max_operation_ids_per_request: 250,
+        max_filters_per_request: 32,
+        max_events_per_request: 1000,
+        server_certificate_path: PathBuf::default(),
+        server_private_key_path: PathBuf::default(),
+        certificate_authority_root_path: PathBuf::default(),
+        client_certificate_authority_root_path: PathBuf::default(),
+        client_certificate_path: PathBuf::default(),
+        client_private_key_path: PathBuf::default(),
+    }
+}
@@ -600,6 +600,7 @@ mod tests {
             max_history_length: 10,
             max_new_elements: 100,
             thread_count: 32,
+            enable_state_tree: false,
         };
 
         let db = Arc::new(RwLock::new(
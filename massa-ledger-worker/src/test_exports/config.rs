@@ -20,6 +20,7 @@ impl Default for FinalLedger {
             max_history_length: 10,
             max_new_elements: 100,
             thread_count: THREAD_COUNT,
+            enable_state_tree: false,
         };
         let db = MassaDB::new(db_config);
         let db = LedgerDB::new(
@@ -15,6 +15,7 @@ use crate::{
     // slot::{Slot, SlotDeserializer, SlotSerializer},
 };
 // use massa_hash::{Hash, HashDeserializer};
+use massa_hash::Hash;
 use massa_serialization::{
     // DeserializeError,
     Deserializer,
@@ -83,6 +84,25 @@ impl SecureShareContent for Block {
         })
     }
 
+    fn new_verifiable_with_signer<SC: Serializer<Self>, U: Id>(
+        self,
+        content_serializer: SC,
+        _public_key: &PublicKey,
+        _sign: impl FnOnce(&Hash) -> Result<Signature, ModelsError>,
+    ) -> Result<SecureShare<Self, U>, ModelsError> {
+        // a block is not signed by itself: it carries the signature of its header
+        let mut content_serialized = Vec::new();
+        content_serializer.serialize(&self, &mut content_serialized)?;
+        Ok(SecureShare {
+            signature: self.header.signature,
+            content_creator_pub_key: self.header.content_creator_pub_key,
+            content_creator_address: self.header.content_creator_address,
+            id: U::new(*self.header.id.get_hash()),
+            content: self,
+            serialized_data: content_serialized,
+        })
+    }
+
     fn serialize(
         _signature: &Signature,
         _creator_public_key: &PublicKey,
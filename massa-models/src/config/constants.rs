@@ -231,6 +231,8 @@ pub const POOL_CONTROLLER_OPERATIONS_CHANNEL_SIZE: usize = 1024;
 pub const POOL_CONTROLLER_ENDORSEMENTS_CHANNEL_SIZE: usize = 1024;
 /// Pool controller denunciations channel size
 pub const POOL_CONTROLLER_DENUNCIATIONS_CHANNEL_SIZE: usize = 1024;
+/// Number of fees of recently included operations kept by the operation pool, per thread, for fee statistics
+pub const POOL_FEE_STATS_INCLUSION_HISTORY_SIZE: usize = 1000;
 
 // ***********************
 // Constants used for execution module (injected from ConsensusConfig)
@@ -240,6 +242,8 @@ pub const POOL_CONTROLLER_DENUNCIATIONS_CHANNEL_SIZE: usize = 1024;
 pub const MAX_GAS_PER_BLOCK: u64 = u32::MAX as u64;
 /// Maximum of GAS allowed for asynchronous messages execution on one slot
 pub const MAX_ASYNC_GAS: u64 = 1_000_000_000;
+/// Safety margin added to the minimal gas limit of an operation when estimating its gas, in percent
+pub const GAS_ESTIMATION_MARGIN_PERCENT: u64 = 10;
 /// Maximum event size in bytes
 pub const MAX_EVENT_DATA_SIZE: usize = 50_000;
 
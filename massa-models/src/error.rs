@@ -32,6 +32,8 @@ pub enum ModelsError {
     AmountParseError(String),
     /// address parsing error: {0}
     AddressParseError(String),
+    /// IP network parsing error: {0}
+    IpNetworkParseError(String),
     /// node id parsing error
     NodeIdParseError,
     /// block id parsing error
@@ -62,6 +64,8 @@ pub enum ModelsError {
     OutdatedBootstrapCursor,
     /// Error raised {0}
     ErrorRaised(String),
+    /// signer error: {0}
+    SignerError(String),
 }
 
 impl From<nom::Err<nom::error::Error<&[u8]>>> for ModelsError {
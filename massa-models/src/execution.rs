@@ -1,6 +1,9 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use crate::{address::Address, operation::OperationId, slot::Slot};
+use crate::{
+    address::Address, amount::Amount, block_id::BlockId, operation::OperationId,
+    output_event::SCOutputEvent, slot::Slot,
+};
 use serde::{Deserialize, Serialize};
 
 /// filter used when retrieving SC output events
@@ -28,4 +31,192 @@ pub struct EventFilter {
     /// Some(false) means events coming from a succeeded sc execution
     /// None means both
     pub is_error: Option<bool>,
+    /// optional number of matching events to skip (pagination)
+    pub offset: Option<usize>,
+    /// optional maximum number of events to return (pagination)
+    pub limit: Option<usize>,
+}
+
+/// final execution record of an operation, as listed in the history of the addresses it involves
+#[derive(Debug, Deserialize, Clone, Serialize, PartialEq, Eq)]
+pub struct AddressHistoryEntry {
+    /// id of the executed operation
+    pub operation_id: OperationId,
+    /// id of the block in which the operation was executed
+    pub block_id: BlockId,
+    /// slot at which the operation was executed
+    pub slot: Slot,
+    /// whether the execution of the operation succeeded
+    pub success: bool,
+}
+
+/// identifier of a traced execution
+#[derive(Debug, Deserialize, Clone, Copy, Serialize, PartialEq, Eq, Hash)]
+pub enum ExecutionTraceId {
+    /// execution of an operation
+    Operation(OperationId),
+    /// execution of an asynchronous message, identified by its emission
+    AsyncMessage {
+        /// slot at which the message was emitted
+        emission_slot: Slot,
+        /// index of the message among the ones emitted at that slot
+        emission_index: u64,
+    },
+}
+
+impl std::fmt::Display for ExecutionTraceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecutionTraceId::Operation(operation_id) => write!(f, "operation {}", operation_id),
+            ExecutionTraceId::AsyncMessage {
+                emission_slot,
+                emission_index,
+            } => write!(
+                f,
+                "async message {} emitted at slot {}",
+                emission_index, emission_slot
+            ),
+        }
+    }
+}
+
+/// something that happened during a traced call, in execution order
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub enum ExecutionTraceStep {
+    /// nested call
+    Call(CallTrace),
+    /// coin transfer
+    Transfer {
+        /// spending address, `None` for coin creation
+        from: Option<Address>,
+        /// credited address, `None` for coin destruction
+        to: Option<Address>,
+        /// amount of coins
+        amount: Amount,
+        /// whether the transfer succeeded
+        success: bool,
+    },
+    /// datastore read
+    DatastoreRead {
+        /// address of the datastore
+        address: Address,
+        /// key of the entry
+        key: Vec<u8>,
+        /// value of the entry, `None` if it was absent
+        value: Option<Vec<u8>>,
+    },
+    /// datastore write
+    DatastoreWrite {
+        /// address of the datastore
+        address: Address,
+        /// key of the entry
+        key: Vec<u8>,
+        /// new value of the entry, `None` if it was deleted
+        value: Option<Vec<u8>>,
+        /// whether the write succeeded
+        success: bool,
+    },
+    /// emitted event
+    Event(SCOutputEvent),
+}
+
+/// trace of a call, with everything that happened during it
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct CallTrace {
+    /// called address
+    pub address: Address,
+    /// coins sent with the call
+    pub coins: Amount,
+    /// gas used by the call, only known for the top-level call
+    pub gas_used: Option<u64>,
+    /// steps of the call, in execution order
+    pub steps: Vec<ExecutionTraceStep>,
+}
+
+/// trace of the execution of an operation or an asynchronous message
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct ExecutionTrace {
+    /// traced execution
+    pub id: ExecutionTraceId,
+    /// slot at which the execution happened
+    pub slot: Slot,
+    /// error that made the execution fail, `None` on success
+    pub error: Option<String>,
+    /// top-level call
+    pub call: CallTrace,
+}
+
+/// filter used when listing the messages of the final asynchronous pool
+#[derive(Default, Debug, Deserialize, Clone, Serialize)]
+pub struct AsyncMessageFilter {
+    /// optional sender address
+    pub sender: Option<Address>,
+    /// optional destination address
+    pub destination: Option<Address>,
+    /// optional slot at which the message was emitted
+    pub emission_slot: Option<Slot>,
+    /// optional index of the message among the ones emitted at its slot
+    pub emission_index: Option<u64>,
+}
+
+/// status of a message of the final asynchronous pool at the slot following the last final slot
+#[derive(Debug, Deserialize, Clone, Copy, Serialize, PartialEq, Eq)]
+pub enum AsyncMessageStatus {
+    /// the validity range of the message has not started yet
+    Pending,
+    /// the message has a trigger that was not activated yet
+    WaitingTrigger,
+    /// the message fits in the asynchronous gas of the slot and will be executed
+    Executable,
+    /// the message can be executed but does not fit in the asynchronous gas of the slot,
+    /// because of messages with a higher priority
+    Starved,
+    /// the validity range of the message is over: it will be removed and its coins reimbursed
+    Expired,
+}
+
+impl std::fmt::Display for AsyncMessageStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AsyncMessageStatus::Pending => write!(f, "pending"),
+            AsyncMessageStatus::WaitingTrigger => write!(f, "waiting for its trigger"),
+            AsyncMessageStatus::Executable => write!(f, "executable"),
+            AsyncMessageStatus::Starved => write!(f, "starved"),
+            AsyncMessageStatus::Expired => write!(f, "expired"),
+        }
+    }
+}
+
+/// message of the final asynchronous pool
+#[derive(Debug, Deserialize, Clone, Serialize, PartialEq, Eq)]
+pub struct AsyncMessageEntry {
+    /// slot at which the message was emitted
+    pub emission_slot: Slot,
+    /// index of the message among the ones emitted at that slot
+    pub emission_index: u64,
+    /// address that sent the message
+    pub sender: Address,
+    /// address towards which the message is sent
+    pub destination: Address,
+    /// function of the destination called to handle the message
+    pub handler: String,
+    /// maximum gas used to handle the message
+    pub max_gas: u64,
+    /// fee paid when the message is handled
+    pub fee: Amount,
+    /// coins sent to the destination
+    pub coins: Amount,
+    /// first slot of the validity range (included)
+    pub validity_start: Slot,
+    /// last slot of the validity range (excluded)
+    pub validity_end: Slot,
+    /// whether the message has a trigger
+    pub has_trigger: bool,
+    /// whether the message can be executed: true if it has no trigger or if its trigger was activated
+    pub can_be_executed: bool,
+    /// status of the message at the slot following the last final slot
+    pub status: AsyncMessageStatus,
+    /// position of the message in the batch of messages executed at that slot,
+    /// by decreasing fee per gas, then by emission. `None` if the message is not executed at that slot.
+    pub execution_rank: Option<u64>,
 }
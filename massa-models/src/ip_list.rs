@@ -0,0 +1,260 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::error::ModelsError;
+use massa_time::MassaTime;
+use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::net::IpAddr;
+use std::str::FromStr;
+
+/// A range of IP addresses in CIDR notation, for example `192.168.0.0/16` or `2001:db8::/32`.
+/// A single IP address is written without prefix length, and covers only itself.
+#[derive(
+    Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, SerializeDisplay, DeserializeFromStr,
+)]
+pub struct IpNetwork {
+    /// first address of the range (the bits after the prefix are zero)
+    addr: IpAddr,
+    /// number of leading bits shared by the addresses of the range
+    prefix_len: u8,
+}
+
+impl IpNetwork {
+    /// Creates a range from an address and a prefix length.
+    /// The bits of the address after the prefix are cleared.
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Result<Self, ModelsError> {
+        let addr = match addr {
+            IpAddr::V4(v4) if prefix_len <= 32 => {
+                IpAddr::V4((u32::from(v4) & mask_v4(prefix_len)).into())
+            }
+            IpAddr::V6(v6) if prefix_len <= 128 => {
+                IpAddr::V6((u128::from(v6) & mask_v6(prefix_len)).into())
+            }
+            _ => {
+                return Err(ModelsError::IpNetworkParseError(format!(
+                    "invalid prefix length {} for {}",
+                    prefix_len, addr
+                )))
+            }
+        };
+        Ok(IpNetwork { addr, prefix_len })
+    }
+
+    /// Get the first address of the range
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// Get the prefix length of the range
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Returns true if the range contains `ip`.
+    /// IPv4-mapped IPv6 addresses match the IPv4 ranges, and conversely.
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(addr), IpAddr::V4(ip)) => {
+                u32::from(*ip) & mask_v4(self.prefix_len) == u32::from(addr)
+            }
+            (IpAddr::V6(addr), IpAddr::V6(ip)) => {
+                u128::from(*ip) & mask_v6(self.prefix_len) == u128::from(addr)
+            }
+            (IpAddr::V4(_), IpAddr::V6(ip)) => ip
+                .to_ipv4_mapped()
+                .map_or(false, |ip| self.contains(&IpAddr::V4(ip))),
+            (IpAddr::V6(_), IpAddr::V4(ip)) => self.contains(&IpAddr::V6(ip.to_ipv6_mapped())),
+        }
+    }
+}
+
+fn mask_v4(prefix_len: u8) -> u32 {
+    u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0)
+}
+
+fn mask_v6(prefix_len: u8) -> u128 {
+    u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0)
+}
+
+impl From<IpAddr> for IpNetwork {
+    fn from(addr: IpAddr) -> Self {
+        let prefix_len = match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        IpNetwork { addr, prefix_len }
+    }
+}
+
+impl std::fmt::Display for IpNetwork {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if *self == IpNetwork::from(self.addr) {
+            write!(f, "{}", self.addr)
+        } else {
+            write!(f, "{}/{}", self.addr, self.prefix_len)
+        }
+    }
+}
+
+impl FromStr for IpNetwork {
+    type Err = ModelsError;
+
+    /// ## Example
+    /// ```rust
+    /// # use massa_models::ip_list::IpNetwork;
+    /// # use std::str::FromStr;
+    /// let network = IpNetwork::from_str("10.1.0.0/16").unwrap();
+    /// assert!(network.contains(&"10.1.2.3".parse().unwrap()));
+    /// assert!(!network.contains(&"10.2.0.1".parse().unwrap()));
+    /// let single = IpNetwork::from_str("2001:db8::1").unwrap();
+    /// assert_eq!(single.prefix_len(), 128);
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_addr = |addr: &str| {
+            IpAddr::from_str(addr).map_err(|err| {
+                ModelsError::IpNetworkParseError(format!("invalid address {}: {}", addr, err))
+            })
+        };
+        match s.split_once('/') {
+            Some((addr, prefix_len)) => {
+                let prefix_len = u8::from_str(prefix_len).map_err(|err| {
+                    ModelsError::IpNetworkParseError(format!(
+                        "invalid prefix length {}: {}",
+                        prefix_len, err
+                    ))
+                })?;
+                IpNetwork::new(parse_addr(addr)?, prefix_len)
+            }
+            None => Ok(IpNetwork::from(parse_addr(s)?)),
+        }
+    }
+}
+
+/// Entry of an IP white/black list
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "IpListEntryRepr")]
+pub struct IpListEntry {
+    /// IP addresses covered by the entry
+    pub network: IpNetwork,
+    /// time after which the entry no longer applies, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expire_at: Option<MassaTime>,
+    /// why the entry was added, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl IpListEntry {
+    /// Returns true if the entry no longer applies at time `now`
+    pub fn is_expired(&self, now: MassaTime) -> bool {
+        self.expire_at.map_or(false, |expire_at| expire_at <= now)
+    }
+}
+
+impl From<IpNetwork> for IpListEntry {
+    fn from(network: IpNetwork) -> Self {
+        IpListEntry {
+            network,
+            expire_at: None,
+            reason: None,
+        }
+    }
+}
+
+impl std::fmt::Display for IpListEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.network)?;
+        if let Some(expire_at) = self.expire_at {
+            write!(f, " (expires at {})", expire_at.format_instant())?;
+        }
+        if let Some(reason) = &self.reason {
+            write!(f, ": {}", reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// The entries of the list files can also be plain addresses or ranges,
+/// as the lists used to contain only IP addresses
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IpListEntryRepr {
+    Network(IpNetwork),
+    Entry {
+        network: IpNetwork,
+        #[serde(default)]
+        expire_at: Option<MassaTime>,
+        #[serde(default)]
+        reason: Option<String>,
+    },
+}
+
+impl From<IpListEntryRepr> for IpListEntry {
+    fn from(repr: IpListEntryRepr) -> Self {
+        match repr {
+            IpListEntryRepr::Network(network) => IpListEntry::from(network),
+            IpListEntryRepr::Entry {
+                network,
+                expire_at,
+                reason,
+            } => IpListEntry {
+                network,
+                expire_at,
+                reason,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ip_network() {
+        let network = IpNetwork::from_str("192.168.1.7/16").unwrap();
+        assert_eq!(network.to_string(), "192.168.0.0/16");
+        assert!(network.contains(&"192.168.255.1".parse().unwrap()));
+        assert!(!network.contains(&"192.169.0.1".parse().unwrap()));
+        assert!(network.contains(&"::ffff:192.168.3.4".parse().unwrap()));
+        assert!(IpNetwork::from_str("0.0.0.0/0")
+            .unwrap()
+            .contains(&"8.8.8.8".parse().unwrap()));
+        assert!(IpNetwork::from_str("10.0.0.0/33").is_err());
+        assert!(IpNetwork::from_str("10.0.0/8").is_err());
+
+        let network = IpNetwork::from_str("2001:db8::/32").unwrap();
+        assert!(network.contains(&"2001:db8:1::1".parse().unwrap()));
+        assert!(!network.contains(&"2001:db9::1".parse().unwrap()));
+        assert!(!network.contains(&"192.168.0.1".parse().unwrap()));
+
+        let single = IpNetwork::from_str("149.202.89.125").unwrap();
+        assert_eq!(single.to_string(), "149.202.89.125");
+        assert!(single.contains(&"149.202.89.125".parse().unwrap()));
+        assert!(!single.contains(&"149.202.89.126".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_ip_list_entry_serde() {
+        let entries: Vec<IpListEntry> = serde_json::from_str(
+            r#"["149.202.89.125", {"network": "10.0.0.0/8", "expire_at": 1000, "reason": "spam"}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            entries[0],
+            IpListEntry::from(IpNetwork::from_str("149.202.89.125").unwrap())
+        );
+        assert_eq!(entries[1].expire_at, Some(MassaTime::from_millis(1000)));
+        assert_eq!(entries[1].reason.as_deref(), Some("spam"));
+        assert!(!entries[0].is_expired(MassaTime::from_millis(2000)));
+        assert!(entries[1].is_expired(MassaTime::from_millis(2000)));
+
+        let serialized = serde_json::to_string(&entries).unwrap();
+        assert_eq!(
+            serialized,
+            r#"[{"network":"149.202.89.125"},{"network":"10.0.0.0/8","expire_at":1000,"reason":"spam"}]"#
+        );
+        let deserialized: Vec<IpListEntry> = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, entries);
+    }
+}
@@ -36,6 +36,8 @@ pub mod endorsement;
 pub mod error;
 /// execution related structures
 pub mod execution;
+/// IP ranges and entries of the IP white/black lists
+pub mod ip_list;
 /// ledger related structures
 pub mod ledger;
 /// mapping grpc
@@ -56,6 +58,8 @@ pub mod secure_share;
 pub mod serialization;
 /// slots
 pub mod slot;
+/// final state inclusion proofs
+pub mod state_proof;
 /// various statistics
 pub mod stats;
 /// bootstrap streaming cursor
@@ -416,6 +416,41 @@ pub type SecureShareOperation = SecureShare<Operation, OperationId>;
 
 impl SecureShareContent for Operation {}
 
+impl Operation {
+    /// get the range of periods during which an operation is valid
+    /// Range: `(op.expire_period - cfg.operation_validity_period) -> op.expire_period` (included)
+    pub fn get_validity_range(&self, operation_validity_period: u64) -> RangeInclusive<u64> {
+        let start = self.expire_period.saturating_sub(operation_validity_period);
+        start..=self.expire_period
+    }
+
+    /// Get the max amount of gas used by the operation (`max_gas`)
+    pub fn get_gas_usage(&self) -> u64 {
+        match &self.op {
+            OperationType::ExecuteSC { max_gas, .. } => *max_gas,
+            OperationType::CallSC { max_gas, .. } => *max_gas,
+            OperationType::RollBuy { .. } => 0,
+            OperationType::RollSell { .. } => 0,
+            OperationType::Transaction { .. } => 0,
+        }
+    }
+
+    /// Gets the maximal amount of coins that may be spent by this operation (incl. fee)
+    pub fn get_max_spending(&self, roll_price: Amount) -> Amount {
+        // compute the max amount of coins spent outside of the fees
+        let max_non_fee_seq_spending = match &self.op {
+            OperationType::Transaction { amount, .. } => *amount,
+            OperationType::RollBuy { roll_count } => roll_price.saturating_mul_u64(*roll_count),
+            OperationType::RollSell { .. } => Amount::zero(),
+            OperationType::ExecuteSC { max_coins, .. } => *max_coins,
+            OperationType::CallSC { coins, .. } => *coins,
+        };
+
+        // add all fees and return
+        max_non_fee_seq_spending.saturating_add(self.fee)
+    }
+}
+
 /// Serializer for `Operation`
 pub struct OperationSerializer {
     u64_serializer: U64VarIntSerializer,
@@ -964,22 +999,12 @@ impl SecureShareOperation {
     /// get the range of periods during which an operation is valid
     /// Range: `(op.expire_period - cfg.operation_validity_period) -> op.expire_period` (included)
     pub fn get_validity_range(&self, operation_validity_period: u64) -> RangeInclusive<u64> {
-        let start = self
-            .content
-            .expire_period
-            .saturating_sub(operation_validity_period);
-        start..=self.content.expire_period
+        self.content.get_validity_range(operation_validity_period)
     }
 
     /// Get the max amount of gas used by the operation (`max_gas`)
     pub fn get_gas_usage(&self) -> u64 {
-        match &self.content.op {
-            OperationType::ExecuteSC { max_gas, .. } => *max_gas,
-            OperationType::CallSC { max_gas, .. } => *max_gas,
-            OperationType::RollBuy { .. } => 0,
-            OperationType::RollSell { .. } => 0,
-            OperationType::Transaction { .. } => 0,
-        }
+        self.content.get_gas_usage()
     }
 
     /// get the addresses that are involved in this operation from a ledger point of view
@@ -1005,17 +1030,7 @@ impl SecureShareOperation {
 
     /// Gets the maximal amount of coins that may be spent by this operation (incl. fee)
     pub fn get_max_spending(&self, roll_price: Amount) -> Amount {
-        // compute the max amount of coins spent outside of the fees
-        let max_non_fee_seq_spending = match &self.content.op {
-            OperationType::Transaction { amount, .. } => *amount,
-            OperationType::RollBuy { roll_count } => roll_price.saturating_mul_u64(*roll_count),
-            OperationType::RollSell { .. } => Amount::zero(),
-            OperationType::ExecuteSC { max_coins, .. } => *max_coins,
-            OperationType::CallSC { coins, .. } => *coins,
-        };
-
-        // add all fees and return
-        max_non_fee_seq_spending.saturating_add(self.content.fee)
+        self.content.get_max_spending(roll_price)
     }
 
     /// get the addresses that are involved in this operation from a rolls point of view
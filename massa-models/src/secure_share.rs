@@ -96,6 +96,28 @@ where
         })
     }
 
+    /// Same as `new_verifiable`, but the signature is delegated to `sign`, which receives the hash to sign.
+    /// Allows signing with a key that is not held by the caller (e.g. by a remote signer).
+    fn new_verifiable_with_signer<Ser: Serializer<Self>, ID: Id>(
+        self,
+        content_serializer: Ser,
+        public_key: &PublicKey,
+        sign: impl FnOnce(&Hash) -> Result<Signature, ModelsError>,
+    ) -> Result<SecureShare<Self, ID>, ModelsError> {
+        let mut content_serialized = Vec::new();
+        content_serializer.serialize(&self, &mut content_serialized)?;
+        let hash = Self::compute_hash(&self, &content_serialized, public_key);
+        let creator_address = Address::from_public_key(public_key);
+        Ok(SecureShare {
+            signature: sign(&self.compute_signed_hash(public_key, &hash))?,
+            content_creator_pub_key: *public_key,
+            content_creator_address: creator_address,
+            content: self,
+            serialized_data: content_serialized,
+            id: ID::new(hash),
+        })
+    }
+
     /// Compute hash
     fn compute_hash(&self, content_serialized: &[u8], content_creator_pub_key: &PublicKey) -> Hash {
         let mut hash_data = Vec::new();
@@ -0,0 +1,110 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Inclusion proofs for the entries of the final state.
+//!
+//! The final state entries are authenticated by a sparse Merkle tree of depth 256:
+//! each entry is a leaf placed at the path given by the hash of its key.
+//! To keep the tree compact, an empty subtree hashes to zero
+//! and a subtree holding a single leaf hashes to that leaf,
+//! so that only the subtrees holding at least two leaves have their own node hash.
+//!
+//! The tree root is not part of consensus: block headers do not commit to it.
+//! A proof only shows that an entry belongs to the state whose root is given,
+//! at the final slot the proof was built at, so that root has to come from a trusted source
+//! (for example several independent nodes agreeing on the root at that slot).
+
+use crate::slot::Slot;
+use massa_hash::{Hash, HASH_SIZE_BYTES};
+use serde::{Deserialize, Serialize};
+
+/// Number of bits of a path in the state tree
+pub const STATE_TREE_DEPTH: usize = HASH_SIZE_BYTES * 8;
+
+/// Path of a key in the state tree
+pub fn state_tree_path(key: &[u8]) -> Hash {
+    Hash::compute_from(key)
+}
+
+/// Hash of the leaf holding a value, at a given path
+pub fn state_tree_leaf_hash(path: &Hash, value_hash: &Hash) -> Hash {
+    Hash::compute_from_tuple(&[&[0u8], path.to_bytes(), value_hash.to_bytes()])
+}
+
+/// Hash of a node holding at least two leaves, from the hashes of its children
+pub fn state_tree_node_hash(left: &Hash, right: &Hash) -> Hash {
+    Hash::compute_from_tuple(&[&[1u8], left.to_bytes(), right.to_bytes()])
+}
+
+/// Bit of a path at a given depth, `true` meaning the right child
+pub fn state_tree_path_bit(path: &Hash, depth: usize) -> bool {
+    (path.to_bytes()[depth / 8] >> (7 - depth % 8)) & 1 == 1
+}
+
+/// Number of leading bits shared by two paths
+pub fn state_tree_common_prefix_len(a: &Hash, b: &Hash) -> usize {
+    for (index, (byte_a, byte_b)) in a.to_bytes().iter().zip(b.to_bytes()).enumerate() {
+        let diff = byte_a ^ byte_b;
+        if diff != 0 {
+            return index * 8 + diff.leading_zeros() as usize;
+        }
+    }
+    STATE_TREE_DEPTH
+}
+
+/// A leaf of the state tree, as found at the end of a proof path
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateProofLeaf {
+    /// path of the leaf
+    pub path: Hash,
+    /// hash of the value held by the leaf
+    pub value_hash: Hash,
+}
+
+/// Proof that a key is (or is not) part of the final state, with a given value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateProof {
+    /// key of the state entry
+    pub key: Vec<u8>,
+    /// value of the state entry, `None` if the key is absent from the state
+    pub value: Option<Vec<u8>>,
+    /// hashes of the siblings along the path of the key, from the root down
+    pub siblings: Vec<Hash>,
+    /// when the key is absent, the other leaf found at the end of its path, if any
+    pub other_leaf: Option<StateProofLeaf>,
+    /// root of the state tree the proof was built against
+    pub root: Hash,
+    /// final slot of the state the proof was built against
+    pub slot: Slot,
+}
+
+impl StateProof {
+    /// Check that the proof is consistent and leads to the given trusted root
+    pub fn verify(&self, trusted_root: &Hash) -> bool {
+        if self.root != *trusted_root || self.siblings.len() >= STATE_TREE_DEPTH {
+            return false;
+        }
+        let path = state_tree_path(&self.key);
+        let mut hash = match (&self.value, &self.other_leaf) {
+            (Some(value), None) => state_tree_leaf_hash(&path, &Hash::compute_from(value)),
+            (None, Some(leaf)) => {
+                // the other leaf must be alone in the subtree the key would belong to
+                if leaf.path == path
+                    || state_tree_common_prefix_len(&leaf.path, &path) < self.siblings.len()
+                {
+                    return false;
+                }
+                state_tree_leaf_hash(&leaf.path, &leaf.value_hash)
+            }
+            (None, None) => Hash::zero(),
+            (Some(_), Some(_)) => return false,
+        };
+        for (depth, sibling) in self.siblings.iter().enumerate().rev() {
+            hash = if state_tree_path_bit(&path, depth) {
+                state_tree_node_hash(sibling, &hash)
+            } else {
+                state_tree_node_hash(&hash, sibling)
+            };
+        }
+        hash == self.root
+    }
+}
@@ -1,6 +1,6 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use crate::slot::Slot;
+use crate::{amount::Amount, slot::Slot};
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
 use std::fmt::Formatter;
@@ -130,3 +130,97 @@ impl std::fmt::Display for PoolStats {
         Ok(())
     }
 }
+
+/// percentiles of a set of operation fees
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeePercentiles {
+    /// 10th percentile
+    pub p10: Amount,
+    /// 25th percentile
+    pub p25: Amount,
+    /// median
+    pub p50: Amount,
+    /// 75th percentile
+    pub p75: Amount,
+    /// 90th percentile
+    pub p90: Amount,
+}
+
+impl FeePercentiles {
+    /// Compute the percentiles of a set of fees, using the nearest-rank method.
+    /// Returns `None` if the set is empty.
+    pub fn from_fees(mut fees: Vec<Amount>) -> Option<Self> {
+        if fees.is_empty() {
+            return None;
+        }
+        fees.sort_unstable();
+        let percentile = |p: usize| fees[((fees.len() * p + 99) / 100).saturating_sub(1)];
+        Some(FeePercentiles {
+            p10: percentile(10),
+            p25: percentile(25),
+            p50: percentile(50),
+            p75: percentile(75),
+            p90: percentile(90),
+        })
+    }
+}
+
+impl std::fmt::Display for FeePercentiles {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "p10: {}, p25: {}, p50: {}, p75: {}, p90: {}",
+            self.p10, self.p25, self.p50, self.p75, self.p90
+        )
+    }
+}
+
+/// fee statistics of a thread, produced by pool module
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadFeeStats {
+    /// thread
+    pub thread: u8,
+    /// number of operations waiting in the pool
+    pub pending_operation_count: usize,
+    /// fee percentiles of the operations waiting in the pool, `None` if there are none
+    pub pending_fees: Option<FeePercentiles>,
+    /// fee percentiles of the operations recently included in final blocks, `None` if there are none
+    pub recent_inclusion_fees: Option<FeePercentiles>,
+    /// fee recommended for an operation of this thread to be included within the target delay
+    pub recommended_fee: Amount,
+}
+
+/// fee market statistics, produced by pool module
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeStats {
+    /// target inclusion delay (in periods) of the recommended fees
+    pub target_delay: u64,
+    /// statistics of each thread
+    pub threads: Vec<ThreadFeeStats>,
+}
+
+impl std::fmt::Display for FeeStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Fee stats (target inclusion delay: {} periods):",
+            self.target_delay
+        )?;
+        for thread_stats in &self.threads {
+            writeln!(f, "\tThread {}:", thread_stats.thread)?;
+            writeln!(
+                f,
+                "\t\tPending operations: {}",
+                thread_stats.pending_operation_count
+            )?;
+            if let Some(pending_fees) = &thread_stats.pending_fees {
+                writeln!(f, "\t\tPending fees: {}", pending_fees)?;
+            }
+            if let Some(recent_inclusion_fees) = &thread_stats.recent_inclusion_fees {
+                writeln!(f, "\t\tRecent inclusion fees: {}", recent_inclusion_fees)?;
+            }
+            writeln!(f, "\t\tRecommended fee: {}", thread_stats.recommended_fee)?;
+        }
+        Ok(())
+    }
+}
@@ -14,16 +14,20 @@ use crossbeam_channel::TryRecvError;
 use dialoguer::Password;
 use massa_api::{ApiServer, ApiV2, Private, Public, RpcServer, StopHandle, API};
 use massa_api_exports::config::APIConfig;
+use massa_archive::{start_archive_worker, Archive, ArchiveConfig, ArchiveManager};
 use massa_async_pool::AsyncPoolConfig;
 use massa_bootstrap::BootstrapError;
 use massa_bootstrap::{
-    get_state, start_bootstrap_server, BootstrapConfig, BootstrapManager, BootstrapTcpListener,
+    export_snapshot, get_state, get_state_from_snapshot, load_final_graph, save_final_graph,
+    start_bootstrap_server, BootstrapConfig, BootstrapManager, BootstrapTcpListener,
     DefaultConnector,
 };
 use massa_channel::receiver::MassaReceiver;
 use massa_channel::MassaChannel;
 use massa_consensus_exports::events::ConsensusEvent;
-use massa_consensus_exports::{ConsensusChannels, ConsensusConfig, ConsensusManager};
+use massa_consensus_exports::{
+    ConsensusChannels, ConsensusConfig, ConsensusController, ConsensusManager,
+};
 use massa_consensus_worker::start_consensus_worker;
 use massa_db_exports::{MassaDBConfig, MassaDBController};
 use massa_db_worker::MassaDB;
@@ -33,10 +37,11 @@ use massa_execution_exports::{
 };
 use massa_execution_worker::start_execution_worker;
 use massa_factory_exports::{FactoryChannels, FactoryConfig, FactoryManager};
-use massa_factory_worker::start_factory;
+use massa_factory_worker::{start_factory, SlashingProtectionDB};
 use massa_final_state::{FinalState, FinalStateConfig};
 use massa_grpc::config::{GrpcConfig, ServiceName};
 use massa_grpc::server::{MassaPrivateGrpc, MassaPublicGrpc};
+use massa_hash::Hash;
 use massa_ledger_exports::LedgerConfig;
 use massa_ledger_worker::FinalLedger;
 use massa_logging::massa_trace;
@@ -88,7 +93,7 @@ use massa_time::MassaTime;
 use massa_versioning::keypair_factory::KeyPairFactory;
 use massa_versioning::mips::get_mip_list;
 use massa_versioning::versioning::{MipStatsConfig, MipStore};
-use massa_wallet::Wallet;
+use massa_wallet::{Signer, Wallet, WalletSigner};
 use num::rational::Ratio;
 use parking_lot::RwLock;
 use settings::GrpcSettings;
@@ -112,6 +117,7 @@ mod survey;
 async fn launch(
     args: &Args,
     node_wallet: Arc<RwLock<Wallet>>,
+    slashing_protection: Arc<SlashingProtectionDB>,
     sig_int_toggled: Arc<(Mutex<bool>, Condvar)>,
 ) -> (
     MassaReceiver<ConsensusEvent>,
@@ -122,6 +128,7 @@ async fn launch(
     Box<dyn PoolManager>,
     Box<dyn ProtocolManager>,
     Box<dyn FactoryManager>,
+    Option<ArchiveManager>,
     StopHandle,
     StopHandle,
     StopHandle,
@@ -129,6 +136,8 @@ async fn launch(
     Option<massa_grpc::server::StopHandle>,
     MetricsStopper,
     MassaSurveyStopper,
+    Box<dyn ConsensusController>,
+    Arc<RwLock<FinalState>>,
 ) {
     let now = MassaTime::now().expect("could not get now time");
     // Do not start if genesis is in the future. This is meant to prevent nodes
@@ -245,15 +254,38 @@ async fn launch(
         SETTINGS.metrics.tick_delay.to_duration(),
     );
 
+    // path of the snapshot file to import the final state from, if any
+    let import_snapshot_path = match &args.command {
+        Some(Command::ImportSnapshot { path, state_hash }) => Some((path, state_hash)),
+        _ => None,
+    };
+
+    // A bootstrap interrupted during a previous run is resumed from the partially bootstrapped ledger
+    let resume_bootstrap = import_snapshot_path.is_none()
+        && args.restart_from_snapshot_at_period.is_none()
+        && SETTINGS.bootstrap.bootstrap_cursor_path.exists();
+
     // Remove current disk ledger if there is one and we don't want to restart from snapshot
     // NOTE: this is temporary, since we cannot currently handle bootstrap from remaining ledger
-    if args.keep_ledger || args.restart_from_snapshot_at_period.is_some() {
+    if resume_bootstrap {
+        info!("Loading partially bootstrapped ledger");
+    } else if import_snapshot_path.is_none()
+        && (args.keep_ledger || args.restart_from_snapshot_at_period.is_some())
+    {
         info!("Loading old ledger for next episode");
     } else {
         if SETTINGS.ledger.disk_ledger_path.exists() {
             std::fs::remove_dir_all(SETTINGS.ledger.disk_ledger_path.clone())
                 .expect("disk ledger delete failed");
         }
+        if SETTINGS.ledger.final_graph_path.exists() {
+            std::fs::remove_file(SETTINGS.ledger.final_graph_path.clone())
+                .expect("final graph delete failed");
+        }
+        if SETTINGS.bootstrap.bootstrap_cursor_path.exists() {
+            std::fs::remove_file(SETTINGS.bootstrap.bootstrap_cursor_path.clone())
+                .expect("bootstrap cursor delete failed");
+        }
         if SETTINGS.execution.hd_cache_path.exists() {
             std::fs::remove_dir_all(SETTINGS.execution.hd_cache_path.clone())
                 .expect("disk hd cache delete failed");
@@ -265,6 +297,7 @@ async fn launch(
         max_history_length: SETTINGS.ledger.final_history_length,
         max_new_elements: MAX_BOOTSTRAPPED_NEW_ELEMENTS as usize,
         thread_count: THREAD_COUNT,
+        enable_state_tree: SETTINGS.ledger.enable_state_proofs,
     };
     let db = Arc::new(RwLock::new(
         Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
@@ -334,7 +367,7 @@ async fn launch(
                     Box::new(ledger),
                     selector_controller.clone(),
                     mip_store,
-                    true,
+                    !resume_bootstrap,
                 )
                 .expect("could not init final state")
             }
@@ -343,73 +376,29 @@ async fn launch(
 
     let mip_store = final_state.read().mip_store.clone();
 
-    let bootstrap_config: BootstrapConfig = BootstrapConfig {
-        bootstrap_list: SETTINGS.bootstrap.bootstrap_list.clone(),
-        bootstrap_protocol: SETTINGS.bootstrap.bootstrap_protocol,
-        bootstrap_whitelist_path: SETTINGS.bootstrap.bootstrap_whitelist_path.clone(),
-        bootstrap_blacklist_path: SETTINGS.bootstrap.bootstrap_blacklist_path.clone(),
-        listen_addr: SETTINGS.bootstrap.bind,
-        connect_timeout: SETTINGS.bootstrap.connect_timeout,
-        bootstrap_timeout: SETTINGS.bootstrap.bootstrap_timeout,
-        read_timeout: SETTINGS.bootstrap.read_timeout,
-        write_timeout: SETTINGS.bootstrap.write_timeout,
-        read_error_timeout: SETTINGS.bootstrap.read_error_timeout,
-        write_error_timeout: SETTINGS.bootstrap.write_error_timeout,
-        retry_delay: SETTINGS.bootstrap.retry_delay,
-        max_ping: SETTINGS.bootstrap.max_ping,
-        max_clock_delta: SETTINGS.bootstrap.max_clock_delta,
-        cache_duration: SETTINGS.bootstrap.cache_duration,
-        keep_ledger: args.keep_ledger,
-        max_listeners_per_peer: MAX_LISTENERS_PER_PEER as u32,
-        max_simultaneous_bootstraps: SETTINGS.bootstrap.max_simultaneous_bootstraps,
-        per_ip_min_interval: SETTINGS.bootstrap.per_ip_min_interval,
-        ip_list_max_size: SETTINGS.bootstrap.ip_list_max_size,
-        rate_limit: SETTINGS.bootstrap.rate_limit,
-        max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
-        randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
-        thread_count: THREAD_COUNT,
-        periods_per_cycle: PERIODS_PER_CYCLE,
-        endorsement_count: ENDORSEMENT_COUNT,
-        max_advertise_length: MAX_ADVERTISE_LENGTH,
-        max_bootstrap_blocks_length: MAX_BOOTSTRAP_BLOCKS,
-        max_bootstrap_error_length: MAX_BOOTSTRAP_ERROR_LENGTH,
-        max_new_elements: MAX_BOOTSTRAPPED_NEW_ELEMENTS,
-        max_async_pool_changes: MAX_BOOTSTRAP_ASYNC_POOL_CHANGES,
-        max_async_pool_length: MAX_ASYNC_POOL_LENGTH,
-        max_async_message_data: MAX_ASYNC_MESSAGE_DATA,
-        max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
-        max_datastore_entry_count: MAX_DATASTORE_ENTRY_COUNT,
-        max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
-        max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
-        max_ledger_changes_count: MAX_LEDGER_CHANGES_COUNT,
-        max_parameters_size: MAX_PARAMETERS_SIZE,
-        max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
-        max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
-        max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
-        max_changes_slot_count: SETTINGS.ledger.final_history_length as u64,
-        max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
-        max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
-        max_credits_length: MAX_DEFERRED_CREDITS_LENGTH,
-        max_executed_ops_length: MAX_EXECUTED_OPS_LENGTH,
-        max_ops_changes_length: MAX_EXECUTED_OPS_CHANGES_LENGTH,
-        consensus_bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
-        max_consensus_block_ids: MAX_CONSENSUS_BLOCKS_IDS,
-        mip_store_stats_block_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
-        max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
-        max_denunciation_changes_length: MAX_DENUNCIATION_CHANGES_LENGTH,
-    };
+    let bootstrap_config = get_bootstrap_config(args.keep_ledger);
 
-    let bootstrap_state = match get_state(
-        &bootstrap_config,
-        final_state.clone(),
-        DefaultConnector,
-        *VERSION,
-        *GENESIS_TIMESTAMP,
-        *END_TIMESTAMP,
-        args.restart_from_snapshot_at_period,
-        sig_int_toggled.clone(),
-        massa_metrics.clone(),
-    ) {
+    let bootstrap_state = match import_snapshot_path {
+        Some((path, state_hash)) => get_state_from_snapshot(
+            &bootstrap_config,
+            final_state.clone(),
+            path,
+            *VERSION,
+            state_hash,
+        ),
+        None => get_state(
+            &bootstrap_config,
+            final_state.clone(),
+            DefaultConnector,
+            *VERSION,
+            *GENESIS_TIMESTAMP,
+            *END_TIMESTAMP,
+            args.restart_from_snapshot_at_period,
+            sig_int_toggled.clone(),
+            massa_metrics.clone(),
+        ),
+    };
+    let bootstrap_state = match bootstrap_state {
         Ok(vals) => vals,
         Err(BootstrapError::Interupted(msg)) => {
             info!("{}", msg);
@@ -467,6 +456,25 @@ async fn launch(
     // launch execution module
     let execution_config = ExecutionConfig {
         max_final_events: SETTINGS.execution.max_final_events,
+        event_store_path: SETTINGS
+            .execution
+            .persistent_event_store
+            .then(|| SETTINGS.execution.event_store_path.clone()),
+        event_store_retention_periods: SETTINGS.execution.event_store_retention_periods,
+        address_history_path: SETTINGS
+            .execution
+            .address_history
+            .then(|| SETTINGS.execution.address_history_path.clone()),
+        output_journal_path: SETTINGS
+            .execution
+            .output_journal
+            .then(|| SETTINGS.execution.output_journal_path.clone()),
+        output_journal_retention_periods: SETTINGS.execution.output_journal_retention_periods,
+        historical_ledger_periods: SETTINGS.execution.historical_ledger_periods,
+        max_final_execution_traces: SETTINGS
+            .execution
+            .execution_traces
+            .then_some(SETTINGS.execution.max_final_execution_traces),
         readonly_queue_length: SETTINGS.execution.readonly_queue_length,
         cursor_delay: SETTINGS.execution.cursor_delay,
         max_async_gas: MAX_ASYNC_GAS,
@@ -535,6 +543,10 @@ async fn launch(
         max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
         max_operation_pool_size: SETTINGS.pool.max_operation_pool_size,
         max_operation_pool_excess_items: SETTINGS.pool.max_operation_pool_excess_items,
+        max_operations_per_sender: SETTINGS.pool.max_operations_per_sender,
+        min_operation_fee_per_byte: SETTINGS.pool.min_operation_fee_per_byte,
+        min_operation_fee_per_gas: SETTINGS.pool.min_operation_fee_per_gas,
+        replacement_fee_increase_percent: SETTINGS.pool.replacement_fee_increase_percent,
         operation_pool_refresh_interval: SETTINGS.pool.operation_pool_refresh_interval,
         operation_max_future_start_delay: SETTINGS.pool.operation_max_future_start_delay,
         max_endorsements_pool_size_per_thread: SETTINGS.pool.max_endorsements_pool_size_per_thread,
@@ -558,6 +570,10 @@ async fn launch(
         endorsement_sender: broadcast::channel(pool_config.broadcast_endorsements_channel_capacity)
             .0,
         operation_sender: broadcast::channel(pool_config.broadcast_operations_channel_capacity).0,
+        operation_rejection_sender: broadcast::channel(
+            pool_config.broadcast_operations_channel_capacity,
+        )
+        .0,
         selector: selector_controller.clone(),
         execution_controller: execution_controller.clone(),
     };
@@ -578,6 +594,7 @@ async fn launch(
         max_known_blocks_size: SETTINGS.protocol.max_known_blocks_size,
         max_node_known_blocks_size: SETTINGS.protocol.max_node_known_blocks_size,
         max_block_propagation_time: SETTINGS.protocol.max_block_propagation_time,
+        compact_block_relay: SETTINGS.protocol.compact_block_relay,
         max_node_wanted_blocks_size: SETTINGS.protocol.max_node_wanted_blocks_size,
         max_known_ops_size: SETTINGS.protocol.max_known_ops_size,
         max_node_known_ops_size: SETTINGS.protocol.max_node_known_ops_size,
@@ -609,6 +626,8 @@ async fn launch(
         max_endorsements_per_message: MAX_ENDORSEMENTS_PER_MESSAGE as u64,
         max_denunciations_in_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
         initial_peers: SETTINGS.protocol.initial_peers_file.clone(),
+        peers_file: SETTINGS.protocol.peers_file.clone(),
+        peers_file_dump_interval: SETTINGS.protocol.peers_file_dump_interval,
         listeners,
         keypair_file: SETTINGS.protocol.keypair_file.clone(),
         max_blocks_kept_for_propagation: SETTINGS.protocol.max_blocks_kept_for_propagation,
@@ -647,6 +666,9 @@ async fn launch(
             as u128,
         try_connection_timer: SETTINGS.protocol.try_connection_timer,
         unban_everyone_timer: SETTINGS.protocol.unban_everyone_timer,
+        peer_score_disconnect_threshold: SETTINGS.protocol.peer_score_disconnect_threshold,
+        peer_score_ban_threshold: SETTINGS.protocol.peer_score_ban_threshold,
+        peer_score_ban_duration: SETTINGS.protocol.peer_score_ban_duration,
         max_in_connections: SETTINGS.protocol.max_in_connections,
         timeout_connection: SETTINGS.protocol.timeout_connection,
         message_timeout: SETTINGS.protocol.message_timeout,
@@ -700,6 +722,32 @@ async fn launch(
             .force_keep_final_periods_without_ops,
     };
 
+    // open the archive of final blocks, operations and endorsements (archive nodes only)
+    let archive = SETTINGS.archive.enabled.then(|| {
+        Arc::new(Archive::new(ArchiveConfig {
+            path: SETTINGS.archive.path.clone(),
+            thread_count: THREAD_COUNT,
+            endorsement_count: ENDORSEMENT_COUNT,
+            max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
+            max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+            max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+            max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
+            max_parameter_size: MAX_PARAMETERS_SIZE,
+            max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+            max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
+            max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        }))
+    });
+    // the final blocks are written to the archive by a dedicated worker
+    let (archive_writer, archive_manager) = match &archive {
+        Some(archive) => {
+            let (archive_writer, archive_manager) =
+                start_archive_worker(archive.clone(), CHANNEL_SIZE);
+            (Some(archive_writer), Some(archive_manager))
+        }
+        None => (None, None),
+    };
+
     let (consensus_event_sender, consensus_event_receiver) =
         MassaChannel::new("consensus_event".to_string(), Some(CHANNEL_SIZE));
     let consensus_channels = ConsensusChannels {
@@ -717,6 +765,8 @@ async fn launch(
             consensus_config.broadcast_filled_blocks_channel_capacity,
         )
         .0,
+        archive: archive.clone(),
+        archive_writer,
     };
 
     let (consensus_controller, consensus_manager) = start_consensus_worker(
@@ -763,11 +813,28 @@ async fn launch(
         protocol: protocol_controller.clone(),
         storage: shared_storage.clone(),
     };
+    let signer: Arc<dyn Signer> = match &SETTINGS.factory.remote_signer_socket_path {
+        #[cfg(unix)]
+        Some(socket_path) => {
+            info!(
+                "staking keys are held by the external signer at {}",
+                socket_path.display()
+            );
+            Arc::new(massa_wallet::UnixSocketSigner::new(
+                socket_path.clone(),
+                SETTINGS.factory.remote_signer_timeout.to_duration(),
+            ))
+        }
+        #[cfg(not(unix))]
+        Some(_) => panic!("external signers are only supported on Unix systems"),
+        None => Arc::new(WalletSigner::new(node_wallet.clone())),
+    };
     let factory_manager = start_factory(
         factory_config,
-        node_wallet.clone(),
+        signer,
         factory_channels,
         mip_store.clone(),
+        slashing_protection,
     );
 
     let bootstrap_manager = bootstrap_config.listen_addr.map(|addr| {
@@ -807,6 +874,7 @@ async fn launch(
         max_connections: SETTINGS.api.max_connections,
         max_subscriptions_per_connection: SETTINGS.api.max_subscriptions_per_connection,
         max_log_length: SETTINGS.api.max_log_length,
+        max_events_per_request: SETTINGS.api.max_events_per_request,
         allow_hosts: SETTINGS.api.allow_hosts.clone(),
         batch_request_limit: SETTINGS.api.batch_request_limit,
         ping_interval: SETTINGS.api.ping_interval,
@@ -831,7 +899,9 @@ async fn launch(
         consensus_controller.clone(),
         consensus_channels.clone(),
         execution_controller.clone(),
+        execution_channels.clone(),
         pool_channels.clone(),
+        shared_storage.clone_without_refs(),
         api_config.clone(),
         *VERSION,
     );
@@ -972,6 +1042,7 @@ async fn launch(
         node_id,
         shared_storage.clone(),
         mip_store.clone(),
+        archive,
     );
     let api_public_handle = api_public
         .serve(&SETTINGS.api.bind_public, &api_config)
@@ -1034,6 +1105,7 @@ async fn launch(
         pool_manager,
         protocol_manager,
         factory_manager,
+        archive_manager,
         api_private_handle,
         api_public_handle,
         api_handle,
@@ -1041,9 +1113,71 @@ async fn launch(
         grpc_public_handle,
         metrics_stopper,
         massa_survey_stopper,
+        consensus_controller,
+        final_state,
     )
 }
 
+// Get the configuration of the bootstrap client and server
+fn get_bootstrap_config(keep_ledger: bool) -> BootstrapConfig {
+    BootstrapConfig {
+        bootstrap_list: SETTINGS.bootstrap.bootstrap_list.clone(),
+        bootstrap_protocol: SETTINGS.bootstrap.bootstrap_protocol,
+        bootstrap_whitelist_path: SETTINGS.bootstrap.bootstrap_whitelist_path.clone(),
+        bootstrap_blacklist_path: SETTINGS.bootstrap.bootstrap_blacklist_path.clone(),
+        bootstrap_cursor_path: SETTINGS.bootstrap.bootstrap_cursor_path.clone(),
+        listen_addr: SETTINGS.bootstrap.bind,
+        connect_timeout: SETTINGS.bootstrap.connect_timeout,
+        bootstrap_timeout: SETTINGS.bootstrap.bootstrap_timeout,
+        read_timeout: SETTINGS.bootstrap.read_timeout,
+        write_timeout: SETTINGS.bootstrap.write_timeout,
+        read_error_timeout: SETTINGS.bootstrap.read_error_timeout,
+        write_error_timeout: SETTINGS.bootstrap.write_error_timeout,
+        retry_delay: SETTINGS.bootstrap.retry_delay,
+        max_ping: SETTINGS.bootstrap.max_ping,
+        max_clock_delta: SETTINGS.bootstrap.max_clock_delta,
+        cache_duration: SETTINGS.bootstrap.cache_duration,
+        keep_ledger,
+        max_listeners_per_peer: MAX_LISTENERS_PER_PEER as u32,
+        max_simultaneous_bootstraps: SETTINGS.bootstrap.max_simultaneous_bootstraps,
+        per_ip_min_interval: SETTINGS.bootstrap.per_ip_min_interval,
+        ip_list_max_size: SETTINGS.bootstrap.ip_list_max_size,
+        rate_limit: SETTINGS.bootstrap.rate_limit,
+        max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
+        randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
+        thread_count: THREAD_COUNT,
+        periods_per_cycle: PERIODS_PER_CYCLE,
+        endorsement_count: ENDORSEMENT_COUNT,
+        max_advertise_length: MAX_ADVERTISE_LENGTH,
+        max_bootstrap_blocks_length: MAX_BOOTSTRAP_BLOCKS,
+        max_bootstrap_error_length: MAX_BOOTSTRAP_ERROR_LENGTH,
+        max_new_elements: MAX_BOOTSTRAPPED_NEW_ELEMENTS,
+        max_async_pool_changes: MAX_BOOTSTRAP_ASYNC_POOL_CHANGES,
+        max_async_pool_length: MAX_ASYNC_POOL_LENGTH,
+        max_async_message_data: MAX_ASYNC_MESSAGE_DATA,
+        max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
+        max_datastore_entry_count: MAX_DATASTORE_ENTRY_COUNT,
+        max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+        max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
+        max_ledger_changes_count: MAX_LEDGER_CHANGES_COUNT,
+        max_parameters_size: MAX_PARAMETERS_SIZE,
+        max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+        max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
+        max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        max_changes_slot_count: SETTINGS.ledger.final_history_length as u64,
+        max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
+        max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
+        max_credits_length: MAX_DEFERRED_CREDITS_LENGTH,
+        max_executed_ops_length: MAX_EXECUTED_OPS_LENGTH,
+        max_ops_changes_length: MAX_EXECUTED_OPS_CHANGES_LENGTH,
+        consensus_bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
+        max_consensus_block_ids: MAX_CONSENSUS_BLOCKS_IDS,
+        mip_store_stats_block_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+        max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+        max_denunciation_changes_length: MAX_DENUNCIATION_CHANGES_LENGTH,
+    }
+}
+
 // Get the configuration of the gRPC server
 fn configure_grpc(
     name: ServiceName,
@@ -1105,6 +1239,7 @@ fn configure_grpc(
         max_endorsement_ids_per_request: settings.max_endorsement_ids_per_request,
         max_operation_ids_per_request: settings.max_operation_ids_per_request,
         max_filters_per_request: settings.max_filters_per_request,
+        max_events_per_request: settings.max_events_per_request,
         certificate_authority_root_path: settings.certificate_authority_root_path.clone(),
         server_certificate_path: settings.server_certificate_path.clone(),
         server_private_key_path: settings.server_private_key_path.clone(),
@@ -1124,6 +1259,7 @@ struct Managers {
     pool_manager: Box<dyn PoolManager>,
     protocol_manager: Box<dyn ProtocolManager>,
     factory_manager: Box<dyn FactoryManager>,
+    archive_manager: Option<ArchiveManager>,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -1137,6 +1273,7 @@ async fn stop(
         mut pool_manager,
         mut protocol_manager,
         mut factory_manager,
+        archive_manager,
     }: Managers,
     api_private_handle: StopHandle,
     api_public_handle: StopHandle,
@@ -1145,6 +1282,8 @@ async fn stop(
     grpc_public_handle: Option<massa_grpc::server::StopHandle>,
     mut metrics_stopper: MetricsStopper,
     mut massa_survey_stopper: MassaSurveyStopper,
+    consensus_controller: Box<dyn ConsensusController>,
+    final_state: Arc<RwLock<FinalState>>,
 ) {
     // stop bootstrap
     if let Some(bootstrap_manager) = bootstrap_manager {
@@ -1194,12 +1333,26 @@ async fn stop(
     // stop consensus
     consensus_manager.stop();
 
+    // stop the archive worker once the final blocks sent by consensus are archived
+    if let Some(mut archive_manager) = archive_manager {
+        archive_manager.stop();
+    }
+
     // stop pool
     pool_manager.stop();
 
     // stop execution controller
     execution_manager.stop();
 
+    // save the final blocks attached to the final state, to be able to export a snapshot of the stopped node
+    if let Err(err) = save_final_graph(
+        &SETTINGS.ledger.final_graph_path,
+        &final_state.read(),
+        consensus_controller.as_ref(),
+    ) {
+        warn!("could not save the final consensus graph: {}", err);
+    }
+
     // stop selector controller
     selector_manager.stop();
 
@@ -1222,6 +1375,17 @@ struct Args {
     #[structopt(long = "restart-from-snapshot-at-period")]
     restart_from_snapshot_at_period: Option<u64>,
 
+    /// Import the records of a slashing protection interchange file before starting the node
+    #[structopt(long = "import-slashing-protection", parse(from_os_str))]
+    import_slashing_protection: Option<PathBuf>,
+
+    /// Export the slashing protection records to an interchange file, then exit
+    #[structopt(long = "export-slashing-protection", parse(from_os_str))]
+    export_slashing_protection: Option<PathBuf>,
+
+    #[structopt(subcommand)]
+    command: Option<Command>,
+
     #[cfg(feature = "op_spammer")]
     /// number of operations
     #[structopt(
@@ -1244,6 +1408,25 @@ struct Args {
     dl_interval: u64,
 }
 
+#[derive(StructOpt)]
+enum Command {
+    /// Export a snapshot of the final state of the stopped node to a file, then exit
+    ExportSnapshot {
+        /// Path of the snapshot file to create
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+    },
+    /// Start the node from the final state of a snapshot file instead of bootstrapping
+    ImportSnapshot {
+        /// Path of the snapshot file
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+        /// Expected hash of the final state, obtained from a trusted node
+        #[structopt(long = "state-hash")]
+        state_hash: Hash,
+    },
+}
+
 /// Load wallet, asking for passwords if necessary
 fn load_wallet(password: Option<String>, path: &Path) -> anyhow::Result<Arc<RwLock<Wallet>>> {
     let password = if path.is_file() {
@@ -1316,12 +1499,58 @@ async fn run(args: Args) -> anyhow::Result<()> {
 
     info!("Node version : {}", *VERSION);
 
+    // snapshots are exported from the disk ledger of the stopped node, without starting it
+    match &cur_args.command {
+        Some(Command::ExportSnapshot { path }) => {
+            let db = MassaDB::new(MassaDBConfig {
+                path: SETTINGS.ledger.disk_ledger_path.clone(),
+                max_history_length: SETTINGS.ledger.final_history_length,
+                max_new_elements: MAX_BOOTSTRAPPED_NEW_ELEMENTS as usize,
+                thread_count: THREAD_COUNT,
+                enable_state_tree: SETTINGS.ledger.enable_state_proofs,
+            });
+            let final_graph = load_final_graph(
+                &SETTINGS.ledger.final_graph_path,
+                &get_bootstrap_config(true),
+            )?;
+            export_snapshot(&db, final_graph, path, *VERSION)?;
+            info!(
+                "exported a snapshot of the final state to {}",
+                path.display()
+            );
+            return Ok(());
+        }
+        Some(Command::ImportSnapshot { .. })
+            if cur_args.restart_from_snapshot_at_period.is_some() =>
+        {
+            anyhow::bail!("cannot import a snapshot when restarting from a snapshot at a period");
+        }
+        _ => {}
+    }
+
     // load or create wallet, asking for password if necessary
     let node_wallet = load_wallet(
         cur_args.password.clone(),
         &SETTINGS.factory.staking_wallet_path,
     )?;
 
+    // open the record of the slots already signed by the staking addresses
+    let slashing_protection = Arc::new(SlashingProtectionDB::new(
+        SETTINGS.factory.slashing_protection_path.clone(),
+    ));
+    if let Some(path) = &cur_args.import_slashing_protection {
+        slashing_protection.import(path)?;
+        info!(
+            "imported slashing protection records from {}",
+            path.display()
+        );
+    }
+    if let Some(path) = &cur_args.export_slashing_protection {
+        slashing_protection.export(path)?;
+        info!("exported slashing protection records to {}", path.display());
+        return Ok(());
+    }
+
     // interrupt signal listener
     let sig_int_toggled = Arc::new((Mutex::new(false), Condvar::new()));
 
@@ -1348,6 +1577,7 @@ async fn run(args: Args) -> anyhow::Result<()> {
             pool_manager,
             protocol_manager,
             factory_manager,
+            archive_manager,
             api_private_handle,
             api_public_handle,
             api_handle,
@@ -1355,7 +1585,15 @@ async fn run(args: Args) -> anyhow::Result<()> {
             grpc_public_handle,
             metrics_stopper,
             massa_survey_stopper,
-        ) = launch(&cur_args, node_wallet.clone(), Arc::clone(&sig_int_toggled)).await;
+            consensus_controller,
+            final_state,
+        ) = launch(
+            &cur_args,
+            node_wallet.clone(),
+            slashing_protection.clone(),
+            Arc::clone(&sig_int_toggled),
+        )
+        .await;
 
         // loop over messages
         let restart = loop {
@@ -1414,6 +1652,7 @@ async fn run(args: Args) -> anyhow::Result<()> {
                 pool_manager,
                 protocol_manager,
                 factory_manager,
+                archive_manager,
             },
             api_private_handle,
             api_public_handle,
@@ -1422,6 +1661,8 @@ async fn run(args: Args) -> anyhow::Result<()> {
             grpc_public_handle,
             metrics_stopper,
             massa_survey_stopper,
+            consensus_controller,
+            final_state,
         )
         .await;
 
@@ -1430,6 +1671,7 @@ async fn run(args: Args) -> anyhow::Result<()> {
         }
         // If we restart because of a desync, then we do not want to restart from a snapshot
         cur_args.restart_from_snapshot_at_period = None;
+        cur_args.command = None;
     }
     Ok(())
 }
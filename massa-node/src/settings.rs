@@ -4,7 +4,7 @@
 use std::{collections::HashMap, path::PathBuf};
 
 use massa_bootstrap::IpType;
-use massa_models::{config::build_massa_settings, node::NodeId};
+use massa_models::{amount::Amount, config::build_massa_settings, node::NodeId};
 use massa_protocol_exports::PeerCategoryInfo;
 use massa_time::MassaTime;
 use serde::Deserialize;
@@ -22,6 +22,22 @@ pub struct LoggingSettings {
 #[derive(Clone, Debug, Deserialize)]
 pub struct ExecutionSettings {
     pub max_final_events: usize,
+    /// whether final events are stored on disk instead of in memory
+    pub persistent_event_store: bool,
+    pub event_store_path: PathBuf,
+    pub event_store_retention_periods: u64,
+    /// whether the history of final executed operations is recorded per address
+    pub address_history: bool,
+    pub address_history_path: PathBuf,
+    /// whether the final execution outputs are journaled on disk to be replayed to the gRPC stream consumers
+    pub output_journal: bool,
+    pub output_journal_path: PathBuf,
+    pub output_journal_retention_periods: u64,
+    /// number of periods during which read-only executions and queries can target a past final slot
+    pub historical_ledger_periods: u64,
+    /// whether the executions of operations and async messages are traced (costly)
+    pub execution_traces: bool,
+    pub max_final_execution_traces: usize,
     pub readonly_queue_length: usize,
     pub cursor_delay: MassaTime,
     pub stats_time_window_duration: MassaTime,
@@ -45,8 +61,10 @@ pub struct SelectionSettings {
 pub struct LedgerSettings {
     pub initial_ledger_path: PathBuf,
     pub disk_ledger_path: PathBuf,
+    pub final_graph_path: PathBuf,
     pub final_history_length: usize,
     pub initial_deferred_credits_path: Option<PathBuf>,
+    pub enable_state_proofs: bool,
 }
 
 /// Bootstrap configuration.
@@ -56,6 +74,7 @@ pub struct BootstrapSettings {
     pub bootstrap_protocol: IpType,
     pub bootstrap_whitelist_path: PathBuf,
     pub bootstrap_blacklist_path: PathBuf,
+    pub bootstrap_cursor_path: PathBuf,
     pub bind: Option<SocketAddr>,
     pub connect_timeout: MassaTime,
     pub read_timeout: MassaTime,
@@ -83,6 +102,12 @@ pub struct FactorySettings {
     pub staking_wallet_path: PathBuf,
     /// stop the production in case we are not connected to anyone
     pub stop_production_when_zero_connections: bool,
+    /// path of the record of the slots signed by the staking addresses
+    pub slashing_protection_path: PathBuf,
+    /// Unix socket of an external signer holding the staking keys, the staking wallet is used if not set
+    pub remote_signer_socket_path: Option<PathBuf>,
+    /// maximum duration of a request to the external signer
+    pub remote_signer_timeout: MassaTime,
 }
 
 /// Pool configuration, read from a file configuration
@@ -90,6 +115,14 @@ pub struct FactorySettings {
 pub struct PoolSettings {
     pub max_operation_pool_size: usize,
     pub max_operation_pool_excess_items: usize,
+    /// max number of operations of a sender in the pool
+    pub max_operations_per_sender: usize,
+    /// min fee per byte of serialized operation for an operation to be admitted in the pool
+    pub min_operation_fee_per_byte: Amount,
+    /// min fee per unit of max gas for an operation to be admitted in the pool
+    pub min_operation_fee_per_gas: Amount,
+    /// min fee increase (in percent) to replace an operation of a sender that reached its limit
+    pub replacement_fee_increase_percent: u64,
     pub operation_max_future_start_delay: MassaTime,
     pub operation_pool_refresh_interval: MassaTime,
     pub max_endorsements_pool_size_per_thread: usize,
@@ -114,6 +147,7 @@ pub struct APISettings {
     pub max_connections: u32,
     pub max_subscriptions_per_connection: u32,
     pub max_log_length: u32,
+    pub max_events_per_request: u32,
     pub allow_hosts: Vec<String>,
     pub batch_request_limit: u32,
     pub ping_interval: MassaTime,
@@ -139,6 +173,7 @@ pub struct Settings {
     pub grpc: GrpcApiSettings,
     pub metrics: MetricsSettings,
     pub versioning: VersioningSettings,
+    pub archive: ArchiveSettings,
 }
 
 /// Consensus configuration
@@ -195,6 +230,8 @@ pub struct ProtocolSettings {
     pub max_block_propagation_time: MassaTime,
     /// Block propagation tick interval, useful for propagating blocks quickly to newly connected peers.
     pub block_propagation_tick: MassaTime,
+    /// Announce blocks with the operation ID prefixes of their operations to the peers that support it, so that they can rebuild them from the operations they already know
+    pub compact_block_relay: bool,
     /// max known blocks our node keeps in its knowledge cache
     pub max_known_blocks_size: usize,
     /// max cache size for which blocks a foreign node knows about
@@ -235,6 +272,10 @@ pub struct ProtocolSettings {
     pub max_endorsements_propagation_time: MassaTime,
     /// Path for initial peers
     pub initial_peers_file: PathBuf,
+    /// Path of the file where the peer database is saved
+    pub peers_file: PathBuf,
+    /// Interval at which the peer database is saved
+    pub peers_file_dump_interval: MassaTime,
     /// Keypair
     pub keypair_file: PathBuf,
     /// Ip we are bind to listen to
@@ -253,6 +294,12 @@ pub struct ProtocolSettings {
     pub try_connection_timer_same_peer: MassaTime,
     /// periodically unban every peer
     pub unban_everyone_timer: MassaTime,
+    /// reputation score at or below which a misbehaving peer is disconnected
+    pub peer_score_disconnect_threshold: i64,
+    /// reputation score at or below which a misbehaving peer is temporarily banned
+    pub peer_score_ban_threshold: i64,
+    /// duration of the bans triggered by the reputation score
+    pub peer_score_ban_duration: MassaTime,
     /// Timeout connection
     pub timeout_connection: MassaTime,
     /// Message timeout
@@ -345,6 +392,8 @@ pub struct GrpcSettings {
     pub max_datastore_entries_per_request: u64,
     /// max number of filters that can be included in a single request
     pub max_filters_per_request: u32,
+    /// max number of smart contract execution events returned by a single request
+    pub max_events_per_request: u32,
     /// certificate authority root path
     pub certificate_authority_root_path: PathBuf,
     /// server certificate path
@@ -374,6 +423,14 @@ pub struct VersioningSettings {
     pub(crate) mip_stats_warn_announced_version: u32,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct ArchiveSettings {
+    /// persist every final block, operation and endorsement to disk and serve them from the APIs
+    pub enabled: bool,
+    /// path to the archive database
+    pub path: PathBuf,
+}
+
 #[cfg(test)]
 #[test]
 fn test_load_node_config() {
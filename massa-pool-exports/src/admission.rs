@@ -0,0 +1,40 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// Reason why the operation pool refused to admit an operation.
+///
+/// Serialized as a stable snake case code, also used by `Display`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationRejectionReason {
+    /// the fee is lower than `min_operation_fee_per_byte` times the size of the operation
+    FeePerByteTooLow,
+    /// the fee is lower than `min_operation_fee_per_gas` times the max gas of the operation
+    FeePerGasTooLow,
+    /// the sender already has `max_operations_per_sender` operations in the pool,
+    /// and none of them has the same validity range
+    SenderLimitReached,
+    /// the sender already has `max_operations_per_sender` operations in the pool,
+    /// and the fee is not high enough to replace one of them with the same validity range
+    ReplacementFeeTooLow,
+}
+
+impl OperationRejectionReason {
+    /// Get the code of the reason
+    pub fn code(&self) -> &'static str {
+        match self {
+            OperationRejectionReason::FeePerByteTooLow => "fee_per_byte_too_low",
+            OperationRejectionReason::FeePerGasTooLow => "fee_per_gas_too_low",
+            OperationRejectionReason::SenderLimitReached => "sender_limit_reached",
+            OperationRejectionReason::ReplacementFeeTooLow => "replacement_fee_too_low",
+        }
+    }
+}
+
+impl Display for OperationRejectionReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
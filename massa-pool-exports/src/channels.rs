@@ -1,7 +1,12 @@
 use massa_execution_exports::ExecutionController;
-use massa_models::{endorsement::SecureShareEndorsement, operation::SecureShareOperation};
+use massa_models::{
+    endorsement::SecureShareEndorsement,
+    operation::{OperationId, SecureShareOperation},
+};
 use massa_pos_exports::SelectorController;
 
+use crate::OperationRejectionReason;
+
 /// channels used by the pool worker
 #[derive(Clone)]
 pub struct PoolChannels {
@@ -11,6 +16,9 @@ pub struct PoolChannels {
     pub endorsement_sender: tokio::sync::broadcast::Sender<SecureShareEndorsement>,
     /// Broadcast channel for new operations
     pub operation_sender: tokio::sync::broadcast::Sender<SecureShareOperation>,
+    /// Broadcast channel for the operations rejected by the admission policy, with the reason of their rejection
+    pub operation_rejection_sender:
+        tokio::sync::broadcast::Sender<(OperationId, OperationRejectionReason)>,
     /// Selector to get draws
     pub selector: Box<dyn SelectorController>,
 }
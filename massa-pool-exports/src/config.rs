@@ -27,6 +27,15 @@ pub struct PoolConfig {
     pub max_operation_pool_size: usize,
     /// max excess on pool size (in-between refreshes)
     pub max_operation_pool_excess_items: usize,
+    /// max number of operations of a sender in the pool
+    pub max_operations_per_sender: usize,
+    /// min fee per byte of serialized operation for an operation to be admitted in the pool
+    pub min_operation_fee_per_byte: Amount,
+    /// min fee per unit of max gas for an operation to be admitted in the pool
+    pub min_operation_fee_per_gas: Amount,
+    /// min fee increase (in percent) for an operation to replace an operation of the same sender and validity range
+    /// when the sender has reached `max_operations_per_sender`
+    pub replacement_fee_increase_percent: u64,
     /// max endorsement pool size per thread (in number of endorsements)
     pub max_endorsements_pool_size_per_thread: usize,
     /// max number of endorsements per block
@@ -4,16 +4,26 @@ use massa_models::{
     block_id::BlockId,
     denunciation::{Denunciation, DenunciationPrecursor},
     endorsement::EndorsementId,
-    operation::OperationId,
+    operation::{OperationId, SecureShareOperation},
     slot::Slot,
+    stats::FeeStats,
 };
 use massa_storage::Storage;
 
+use crate::OperationRejectionReason;
+
 /// Trait defining a pool controller
 pub trait PoolController: Send + Sync {
     /// Asynchronously add operations to pool. Simply print a warning on failure.
     fn add_operations(&mut self, ops: Storage);
 
+    /// Check operations against the admission policy of the pool, without adding them.
+    /// Returns one result per operation, the operations being checked together in order.
+    fn check_operations_admission(
+        &self,
+        ops: &[SecureShareOperation],
+    ) -> Vec<Result<(), OperationRejectionReason>>;
+
     /// Asynchronously add endorsements to pool. Simply print a warning on failure.
     fn add_endorsements(&mut self, endorsements: Storage);
 
@@ -23,6 +33,9 @@ pub trait PoolController: Send + Sync {
     /// Asynchronously notify of new consensus final periods. Simply print a warning on failure.
     fn notify_final_cs_periods(&mut self, final_cs_periods: &[u64]);
 
+    /// Asynchronously notify of newly final blocks, with their operations. Simply print a warning on failure.
+    fn notify_final_blocks(&mut self, final_blocks: Storage);
+
     /// Get operations for block creation.
     fn get_block_operations(&self, slot: &Slot) -> (Vec<OperationId>, Storage);
 
@@ -61,6 +74,10 @@ pub trait PoolController: Send + Sync {
 
     /// Get final cs periods (updated regularly from consensus)
     fn get_final_cs_periods(&self) -> &Vec<u64>;
+
+    /// Get the fee statistics of the operation pool,
+    /// with the fees recommended for an operation to be included within `target_delay` periods.
+    fn get_fee_stats(&self, target_delay: u64) -> FeeStats;
 }
 
 /// Allow cloning `Box<dyn PoolController>`
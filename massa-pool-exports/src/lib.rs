@@ -5,10 +5,12 @@
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
 
+mod admission;
 mod channels;
 mod config;
 mod controller_traits;
 
+pub use admission::OperationRejectionReason;
 pub use channels::PoolChannels;
 pub use config::PoolConfig;
 pub use controller_traits::{PoolController, PoolManager};
@@ -1,5 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_models::amount::Amount;
 use massa_models::config::{
     DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT, MAX_BLOCK_SIZE,
     MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_GAS_PER_BLOCK, MAX_OPERATIONS_PER_BLOCK,
@@ -19,6 +20,10 @@ impl Default for PoolConfig {
             max_block_size: MAX_BLOCK_SIZE,
             max_operation_pool_size: 32000,
             max_operation_pool_excess_items: 10000,
+            max_operations_per_sender: 10000,
+            min_operation_fee_per_byte: Amount::zero(),
+            min_operation_fee_per_gas: Amount::zero(),
+            replacement_fee_increase_percent: 10,
             max_endorsements_pool_size_per_thread: 1000,
             max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
             max_block_endorsement_count: ENDORSEMENT_COUNT,
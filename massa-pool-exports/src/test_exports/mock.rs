@@ -11,13 +11,17 @@ use std::sync::{
 
 use massa_models::config::THREAD_COUNT;
 use massa_models::denunciation::{Denunciation, DenunciationPrecursor};
+use massa_models::stats::FeeStats;
 use massa_models::{
-    block_id::BlockId, endorsement::EndorsementId, operation::OperationId, slot::Slot,
+    block_id::BlockId,
+    endorsement::EndorsementId,
+    operation::{OperationId, SecureShareOperation},
+    slot::Slot,
 };
 use massa_storage::Storage;
 use massa_time::MassaTime;
 
-use crate::PoolController;
+use crate::{OperationRejectionReason, PoolController};
 
 /// Test tool to mock pool controller responses
 pub struct PoolEventReceiver(pub Receiver<MockPoolControllerMessage>);
@@ -100,11 +104,23 @@ pub enum MockPoolControllerMessage {
         /// Response channel
         response_tx: mpsc::Sender<(usize, usize)>,
     },
+    /// Get fee statistics
+    GetFeeStats {
+        /// Target inclusion delay (in periods)
+        target_delay: u64,
+        /// Response channel
+        response_tx: mpsc::Sender<FeeStats>,
+    },
     /// Notify that periods became final
     NotifyFinalCsPeriods {
         /// Periods that are final
         periods: Vec<u64>,
     },
+    /// Notify that blocks became final
+    NotifyFinalBlocks {
+        /// Storage that contains the final blocks and their operations
+        final_blocks: Storage,
+    },
     /// No need to specify the response
     Any,
 }
@@ -170,6 +186,13 @@ impl PoolController for MockPoolController {
             .unwrap();
     }
 
+    fn check_operations_admission(
+        &self,
+        ops: &[SecureShareOperation],
+    ) -> Vec<Result<(), OperationRejectionReason>> {
+        vec![Ok(()); ops.len()]
+    }
+
     fn get_block_endorsements(
         &self,
         target_block: &BlockId,
@@ -258,6 +281,14 @@ impl PoolController for MockPoolController {
             .unwrap();
     }
 
+    fn notify_final_blocks(&mut self, final_blocks: Storage) {
+        self.q
+            .lock()
+            .unwrap()
+            .send(MockPoolControllerMessage::NotifyFinalBlocks { final_blocks })
+            .unwrap();
+    }
+
     fn clone_box(&self) -> Box<dyn PoolController> {
         Box::new(self.clone())
     }
@@ -266,6 +297,19 @@ impl PoolController for MockPoolController {
         &self.last_final_cs_periods
     }
 
+    fn get_fee_stats(&self, target_delay: u64) -> FeeStats {
+        let (response_tx, response_rx) = mpsc::channel();
+        self.q
+            .lock()
+            .unwrap()
+            .send(MockPoolControllerMessage::GetFeeStats {
+                target_delay,
+                response_tx,
+            })
+            .unwrap();
+        response_rx.recv().unwrap()
+    }
+
     fn add_denunciation_precursor(&self, denunciation_precursor: DenunciationPrecursor) {
         self.q
             .lock()
@@ -3,10 +3,15 @@
 //! Pool controller implementation
 
 use massa_models::{
-    block_id::BlockId, denunciation::Denunciation, denunciation::DenunciationPrecursor,
-    endorsement::EndorsementId, operation::OperationId, slot::Slot,
+    block_id::BlockId,
+    denunciation::Denunciation,
+    denunciation::DenunciationPrecursor,
+    endorsement::EndorsementId,
+    operation::{OperationId, SecureShareOperation},
+    slot::Slot,
+    stats::FeeStats,
 };
-use massa_pool_exports::{PoolConfig, PoolController, PoolManager};
+use massa_pool_exports::{OperationRejectionReason, PoolConfig, PoolController, PoolManager};
 use massa_storage::Storage;
 use parking_lot::RwLock;
 use std::sync::mpsc::TrySendError;
@@ -15,7 +20,7 @@ use tracing::{info, warn};
 
 use crate::{
     denunciation_pool::DenunciationPool, endorsement_pool::EndorsementPool,
-    operation_pool::OperationPool,
+    operation_pool::OperationPool, types::OperationInfo,
 };
 
 /// A generic command to send commands to a pool
@@ -27,6 +32,8 @@ pub enum Command {
     AddDenunciationPrecursor(DenunciationPrecursor),
     /// Notify of new final consensus periods
     NotifyFinalCsPeriods(Vec<u64>),
+    /// Notify of newly final blocks
+    NotifyFinalBlocks(Storage),
     /// Stop the worker
     Stop,
 }
@@ -35,7 +42,7 @@ pub enum Command {
 #[derive(Clone)]
 pub struct PoolControllerImpl {
     /// Config
-    pub(crate) _config: PoolConfig,
+    pub(crate) config: PoolConfig,
     /// Shared reference to the operation pool
     pub(crate) operation_pool: Arc<RwLock<OperationPool>>,
     /// Shared reference to the endorsement pool
@@ -69,6 +76,30 @@ impl PoolController for PoolControllerImpl {
         }
     }
 
+    /// Check operations against the admission policy of the pool, without adding them.
+    fn check_operations_admission(
+        &self,
+        ops: &[SecureShareOperation],
+    ) -> Vec<Result<(), OperationRejectionReason>> {
+        let op_infos: Vec<OperationInfo> = ops
+            .iter()
+            .map(|op| {
+                OperationInfo::from_op(
+                    op,
+                    self.config.operation_validity_periods,
+                    self.config.roll_price,
+                    self.config.thread_count,
+                )
+            })
+            .collect();
+        self.operation_pool
+            .read()
+            .check_admission(&op_infos)
+            .into_iter()
+            .map(|admission| admission.map(|_| ()))
+            .collect()
+    }
+
     /// Asynchronously add endorsements to pool. Simply print a warning on failure.
     fn add_endorsements(&mut self, endorsements: Storage) {
         // Send endorsements to the denunciation pool - so we got unfiltered endorsements
@@ -171,6 +202,26 @@ impl PoolController for PoolControllerImpl {
         }
     }
 
+    /// Asynchronously notify the operation pool of newly final blocks. Simply print a warning on failure.
+    fn notify_final_blocks(&mut self, final_blocks: Storage) {
+        match self
+            .operations_input_sender
+            .try_send(Command::NotifyFinalBlocks(final_blocks))
+        {
+            Err(TrySendError::Disconnected(_)) => {
+                warn!(
+                    "Could not notify operation pool of new final blocks: worker is unreachable."
+                );
+            }
+            Err(TrySendError::Full(_)) => {
+                warn!(
+                    "Could not notify operation pool of new final blocks: worker channel is full."
+                );
+            }
+            Ok(_) => {}
+        }
+    }
+
     /// get operations for block creation
     fn get_block_operations(&self, slot: &Slot) -> (Vec<OperationId>, Storage) {
         self.operation_pool.read().get_block_operations(slot)
@@ -237,6 +288,11 @@ impl PoolController for PoolControllerImpl {
     fn get_final_cs_periods(&self) -> &Vec<u64> {
         &self.last_cs_final_periods
     }
+
+    /// Get the fee statistics of the operation pool
+    fn get_fee_stats(&self, target_delay: u64) -> FeeStats {
+        self.operation_pool.read().get_fee_stats(target_delay)
+    }
 }
 
 /// Implementation of the pool manager.
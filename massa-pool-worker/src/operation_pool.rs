@@ -3,17 +3,26 @@
 use massa_models::{
     address::Address,
     amount::Amount,
+    config::POOL_FEE_STATS_INCLUSION_HISTORY_SIZE,
     operation::OperationId,
     prehash::{CapacityAllocator, PreHashMap, PreHashSet},
     slot::Slot,
+    stats::{FeePercentiles, FeeStats, ThreadFeeStats},
     timeslots::get_latest_block_slot_at_timestamp,
 };
-use massa_pool_exports::{PoolChannels, PoolConfig};
+use massa_pool_exports::{OperationRejectionReason, PoolChannels, PoolConfig};
 use massa_storage::Storage;
 use massa_time::MassaTime;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
-use std::{cmp::max, cmp::Ordering, cmp::PartialOrd, collections::BTreeSet, sync::Arc};
+use std::{
+    cmp::max,
+    cmp::Ordering,
+    cmp::PartialOrd,
+    collections::{BTreeSet, VecDeque},
+    ops::RangeInclusive,
+    sync::Arc,
+};
 use tracing::{debug, trace, warn};
 
 use crate::types::OperationInfo;
@@ -25,12 +34,18 @@ pub struct OperationPool {
     /// operations map
     sorted_ops: Vec<OperationInfo>,
 
+    /// ID, fee and validity range of the operations of `sorted_ops`, by sender
+    sender_ops: PreHashMap<Address, Vec<(OperationId, Amount, RangeInclusive<u64>)>>,
+
     /// storage instance
     pub(crate) storage: Storage,
 
     /// last consensus final periods, per thread
     last_cs_final_periods: Vec<u64>,
 
+    /// fees of the operations recently included in final blocks, per thread, oldest first
+    recent_inclusion_fees: Vec<VecDeque<Amount>>,
+
     /// channels used by the pool worker
     channels: PoolChannels,
 
@@ -51,7 +66,9 @@ impl OperationPool {
                     .max_operation_pool_size
                     .saturating_add(config.max_operation_pool_excess_items),
             ),
+            sender_ops: PreHashMap::default(),
             last_cs_final_periods: vec![0u64; config.thread_count as usize],
+            recent_inclusion_fees: vec![VecDeque::new(); config.thread_count as usize],
             config,
             storage: storage.clone_without_refs(),
             channels,
@@ -124,6 +141,37 @@ impl OperationPool {
             .collect()
     }
 
+    /// Add an operation of `sorted_ops` to the index of the operations by sender
+    fn index_sender_op(&mut self, op_info: &OperationInfo) {
+        self.sender_ops
+            .entry(op_info.creator_address)
+            .or_default()
+            .push((
+                op_info.id,
+                op_info.fee,
+                op_info.validity_period_range.clone(),
+            ));
+    }
+
+    /// Rebuild the index of the operations by sender after `sorted_ops` was filtered
+    fn rebuild_sender_index(&mut self) {
+        self.sender_ops.clear();
+        let sorted_ops = std::mem::take(&mut self.sorted_ops);
+        for op_info in &sorted_ops {
+            self.index_sender_op(op_info);
+        }
+        self.sorted_ops = sorted_ops;
+    }
+
+    /// Record the fee of an operation included in a final block
+    fn record_inclusion_fee(&mut self, thread: u8, fee: Amount) {
+        let fees = &mut self.recent_inclusion_fees[thread as usize];
+        if fees.len() >= POOL_FEE_STATS_INCLUSION_HISTORY_SIZE {
+            fees.pop_front();
+        }
+        fees.push_back(fee);
+    }
+
     /// Get the candidate balances of the addresses sending the ops.
     /// Addresses that don't exist are not returned.
     fn get_sender_balances(&self) -> PreHashMap<Address, Amount> {
@@ -357,6 +405,9 @@ impl OperationPool {
 
         // eliminate container size overflows
         self.truncate_container();
+
+        // index the remaining ops by sender
+        self.rebuild_sender_index();
     }
 
     /// Get the number of stored elements
@@ -369,6 +420,70 @@ impl OperationPool {
         self.storage.get_op_refs().contains(id)
     }
 
+    /// Get the fee statistics of the pool.
+    ///
+    /// The recommended fee of a thread is the fee needed to be placed, among the pending ops sorted by decreasing fee,
+    /// before the ops that would not fit in the blocks of the next `target_delay` periods of the thread.
+    /// This assumes that the block producers include the ops with the highest fees first.
+    /// If all the pending ops fit in those blocks, the lowest fees recently included in final blocks are recommended.
+    pub fn get_fee_stats(&self, target_delay: u64) -> FeeStats {
+        let target_delay = max(target_delay, 1);
+        let mut pending_ops = vec![Vec::new(); self.config.thread_count as usize];
+        for op_info in &self.sorted_ops {
+            pending_ops[op_info.thread as usize].push(op_info);
+        }
+
+        let threads = pending_ops
+            .into_iter()
+            .zip(&self.recent_inclusion_fees)
+            .enumerate()
+            .map(|(thread, (mut thread_ops, inclusion_fees))| {
+                let pending_fees =
+                    FeePercentiles::from_fees(thread_ops.iter().map(|op| op.fee).collect());
+                let recent_inclusion_fees =
+                    FeePercentiles::from_fees(inclusion_fees.iter().copied().collect());
+
+                // fill the blocks of the target delay with the pending ops of highest fee
+                thread_ops.sort_unstable_by(|op1, op2| op2.fee.cmp(&op1.fee));
+                let mut remaining_ops =
+                    u64::from(self.config.max_operations_per_block).saturating_mul(target_delay);
+                let mut remaining_space =
+                    u64::from(self.config.max_block_size).saturating_mul(target_delay);
+                let mut remaining_gas = self.config.max_block_gas.saturating_mul(target_delay);
+                let first_excluded = thread_ops.iter().find(|op| {
+                    if remaining_ops == 0
+                        || op.size as u64 > remaining_space
+                        || op.max_gas > remaining_gas
+                    {
+                        return true;
+                    }
+                    remaining_ops -= 1;
+                    remaining_space -= op.size as u64;
+                    remaining_gas -= op.max_gas;
+                    false
+                });
+                let recommended_fee = match first_excluded {
+                    // outbid the first op that would not be included in time
+                    Some(op) => op.fee.saturating_add(Amount::from_raw(1)),
+                    None => recent_inclusion_fees.map_or(Amount::zero(), |fees| fees.p10),
+                };
+
+                ThreadFeeStats {
+                    thread: thread as u8,
+                    pending_operation_count: thread_ops.len(),
+                    pending_fees,
+                    recent_inclusion_fees,
+                    recommended_fee,
+                }
+            })
+            .collect();
+
+        FeeStats {
+            target_delay,
+            threads,
+        }
+    }
+
     /// notify of new final slot
     pub(crate) fn notify_final_cs_periods(&mut self, final_cs_periods: &[u64]) {
         // update internal final slot counter
@@ -379,32 +494,199 @@ impl OperationPool {
         );
     }
 
+    /// Record the fees of the operations included in newly final blocks,
+    /// whether or not they went through this pool.
+    pub(crate) fn notify_final_blocks(&mut self, final_blocks: Storage) {
+        let mut fees =
+            {
+                let blocks = final_blocks.read_blocks();
+                let ops = final_blocks.read_operations();
+                final_blocks
+                    .get_block_refs()
+                    .iter()
+                    .filter_map(|block_id| blocks.get(block_id))
+                    .flat_map(|block| {
+                        let slot = block.content.header.content.slot;
+                        block.content.operations.iter().filter_map(move |op_id| {
+                            ops.get(op_id).map(|op| (slot, op.content.fee))
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            };
+        // record the oldest inclusions first
+        fees.sort_by_key(|(slot, _fee)| *slot);
+        for (slot, fee) in fees {
+            self.record_inclusion_fee(slot.thread, fee);
+        }
+    }
+
+    /// Apply the admission policy to new operations, checked together in order.
+    /// Returns, for each operation, the ID of the operation it replaces if any, or the reason why it is rejected.
+    pub(crate) fn check_admission(
+        &self,
+        new_ops: &[OperationInfo],
+    ) -> Vec<Result<Option<OperationId>, OperationRejectionReason>> {
+        // fee and validity range of the operations of the senders of the new operations
+        let mut sender_ops: PreHashMap<Address, Vec<(OperationId, Amount, RangeInclusive<u64>)>> =
+            PreHashMap::default();
+        for op_info in new_ops {
+            if let Some(ops) = self.sender_ops.get(&op_info.creator_address) {
+                sender_ops
+                    .entry(op_info.creator_address)
+                    .or_insert_with(|| ops.clone());
+            }
+        }
+
+        new_ops
+            .iter()
+            .map(|op_info| {
+                // operations already in the pool are not checked again
+                if self.contains(&op_info.id) {
+                    return Ok(None);
+                }
+
+                // check the min fees
+                let min_fee_per_byte = self
+                    .config
+                    .min_operation_fee_per_byte
+                    .saturating_mul_u64(op_info.size as u64);
+                if op_info.fee < min_fee_per_byte {
+                    return Err(OperationRejectionReason::FeePerByteTooLow);
+                }
+                let min_fee_per_gas = self
+                    .config
+                    .min_operation_fee_per_gas
+                    .saturating_mul_u64(op_info.max_gas);
+                if op_info.fee < min_fee_per_gas {
+                    return Err(OperationRejectionReason::FeePerGasTooLow);
+                }
+
+                // check the limit of operations per sender
+                let ops = sender_ops.entry(op_info.creator_address).or_default();
+                let new_entry = (
+                    op_info.id,
+                    op_info.fee,
+                    op_info.validity_period_range.clone(),
+                );
+                if ops.len() < self.config.max_operations_per_sender {
+                    ops.push(new_entry);
+                    return Ok(None);
+                }
+
+                // the sender reached its limit:
+                // the operation can only replace the cheapest operation of the sender with the same validity range
+                let Some((index, replaced_fee)) = ops
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, _, range))| range == &op_info.validity_period_range)
+                    .min_by_key(|(_, (_, fee, _))| *fee)
+                    .map(|(index, (_, fee, _))| (index, *fee))
+                else {
+                    return Err(OperationRejectionReason::SenderLimitReached);
+                };
+                let fee_increase = max(
+                    replaced_fee
+                        .saturating_mul_u64(self.config.replacement_fee_increase_percent)
+                        .checked_div_u64(100)
+                        .unwrap_or_default(),
+                    Amount::from_raw(1),
+                );
+                if op_info.fee < replaced_fee.saturating_add(fee_increase) {
+                    return Err(OperationRejectionReason::ReplacementFeeTooLow);
+                }
+                let (replaced_id, _, _) = std::mem::replace(&mut ops[index], new_entry);
+                Ok(Some(replaced_id))
+            })
+            .collect()
+    }
+
     /// Add a list of operations to the end of the pool.
     /// They will be cleaned up at the next refresh.
     pub(crate) fn add_operations(&mut self, mut ops_storage: Storage) {
         // List all the new operations
         let mut new_op_ids = ops_storage.get_op_refs() - self.storage.get_op_refs();
+        let new_ops: Vec<OperationInfo> = {
+            let ops = ops_storage.read_operations();
+            new_op_ids
+                .iter()
+                .map(|new_op_id| {
+                    OperationInfo::from_op(
+                        ops.get(new_op_id)
+                            .expect("operation not found in storage but listed as owned"),
+                        self.config.operation_validity_periods,
+                        self.config.roll_price,
+                        self.config.thread_count,
+                    )
+                })
+                .collect()
+        };
+
+        // Apply the admission policy: drop the rejected operations and the replaced ones,
+        // which are either in the pool or among the new operations
+        let mut replaced_ids = PreHashSet::default();
+        let mut replacing_ids = PreHashSet::default();
+        for (op_info, admission) in new_ops.iter().zip(self.check_admission(&new_ops)) {
+            match admission {
+                Ok(None) => {}
+                Ok(Some(replaced_id)) => {
+                    replaced_ids.insert(replaced_id);
+                    replacing_ids.insert(op_info.id);
+                    // the replaced operation has the same sender
+                    if let Some(ops) = self.sender_ops.get_mut(&op_info.creator_address) {
+                        ops.retain(|(id, _, _)| id != &replaced_id);
+                    }
+                }
+                Err(reason) => {
+                    debug!("operation {} rejected by the pool: {}", op_info.id, reason);
+                    new_op_ids.remove(&op_info.id);
+
+                    // Broadcast the rejection to active channel subscribers.
+                    if self.config.broadcast_enabled {
+                        if let Err(err) = self
+                            .channels
+                            .operation_rejection_sender
+                            .send((op_info.id, reason))
+                        {
+                            trace!(
+                                "error, failed to broadcast rejection of operation {}: {}",
+                                op_info.id,
+                                err
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        if !replaced_ids.is_empty() {
+            new_op_ids.retain(|id| !replaced_ids.contains(id));
+            self.sorted_ops
+                .retain(|op_info| !replaced_ids.contains(&op_info.id));
+            self.storage.drop_operation_refs(&replaced_ids);
+        }
 
         // If there are too many extra operations,
         // we don't want the container to fill up too much in-between refreshes so we drop any excess.
         // This is because refreshing the container is very heavy and is only called periodically.
-        let dropped_items = self
+        // Operations that replaced another one are kept: their sender would otherwise lose both.
+        let excess_items = self
             .sorted_ops
             .len()
             .saturating_add(new_op_ids.len())
             .saturating_sub(self.config.max_operation_pool_size)
             .saturating_sub(self.config.max_operation_pool_excess_items);
-        for _ in 0..dropped_items {
-            if let Some(id) = new_op_ids.iter().next().copied() {
-                new_op_ids.remove(&id);
-            } else {
-                break;
-            }
+        let dropped_ids: Vec<OperationId> = new_op_ids
+            .iter()
+            .filter(|id| !replacing_ids.contains(*id))
+            .take(excess_items)
+            .copied()
+            .collect();
+        for id in dropped_ids.iter() {
+            new_op_ids.remove(id);
         }
-        if dropped_items > 0 {
+        if !dropped_ids.is_empty() {
             warn!(
                 "Operation pool excess limit reached. Dropping {} non-scored operations.",
-                dropped_items
+                dropped_ids.len()
             );
         }
 
@@ -414,24 +696,23 @@ impl OperationPool {
         // because in that case we don't know anything about their quality.
         {
             let ops = ops_storage.read_operations();
-            for new_op_id in &new_op_ids {
-                let op = ops
-                    .get(new_op_id)
-                    .expect("operation not found in storage but listed as owned");
+            for op_info in new_ops {
+                if !new_op_ids.contains(&op_info.id) {
+                    continue;
+                }
 
                 // Broadcast operations to active channel subscribers.
                 if self.config.broadcast_enabled {
+                    let op = ops
+                        .get(&op_info.id)
+                        .expect("operation not found in storage but listed as owned");
                     if let Err(err) = self.channels.operation_sender.send(op.clone()) {
                         trace!("error, failed to broadcast operations {}: {}", op.id, err);
                     }
                 }
 
-                self.sorted_ops.push(OperationInfo::from_op(
-                    op,
-                    self.config.operation_validity_periods,
-                    self.config.roll_price,
-                    self.config.thread_count,
-                ));
+                self.index_sender_op(&op_info);
+                self.sorted_ops.push(op_info);
             }
         }
 
@@ -12,6 +12,15 @@
 //! Same as classic but we try to add irrelevant operation. (See the definition
 //! chapter below)
 //!
+//! # Fee statistics
+//! Function: [`test_fee_stats`]
+//! Fee percentiles and recommended fee of the pending operations of a thread,
+//! and fees of the operations included in final blocks.
+//!
+//! # Admission policy
+//! Function: [`test_admission_policy`]
+//! Min fees, per-sender limit and replacement of the operations of a sender.
+//!
 //! # Definition
 //! Relevant operation: Operation with a validity range corresponding to the
 //! latest period given his own thread. All operation which doesn't fit these
@@ -19,11 +28,16 @@
 //!
 use crate::tests::tools::OpGenerator;
 
-use super::tools::{create_some_operations, operation_pool_test, PoolTestBoilerPlate};
+use super::tools::{
+    create_block_with_operations, create_some_operations, operation_pool_test, PoolTestBoilerPlate,
+};
 use massa_execution_exports::MockExecutionController;
-use massa_models::{amount::Amount, config::ENDORSEMENT_COUNT, operation::OperationId, slot::Slot};
-use massa_pool_exports::PoolConfig;
+use massa_models::{
+    address::Address, amount::Amount, config::ENDORSEMENT_COUNT, operation::OperationId, slot::Slot,
+};
+use massa_pool_exports::{OperationRejectionReason, PoolConfig};
 use massa_pos_exports::{MockSelectorController, Selection};
+use massa_signature::KeyPair;
 use std::{collections::BTreeMap, time::Duration};
 
 #[test]
@@ -181,6 +195,278 @@ fn test_add_irrelevant_operation() {
     );
 }
 
+/// Test the fee statistics and the recommended fee of the pending operations of a thread.
+#[test]
+fn test_fee_stats() {
+    let execution_controller = {
+        let mut res = Box::new(MockExecutionController::new());
+        res.expect_clone_box().returning(|| {
+            let mut story = MockExecutionController::new();
+            story
+                .expect_get_ops_exec_status()
+                .returning(|ops| vec![(None, None); ops.len()]);
+            story
+                .expect_get_final_and_candidate_balance()
+                .returning(|addrs| {
+                    vec![
+                        (
+                            // Operations need to be paid for
+                            Some(Amount::const_init(1_000_000_000, 0)),
+                            Some(Amount::const_init(1_000_000_000, 0)),
+                        );
+                        addrs.len()
+                    ]
+                });
+
+            Box::new(story)
+        });
+        res
+    };
+    let selector_controller = {
+        let mut res = Box::new(MockSelectorController::new());
+        res.expect_clone_box().times(2).returning(|| {
+            let mut story = MockSelectorController::new();
+            story
+                .expect_get_available_selections_in_range()
+                .returning(|slot_range, opt_addrs| {
+                    let mut all_slots = BTreeMap::new();
+                    let addr = *opt_addrs
+                        .expect("No addresses filter given")
+                        .into_iter()
+                        .next()
+                        .expect("No addresses given");
+                    for i in 0..15 {
+                        for j in 0..32 {
+                            let s = Slot::new(i, j);
+                            if slot_range.contains(&s) {
+                                all_slots.insert(
+                                    s,
+                                    Selection {
+                                        producer: addr,
+                                        endorsements: vec![addr; ENDORSEMENT_COUNT as usize],
+                                    },
+                                );
+                            }
+                        }
+                    }
+                    Ok(all_slots)
+                });
+            Box::new(story)
+        });
+        res
+    };
+    let pool_config = PoolConfig {
+        max_operations_per_block: 2,
+        ..PoolConfig::default()
+    };
+    let creator = KeyPair::generate(0).unwrap();
+    let thread = Address::from_public_key(&creator.get_public_key())
+        .get_thread(pool_config.thread_count) as usize;
+    operation_pool_test(
+        pool_config,
+        execution_controller,
+        selector_controller,
+        |mut operation_pool, mut storage| {
+            let ops = (1..=5)
+                .map(|fee| {
+                    OpGenerator::default()
+                        .expirery(2)
+                        .creator(creator.clone())
+                        .fee(Amount::from_raw(fee))
+                        .generate()
+                })
+                .collect();
+            let mut final_blocks = storage.clone_without_refs();
+            storage.store_operations(ops);
+            operation_pool.add_operations(storage);
+            // Allow some time for the pool to add the operations
+            std::thread::sleep(Duration::from_secs(3));
+
+            // two ops per block: the op of fee 3 is the first one that would wait for a second period
+            let fee_stats = operation_pool.get_fee_stats(1);
+            let thread_stats = &fee_stats.threads[thread];
+            assert_eq!(thread_stats.pending_operation_count, 5);
+            let pending_fees = thread_stats.pending_fees.unwrap();
+            assert_eq!(pending_fees.p10, Amount::from_raw(1));
+            assert_eq!(pending_fees.p50, Amount::from_raw(3));
+            assert_eq!(pending_fees.p90, Amount::from_raw(5));
+            assert_eq!(thread_stats.recommended_fee, Amount::from_raw(4));
+            assert!(thread_stats.recent_inclusion_fees.is_none());
+
+            // all the ops fit in the blocks of the next three periods
+            let fee_stats = operation_pool.get_fee_stats(3);
+            assert_eq!(fee_stats.threads[thread].recommended_fee, Amount::zero());
+
+            // the fees of final blocks are recorded even if their ops never went through the pool
+            let included_ops: Vec<_> = (10..=14)
+                .map(|fee| OpGenerator::default().fee(Amount::from_raw(fee)).generate())
+                .collect();
+            let block = create_block_with_operations(Slot::new(1, thread as u8), &included_ops);
+            final_blocks.store_operations(included_ops);
+            final_blocks.store_block(block);
+            operation_pool.notify_final_blocks(final_blocks);
+            std::thread::sleep(Duration::from_millis(500));
+
+            let fee_stats = operation_pool.get_fee_stats(3);
+            let thread_stats = &fee_stats.threads[thread];
+            assert_eq!(thread_stats.pending_operation_count, 5);
+            let recent_inclusion_fees = thread_stats.recent_inclusion_fees.unwrap();
+            assert_eq!(recent_inclusion_fees.p10, Amount::from_raw(10));
+            assert_eq!(recent_inclusion_fees.p90, Amount::from_raw(14));
+            // all the pending ops still fit: the lowest recent inclusion fees are recommended
+            assert_eq!(thread_stats.recommended_fee, Amount::from_raw(10));
+        },
+    );
+}
+
+/// Test the min fees, the per-sender limit and the replacement rules of the admission policy,
+/// and that a replacing operation is kept when the pool drops its excess operations.
+#[test]
+fn test_admission_policy() {
+    let execution_controller = {
+        let mut res = Box::new(MockExecutionController::new());
+        res.expect_clone_box().returning(|| {
+            let mut story = MockExecutionController::new();
+            story
+                .expect_get_ops_exec_status()
+                .returning(|ops| vec![(None, None); ops.len()]);
+            story
+                .expect_get_final_and_candidate_balance()
+                .returning(|addrs| {
+                    vec![
+                        (
+                            Some(Amount::const_init(1_000_000_000, 0)),
+                            Some(Amount::const_init(1_000_000_000, 0)),
+                        );
+                        addrs.len()
+                    ]
+                });
+            Box::new(story)
+        });
+        res
+    };
+    let selector_controller = {
+        let mut res = Box::new(MockSelectorController::new());
+        res.expect_clone_box().times(2).returning(|| {
+            let mut story = MockSelectorController::new();
+            story
+                .expect_get_available_selections_in_range()
+                .returning(|slot_range, opt_addrs| {
+                    let mut all_slots = BTreeMap::new();
+                    let addr = *opt_addrs
+                        .expect("No addresses filter given")
+                        .into_iter()
+                        .next()
+                        .expect("No addresses given");
+                    for i in 0..15 {
+                        for j in 0..32 {
+                            let s = Slot::new(i, j);
+                            if slot_range.contains(&s) {
+                                all_slots.insert(
+                                    s,
+                                    Selection {
+                                        producer: addr,
+                                        endorsements: vec![addr; ENDORSEMENT_COUNT as usize],
+                                    },
+                                );
+                            }
+                        }
+                    }
+                    Ok(all_slots)
+                });
+            Box::new(story)
+        });
+        res
+    };
+    let pool_config = PoolConfig {
+        max_operations_per_sender: 2,
+        min_operation_fee_per_byte: Amount::from_raw(1),
+        replacement_fee_increase_percent: 10,
+        max_operation_pool_size: 2,
+        max_operation_pool_excess_items: 0,
+        ..PoolConfig::default()
+    };
+    let creator = KeyPair::generate(0).unwrap();
+    let other_creator = KeyPair::generate(0).unwrap();
+    let op_gen = |expirery: u64, fee: u64| {
+        OpGenerator::default()
+            .expirery(expirery)
+            .creator(creator.clone())
+            .fee(Amount::from_raw(fee))
+            .generate()
+    };
+    operation_pool_test(
+        pool_config,
+        execution_controller,
+        selector_controller,
+        |mut operation_pool, mut storage| {
+            let first_op = op_gen(2, 1000);
+            let second_op = op_gen(3, 1000);
+
+            // the operations of a batch are checked together
+            let admissions = operation_pool.check_operations_admission(&[
+                first_op.clone(),
+                second_op.clone(),
+                op_gen(4, 1000),
+                op_gen(2, 1),
+            ]);
+            assert_eq!(
+                admissions,
+                vec![
+                    Ok(()),
+                    Ok(()),
+                    Err(OperationRejectionReason::SenderLimitReached),
+                    Err(OperationRejectionReason::FeePerByteTooLow),
+                ]
+            );
+
+            storage.store_operations(vec![first_op.clone(), second_op.clone()]);
+            operation_pool.add_operations(storage.clone());
+            // Allow some time for the pool to add the operations
+            std::thread::sleep(Duration::from_secs(3));
+            assert_eq!(operation_pool.get_operation_count(), 2);
+
+            // an operation with the same validity range needs a 10% higher fee to replace the first one
+            let replacing_op = op_gen(2, 1100);
+            let admissions =
+                operation_pool.check_operations_admission(&[op_gen(2, 1050), replacing_op.clone()]);
+            assert_eq!(
+                admissions,
+                vec![Err(OperationRejectionReason::ReplacementFeeTooLow), Ok(())]
+            );
+
+            // the pool is full: the operation of the other sender is dropped as excess, not the replacing one
+            let other_op = OpGenerator::default()
+                .expirery(2)
+                .creator(other_creator.clone())
+                .fee(Amount::from_raw(1000))
+                .generate();
+            let mut replacing_storage = storage.clone_without_refs();
+            replacing_storage.store_operations(vec![replacing_op.clone(), other_op.clone()]);
+            operation_pool.add_operations(replacing_storage);
+            std::thread::sleep(Duration::from_secs(3));
+            assert_eq!(operation_pool.get_operation_count(), 2);
+            assert_eq!(
+                operation_pool.contains_operations(&[
+                    first_op.id,
+                    second_op.id,
+                    replacing_op.id,
+                    other_op.id
+                ]),
+                vec![false, true, true, false]
+            );
+
+            // the replaced operation no longer counts for its sender, the replacing one does
+            let admissions =
+                operation_pool.check_operations_admission(&[op_gen(4, 1000), op_gen(3, 1100)]);
+            assert_eq!(
+                admissions,
+                vec![Err(OperationRejectionReason::SenderLimitReached), Ok(())]
+            );
+        },
+    );
+}
+
 /// TODO refactor old tests
 #[test]
 fn test_pool() {
@@ -9,6 +9,8 @@ use massa_hash::Hash;
 use massa_models::{
     address::Address,
     amount::Amount,
+    block::{Block, BlockSerializer, SecureShareBlock},
+    block_header::{BlockHeader, BlockHeaderSerializer},
     block_id::BlockId,
     endorsement::{Endorsement, EndorsementSerializer, SecureShareEndorsement},
     operation::{Operation, OperationSerializer, OperationType, SecureShareOperation},
@@ -119,6 +121,7 @@ impl PoolTestBoilerPlate {
                 execution_controller: execution_story,
                 endorsement_sender,
                 operation_sender,
+                operation_rejection_sender: broadcast::channel(5000).0,
                 selector: selector_story,
             },
             wallet,
@@ -155,6 +158,7 @@ pub fn operation_pool_test<F>(
             execution_controller,
             endorsement_sender,
             operation_sender,
+            operation_rejection_sender: broadcast::channel(5000).0,
             selector,
         },
         wallet,
@@ -174,3 +178,34 @@ pub fn _create_endorsement(slot: Slot) -> SecureShareEndorsement {
     };
     Endorsement::new_verifiable(content, EndorsementSerializer::new(), &sender_keypair).unwrap()
 }
+
+/// Creates a block containing the given operations, for use in pool tests.
+pub fn create_block_with_operations(
+    slot: Slot,
+    operations: &[SecureShareOperation],
+) -> SecureShareBlock {
+    let keypair = KeyPair::generate(0).unwrap();
+    let header = BlockHeader::new_verifiable(
+        BlockHeader {
+            current_version: 0,
+            announced_version: None,
+            slot,
+            parents: vec![],
+            operation_merkle_root: Hash::compute_from("merkle".as_bytes()),
+            endorsements: Vec::new(),
+            denunciations: Vec::new(),
+        },
+        BlockHeaderSerializer::new(),
+        &keypair,
+    )
+    .unwrap();
+    Block::new_verifiable(
+        Block {
+            header,
+            operations: operations.iter().map(|op| op.id).collect(),
+        },
+        BlockSerializer::new(),
+        &keypair,
+    )
+    .unwrap()
+}
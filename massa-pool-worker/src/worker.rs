@@ -113,6 +113,10 @@ impl OperationPoolThread {
                         .operation_pool
                         .write()
                         .notify_final_cs_periods(&final_cs_periods),
+                    Ok(Command::NotifyFinalBlocks(final_blocks)) => self
+                        .operation_pool
+                        .write()
+                        .notify_final_blocks(final_blocks),
                     Ok(_) => {
                         warn!("OperationPoolThread received an unexpected command");
                         continue;
@@ -208,7 +212,7 @@ pub fn start_pool_controller(
     )));
     let denunciation_pool = Arc::new(RwLock::new(DenunciationPool::init(config, channels)));
     let controller = PoolControllerImpl {
-        _config: config,
+        config,
         operation_pool: operation_pool.clone(),
         endorsement_pool: endorsement_pool.clone(),
         denunciation_pool: denunciation_pool.clone(),
@@ -1676,6 +1676,7 @@ mod tests {
             max_history_length: 10,
             max_new_elements: 100,
             thread_count: 2,
+            enable_state_tree: false,
         };
         let db = Arc::new(RwLock::new(
             Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
@@ -1787,6 +1788,7 @@ mod tests {
             max_history_length: 10,
             max_new_elements: 100,
             thread_count: 2,
+            enable_state_tree: false,
         };
         let db = Arc::new(RwLock::new(
             Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
@@ -1900,6 +1902,7 @@ mod tests {
             max_history_length: 10,
             max_new_elements: 100,
             thread_count: 2,
+            enable_state_tree: false,
         };
         let db = Arc::new(RwLock::new(
             Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
@@ -4,13 +4,14 @@ use std::collections::HashMap;
 use std::net::SocketAddr;
 
 use crate::error::ProtocolError;
-use crate::BootstrapPeers;
+use crate::{BootstrapPeers, ConnectedPeerInfo, KnownPeerInfo};
 
 use crate::PeerId;
 use massa_models::prehash::{PreHashMap, PreHashSet};
 use massa_models::stats::NetworkStats;
 use massa_models::{block_header::SecuredHeader, block_id::BlockId};
 use massa_storage::Storage;
+use massa_time::MassaTime;
 use peernet::peer::PeerConnectionType;
 
 #[cfg_attr(any(test, feature = "testing"), mockall::automock)]
@@ -73,11 +74,26 @@ pub trait ProtocolController: Send + Sync {
     fn get_bootstrap_peers(&self) -> Result<BootstrapPeers, ProtocolError>;
 
     /// Ban a list of Peer Id
-    fn ban_peers(&self, peer_ids: Vec<PeerId>) -> Result<(), ProtocolError>;
+    ///
+    /// # Arguments
+    /// * `reason`: reason of the ban, a generic one is recorded if None
+    /// * `duration`: duration of the ban, forever if None
+    fn ban_peers(
+        &self,
+        peer_ids: Vec<PeerId>,
+        reason: Option<String>,
+        duration: Option<MassaTime>,
+    ) -> Result<(), ProtocolError>;
 
     /// Unban a list of Peer Id
     fn unban_peers(&self, peer_ids: Vec<PeerId>) -> Result<(), ProtocolError>;
 
+    /// Get the peers of the peer database, with their state, bans and connection statistics
+    fn get_known_peers(&self) -> Result<Vec<KnownPeerInfo>, ProtocolError>;
+
+    /// Get the connected peers, with their traffic and connection statistics
+    fn get_peers(&self) -> Result<Vec<ConnectedPeerInfo>, ProtocolError>;
+
     /// Returns a boxed clone of self.
     /// Useful to allow cloning `Box<dyn ProtocolController>`.
     fn clone_box(&self) -> Box<dyn ProtocolController>;
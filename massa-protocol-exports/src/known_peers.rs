@@ -0,0 +1,113 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::PeerId;
+use massa_time::MassaTime;
+use peernet::peer::PeerConnectionType;
+use peernet::transports::TransportType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+/// State of a peer known by the node
+#[warn(dead_code)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum PeerState {
+    /// the peer is banned
+    Banned,
+    /// a handshake with the peer is in progress
+    InHandshake,
+    /// the last handshake with the peer failed
+    HandshakeFailed,
+    /// the last handshake with the peer succeeded
+    Trusted,
+}
+
+/// Ban of a peer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerBan {
+    /// why the peer was banned, if known
+    pub reason: Option<String>,
+    /// time after which the ban is lifted, if any
+    pub expire_at: Option<MassaTime>,
+}
+
+impl PeerBan {
+    /// Returns true if the ban no longer applies at time `now`
+    pub fn is_expired(&self, now: MassaTime) -> bool {
+        self.expire_at.map_or(false, |expire_at| expire_at <= now)
+    }
+}
+
+/// Outcome of the last connections and tests made to a peer address
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectionStats {
+    /// last successful connection
+    pub last_success: Option<MassaTime>,
+    /// last failed connection
+    pub last_failure: Option<MassaTime>,
+    /// last connection attempt
+    pub last_try_connect: Option<MassaTime>,
+    /// last successful test by the peer tester
+    pub last_test_success: Option<MassaTime>,
+    /// last failed test by the peer tester
+    pub last_test_failure: Option<MassaTime>,
+}
+
+/// Peer of the peer database of the node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KnownPeerInfo {
+    /// peer id
+    pub peer_id: PeerId,
+    /// state of the peer
+    pub state: PeerState,
+    /// ban of the peer, if it is banned
+    pub ban: Option<PeerBan>,
+    /// timestamp of the last announcement received from the peer
+    pub last_announce: Option<MassaTime>,
+    /// listeners of the last announcement received from the peer
+    pub listeners: HashMap<SocketAddr, TransportType>,
+    /// connection statistics of the listeners of the peer
+    pub connection_stats: HashMap<SocketAddr, ConnectionStats>,
+}
+
+/// Number of messages received from a peer, by handler
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerMessageCounts {
+    /// block messages
+    pub block: u64,
+    /// operation messages
+    pub operation: u64,
+    /// endorsement messages
+    pub endorsement: u64,
+    /// peer management messages
+    pub peer_management: u64,
+}
+
+/// Peer currently connected to the node
+#[derive(Debug, Clone)]
+pub struct ConnectedPeerInfo {
+    /// peer id
+    pub peer_id: PeerId,
+    /// address of the connection
+    pub address: SocketAddr,
+    /// category of the peer in the `peers_categories` of the configuration, `default` if none
+    pub category: String,
+    /// direction of the connection
+    pub connection_type: PeerConnectionType,
+    /// listeners announced by the peer
+    pub listeners: HashMap<SocketAddr, TransportType>,
+    /// bytes sent to the peer on this connection
+    pub bytes_sent: u64,
+    /// bytes received from the peer on this connection
+    pub bytes_received: u64,
+    /// messages received from the peer on this connection
+    pub messages_received: PeerMessageCounts,
+    /// last measured round trip time: the one of the handshake, refreshed by the peer tester
+    pub ping: Option<MassaTime>,
+    /// time at which the handshake completed
+    pub handshake_at: Option<MassaTime>,
+    /// reputation score of the peer
+    pub score: i64,
+    /// connection and peer tester statistics of the listeners of the peer
+    pub connection_stats: HashMap<SocketAddr, ConnectionStats>,
+}
@@ -1,6 +1,7 @@
 mod bootstrap_peers;
 mod controller_trait;
 mod error;
+mod known_peers;
 mod peer_id;
 mod settings;
 
@@ -9,6 +10,9 @@ pub use bootstrap_peers::{
 };
 pub use controller_trait::{ProtocolController, ProtocolManager};
 pub use error::ProtocolError;
+pub use known_peers::{
+    ConnectedPeerInfo, ConnectionStats, KnownPeerInfo, PeerBan, PeerMessageCounts, PeerState,
+};
 pub use peer_id::{PeerId, PeerIdDeserializer, PeerIdSerializer};
 pub use peernet::peer::PeerConnectionType;
 pub use peernet::transports::TransportType;
@@ -17,6 +17,18 @@ pub struct PeerCategoryInfo {
     pub target_out_connections: usize,
     pub max_in_connections: usize,
     pub max_in_connections_per_ip: usize,
+    /// bytes per second received from all the peers of the category, unlimited if none
+    #[serde(default)]
+    pub max_in_bytes_per_second: Option<u64>,
+    /// bytes per second sent to all the peers of the category, unlimited if none
+    #[serde(default)]
+    pub max_out_bytes_per_second: Option<u64>,
+    /// bytes per second received from each peer of the category, unlimited if none
+    #[serde(default)]
+    pub max_in_bytes_per_second_per_peer: Option<u64>,
+    /// bytes per second sent to each peer of the category, unlimited if none
+    #[serde(default)]
+    pub max_out_bytes_per_second_per_peer: Option<u64>,
 }
 
 /// Dynamic protocol configuration mix in static settings and constants configurations.
@@ -28,6 +40,10 @@ pub struct ProtocolConfig {
     pub listeners: HashMap<SocketAddr, TransportType>,
     /// initial peers path
     pub initial_peers: PathBuf,
+    /// path of the file where the peer database is saved
+    pub peers_file: PathBuf,
+    /// interval at which the peer database is saved
+    pub peers_file_dump_interval: MassaTime,
     /// after `ask_block_timeout` milliseconds we try to ask a block to another node
     pub ask_block_timeout: MassaTime,
     /// Max known blocks we keep during their propagation
@@ -36,6 +52,8 @@ pub struct ProtocolConfig {
     pub max_block_propagation_time: MassaTime,
     /// Block propagation tick interval, useful for propagating blocks quickly to newly connected peers.
     pub block_propagation_tick: MassaTime,
+    /// Announce blocks with the operation ID prefixes of their operations to the peers that support it, so that they can rebuild them from the operations they already know
+    pub compact_block_relay: bool,
     /// max known blocks of current nodes we keep in memory
     pub max_known_blocks_size: usize,
     /// max known blocks of foreign nodes we keep in memory (by node)
@@ -150,6 +168,12 @@ pub struct ProtocolConfig {
     pub try_connection_timer_same_peer: MassaTime,
     /// periodically unban every peer
     pub unban_everyone_timer: MassaTime,
+    /// reputation score at or below which a misbehaving peer is disconnected
+    pub peer_score_disconnect_threshold: i64,
+    /// reputation score at or below which a misbehaving peer is temporarily banned
+    pub peer_score_ban_threshold: i64,
+    /// duration of the bans triggered by the reputation score
+    pub peer_score_ban_duration: MassaTime,
     /// Max in connections
     pub max_in_connections: usize,
     /// Timeout connection
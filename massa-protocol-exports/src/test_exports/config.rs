@@ -18,6 +18,7 @@ impl Default for ProtocolConfig {
             max_blocks_kept_for_propagation: 300,
             max_block_propagation_time: MassaTime::from_millis(40000),
             block_propagation_tick: MassaTime::from_millis(1000),
+            compact_block_relay: false,
             max_known_blocks_size: 100,
             max_node_known_blocks_size: 100,
             max_node_wanted_blocks_size: 100,
@@ -48,6 +49,11 @@ impl Default for ProtocolConfig {
                 .expect("cannot create temp file")
                 .path()
                 .to_path_buf(),
+            peers_file: NamedTempFile::new()
+                .expect("cannot create temp file")
+                .path()
+                .to_path_buf(),
+            peers_file_dump_interval: MassaTime::from_millis(10000),
             listeners: HashMap::default(),
             thread_tester_count: 2,
             max_size_channel_commands_connectivity: 1000,
@@ -82,6 +88,9 @@ impl Default for ProtocolConfig {
             timeout_connection: MassaTime::from_millis(1000),
             try_connection_timer: MassaTime::from_millis(5000),
             unban_everyone_timer: MassaTime::from_millis(ONE_DAY_MS),
+            peer_score_disconnect_threshold: -100,
+            peer_score_ban_threshold: -200,
+            peer_score_ban_duration: MassaTime::from_millis(60 * 60 * 1000),
             routable_ip: None,
             max_in_connections: 10,
             debug: true,
@@ -91,6 +100,10 @@ impl Default for ProtocolConfig {
                 max_in_connections: 10,
                 target_out_connections: 10,
                 max_in_connections_per_ip: 0,
+                max_in_bytes_per_second: None,
+                max_out_bytes_per_second: None,
+                max_in_bytes_per_second_per_peer: None,
+                max_out_bytes_per_second_per_peer: None,
             },
             version: "TEST.23.2".parse().unwrap(),
             try_connection_timer_same_peer: MassaTime::from_millis(1000),
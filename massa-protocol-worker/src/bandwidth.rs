@@ -0,0 +1,330 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Bandwidth quotas of the peer categories and of the peers of each category.
+//!
+//! Each quota is a token bucket holding at most one second of traffic.
+//! When a bucket runs low, the messages are dropped by order of `TrafficClass`:
+//! peer management messages first, then operations.
+//! Consensus messages (blocks and endorsements) are never dropped, but they are counted
+//! in the quotas so that they starve the other classes.
+//!
+//! Inbound messages are counted when they are received and dropped before being handled.
+//! Outbound traffic is measured on the connections and only checked before sending unsolicited
+//! announcements (operation ids and peer lists): replies to requests are always sent.
+
+use massa_protocol_exports::{PeerCategoryInfo, PeerId};
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::ip::to_canonical;
+use crate::messages::MessageTypeId;
+
+/// Name of the category of the peers that are in none of the configured categories
+const DEFAULT_CATEGORY: &str = "default";
+
+/// Priority of a message when a bandwidth quota runs low
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrafficClass {
+    /// block headers, blocks and endorsements: never dropped
+    Consensus,
+    /// operations: dropped when less than a quarter of the quota remains
+    Operations,
+    /// peer announcements: dropped when less than half of the quota remains
+    PeerManagement,
+}
+
+impl From<&MessageTypeId> for TrafficClass {
+    fn from(id: &MessageTypeId) -> Self {
+        match id {
+            MessageTypeId::Block | MessageTypeId::Endorsement => TrafficClass::Consensus,
+            MessageTypeId::Operation => TrafficClass::Operations,
+            MessageTypeId::PeerManagement => TrafficClass::PeerManagement,
+        }
+    }
+}
+
+/// Token bucket refilled at `bytes_per_second`, holding at most one second of traffic
+struct Bucket {
+    bytes_per_second: i64,
+    /// can be negative when consensus messages exceeded the quota
+    available: i64,
+    last_refill: Instant,
+}
+
+impl Bucket {
+    fn new(bytes_per_second: u64) -> Self {
+        let bytes_per_second = i64::try_from(bytes_per_second).unwrap_or(i64::MAX);
+        Bucket {
+            bytes_per_second,
+            available: bytes_per_second,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        let credit =
+            (self.bytes_per_second as u128).saturating_mul(elapsed.as_nanos()) / 1_000_000_000;
+        // the time is only consumed once it earned some bytes, so that frequent refills don't lose credit
+        if credit > 0 {
+            self.available = self
+                .available
+                .saturating_add(i64::try_from(credit).unwrap_or(i64::MAX))
+                .min(self.bytes_per_second);
+            self.last_refill = now;
+        }
+    }
+
+    fn allows(&self, class: TrafficClass) -> bool {
+        match class {
+            TrafficClass::Consensus => true,
+            TrafficClass::Operations => self.available >= self.bytes_per_second / 4,
+            TrafficClass::PeerManagement => self.available >= self.bytes_per_second / 2,
+        }
+    }
+
+    fn consume(&mut self, bytes: u64) {
+        // the debt is capped to one second of traffic to recover quickly after a burst
+        self.available = self
+            .available
+            .saturating_sub(i64::try_from(bytes).unwrap_or(i64::MAX))
+            .max(-self.bytes_per_second);
+    }
+}
+
+/// Inbound and outbound quotas
+#[derive(Default)]
+struct Buckets {
+    inbound: Option<Bucket>,
+    outbound: Option<Bucket>,
+}
+
+impl Buckets {
+    fn new(in_bytes_per_second: Option<u64>, out_bytes_per_second: Option<u64>) -> Self {
+        Buckets {
+            inbound: in_bytes_per_second.map(Bucket::new),
+            outbound: out_bytes_per_second.map(Bucket::new),
+        }
+    }
+}
+
+/// Quotas of a connected peer
+struct PeerBandwidth {
+    category: String,
+    buckets: Buckets,
+    /// bytes sent to the peer when the outbound quotas were last updated
+    last_bytes_sent: u64,
+}
+
+/// Bandwidth quotas of the peer categories and of the connected peers
+#[derive(Default)]
+pub struct BandwidthLimiter {
+    /// categories, with the IP addresses of their peers and their quotas
+    categories: HashMap<String, (Vec<IpAddr>, PeerCategoryInfo)>,
+    /// quotas of the peers that are in none of the categories
+    default_category_info: Option<PeerCategoryInfo>,
+    /// quotas shared by the peers of each category
+    category_buckets: HashMap<String, Buckets>,
+    /// quotas of each peer
+    peers: HashMap<PeerId, PeerBandwidth>,
+}
+
+pub type SharedBandwidthLimiter = Arc<Mutex<BandwidthLimiter>>;
+
+/// Counts some traffic in a peer bucket and its category bucket, and returns true if the buckets allow the traffic class.
+/// If `count_first` is false, the buckets are checked before counting the traffic.
+fn apply(
+    peer_bucket: Option<&mut Bucket>,
+    category_bucket: Option<&mut Bucket>,
+    class: TrafficClass,
+    bytes: u64,
+    count_first: bool,
+) -> bool {
+    let now = Instant::now();
+    let mut allowed = true;
+    for bucket in [peer_bucket, category_bucket].into_iter().flatten() {
+        bucket.refill(now);
+        if count_first {
+            bucket.consume(bytes);
+            allowed &= bucket.allows(class);
+        } else {
+            allowed &= bucket.allows(class);
+            bucket.consume(bytes);
+        }
+    }
+    allowed
+}
+
+impl BandwidthLimiter {
+    /// Creates a limiter from the categories of the configuration
+    pub fn new(
+        categories: HashMap<String, (Vec<IpAddr>, PeerCategoryInfo)>,
+        default_category_info: PeerCategoryInfo,
+    ) -> Self {
+        let mut category_buckets: HashMap<String, Buckets> = categories
+            .iter()
+            .map(|(name, (_, info))| {
+                (
+                    name.clone(),
+                    Buckets::new(info.max_in_bytes_per_second, info.max_out_bytes_per_second),
+                )
+            })
+            .collect();
+        category_buckets.insert(
+            DEFAULT_CATEGORY.to_string(),
+            Buckets::new(
+                default_category_info.max_in_bytes_per_second,
+                default_category_info.max_out_bytes_per_second,
+            ),
+        );
+        BandwidthLimiter {
+            categories,
+            default_category_info: Some(default_category_info),
+            category_buckets,
+            peers: HashMap::new(),
+        }
+    }
+
+    /// Start applying the quotas of its category to a newly connected peer
+    pub fn register_peer(&mut self, peer_id: &PeerId, ip: IpAddr) {
+        let ip = to_canonical(ip);
+        let (category, info) = match self
+            .categories
+            .iter()
+            .find(|(_, (ips, _))| ips.contains(&ip))
+        {
+            Some((name, (_, info))) => (name.clone(), Some(*info)),
+            None => (DEFAULT_CATEGORY.to_string(), self.default_category_info),
+        };
+        let buckets = info
+            .map(|info| {
+                Buckets::new(
+                    info.max_in_bytes_per_second_per_peer,
+                    info.max_out_bytes_per_second_per_peer,
+                )
+            })
+            .unwrap_or_default();
+        self.peers.insert(
+            peer_id.clone(),
+            PeerBandwidth {
+                category,
+                buckets,
+                last_bytes_sent: 0,
+            },
+        );
+    }
+
+    /// Forget the peers that are no longer connected
+    pub fn retain_peers(&mut self, peer_ids: &HashSet<PeerId>) {
+        self.peers.retain(|peer_id, _| peer_ids.contains(peer_id));
+    }
+
+    /// Count a message received from a peer, and return true if it should be handled
+    pub fn allow_inbound(&mut self, peer_id: &PeerId, class: TrafficClass, bytes: u64) -> bool {
+        let Some(peer) = self.peers.get_mut(peer_id) else {
+            return true;
+        };
+        let category_bucket = self
+            .category_buckets
+            .get_mut(&peer.category)
+            .and_then(|buckets| buckets.inbound.as_mut());
+        // the message is checked before being counted, as dropping it saves handling it
+        apply(
+            peer.buckets.inbound.as_mut(),
+            category_bucket,
+            class,
+            bytes,
+            false,
+        )
+    }
+
+    /// Count the bytes sent to a peer since the last call, given the total bytes sent on the connection,
+    /// and return true if a message of the given class can be sent
+    pub fn allow_outbound(
+        &mut self,
+        peer_id: &PeerId,
+        class: TrafficClass,
+        total_bytes_sent: u64,
+    ) -> bool {
+        let Some(peer) = self.peers.get_mut(peer_id) else {
+            return true;
+        };
+        let bytes = total_bytes_sent.saturating_sub(peer.last_bytes_sent);
+        peer.last_bytes_sent = total_bytes_sent;
+        let category_bucket = self
+            .category_buckets
+            .get_mut(&peer.category)
+            .and_then(|buckets| buckets.outbound.as_mut());
+        // the bytes were already sent, so they are counted before checking the next message
+        apply(
+            peer.buckets.outbound.as_mut(),
+            category_bucket,
+            class,
+            bytes,
+            true,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+
+    fn category_info(
+        max_in_bytes_per_second: Option<u64>,
+        max_out_bytes_per_second_per_peer: Option<u64>,
+    ) -> PeerCategoryInfo {
+        PeerCategoryInfo {
+            allow_local_peers: true,
+            target_out_connections: 10,
+            max_in_connections: 10,
+            max_in_connections_per_ip: 10,
+            max_in_bytes_per_second,
+            max_out_bytes_per_second: None,
+            max_in_bytes_per_second_per_peer: None,
+            max_out_bytes_per_second_per_peer,
+        }
+    }
+
+    #[test]
+    fn test_bandwidth_limiter() {
+        let category_ip: IpAddr = "10.0.0.1".parse().unwrap();
+        let mut limiter = BandwidthLimiter::new(
+            [(
+                "Metered".to_string(),
+                (vec![category_ip], category_info(Some(1000), None)),
+            )]
+            .into_iter()
+            .collect(),
+            category_info(None, Some(1000)),
+        );
+        let categorized_peer =
+            PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        let default_peer = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        limiter.register_peer(&categorized_peer, category_ip);
+        limiter.register_peer(&default_peer, "10.0.0.2".parse().unwrap());
+
+        // inbound quota of the category: peer management messages are dropped first
+        assert!(limiter.allow_inbound(&categorized_peer, TrafficClass::PeerManagement, 600));
+        assert!(!limiter.allow_inbound(&categorized_peer, TrafficClass::PeerManagement, 10));
+        assert!(limiter.allow_inbound(&categorized_peer, TrafficClass::Operations, 200));
+        assert!(!limiter.allow_inbound(&categorized_peer, TrafficClass::Operations, 10));
+        assert!(limiter.allow_inbound(&categorized_peer, TrafficClass::Consensus, 10_000));
+        // the default category has no inbound quota
+        assert!(limiter.allow_inbound(&default_peer, TrafficClass::PeerManagement, 10_000));
+
+        // outbound quota of each peer of the default category
+        assert!(limiter.allow_outbound(&default_peer, TrafficClass::Operations, 0));
+        assert!(!limiter.allow_outbound(&default_peer, TrafficClass::Operations, 800));
+        assert!(limiter.allow_outbound(&default_peer, TrafficClass::Consensus, 800));
+        assert!(limiter.allow_outbound(&categorized_peer, TrafficClass::Operations, 10_000));
+
+        // disconnected peers are no longer limited
+        limiter.retain_peers(&HashSet::new());
+        assert!(limiter.allow_inbound(&categorized_peer, TrafficClass::PeerManagement, 10));
+    }
+}
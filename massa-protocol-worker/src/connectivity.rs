@@ -7,7 +7,9 @@ use massa_metrics::MassaMetrics;
 use massa_models::stats::NetworkStats;
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
-use massa_protocol_exports::{PeerCategoryInfo, PeerId, ProtocolConfig, ProtocolError};
+use massa_protocol_exports::{
+    ConnectedPeerInfo, PeerCategoryInfo, PeerId, ProtocolConfig, ProtocolError,
+};
 use massa_storage::Storage;
 use massa_versioning::versioning::MipStore;
 use parking_lot::RwLock;
@@ -24,7 +26,10 @@ use crate::{
     ip::to_canonical,
     worker::ProtocolChannels,
 };
-use crate::{handlers::peer_handler::PeerManagementHandler, messages::MessagesHandler};
+use crate::{
+    handlers::peer_handler::PeerManagementHandler,
+    messages::{MessagesHandler, PeerMessageCounters},
+};
 use crate::{
     handlers::{
         block_handler::{cache::BlockCache, BlockHandler},
@@ -45,6 +50,9 @@ pub enum ConnectivityCommand {
             HashMap<PeerId, (SocketAddr, PeerConnectionType)>,
         )>,
     },
+    GetPeers {
+        responder: MassaSender<Vec<ConnectedPeerInfo>>,
+    },
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -118,6 +126,9 @@ pub(crate) fn start_connectivity_thread(
                 config.max_node_known_blocks_size.try_into().unwrap(),
             )));
 
+            let message_counts = messages_handler.message_counts.clone();
+            let bandwidth_limiter = messages_handler.bandwidth_limiter.clone();
+
             // Start handlers
             let mut peer_management_handler = PeerManagementHandler::new(
                 initial_peers,
@@ -229,6 +240,32 @@ pub(crate) fn start_connectivity_thread(
                                 }).collect();
                                 responder.try_send((stats, peers)).unwrap_or_else(|_| warn!("Failed to send stats to responder"));
                             }
+                            Ok(ConnectivityCommand::GetPeers { responder }) => {
+                                let active_conn = network_controller.get_active_connections();
+                                let bandwidths = active_conn.get_peers_connections_bandwidth();
+                                let message_counts = message_counts.read();
+                                let peer_db_read = peer_db.read();
+                                let peers = active_conn.get_peers_connected().into_iter().map(|(peer_id, (address, connection_type, category))| {
+                                    let (bytes_sent, bytes_received) = bandwidths.get(&peer_id.to_string()).cloned().unwrap_or_default();
+                                    let latency = peer_db_read.latencies.get(&peer_id);
+                                    let listeners = peer_db_read.get_listeners(&peer_id);
+                                    ConnectedPeerInfo {
+                                        address,
+                                        category: category.unwrap_or_else(|| "default".to_string()),
+                                        connection_type,
+                                        bytes_sent,
+                                        bytes_received,
+                                        messages_received: message_counts.get(&peer_id).map(PeerMessageCounters::get).unwrap_or_default(),
+                                        ping: latency.map(|latency| latency.ping),
+                                        score: peer_db_read.reputation.get_score(&peer_id),
+                                        handshake_at: latency.map(|latency| latency.handshake_at),
+                                        connection_stats: peer_db_read.get_connection_stats(&listeners),
+                                        listeners,
+                                        peer_id,
+                                    }
+                                }).collect();
+                                responder.try_send(peers).unwrap_or_else(|_| warn!("Failed to send peers to responder"));
+                            }
                             Err(_) => {
                                 warn!("Channel to connectivity thread is closed. Stopping the protocol");
                                 break;
@@ -242,6 +279,11 @@ pub(crate) fn start_connectivity_thread(
                         massa_metrics.set_active_connections(active_conn.get_nb_in_connections(), active_conn.get_nb_out_connections());
                         let peers_map = active_conn.get_peers_connections_bandwidth();
                         massa_metrics.update_peers_tx_rx(peers_map);
+                        // forget the message counts, bandwidth quotas and latencies of the peers that are no longer connected
+                        let peer_ids_connected = active_conn.get_peer_ids_connected();
+                        message_counts.write().retain(|peer_id, _| peer_ids_connected.contains(peer_id));
+                        bandwidth_limiter.lock().retain_peers(&peer_ids_connected);
+                        peer_db.write().retain_latencies(&peer_ids_connected);
                         let peer_db_read = peer_db.read();
                         massa_metrics.set_known_peers(peer_db_read.peers.len());
                         massa_metrics.set_banned_peers(peer_db_read.get_banned_peer_count() as usize);
@@ -327,7 +369,7 @@ pub(crate) fn start_connectivity_thread(
                                                 continue;
                                             }
 
-                                            addresses_can_connect.push((*addr, connection_metadata, category_found));
+                                            addresses_can_connect.push((*addr, connection_metadata, category_found, peer_db_read.reputation.get_score(peer_id)));
                                         } else {
                                             tracing::log::warn!("No listeners for the peer {peer_id}"); 
                                         }
@@ -336,12 +378,12 @@ pub(crate) fn start_connectivity_thread(
                             }
                         }
 
-                        // Sort addresses using the metadata
-                        addresses_can_connect.sort_by(|a, b| a.1.cmp(&b.1));
+                        // Sort addresses using the metadata, peers with a negative reputation score last
+                        addresses_can_connect.sort_by(|a, b| b.3.min(0).cmp(&a.3.min(0)).then_with(|| a.1.cmp(&b.1)));
 
                         // Connect to the given addresses, trying to fill all the slots available
                         let mut addresses_connected = vec![];
-                        for (addr, _, category) in addresses_can_connect.iter() {
+                        for (addr, _, category, _) in addresses_can_connect.iter() {
                             if addresses_connected.contains(addr) {
                                 continue;
                             }
@@ -7,8 +7,12 @@ use massa_models::{
     prehash::{PreHashMap, PreHashSet},
     stats::NetworkStats,
 };
-use massa_protocol_exports::{BootstrapPeers, PeerId, ProtocolController, ProtocolError};
+use massa_protocol_exports::{
+    BootstrapPeers, ConnectedPeerInfo, KnownPeerInfo, PeerBan, PeerId, ProtocolController,
+    ProtocolError,
+};
 use massa_storage::Storage;
+use massa_time::MassaTime;
 use peernet::peer::PeerConnectionType;
 
 use crate::{
@@ -156,11 +160,26 @@ impl ProtocolController for ProtocolControllerImpl {
             .map_err(|_| ProtocolError::ChannelError("get_stats command receive error".into()))
     }
 
-    fn ban_peers(&self, peer_ids: Vec<PeerId>) -> Result<(), ProtocolError> {
+    fn ban_peers(
+        &self,
+        peer_ids: Vec<PeerId>,
+        reason: Option<String>,
+        duration: Option<MassaTime>,
+    ) -> Result<(), ProtocolError> {
+        let expire_at = match duration {
+            Some(duration) => Some(MassaTime::now()?.saturating_add(duration)),
+            None => None,
+        };
         self.sender_peer_management_thread
             .as_ref()
             .unwrap()
-            .try_send(PeerManagementCmd::Ban(peer_ids))
+            .try_send(PeerManagementCmd::Ban(
+                peer_ids,
+                PeerBan {
+                    reason: Some(reason.unwrap_or_else(|| "manual ban".to_string())),
+                    expire_at,
+                },
+            ))
             .map_err(|_| ProtocolError::ChannelError("ban_peers command send error".into()))
     }
 
@@ -172,6 +191,32 @@ impl ProtocolController for ProtocolControllerImpl {
             .map_err(|_| ProtocolError::ChannelError("unban_peers command send error".into()))
     }
 
+    fn get_known_peers(&self) -> Result<Vec<KnownPeerInfo>, ProtocolError> {
+        let (sender, receiver) = MassaChannel::new("get_known_peers".to_string(), Some(1));
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::GetKnownPeers { responder: sender })
+            .map_err(|_| {
+                ProtocolError::ChannelError("get_known_peers command send error".into())
+            })?;
+        receiver.recv_timeout(Duration::from_secs(10)).map_err(|_| {
+            ProtocolError::ChannelError("get_known_peers command receive error".into())
+        })
+    }
+
+    fn get_peers(&self) -> Result<Vec<ConnectedPeerInfo>, ProtocolError> {
+        let (sender, receiver) = MassaChannel::new("get_peers".to_string(), Some(1));
+        self.sender_connectivity_thread
+            .as_ref()
+            .unwrap()
+            .try_send(ConnectivityCommand::GetPeers { responder: sender })
+            .map_err(|_| ProtocolError::ChannelError("get_peers command send error".into()))?;
+        receiver
+            .recv_timeout(Duration::from_secs(10))
+            .map_err(|_| ProtocolError::ChannelError("get_peers command receive error".into()))
+    }
+
     fn get_bootstrap_peers(&self) -> Result<BootstrapPeers, ProtocolError> {
         let (sender, receiver) = MassaChannel::new("get_bootstrap_peers".to_string(), Some(1));
         self.sender_peer_management_thread
@@ -17,6 +17,8 @@ pub struct BlockCache {
     pub blocks_known_by_peer: HashMap<PeerId, LruMap<BlockId, (bool, Instant)>>,
     /// max number of blocks known in peer knowledge cache
     pub max_known_blocks_by_peer: u32,
+    /// connected peers that announced their support of compact block relay
+    pub compact_relay_peers: HashSet<PeerId>,
 }
 
 impl BlockCache {
@@ -51,6 +53,7 @@ impl BlockCache {
             checked_headers: LruMap::new(ByLength::new(max_known_blocks)),
             blocks_known_by_peer: HashMap::new(),
             max_known_blocks_by_peer,
+            compact_relay_peers: HashSet::new(),
         }
     }
 
@@ -58,6 +61,8 @@ impl BlockCache {
         // Remove disconnected peers from cache
         self.blocks_known_by_peer
             .retain(|peer_id, _| peers_connected.contains(peer_id));
+        self.compact_relay_peers
+            .retain(|peer_id| peers_connected.contains(peer_id));
 
         // Add new connected peers to cache
         for peer_id in peers_connected {
@@ -2,16 +2,18 @@ use massa_models::{
     block_header::{BlockHeader, BlockHeaderDeserializer, SecuredHeader},
     block_id::{BlockId, BlockIdDeserializer, BlockIdSerializer},
     operation::{
-        OperationId, OperationIdSerializer, OperationIdsDeserializer, OperationsDeserializer,
-        SecureShareOperation,
+        OperationId, OperationIdSerializer, OperationIdsDeserializer, OperationPrefixId,
+        OperationPrefixIdDeserializer, OperationsDeserializer, SecureShareOperation,
     },
     secure_share::{SecureShareDeserializer, SecureShareSerializer},
 };
 use massa_serialization::{
-    Deserializer, SerializeError, Serializer, U64VarIntDeserializer, U64VarIntSerializer,
+    Deserializer, SerializeError, Serializer, U32VarIntDeserializer, U64VarIntDeserializer,
+    U64VarIntSerializer,
 };
 use nom::{
     error::{context, ContextError, ParseError},
+    multi::length_count,
     sequence::tuple,
     IResult, Parser,
 };
@@ -28,6 +30,8 @@ pub enum AskForBlockInfo {
     OperationIds,
     /// Ask for a subset of operations of the block
     Operations(Vec<OperationId>),
+    /// Ask for the operations of the block matching operation ID prefixes announced in a compact header
+    OperationsByPrefix(Vec<OperationPrefixId>),
 }
 
 /// Reply to a block data request
@@ -50,6 +54,14 @@ pub enum BlockInfoReply {
 pub enum BlockMessage {
     /// Block header
     Header(SecuredHeader),
+    /// Block header with the ordered operation ID prefixes of the block,
+    /// so that the receiver can rebuild the block from the operations it already knows
+    CompactHeader {
+        /// Block header
+        header: SecuredHeader,
+        /// Prefixes of the operation IDs of the block, in block order
+        operation_prefix_ids: Vec<OperationPrefixId>,
+    },
     /// Message asking the peer for info on a list of blocks.
     DataRequest {
         /// ID of the block to ask info for.
@@ -64,6 +76,8 @@ pub enum BlockMessage {
         /// Block info reply.
         block_info: BlockInfoReply,
     },
+    /// Announcement that the sender understands compact headers and operation requests by prefix
+    CompactRelaySupport,
 }
 
 #[derive(IntoPrimitive, Debug, Eq, PartialEq, TryFromPrimitive)]
@@ -72,6 +86,8 @@ pub enum MessageTypeId {
     Header,
     DataRequest,
     DataResponse,
+    CompactHeader,
+    CompactRelaySupport,
 }
 
 impl From<&BlockMessage> for MessageTypeId {
@@ -80,6 +96,8 @@ impl From<&BlockMessage> for MessageTypeId {
             BlockMessage::Header(_) => MessageTypeId::Header,
             BlockMessage::DataRequest { .. } => MessageTypeId::DataRequest,
             BlockMessage::DataResponse { .. } => MessageTypeId::DataResponse,
+            BlockMessage::CompactHeader { .. } => MessageTypeId::CompactHeader,
+            BlockMessage::CompactRelaySupport => MessageTypeId::CompactRelaySupport,
         }
     }
 }
@@ -91,6 +109,7 @@ pub enum BlockInfoType {
     OperationIds = 1,
     Operations = 2,
     NotFound = 3,
+    OperationsByPrefix = 4,
 }
 
 #[derive(Default, Clone)]
@@ -130,6 +149,17 @@ impl Serializer<BlockMessage> for BlockMessageSerializer {
             BlockMessage::Header(header) => {
                 self.secure_share_serializer.serialize(header, buffer)?;
             }
+            BlockMessage::CompactHeader {
+                header,
+                operation_prefix_ids,
+            } => {
+                self.secure_share_serializer.serialize(header, buffer)?;
+                self.length_serializer
+                    .serialize(&(operation_prefix_ids.len() as u64), buffer)?;
+                for prefix in operation_prefix_ids {
+                    buffer.extend(Vec::<u8>::from(prefix));
+                }
+            }
             BlockMessage::DataRequest {
                 block_id,
                 block_info,
@@ -154,6 +184,15 @@ impl Serializer<BlockMessage> for BlockMessageSerializer {
                                 .serialize(operation_id, buffer)?;
                         }
                     }
+                    AskForBlockInfo::OperationsByPrefix(prefixes) => {
+                        self.id_serializer
+                            .serialize(&(BlockInfoType::OperationsByPrefix as u64), buffer)?;
+                        self.length_serializer
+                            .serialize(&(prefixes.len() as u64), buffer)?;
+                        for prefix in prefixes {
+                            buffer.extend(Vec::<u8>::from(prefix));
+                        }
+                    }
                 }
             }
             BlockMessage::DataResponse {
@@ -192,6 +231,7 @@ impl Serializer<BlockMessage> for BlockMessageSerializer {
                     }
                 }
             }
+            BlockMessage::CompactRelaySupport => {}
         }
         Ok(())
     }
@@ -202,6 +242,8 @@ pub struct BlockMessageDeserializer {
     block_header_deserializer: SecureShareDeserializer<BlockHeader, BlockHeaderDeserializer>,
     block_id_deserializer: BlockIdDeserializer,
     operation_ids_deserializer: OperationIdsDeserializer,
+    operation_prefix_ids_length_deserializer: U32VarIntDeserializer,
+    operation_prefix_id_deserializer: OperationPrefixIdDeserializer,
     operations_deserializer: OperationsDeserializer,
 }
 
@@ -233,6 +275,11 @@ impl BlockMessageDeserializer {
             operation_ids_deserializer: OperationIdsDeserializer::new(
                 args.max_operations_per_block,
             ),
+            operation_prefix_ids_length_deserializer: U32VarIntDeserializer::new(
+                Included(0),
+                Included(args.max_operations_per_block),
+            ),
+            operation_prefix_id_deserializer: OperationPrefixIdDeserializer::new(),
             operations_deserializer: OperationsDeserializer::new(
                 args.max_operations_per_block,
                 args.max_datastore_value_length,
@@ -246,6 +293,28 @@ impl BlockMessageDeserializer {
     }
 }
 
+impl BlockMessageDeserializer {
+    /// Deserialize an ordered list of operation ID prefixes
+    fn deserialize_operation_prefix_ids<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], Vec<OperationPrefixId>, E> {
+        context(
+            "Failed operation prefix ids deserialization",
+            length_count(
+                context("Failed length deserialization", |input| {
+                    self.operation_prefix_ids_length_deserializer
+                        .deserialize(input)
+                }),
+                context("Failed OperationPrefixId deserialization", |input| {
+                    self.operation_prefix_id_deserializer.deserialize(input)
+                }),
+            ),
+        )
+        .parse(buffer)
+    }
+}
+
 impl Deserializer<BlockMessage> for BlockMessageDeserializer {
     fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
         &self,
@@ -265,6 +334,22 @@ impl Deserializer<BlockMessage> for BlockMessageDeserializer {
                 })
                 .map(BlockMessage::Header)
                 .parse(buffer),
+                MessageTypeId::CompactHeader => context(
+                    "Failed CompactHeader deserialization",
+                    tuple((
+                        context("Failed BlockHeader deserialization", |input| {
+                            self.block_header_deserializer.deserialize(input)
+                        }),
+                        |input| self.deserialize_operation_prefix_ids(input),
+                    )),
+                )
+                .map(
+                    |(header, operation_prefix_ids)| BlockMessage::CompactHeader {
+                        header,
+                        operation_prefix_ids,
+                    },
+                )
+                .parse(buffer),
                 MessageTypeId::DataRequest => context(
                     "Failed BlockDataRequest deserialization",
                     tuple((
@@ -292,6 +377,11 @@ impl Deserializer<BlockMessage> for BlockMessageDeserializer {
                                     .map(|(rest, operation_ids)| {
                                         (rest, AskForBlockInfo::Operations(operation_ids))
                                     }),
+                                BlockInfoType::OperationsByPrefix => self
+                                    .deserialize_operation_prefix_ids(rest)
+                                    .map(|(rest, prefixes)| {
+                                        (rest, AskForBlockInfo::OperationsByPrefix(prefixes))
+                                    }),
                                 BlockInfoType::NotFound => {
                                     Err(nom::Err::Error(ParseError::from_error_kind(
                                         buffer,
@@ -341,6 +431,12 @@ impl Deserializer<BlockMessage> for BlockMessageDeserializer {
                                         (rest, BlockInfoReply::Operations(operations))
                                     }),
                                 BlockInfoType::NotFound => Ok((rest, BlockInfoReply::NotFound)),
+                                BlockInfoType::OperationsByPrefix => {
+                                    Err(nom::Err::Error(ParseError::from_error_kind(
+                                        buffer,
+                                        nom::error::ErrorKind::Digit,
+                                    )))
+                                }
                             }
                         }),
                     )),
@@ -350,6 +446,9 @@ impl Deserializer<BlockMessage> for BlockMessageDeserializer {
                     block_info,
                 })
                 .parse(buffer),
+                MessageTypeId::CompactRelaySupport => {
+                    Ok((buffer, BlockMessage::CompactRelaySupport))
+                }
             }
         })
         .parse(buffer)
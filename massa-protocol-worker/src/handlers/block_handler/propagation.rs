@@ -24,10 +24,12 @@ use crossbeam::channel::RecvTimeoutError;
 use massa_channel::{receiver::MassaReceiver, sender::MassaSender};
 use massa_models::block_header::SecuredHeader;
 use massa_models::block_id::BlockId;
+use massa_models::operation::OperationPrefixId;
 use massa_protocol_exports::PeerId;
-use massa_protocol_exports::{ProtocolConfig, ProtocolError};
+use massa_protocol_exports::{PeerBan, ProtocolConfig, ProtocolError};
 use massa_storage::Storage;
 use schnellru::{ByLength, LruMap};
+use std::collections::HashSet;
 use std::thread::JoinHandle;
 use std::time::Instant;
 use tracing::{debug, info, warn};
@@ -40,6 +42,8 @@ struct BlockPropagationData {
     pub _storage: Storage,
     /// Clone of the block header to avoid locking storage during propagation
     pub header: SecuredHeader,
+    /// Prefixes of the operation IDs of the block, announced with the header in compact block relay
+    pub operation_prefix_ids: Vec<OperationPrefixId>,
 }
 
 pub struct PropagationThread {
@@ -57,6 +61,8 @@ pub struct PropagationThread {
     peer_cmd_sender: MassaSender<PeerManagementCmd>,
     /// Serializer for block-related messages
     block_serializer: MessagesSerializer,
+    /// Connected peers to which we announced our support of compact block relay
+    compact_relay_announced_to: HashSet<PeerId>,
 }
 
 impl PropagationThread {
@@ -73,13 +79,20 @@ impl PropagationThread {
                         BlockHandlerPropagationCommand::IntegratedBlock { block_id, storage } => {
                             debug!("received IntegratedBlock({})", block_id);
 
-                            // get the block header
-                            let header = match storage
-                                .read_blocks()
-                                .get(&block_id)
-                                .map(|block| block.content.header.clone())
-                            {
-                                Some(h) => h,
+                            // get the block header and the prefixes of its operations
+                            let block_data = storage.read_blocks().get(&block_id).map(|block| {
+                                (
+                                    block.content.header.clone(),
+                                    block
+                                        .content
+                                        .operations
+                                        .iter()
+                                        .map(|op_id| op_id.prefix())
+                                        .collect(),
+                                )
+                            });
+                            let (header, operation_prefix_ids) = match block_data {
+                                Some(data) => data,
                                 None => {
                                     warn!(
                                         "claimed block {} absent from storage on propagation",
@@ -97,6 +110,7 @@ impl PropagationThread {
                                     time_added: Instant::now(),
                                     _storage: storage,
                                     header,
+                                    operation_prefix_ids,
                                 },
                             );
 
@@ -122,7 +136,10 @@ impl PropagationThread {
                                     }
                                 })
                                 .collect();
-                            self.ban_peers(&peers_to_ban);
+                            self.ban_peers(
+                                &peers_to_ban,
+                                format!("knew the attack block {}", block_id),
+                            );
                         }
                         BlockHandlerPropagationCommand::Stop => {
                             info!("Stop block propagation thread");
@@ -169,9 +186,41 @@ impl PropagationThread {
         let peers_connected = self.active_connections.get_peer_ids_connected();
         let mut cache_lock = self.cache.write();
         cache_lock.update_cache(&peers_connected);
-        'peer_loop: for (peer_id, known_by_peer) in cache_lock.blocks_known_by_peer.iter_mut() {
-            for (block_id, BlockPropagationData { header, .. }) in
-                self.stored_for_propagation.iter()
+        let cache = &mut *cache_lock;
+        if self.config.compact_block_relay {
+            self.compact_relay_announced_to
+                .retain(|peer_id| peers_connected.contains(peer_id));
+        }
+        'peer_loop: for (peer_id, known_by_peer) in cache.blocks_known_by_peer.iter_mut() {
+            // tell the newly connected peers that we understand compact headers
+            if self.config.compact_block_relay && !self.compact_relay_announced_to.contains(peer_id)
+            {
+                match self.active_connections.send_to_peer(
+                    peer_id,
+                    &self.block_serializer,
+                    BlockMessage::CompactRelaySupport.into(),
+                    false,
+                ) {
+                    Ok(()) => {
+                        self.compact_relay_announced_to.insert(peer_id.clone());
+                    }
+                    Err(err) => {
+                        warn!(
+                            "Error while announcing compact block relay support to peer {} err: {:?}",
+                            peer_id, err
+                        );
+                    }
+                }
+            }
+
+            for (
+                block_id,
+                BlockPropagationData {
+                    header,
+                    operation_prefix_ids,
+                    ..
+                },
+            ) in self.stored_for_propagation.iter()
             {
                 // if the peer already knows about the block, do not propagate it
                 if let Some((true, _)) = known_by_peer.peek(block_id) {
@@ -180,10 +229,21 @@ impl PropagationThread {
 
                 // try to propagate
                 debug!("announcing header {} to peer {}", block_id, peer_id);
+                // only the peers that announced their support understand compact headers
+                let message = if self.config.compact_block_relay
+                    && cache.compact_relay_peers.contains(peer_id)
+                {
+                    BlockMessage::CompactHeader {
+                        header: header.clone(),
+                        operation_prefix_ids: operation_prefix_ids.clone(),
+                    }
+                } else {
+                    BlockMessage::Header(header.clone())
+                };
                 match self.active_connections.send_to_peer(
                     peer_id,
                     &self.block_serializer,
-                    BlockMessage::Header(header.clone()).into(),
+                    message.into(),
                     true,
                 ) {
                     Ok(()) => {
@@ -203,10 +263,16 @@ impl PropagationThread {
     }
 
     /// try to ban a list of peers
-    fn ban_peers(&mut self, peer_ids: &[PeerId]) {
+    fn ban_peers(&mut self, peer_ids: &[PeerId], reason: String) {
         if let Err(err) = self
             .peer_cmd_sender
-            .try_send(PeerManagementCmd::Ban(peer_ids.to_vec()))
+            .try_send(PeerManagementCmd::Ban(
+                peer_ids.to_vec(),
+                PeerBan {
+                    reason: Some(reason),
+                    expire_at: None,
+                },
+            ))
             .map_err(|err| ProtocolError::SendError(err.to_string()))
         {
             warn!("could not send Ban command to peer manager: {}", err);
@@ -239,6 +305,7 @@ pub fn start_propagation_thread(
                 peer_cmd_sender,
                 active_connections,
                 block_serializer,
+                compact_relay_announced_to: HashSet::new(),
             };
             propagation_thread.run();
         })
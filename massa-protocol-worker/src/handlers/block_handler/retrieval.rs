@@ -14,7 +14,10 @@ use crate::{
         operation_handler::{
             cache::SharedOperationCache, commands_propagation::OperationHandlerPropagationCommand,
         },
-        peer_handler::models::{PeerManagementCmd, PeerMessageTuple},
+        peer_handler::{
+            models::{PeerManagementCmd, PeerMessageTuple},
+            reputation::{report_peer, PeerReputationEvent},
+        },
     },
     messages::{Message, MessagesSerializer},
     wrap_network::ActiveConnectionsTrait,
@@ -33,7 +36,8 @@ use massa_models::{
     block_id::BlockId,
     endorsement::EndorsementId,
     operation::{
-        compute_operations_hash, OperationId, OperationIdSerializer, SecureShareOperation,
+        compute_operations_hash, OperationId, OperationIdSerializer, OperationPrefixId,
+        SecureShareOperation,
     },
     prehash::{PreHashMap, PreHashSet},
     secure_share::SecureShare,
@@ -42,13 +46,14 @@ use massa_models::{
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
 use massa_protocol_exports::PeerId;
-use massa_protocol_exports::{ProtocolConfig, ProtocolError};
+use massa_protocol_exports::{PeerBan, ProtocolConfig, ProtocolError};
 use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use massa_storage::Storage;
 use massa_time::TimeError;
 use massa_versioning::versioning::MipStore;
 use rand::thread_rng;
 use rand::{seq::SliceRandom, Rng};
+use schnellru::{ByLength, LruMap};
 use tracing::{debug, info, warn};
 
 use super::{
@@ -95,6 +100,9 @@ pub struct RetrievalThread {
     receiver: MassaReceiver<BlockHandlerRetrievalCommand>,
     block_message_serializer: MessagesSerializer,
     block_wishlist: PreHashMap<BlockId, BlockInfo>,
+    /// Operation ID prefixes announced with compact headers, by block.
+    /// `None` if the prefixes could not be resolved and the full operation ID list must be asked.
+    announced_operation_prefixes: LruMap<BlockId, Option<Vec<OperationPrefixId>>>,
     asked_blocks: HashMap<PeerId, PreHashMap<BlockId, Instant>>,
     peer_cmd_sender: MassaSender<PeerManagementCmd>,
     sender_propagation_ops: MassaSender<OperationHandlerPropagationCommand>,
@@ -139,6 +147,7 @@ impl RetrievalThread {
                                 Ok((rest, message)) => (rest, message),
                                 Err(err) => {
                                     warn!("Error in deserializing block message: {:?}", err);
+                                    report_peer(&self.peer_cmd_sender, &peer_id, PeerReputationEvent::InvalidMessage);
                                     continue;
                                 }
                             };
@@ -158,6 +167,13 @@ impl RetrievalThread {
                                     self.on_block_header_received(peer_id.clone(), header);
                                     self.update_block_retrieval();
                                 }
+                                BlockMessage::CompactHeader { header, operation_prefix_ids } => {
+                                    self.on_compact_header_received(peer_id.clone(), header, operation_prefix_ids);
+                                    self.update_block_retrieval();
+                                }
+                                BlockMessage::CompactRelaySupport => {
+                                    self.cache.write().compact_relay_peers.insert(peer_id.clone());
+                                }
                             }
                         },
                         Err(_) => {
@@ -325,6 +341,28 @@ impl RetrievalThread {
                         .collect::<PreHashSet<OperationId>>(),
                 );
 
+                BlockInfoReply::Operations(returned_ops)
+            }
+            (Some((_, block_op_ids)), AskForBlockInfo::OperationsByPrefix(asked_prefixes)) => {
+                // the peer asked for the operations of the block matching prefixes of a compact header
+                let asked_prefixes: PreHashSet<OperationPrefixId> =
+                    asked_prefixes.into_iter().collect();
+
+                // Send the operations that are available in storage
+                let returned_ops: Vec<_> = {
+                    let op_storage_lock = self.storage.read_operations();
+                    block_op_ids
+                        .iter()
+                        .filter(|id| asked_prefixes.contains(&id.prefix()))
+                        .filter_map(|id| op_storage_lock.get(id))
+                        .cloned()
+                        .collect()
+                };
+
+                // mark the peer as knowing about those operations,
+                // no need to announce their IDs to them anymore
+                operation_knowledge_updates.extend(returned_ops.iter().map(|op| op.id));
+
                 BlockInfoReply::Operations(returned_ops)
             }
         };
@@ -417,7 +455,9 @@ impl RetrievalThread {
     }
 
     /// On block header received from a node.
-    fn on_block_header_received(&mut self, from_peer_id: PeerId, header: SecuredHeader) {
+    ///
+    /// Returns false if the header was invalid.
+    fn on_block_header_received(&mut self, from_peer_id: PeerId, header: SecuredHeader) -> bool {
         debug!("received header {} from {}", header.id, from_peer_id);
 
         let block_id = header.id;
@@ -430,10 +470,13 @@ impl RetrievalThread {
                     "peer {} sent us critically incorrect header: {}",
                     &from_peer_id, err
                 );
-                if let Err(err) = self.ban_peers(&[from_peer_id.clone()]) {
+                if let Err(err) = self.ban_peers(
+                    &[from_peer_id.clone()],
+                    format!("sent critically incorrect header: {}", err),
+                ) {
                     warn!("Error while banning peer {} err: {:?}", &from_peer_id, err);
                 }
-                return;
+                return false;
             }
         };
 
@@ -442,6 +485,11 @@ impl RetrievalThread {
 
             if info.header.is_none() {
                 // we were looking for the missing header
+                report_peer(
+                    &self.peer_cmd_sender,
+                    &from_peer_id,
+                    PeerReputationEvent::UsefulData,
+                );
 
                 // save the header
                 info.header = Some(header);
@@ -457,6 +505,26 @@ impl RetrievalThread {
             self.consensus_controller
                 .register_block_header(block_id, header);
         }
+        true
+    }
+
+    /// On compact header received from a node: the header is handled as a regular header,
+    /// and the announced operation ID prefixes are kept to rebuild the block once it is wanted.
+    fn on_compact_header_received(
+        &mut self,
+        from_peer_id: PeerId,
+        header: SecuredHeader,
+        operation_prefix_ids: Vec<OperationPrefixId>,
+    ) {
+        let block_id = header.id;
+        if !self.on_block_header_received(from_peer_id, header) {
+            return;
+        }
+        // keep the first announced prefixes, and do not retry blocks whose prefixes could not be resolved
+        if self.announced_operation_prefixes.peek(&block_id).is_none() {
+            self.announced_operation_prefixes
+                .insert(block_id, Some(operation_prefix_ids));
+        }
     }
 
     /// Check if the incoming header network version is compatible with the current node
@@ -654,9 +722,15 @@ impl RetrievalThread {
     }
 
     /// send a ban peer command to the peer handler
-    fn ban_peers(&mut self, peer_ids: &[PeerId]) -> Result<(), ProtocolError> {
+    fn ban_peers(&mut self, peer_ids: &[PeerId], reason: String) -> Result<(), ProtocolError> {
         self.peer_cmd_sender
-            .try_send(PeerManagementCmd::Ban(peer_ids.to_vec()))
+            .try_send(PeerManagementCmd::Ban(
+                peer_ids.to_vec(),
+                PeerBan {
+                    reason: Some(reason),
+                    expire_at: None,
+                },
+            ))
             .map_err(|err| ProtocolError::SendError(err.to_string()))
     }
 
@@ -691,7 +765,10 @@ impl RetrievalThread {
             }
         }
         if !peers_to_ban.is_empty() {
-            if let Err(err) = self.ban_peers(&peers_to_ban) {
+            if let Err(err) = self.ban_peers(
+                &peers_to_ban,
+                format!("knew the invalid block {}", block_id),
+            ) {
                 warn!(
                     "Error while banning peers {:?} err: {:?}",
                     peers_to_ban, err
@@ -743,6 +820,11 @@ impl RetrievalThread {
         } else {
             // we were not actively looking for that data, but mark the remote node as knowing the block
             debug!("peer {} sent us a list of operation IDs for block id {} but we were not looking for it", from_peer_id, block_id);
+            report_peer(
+                &self.peer_cmd_sender,
+                &from_peer_id,
+                PeerReputationEvent::UnrequestedData,
+            );
             self.cache
                 .write()
                 .insert_peer_known_block(&from_peer_id, &[block_id], true);
@@ -762,7 +844,13 @@ impl RetrievalThread {
             != computed_operations_hash
         {
             warn!("Peer id {} sent us a operation list for block id {} but the hash in the header doesn't match.", from_peer_id, block_id);
-            if let Err(err) = self.ban_peers(&[from_peer_id.clone()]) {
+            if let Err(err) = self.ban_peers(
+                &[from_peer_id.clone()],
+                format!(
+                    "sent an operation list not matching the header of block {}",
+                    block_id
+                ),
+            ) {
                 warn!("Error while banning peer {} err: {:?}", from_peer_id, err);
             }
             return;
@@ -773,8 +861,22 @@ impl RetrievalThread {
             .write()
             .insert_peer_known_block(&from_peer_id, &[block_id], true);
 
+        // Release the operations received for a compact relay of the block that do not belong to it
+        let unrelated_ops: PreHashSet<OperationId> = wishlist_info
+            .storage
+            .get_op_refs()
+            .difference(&operation_ids_set)
+            .copied()
+            .collect();
+        wishlist_info.storage.drop_operation_refs(&unrelated_ops);
+
         // Save the received operation ID list to the wishlist
         wishlist_info.operation_ids = Some(operation_ids);
+        report_peer(
+            &self.peer_cmd_sender,
+            &from_peer_id,
+            PeerReputationEvent::UsefulData,
+        );
 
         // free up all the nodes that we asked for that operation list
         self.remove_asked_blocks(&[block_id].into_iter().collect());
@@ -802,6 +904,25 @@ impl RetrievalThread {
             block_id, &from_peer_id
         );
 
+        // The operations may have been asked by prefix to rebuild a block announced with a compact header
+        if let Some(Some(operation_prefix_ids)) =
+            self.announced_operation_prefixes.peek(&block_id).cloned()
+        {
+            if self
+                .block_wishlist
+                .get(&block_id)
+                .map_or(false, |i| i.header.is_some() && i.operation_ids.is_none())
+            {
+                self.on_compact_block_operations_received(
+                    from_peer_id,
+                    block_id,
+                    operation_prefix_ids,
+                    operations,
+                );
+                return;
+            }
+        }
+
         // Ensure that we were looking for that data.
         let wishlist_info = if let Some(info) = self
             .block_wishlist
@@ -815,6 +936,11 @@ impl RetrievalThread {
                 "Peer id {} sent us full operations for block id {} but we were not looking for it",
                 from_peer_id, block_id
             );
+            report_peer(
+                &self.peer_cmd_sender,
+                &from_peer_id,
+                PeerReputationEvent::UnrequestedData,
+            );
             // still mark the sender as knowing the block and operations
             self.cache
                 .write()
@@ -887,13 +1013,23 @@ impl RetrievalThread {
                 "Peer id {} sent us operations for block id {} but they failed validity checks: {}",
                 from_peer_id, block_id, err
             );
-            if let Err(err) = self.ban_peers(&[from_peer_id.clone()]) {
+            if let Err(err) = self.ban_peers(
+                &[from_peer_id.clone()],
+                format!("sent invalid operations for block {}: {}", block_id, err),
+            ) {
                 warn!("Error while banning peer {} err: {:?}", from_peer_id, err);
             }
             return;
         }
 
         // add received operations to local storage and claim ref
+        if !operations.is_empty() {
+            report_peer(
+                &self.peer_cmd_sender,
+                &from_peer_id,
+                PeerReputationEvent::UsefulData,
+            );
+        }
         wishlist_info
             .storage
             .store_operations(operations.into_values().collect());
@@ -920,6 +1056,140 @@ impl RetrievalThread {
         }
     }
 
+    /// We received operations of a block that we are rebuilding from the operation ID prefixes of its compact header.
+    fn on_compact_block_operations_received(
+        &mut self,
+        from_peer_id: PeerId,
+        block_id: BlockId,
+        operation_prefix_ids: Vec<OperationPrefixId>,
+        operations: Vec<SecureShareOperation>,
+    ) {
+        // mark the sender as knowing the operations
+        self.operation_cache.write().insert_peer_known_ops(
+            &from_peer_id,
+            &operations
+                .iter()
+                .map(|op| op.id.prefix())
+                .collect::<Vec<_>>(),
+        );
+
+        // keep only the operations matching the announced prefixes
+        let operation_prefix_ids: PreHashSet<OperationPrefixId> =
+            operation_prefix_ids.into_iter().collect();
+        let operations: Vec<SecureShareOperation> = operations
+            .into_iter()
+            .filter(|op| operation_prefix_ids.contains(&op.id.prefix()))
+            .collect();
+
+        // Check the validity of the received operations.
+        if let Err(err) = note_operations_from_peer(
+            &self.storage,
+            &mut self.operation_cache,
+            &self.config,
+            operations.clone(),
+            &from_peer_id,
+            &mut self.sender_propagation_ops,
+            &mut self.pool_controller,
+        ) {
+            warn!(
+                "Peer id {} sent us operations for block id {} but they failed validity checks: {}",
+                from_peer_id, block_id, err
+            );
+            if let Err(err) = self.ban_peers(
+                &[from_peer_id.clone()],
+                format!("sent invalid operations for block {}: {}", block_id, err),
+            ) {
+                warn!("Error while banning peer {} err: {:?}", from_peer_id, err);
+            }
+            return;
+        }
+
+        // the sender is no longer asked for the block:
+        // the remaining prefixes are resolved or asked again at the next block retrieval update
+        if let Some(asked) = self.asked_blocks.get_mut(&from_peer_id) {
+            asked.remove(&block_id);
+        }
+        self.cache.write().insert_peer_known_block(
+            &from_peer_id,
+            &[block_id],
+            !operations.is_empty(),
+        );
+        if operations.is_empty() {
+            return;
+        }
+        report_peer(
+            &self.peer_cmd_sender,
+            &from_peer_id,
+            PeerReputationEvent::UsefulData,
+        );
+
+        // add received operations to the storage of the block and claim ref
+        if let Some(wishlist_info) = self.block_wishlist.get_mut(&block_id) {
+            wishlist_info.storage.store_operations(operations);
+        }
+    }
+
+    /// Try to rebuild the operation ID list of a wanted block from the operation ID prefixes of its compact header,
+    /// using the operations we already know from the pool and other blocks.
+    ///
+    /// Returns the request to send to a peer to continue the retrieval of the block,
+    /// or `None` if there is nothing to ask.
+    fn resolve_compact_block(&mut self, block_id: &BlockId) -> Option<AskForBlockInfo> {
+        let Some(Some(operation_prefix_ids)) =
+            self.announced_operation_prefixes.peek(block_id).cloned()
+        else {
+            return Some(AskForBlockInfo::OperationIds);
+        };
+
+        let mut operation_ids = Vec::with_capacity(operation_prefix_ids.len());
+        let mut missing_prefixes = Vec::new();
+        let mut collision = false;
+        {
+            let op_read_lock = self.storage.read_operations();
+            for prefix in operation_prefix_ids {
+                match op_read_lock.get_operations_by_prefix(&prefix) {
+                    Some(ids) if ids.len() > 1 => {
+                        // several known operations share that prefix
+                        collision = true;
+                        break;
+                    }
+                    Some(ids) if ids.len() == 1 => operation_ids.extend(ids.iter().copied()),
+                    _ => missing_prefixes.push(prefix),
+                }
+            }
+        }
+
+        if !collision && !missing_prefixes.is_empty() {
+            return Some(AskForBlockInfo::OperationsByPrefix(missing_prefixes));
+        }
+
+        let wishlist_info = self.block_wishlist.get_mut(block_id)?;
+        let operation_merkle_root = wishlist_info
+            .header
+            .as_ref()
+            .expect("header presence in wishlist should have been checked before")
+            .content
+            .operation_merkle_root;
+        if collision
+            || compute_operations_hash(&operation_ids, &self.operation_id_serializer)
+                != operation_merkle_root
+        {
+            // a prefix matches several operations, or an operation that is not the one of the block:
+            // fall back to the full list of operation IDs
+            debug!(
+                "could not rebuild block {} from the operation prefixes of its compact header",
+                block_id
+            );
+            self.announced_operation_prefixes.insert(*block_id, None);
+            return Some(AskForBlockInfo::OperationIds);
+        }
+
+        // the operation list of the block is rebuilt: gather its operations as if we had received the list
+        wishlist_info.operation_ids = Some(operation_ids);
+        self.gather_missing_block_ops(block_id)
+            .map(AskForBlockInfo::Operations)
+    }
+
     /// function that updates the global state of block retrieval
     pub(crate) fn update_block_retrieval(&mut self) {
         let ask_block_timeout = self.config.ask_block_timeout.to_duration();
@@ -959,6 +1229,11 @@ impl RetrievalThread {
                     .expect("could not compute block ask expiry");
                 if expiry <= now {
                     // the block has been asked for the block data a long time agp and did not respond
+                    report_peer(
+                        &self.peer_cmd_sender,
+                        peer_id,
+                        PeerReputationEvent::SlowReply,
+                    );
 
                     // we mark this peer as not knowing this block
                     self.cache
@@ -1059,8 +1334,12 @@ impl RetrievalThread {
             ) {
                 // ask for header
                 (false, false) => AskForBlockInfo::Header,
-                // ask for the list of operation IDs in the block
-                (true, false) => AskForBlockInfo::OperationIds,
+                // ask for the list of operation IDs in the block,
+                // unless it can be rebuilt from the operation ID prefixes of a compact header
+                (true, false) => match self.resolve_compact_block(&block_id) {
+                    Some(request) => request,
+                    None => continue,
+                },
                 // ask for missing operations in the block
                 (true, true) => {
                     // gather missing block operations and perform necessary followups
@@ -1279,6 +1558,12 @@ pub fn start_retrieval_thread(
                 pool_controller,
                 next_timer_ask_block: Instant::now() + config.ask_block_timeout.to_duration(),
                 block_wishlist: PreHashMap::default(),
+                announced_operation_prefixes: LruMap::new(ByLength::new(
+                    config
+                        .max_known_blocks_size
+                        .try_into()
+                        .expect("max_known_blocks_size does not fit in u32"),
+                )),
                 asked_blocks: HashMap::default(),
                 peer_cmd_sender,
                 sender_propagation_ops,
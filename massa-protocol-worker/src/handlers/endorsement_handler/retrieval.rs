@@ -12,7 +12,7 @@ use massa_models::{
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
 use massa_protocol_exports::PeerId;
-use massa_protocol_exports::{ProtocolConfig, ProtocolError};
+use massa_protocol_exports::{PeerBan, ProtocolConfig, ProtocolError};
 use massa_serialization::{DeserializeError, Deserializer};
 use massa_storage::Storage;
 use massa_time::MassaTime;
@@ -21,7 +21,10 @@ use tracing::{debug, info, warn};
 use crate::{
     handlers::{
         endorsement_handler::messages::EndorsementMessage,
-        peer_handler::models::{PeerManagementCmd, PeerMessageTuple},
+        peer_handler::{
+            models::{PeerManagementCmd, PeerMessageTuple},
+            reputation::{report_peer, PeerReputationEvent},
+        },
     },
     sig_verifier::verify_sigs_batch,
 };
@@ -108,6 +111,11 @@ impl RetrievalThread {
                     "Error while deserializing message from peer {} err: {:?}",
                     peer_id, err
                 );
+                report_peer(
+                    &self.peer_cmd_sender,
+                    &peer_id,
+                    PeerReputationEvent::InvalidMessage,
+                );
                 return;
             }
         };
@@ -134,7 +142,10 @@ impl RetrievalThread {
                         loss of sync between us and the remote node. Err = {}",
                         peer_id, err
                     );
-                    if let Err(err) = self.ban_peer(&peer_id) {
+                    if let Err(err) = self.ban_peer(
+                        &peer_id,
+                        format!("sent critically incorrect endorsements: {}", err),
+                    ) {
                         warn!("Error while banning peer {} err: {:?}", peer_id, err);
                     }
                 }
@@ -143,10 +154,16 @@ impl RetrievalThread {
     }
 
     /// send a ban peer command to the peer handler
-    fn ban_peer(&mut self, peer_id: &PeerId) -> Result<(), ProtocolError> {
+    fn ban_peer(&mut self, peer_id: &PeerId, reason: String) -> Result<(), ProtocolError> {
         massa_trace!("ban node from retrieval thread", { "peer_id": peer_id.to_string() });
         self.peer_cmd_sender
-            .try_send(PeerManagementCmd::Ban(vec![peer_id.clone()]))
+            .try_send(PeerManagementCmd::Ban(
+                vec![peer_id.clone()],
+                PeerBan {
+                    reason: Some(reason),
+                    expire_at: None,
+                },
+            ))
             .map_err(|err| ProtocolError::SendError(err.to_string()))
     }
 }
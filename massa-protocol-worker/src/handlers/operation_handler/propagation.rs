@@ -15,8 +15,8 @@ use massa_storage::Storage;
 use tracing::{debug, info, log::warn};
 
 use crate::{
-    handlers::operation_handler::OperationMessage, messages::MessagesSerializer,
-    wrap_network::ActiveConnectionsTrait,
+    bandwidth::TrafficClass, handlers::operation_handler::OperationMessage,
+    messages::MessagesSerializer, wrap_network::ActiveConnectionsTrait,
 };
 
 use super::{
@@ -154,6 +154,14 @@ impl PropagationThread {
             // Propagate to peers
             let all_keys: Vec<PeerId> = cache_write.ops_known_by_peer.keys().cloned().collect();
             for peer_id in all_keys {
+                // operations are not announced to the peers that are over their bandwidth quota,
+                // they will get them from other peers
+                if !self
+                    .active_connections
+                    .has_bandwidth(&peer_id, TrafficClass::Operations)
+                {
+                    continue;
+                }
                 let ops = cache_write.ops_known_by_peer.get_mut(&peer_id).unwrap();
                 let new_ops: Vec<OperationId> = operation_ids
                     .iter()
@@ -13,14 +13,17 @@ use massa_models::{
 };
 use massa_pool_exports::PoolController;
 use massa_protocol_exports::PeerId;
-use massa_protocol_exports::{ProtocolConfig, ProtocolError};
+use massa_protocol_exports::{PeerBan, ProtocolConfig, ProtocolError};
 use massa_serialization::{DeserializeError, Deserializer};
 use massa_storage::Storage;
 use massa_time::{MassaTime, TimeError};
 use schnellru::{ByLength, LruMap};
 
 use crate::{
-    handlers::peer_handler::models::{PeerManagementCmd, PeerMessageTuple},
+    handlers::peer_handler::{
+        models::{PeerManagementCmd, PeerMessageTuple},
+        reputation::{report_peer, PeerReputationEvent},
+    },
     messages::MessagesSerializer,
     sig_verifier::verify_sigs_batch,
     wrap_network::ActiveConnectionsTrait,
@@ -89,6 +92,7 @@ impl RetrievalThread {
                                     Ok((rest, message)) => (rest, message),
                                     Err(err) => {
                                         warn!("Error when deserializing message from peer {}: Err = {}", peer_id, err);
+                                        report_peer(&self.peer_cmd_sender, &peer_id, PeerReputationEvent::InvalidMessage);
                                         continue;
                                     }
                                 };
@@ -99,6 +103,9 @@ impl RetrievalThread {
                             match message {
                                 OperationMessage::Operations(ops) => {
                                     debug!("Received operation message: Operations from {}", peer_id);
+                                    if self.is_unrequested(&ops, &peer_id) {
+                                        report_peer(&self.peer_cmd_sender, &peer_id, PeerReputationEvent::UnrequestedData);
+                                    }
                                     if let Err(err) = note_operations_from_peer(
                                         &self.storage,
                                         &mut self.cache,
@@ -110,13 +117,16 @@ impl RetrievalThread {
                                     ) {
                                         warn!("peer {} sent us critically incorrect operation, which may be an attack attempt by the remote peer or a loss of sync between us and the remote peer. Err = {}", peer_id, err);
 
-                                        if let Err(e) = self.ban_node(&peer_id) {
+                                        if let Err(e) = self.ban_node(&peer_id, format!("sent critically incorrect operations: {}", err)) {
                                             warn!("Error when banning node: {}", e);
                                         }
                                     }
                                 }
                                 OperationMessage::OperationsAnnouncement(announcement) => {
                                     debug!("Received operation message: OperationsAnnouncement from {}", peer_id);
+                                    if self.is_already_announced(&announcement, &peer_id) {
+                                        report_peer(&self.peer_cmd_sender, &peer_id, PeerReputationEvent::Spam);
+                                    }
                                     if let Err(err) =
                                         self.on_operations_announcements_received(announcement, &peer_id)
                                     {
@@ -351,11 +361,42 @@ impl RetrievalThread {
         Ok(())
     }
 
+    /// Returns true if none of the operations were asked to the peer
+    fn is_unrequested(&self, ops: &[SecureShareOperation], peer_id: &PeerId) -> bool {
+        !ops.is_empty()
+            && ops.iter().all(|op| {
+                self.asked_operations
+                    .peek(&op.id.prefix())
+                    .map_or(true, |(_, asked_peers)| !asked_peers.contains(peer_id))
+            })
+    }
+
+    /// Returns true if the peer already announced all the operations of the announcement
+    fn is_already_announced(&self, op_batch: &OperationPrefixIds, peer_id: &PeerId) -> bool {
+        !op_batch.is_empty()
+            && self
+                .cache
+                .read()
+                .ops_known_by_peer
+                .get(peer_id)
+                .map_or(false, |known_ops| {
+                    op_batch
+                        .iter()
+                        .all(|prefix| known_ops.peek(prefix).is_some())
+                })
+    }
+
     /// send a ban peer command to the peer handler
-    fn ban_node(&mut self, peer_id: &PeerId) -> Result<(), ProtocolError> {
+    fn ban_node(&mut self, peer_id: &PeerId, reason: String) -> Result<(), ProtocolError> {
         massa_trace!("ban node from retrieval thread", { "peer_id": peer_id.to_string() });
         self.peer_cmd_sender
-            .try_send(PeerManagementCmd::Ban(vec![peer_id.clone()]))
+            .try_send(PeerManagementCmd::Ban(
+                vec![peer_id.clone()],
+                PeerBan {
+                    reason: Some(reason),
+                    expire_at: None,
+                },
+            ))
             .map_err(|err| ProtocolError::SendError(err.to_string()))
     }
 }
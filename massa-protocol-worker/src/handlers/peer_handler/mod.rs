@@ -1,4 +1,5 @@
 use std::net::IpAddr;
+use std::time::Instant;
 use std::{collections::HashMap, net::SocketAddr, thread::JoinHandle, time::Duration};
 
 use crossbeam::channel::tick;
@@ -9,10 +10,11 @@ use massa_metrics::MassaMetrics;
 use massa_models::config::SIGNATURE_DESER_SIZE;
 use massa_models::version::{VersionDeserializer, VersionSerializer};
 use massa_protocol_exports::{
-    BootstrapPeers, PeerId, PeerIdDeserializer, PeerIdSerializer, ProtocolConfig,
+    BootstrapPeers, PeerBan, PeerId, PeerIdDeserializer, PeerIdSerializer, ProtocolConfig,
 };
 use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use massa_signature::Signature;
+use massa_time::MassaTime;
 use peernet::context::Context as _;
 use peernet::messages::MessagesSerializer as _;
 use rand::{rngs::StdRng, RngCore, SeedableRng};
@@ -25,12 +27,14 @@ use peernet::{
 };
 use tracing::log::{debug, error, info, warn};
 
+use crate::bandwidth::TrafficClass;
 use crate::context::Context;
 use crate::handlers::peer_handler::models::PeerState;
 use crate::messages::{Message, MessagesHandler, MessagesSerializer};
 use crate::wrap_network::ActiveConnectionsTrait;
 
-use self::models::{ConnectionMetadata, PeerInfo};
+use self::models::{flush_peer_db, ConnectionMetadata, PeerInfo};
+use self::reputation::PeerReputationEvent;
 use self::{
     models::{
         InitialPeers, PeerManagementChannel, PeerManagementCmd, PeerMessageTuple, SharedPeerDB,
@@ -52,6 +56,7 @@ use self::{
 mod announcement;
 mod messages;
 pub mod models;
+pub mod reputation;
 mod tester;
 
 pub(crate) use messages::{PeerManagementMessage, PeerManagementMessageSerializer};
@@ -102,6 +107,7 @@ impl PeerManagementHandler {
             let peer_db = peer_db.clone();
             let ticker = tick(Duration::from_secs(10));
             let config = config.clone();
+            let tick_flush = tick(config.peers_file_dump_interval.to_duration());
             let message_serializer = MessagesSerializer::new()
                 .with_peer_management_message_serializer(PeerManagementMessageSerializer::new());
             let message_deserializer =
@@ -114,6 +120,7 @@ impl PeerManagementHandler {
                 loop {
                     select! {
                         recv(ticker) -> _ => {
+                            peer_db.write().reputation.recover();
                             let peers_to_send = peer_db.read().get_rand_peers_to_send(100);
                             if peers_to_send.is_empty() {
                                 continue;
@@ -122,29 +129,43 @@ impl PeerManagementHandler {
                             let msg = PeerManagementMessage::ListPeers(peers_to_send);
 
                             for peer_id in &active_connections.get_peer_ids_connected() {
+                                // peer announcements are the first traffic to give up when a quota runs low
+                                if !active_connections.has_bandwidth(peer_id, TrafficClass::PeerManagement) {
+                                    continue;
+                                }
                                 if let Err(e) = active_connections
                                     .send_to_peer(peer_id, &message_serializer, msg.clone().into(), false) {
                                     error!("error sending ListPeers message to peer: {:?}", e);
                                }
                             }
                         }
+                        recv(tick_flush) -> _ => {
+                            match MassaTime::now() {
+                                Ok(now) => peer_db.write().unban_expired_peers(now),
+                                Err(err) => warn!("could not get the current time: {}", err),
+                            }
+                            flush_peer_db(&peer_db, &config.peers_file);
+                        }
                         recv(receiver_cmd) -> cmd => {
                             receiver_cmd.update_metrics();
                             // internal command
                            match cmd {
-                             Ok(PeerManagementCmd::Ban(peer_ids)) => {
+                             Ok(PeerManagementCmd::Ban(peer_ids, ban)) => {
                                 // remove running handshake ?
                                 for peer_id in peer_ids {
                                     active_connections.shutdown_connection(&peer_id);
 
                                     // update peer_db
-                                    peer_db.write().ban_peer(&peer_id);
+                                    peer_db.write().ban_peer(&peer_id, ban.clone());
                                 }
                             },
                              Ok(PeerManagementCmd::Unban(peer_ids)) => {
                                 for peer_id in peer_ids {
                                     peer_db.write().unban_peer(&peer_id);
                                 }
+                            },
+                             Ok(PeerManagementCmd::Report(peer_id, event)) => {
+                                apply_reputation_event(&peer_db, active_connections.as_mut(), &config, &peer_id, event);
                             },
                              Ok(PeerManagementCmd::GetBootstrapPeers { responder }) => {
                                 let mut peers = peer_db.read().get_rand_peers_to_send(100);
@@ -159,10 +180,17 @@ impl PeerManagementHandler {
                                     warn!("error sending bootstrap peers: {:?}", err);
                                 }
                              },
+                             Ok(PeerManagementCmd::GetKnownPeers { responder }) => {
+                                let known_peers = peer_db.read().get_known_peers();
+                                if let Err(err) = responder.try_send(known_peers) {
+                                    warn!("error sending known peers: {:?}", err);
+                                }
+                             },
                              Ok(PeerManagementCmd::Stop) => {
                                 while let Ok(_msg) = test_receiver.try_recv() {
                                     // nothing to do just clean the channel
                                 }
+                                flush_peer_db(&peer_db, &config.peers_file);
                                 return;
                              },
                             Err(e) => {
@@ -190,6 +218,7 @@ impl PeerManagementHandler {
                                 Ok((rest, message)) => (rest, message),
                                 Err(e) => {
                                     warn!("error when deserializing message: {:?}", e);
+                                    apply_reputation_event(&peer_db, active_connections.as_mut(), &config, &peer_id, PeerReputationEvent::InvalidMessage);
                                     continue;
                                 }
                             };
@@ -256,6 +285,46 @@ impl PeerManagementHandler {
     }
 }
 
+/// Update the reputation of a peer, and disconnect or ban it if its score went too low
+fn apply_reputation_event(
+    peer_db: &SharedPeerDB,
+    active_connections: &mut dyn ActiveConnectionsTrait,
+    config: &ProtocolConfig,
+    peer_id: &PeerId,
+    event: PeerReputationEvent,
+) {
+    let score = peer_db.write().reputation.report(peer_id, event);
+    if score <= config.peer_score_ban_threshold {
+        let now = match MassaTime::now() {
+            Ok(now) => now,
+            Err(err) => {
+                warn!("could not get the current time: {}", err);
+                return;
+            }
+        };
+        info!(
+            "banning peer {} with reputation score {} after {}",
+            peer_id, score, event
+        );
+        active_connections.shutdown_connection(peer_id);
+        let mut peer_db_write = peer_db.write();
+        peer_db_write.ban_peer(
+            peer_id,
+            PeerBan {
+                reason: Some(format!("reputation score {} after {}", score, event)),
+                expire_at: Some(now.saturating_add(config.peer_score_ban_duration)),
+            },
+        );
+        peer_db_write.reputation.reset(peer_id);
+    } else if score <= config.peer_score_disconnect_threshold && event.score_delta() < 0 {
+        debug!(
+            "disconnecting peer {} with reputation score {} after {}",
+            peer_id, score, event
+        );
+        active_connections.shutdown_connection(peer_id);
+    }
+}
+
 #[derive(Clone)]
 pub struct MassaHandshake {
     pub announcement_serializer: AnnouncementSerializer,
@@ -349,8 +418,10 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
                     Some(format!("Failed to serialize announcement: {}", err)),
                 )
             })?;
+        let send_instant = Instant::now();
         endpoint.send::<PeerId>(&bytes)?;
         let received = endpoint.receive::<PeerId>()?;
+        let round_trip = send_instant.elapsed();
         if received.len() < 32 {
             self.handshake_fail(&addr);
             return Err(PeerNetError::HandshakeError.error(
@@ -517,11 +588,16 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
             match &res {
                 Ok((peer_id, Some(announcement))) => {
                     info!("Peer connected: {:?}", peer_id);
+                    self.message_handlers
+                        .bandwidth_limiter
+                        .lock()
+                        .register_peer(peer_id, addr.ip());
                     peer_db_write
                         .try_connect_history
                         .entry(addr)
                         .or_insert(ConnectionMetadata::default())
                         .success();
+                    peer_db_write.record_handshake(peer_id, round_trip);
                     peer_db_write
                         .peers
                         .entry(peer_id.clone())
@@ -1,17 +1,27 @@
 use massa_channel::sender::MassaSender;
-use massa_protocol_exports::{BootstrapPeers, PeerId, ProtocolError};
+use massa_protocol_exports::{
+    BootstrapPeers, ConnectionStats, KnownPeerInfo, PeerBan, PeerId, ProtocolError,
+};
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use massa_time::MassaTime;
 use parking_lot::RwLock;
 use peernet::transports::TransportType;
 use rand::seq::SliceRandom;
 use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::HashSet;
+use std::path::Path;
 use std::time::Duration;
 use std::{collections::HashMap, net::SocketAddr, sync::Arc};
-use tracing::log::info;
+use tracing::log::{info, warn};
 
-use super::announcement::Announcement;
+use super::announcement::{
+    Announcement, AnnouncementDeserializer, AnnouncementDeserializerArgs, AnnouncementSerializer,
+};
+use super::reputation::{PeerReputation, PeerReputationEvent};
+
+pub use massa_protocol_exports::PeerState;
 
 const THREE_DAYS_MS: u64 = 3 * 24 * 60 * 60 * 1_000;
 
@@ -100,6 +110,31 @@ impl PartialOrd for ConnectionMetadata {
     }
 }
 
+impl From<&ConnectionMetadata> for ConnectionStats {
+    fn from(metadata: &ConnectionMetadata) -> Self {
+        ConnectionStats {
+            last_success: metadata.last_success,
+            last_failure: metadata.last_failure,
+            last_try_connect: metadata.last_try_connect,
+            last_test_success: metadata.last_test_success,
+            last_test_failure: metadata.last_test_failure,
+        }
+    }
+}
+
+impl From<ConnectionStats> for ConnectionMetadata {
+    fn from(stats: ConnectionStats) -> Self {
+        ConnectionMetadata {
+            last_success: stats.last_success,
+            last_failure: stats.last_failure,
+            last_try_connect: stats.last_try_connect,
+            last_test_success: stats.last_test_success,
+            last_test_failure: stats.last_test_failure,
+            ..Default::default()
+        }
+    }
+}
+
 impl ConnectionMetadata {
     // Only used in tests
     #[allow(dead_code)]
@@ -154,6 +189,21 @@ pub struct PeerDB {
     pub try_connect_history: HashMap<SocketAddr, ConnectionMetadata>,
     /// peers currently tested
     pub peers_in_test: HashSet<SocketAddr>,
+    /// details of the bans of the peers in the `Banned` state
+    pub bans: HashMap<PeerId, PeerBan>,
+    /// latencies of the connected peers
+    pub latencies: HashMap<PeerId, PeerLatency>,
+    /// reputation scores of the peers
+    pub reputation: PeerReputation,
+}
+
+/// Latency of a connected peer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PeerLatency {
+    /// time at which the handshake of the connection completed
+    pub handshake_at: MassaTime,
+    /// last measured round trip time: the one of the handshake, then refreshed by each successful test of the peer
+    pub ping: MassaTime,
 }
 
 pub type SharedPeerDB = Arc<RwLock<PeerDB>>;
@@ -166,41 +216,58 @@ pub struct PeerInfo {
     pub state: PeerState,
 }
 
-#[warn(dead_code)]
-#[derive(Eq, PartialEq, Clone, Debug)]
-pub enum PeerState {
-    Banned,
-    InHandshake,
-    HandshakeFailed,
-    Trusted,
-}
-
 #[derive(Clone)]
 pub enum PeerManagementCmd {
-    Ban(Vec<PeerId>),
+    Ban(Vec<PeerId>, PeerBan),
     Unban(Vec<PeerId>),
+    Report(PeerId, PeerReputationEvent),
     GetBootstrapPeers {
         responder: MassaSender<BootstrapPeers>,
     },
+    GetKnownPeers {
+        responder: MassaSender<Vec<KnownPeerInfo>>,
+    },
     Stop,
 }
 
+/// Content of the file where the peer database is saved
+#[derive(Default, Serialize, Deserialize)]
+struct PeersFile {
+    peers: Vec<PeersFileEntry>,
+    tested_addresses: HashMap<SocketAddr, MassaTime>,
+    connection_stats: HashMap<SocketAddr, ConnectionStats>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PeersFileEntry {
+    peer_id: PeerId,
+    state: PeerState,
+    #[serde(default)]
+    ban: Option<PeerBan>,
+    /// last announcement of the peer, serialized and encoded in base58check
+    #[serde(default)]
+    announcement: Option<String>,
+}
+
 pub struct PeerManagementChannel {
     pub msg_sender: MassaSender<PeerMessageTuple>,
     pub command_sender: MassaSender<PeerManagementCmd>,
 }
 
 impl PeerDB {
-    pub fn ban_peer(&mut self, peer_id: &PeerId) {
+    pub fn ban_peer(&mut self, peer_id: &PeerId, ban: PeerBan) {
         if let Some(peer) = self.peers.get_mut(peer_id) {
             peer.state = PeerState::Banned;
-            info!("Banned peer: {:?}", peer_id);
+            info!("Banned peer: {:?} ({:?})", peer_id, ban.reason);
+            self.bans.insert(peer_id.clone(), ban);
         } else {
             info!("Tried to ban unknown peer: {:?}", peer_id);
         };
     }
 
     pub fn unban_peer(&mut self, peer_id: &PeerId) {
+        self.bans.remove(peer_id);
+        self.reputation.reset(peer_id);
         if let Some(peer) = self.peers.get_mut(peer_id) {
             // We set the state to HandshakeFailed to force the peer to be tested again
             peer.state = PeerState::HandshakeFailed;
@@ -285,8 +352,350 @@ impl PeerDB {
             .count() as u64
     }
 
-    // Flush PeerDB to disk ?
-    fn _flush(&self) -> Result<(), ProtocolError> {
-        unimplemented!()
+    /// Unban the peers whose ban expired at time `now`
+    pub fn unban_expired_peers(&mut self, now: MassaTime) {
+        let expired: Vec<PeerId> = self
+            .bans
+            .iter()
+            .filter(|(_, ban)| ban.is_expired(now))
+            .map(|(peer_id, _)| peer_id.clone())
+            .collect();
+        for peer_id in expired {
+            self.unban_peer(&peer_id);
+        }
+    }
+
+    /// Get the known peers with their ban and the connection statistics of their listeners
+    pub fn get_known_peers(&self) -> Vec<KnownPeerInfo> {
+        self.peers
+            .iter()
+            .map(|(peer_id, peer)| {
+                let listeners = self.get_listeners(peer_id);
+                KnownPeerInfo {
+                    peer_id: peer_id.clone(),
+                    state: peer.state,
+                    ban: self.get_ban(peer_id, peer),
+                    last_announce: peer
+                        .last_announce
+                        .as_ref()
+                        .map(|announce| MassaTime::from_millis(announce.timestamp)),
+                    connection_stats: self.get_connection_stats(&listeners),
+                    listeners,
+                }
+            })
+            .collect()
+    }
+
+    /// Record the latency of a peer that just completed a handshake
+    pub fn record_handshake(&mut self, peer_id: &PeerId, round_trip: Duration) {
+        let Ok(now) = MassaTime::now() else {
+            return;
+        };
+        self.latencies.insert(
+            peer_id.clone(),
+            PeerLatency {
+                handshake_at: now,
+                ping: MassaTime::try_from(round_trip).unwrap_or(MassaTime::from_millis(u64::MAX)),
+            },
+        );
+    }
+
+    /// Refresh the ping of a connected peer with a new round trip time measurement
+    pub fn record_ping(&mut self, peer_id: &PeerId, round_trip: Duration) {
+        if let Some(latency) = self.latencies.get_mut(peer_id) {
+            latency.ping =
+                MassaTime::try_from(round_trip).unwrap_or(MassaTime::from_millis(u64::MAX));
+        }
+    }
+
+    /// Forget the latencies of the peers that are no longer connected
+    pub fn retain_latencies(&mut self, peer_ids_connected: &HashSet<PeerId>) {
+        self.latencies
+            .retain(|peer_id, _| peer_ids_connected.contains(peer_id));
+    }
+
+    /// Get the listeners of the last announcement of a peer
+    pub fn get_listeners(&self, peer_id: &PeerId) -> HashMap<SocketAddr, TransportType> {
+        self.peers
+            .get(peer_id)
+            .and_then(|peer| peer.last_announce.as_ref())
+            .map(|announce| announce.listeners.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get the connection statistics of some listeners
+    pub fn get_connection_stats(
+        &self,
+        listeners: &HashMap<SocketAddr, TransportType>,
+    ) -> HashMap<SocketAddr, ConnectionStats> {
+        listeners
+            .keys()
+            .filter_map(|addr| {
+                self.try_connect_history
+                    .get(addr)
+                    .map(|metadata| (*addr, metadata.into()))
+            })
+            .collect()
+    }
+
+    /// Get the ban of a peer, if it is still banned
+    fn get_ban(&self, peer_id: &PeerId, peer: &PeerInfo) -> Option<PeerBan> {
+        if peer.state == PeerState::Banned {
+            self.bans.get(peer_id).cloned()
+        } else {
+            None
+        }
+    }
+
+    /// Load the peer database saved in a file by `flush`.
+    /// Returns an empty database if the file does not exist.
+    ///
+    /// # Arguments
+    /// * `path`: path of the file
+    /// * `max_listeners_per_peer`: maximum number of listeners in the saved announcements
+    pub fn load(path: &Path, max_listeners_per_peer: u64) -> Result<Self, ProtocolError> {
+        if !path.is_file() {
+            return Ok(PeerDB::default());
+        }
+        let file: PeersFile = serde_json::from_slice(&std::fs::read(path)?)?;
+        let announcement_deserializer =
+            AnnouncementDeserializer::new(AnnouncementDeserializerArgs {
+                max_listeners: max_listeners_per_peer,
+            });
+        let now = MassaTime::now()?;
+        let mut peer_db = PeerDB {
+            tested_addresses: file.tested_addresses,
+            try_connect_history: file
+                .connection_stats
+                .into_iter()
+                .map(|(addr, stats)| (addr, stats.into()))
+                .collect(),
+            ..Default::default()
+        };
+        for entry in file.peers {
+            let last_announce = match entry.announcement {
+                Some(encoded) => {
+                    let decoded =
+                        bs58::decode(&encoded)
+                            .with_check(None)
+                            .into_vec()
+                            .map_err(|err| {
+                                ProtocolError::GeneralProtocolError(format!(
+                                    "invalid announcement of peer {}: {}",
+                                    entry.peer_id, err
+                                ))
+                            })?;
+                    let (_, announcement) = announcement_deserializer
+                        .deserialize::<DeserializeError>(&decoded)
+                        .map_err(|err| {
+                            ProtocolError::GeneralProtocolError(format!(
+                                "invalid announcement of peer {}: {}",
+                                entry.peer_id, err
+                            ))
+                        })?;
+                    Some(announcement)
+                }
+                None => None,
+            };
+            // a handshake interrupted by the shutdown is considered as failed
+            let state = match (entry.state, &entry.ban) {
+                (PeerState::InHandshake, _) => PeerState::HandshakeFailed,
+                (PeerState::Banned, Some(ban)) if ban.is_expired(now) => PeerState::HandshakeFailed,
+                (state, _) => state,
+            };
+            if let (PeerState::Banned, Some(ban)) = (state, entry.ban) {
+                peer_db.bans.insert(entry.peer_id.clone(), ban);
+            }
+            peer_db.peers.insert(
+                entry.peer_id,
+                PeerInfo {
+                    last_announce,
+                    state,
+                },
+            );
+        }
+        Ok(peer_db)
+    }
+
+    /// Save the peer database in a file, to be reloaded by `load` at the next start
+    pub fn flush(&self, path: &Path) -> Result<(), ProtocolError> {
+        let announcement_serializer = AnnouncementSerializer::new();
+        let mut peers = Vec::with_capacity(self.peers.len());
+        for (peer_id, peer) in &self.peers {
+            let announcement = match &peer.last_announce {
+                Some(announcement) => {
+                    let mut buffer = Vec::new();
+                    announcement_serializer
+                        .serialize(announcement, &mut buffer)
+                        .map_err(|err| ProtocolError::GeneralProtocolError(err.to_string()))?;
+                    Some(bs58::encode(buffer).with_check().into_string())
+                }
+                None => None,
+            };
+            peers.push(PeersFileEntry {
+                peer_id: peer_id.clone(),
+                state: peer.state,
+                ban: self.get_ban(peer_id, peer),
+                announcement,
+            });
+        }
+        let file = PeersFile {
+            peers,
+            tested_addresses: self.tested_addresses.clone(),
+            connection_stats: self
+                .try_connect_history
+                .iter()
+                .map(|(addr, metadata)| (*addr, metadata.into()))
+                .collect(),
+        };
+        // write to a temporary file first so that a crash never leaves a truncated file
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec(&file)?)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+}
+
+/// Save the peer database, logging the errors
+pub fn flush_peer_db(peer_db: &SharedPeerDB, path: &Path) {
+    if let Err(err) = peer_db.read().flush(path) {
+        warn!(
+            "could not save the peer database to {}: {}",
+            path.display(),
+            err
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_peer_db_flush_load() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let peer_id = PeerId::from_public_key(keypair.get_public_key());
+        let addr: SocketAddr = "10.0.0.1:31244".parse().unwrap();
+        let announcement = Announcement::new(
+            HashMap::from([(addr, TransportType::Tcp)]),
+            Some(addr.ip()),
+            &keypair,
+        )
+        .unwrap();
+        let banned_peer_id =
+            PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        let expired_peer_id =
+            PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+
+        let mut peer_db = PeerDB::default();
+        peer_db.peers.insert(
+            peer_id.clone(),
+            PeerInfo {
+                last_announce: Some(announcement.clone()),
+                state: PeerState::Trusted,
+            },
+        );
+        for id in [&banned_peer_id, &expired_peer_id] {
+            peer_db.peers.insert(
+                id.clone(),
+                PeerInfo {
+                    last_announce: None,
+                    state: PeerState::InHandshake,
+                },
+            );
+        }
+        peer_db.ban_peer(
+            &banned_peer_id,
+            PeerBan {
+                reason: Some("spam".to_string()),
+                expire_at: None,
+            },
+        );
+        peer_db.ban_peer(
+            &expired_peer_id,
+            PeerBan {
+                reason: None,
+                expire_at: Some(MassaTime::from_millis(1)),
+            },
+        );
+        peer_db
+            .try_connect_history
+            .entry(addr)
+            .or_default()
+            .success();
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("peers.json");
+        peer_db.flush(&path).unwrap();
+        let loaded = PeerDB::load(&path, 100).unwrap();
+
+        let peer = loaded.peers.get(&peer_id).unwrap();
+        assert_eq!(peer.state, PeerState::Trusted);
+        assert_eq!(peer.last_announce, Some(announcement));
+        assert_eq!(
+            loaded.peers.get(&banned_peer_id).unwrap().state,
+            PeerState::Banned
+        );
+        assert_eq!(
+            loaded.bans.get(&banned_peer_id).unwrap().reason.as_deref(),
+            Some("spam")
+        );
+        // the expired ban is lifted when loading
+        assert_eq!(
+            loaded.peers.get(&expired_peer_id).unwrap().state,
+            PeerState::HandshakeFailed
+        );
+        assert!(!loaded.bans.contains_key(&expired_peer_id));
+        assert!(loaded
+            .try_connect_history
+            .get(&addr)
+            .unwrap()
+            .last_success
+            .is_some());
+
+        let known_peer = loaded
+            .get_known_peers()
+            .into_iter()
+            .find(|known_peer| known_peer.peer_id == peer_id)
+            .unwrap();
+        assert_eq!(known_peer.listeners.get(&addr), Some(&TransportType::Tcp));
+        assert!(known_peer.connection_stats.contains_key(&addr));
+
+        assert!(PeerDB::load(&temp_dir.path().join("missing.json"), 100)
+            .unwrap()
+            .peers
+            .is_empty());
+    }
+
+    #[test]
+    fn test_peer_db_latencies() {
+        let peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        let other_peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        let mut peer_db = PeerDB::default();
+
+        // only the peers that completed a handshake get a ping
+        peer_db.record_handshake(&peer_id, Duration::from_millis(80));
+        peer_db.record_ping(&other_peer_id, Duration::from_millis(10));
+        let latency = *peer_db.latencies.get(&peer_id).unwrap();
+        assert_eq!(latency.ping, MassaTime::from_millis(80));
+        assert!(!peer_db.latencies.contains_key(&other_peer_id));
+
+        // the ping is refreshed by later measurements
+        peer_db.record_ping(&peer_id, Duration::from_millis(30));
+        assert_eq!(
+            peer_db.latencies.get(&peer_id),
+            Some(&PeerLatency {
+                ping: MassaTime::from_millis(30),
+                ..latency
+            })
+        );
+
+        // the latencies of disconnected peers are forgotten
+        peer_db.retain_latencies(&HashSet::from([peer_id.clone()]));
+        assert!(peer_db.latencies.contains_key(&peer_id));
+        peer_db.retain_latencies(&HashSet::new());
+        assert!(peer_db.latencies.is_empty());
     }
 }
@@ -0,0 +1,150 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Reputation of the peers.
+//!
+//! The handlers report the misbehaviours and the useful contributions of the peers
+//! to the peer handler, which keeps a score for each peer.
+//! A peer whose score goes down to `peer_score_disconnect_threshold` is disconnected,
+//! and down to `peer_score_ban_threshold` is banned for `peer_score_ban_duration`.
+//! Peers with a negative score are tried last when filling the outgoing connection slots.
+//! Scores slowly recover towards 0, so that occasional mistakes are forgotten.
+
+use massa_channel::sender::MassaSender;
+use massa_protocol_exports::PeerId;
+use std::collections::HashMap;
+use tracing::warn;
+
+use super::models::PeerManagementCmd;
+
+/// Highest reachable score, so that a peer cannot bank credits to misbehave later
+const MAX_SCORE: i64 = 100;
+
+/// Points recovered by each score at each reputation tick
+const SCORE_RECOVERY_PER_TICK: i64 = 1;
+
+/// Event changing the reputation of a peer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PeerReputationEvent {
+    /// the peer sent a message that could not be deserialized, for example because it exceeds the size limits
+    InvalidMessage,
+    /// the peer sent data that we did not ask for
+    UnrequestedData,
+    /// the peer did not answer an `AskForBlockInfo` in time
+    SlowReply,
+    /// the peer sent redundant announcements
+    Spam,
+    /// the peer sent data that we were missing
+    UsefulData,
+}
+
+impl PeerReputationEvent {
+    /// Get the change of score caused by the event
+    pub fn score_delta(&self) -> i64 {
+        match self {
+            PeerReputationEvent::InvalidMessage => -20,
+            PeerReputationEvent::UnrequestedData => -5,
+            PeerReputationEvent::SlowReply => -10,
+            PeerReputationEvent::Spam => -5,
+            PeerReputationEvent::UsefulData => 2,
+        }
+    }
+}
+
+impl std::fmt::Display for PeerReputationEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PeerReputationEvent::InvalidMessage => write!(f, "invalid message"),
+            PeerReputationEvent::UnrequestedData => write!(f, "unrequested data"),
+            PeerReputationEvent::SlowReply => write!(f, "slow reply"),
+            PeerReputationEvent::Spam => write!(f, "spam"),
+            PeerReputationEvent::UsefulData => write!(f, "useful data"),
+        }
+    }
+}
+
+/// Reputation scores of the peers. Peers without score have a score of 0.
+#[derive(Default)]
+pub struct PeerReputation {
+    scores: HashMap<PeerId, i64>,
+}
+
+impl PeerReputation {
+    /// Get the score of a peer
+    pub fn get_score(&self, peer_id: &PeerId) -> i64 {
+        self.scores.get(peer_id).copied().unwrap_or_default()
+    }
+
+    /// Apply an event to the score of a peer and return the new score
+    pub fn report(&mut self, peer_id: &PeerId, event: PeerReputationEvent) -> i64 {
+        let score = self.scores.entry(peer_id.clone()).or_default();
+        *score = score.saturating_add(event.score_delta()).min(MAX_SCORE);
+        *score
+    }
+
+    /// Forget the score of a peer
+    pub fn reset(&mut self, peer_id: &PeerId) {
+        self.scores.remove(peer_id);
+    }
+
+    /// Move all the scores towards 0
+    pub fn recover(&mut self) {
+        self.scores.retain(|_, score| {
+            *score -= score.signum() * SCORE_RECOVERY_PER_TICK.min(score.abs());
+            *score != 0
+        });
+    }
+}
+
+/// Report an event about a peer to the peer handler
+pub(crate) fn report_peer(
+    peer_cmd_sender: &MassaSender<PeerManagementCmd>,
+    peer_id: &PeerId,
+    event: PeerReputationEvent,
+) {
+    if let Err(err) = peer_cmd_sender.try_send(PeerManagementCmd::Report(peer_id.clone(), event)) {
+        warn!(
+            "error when reporting {} of peer {}: {}",
+            event, peer_id, err
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+
+    #[test]
+    fn test_peer_reputation() {
+        let peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        let mut reputation = PeerReputation::default();
+        assert_eq!(reputation.get_score(&peer_id), 0);
+
+        // credits are capped
+        for _ in 0..100 {
+            reputation.report(&peer_id, PeerReputationEvent::UsefulData);
+        }
+        assert_eq!(reputation.get_score(&peer_id), MAX_SCORE);
+
+        // penalties accumulate
+        for _ in 0..3 {
+            reputation.report(&peer_id, PeerReputationEvent::InvalidMessage);
+        }
+        assert_eq!(
+            reputation.report(&peer_id, PeerReputationEvent::SlowReply),
+            MAX_SCORE - 70
+        );
+
+        // scores recover towards 0
+        reputation.recover();
+        assert_eq!(reputation.get_score(&peer_id), MAX_SCORE - 69);
+        for _ in 0..100 {
+            reputation.recover();
+        }
+        assert_eq!(reputation.get_score(&peer_id), 0);
+
+        reputation.report(&peer_id, PeerReputationEvent::Spam);
+        reputation.reset(&peer_id);
+        assert_eq!(reputation.get_score(&peer_id), 0);
+    }
+}
@@ -3,7 +3,7 @@ use std::{
     io::Read,
     net::{IpAddr, SocketAddr},
     thread::JoinHandle,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use crate::{ip::to_canonical, messages::MessagesHandler};
@@ -85,9 +85,12 @@ impl Tester {
         let our_version = config.version;
 
         let exec_handshake = || {
+            // the TCP connection takes one round trip with the peer
+            let connect_instant = Instant::now();
             let mut socket =
                 std::net::TcpStream::connect_timeout(&addr, config.tester_timeout.into())
                     .map_err(|e| PeerNetError::PeerConnectionError.new("connect", e, None))?;
+            let round_trip = connect_instant.elapsed();
             socket
                 .set_read_timeout(Some(config.tester_timeout.into()))
                 .map_err(|err| PeerNetError::PeerConnectionError.new("read timeout", err, None))?;
@@ -250,6 +253,7 @@ impl Tester {
                         .entry(addr)
                         .or_insert(ConnectionMetadata::default())
                         .test_success();
+                    peer_db_write.record_ping(&peer_id, round_trip);
                 }
             }
 
@@ -1,3 +1,4 @@
+mod bandwidth;
 mod connectivity;
 mod context;
 mod controller;
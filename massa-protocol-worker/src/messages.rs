@@ -1,5 +1,5 @@
 use massa_channel::sender::MassaSender;
-use massa_protocol_exports::PeerId;
+use massa_protocol_exports::{PeerId, PeerMessageCounts};
 use massa_serialization::{
     DeserializeError, Deserializer, Serializer, U64VarIntDeserializer, U64VarIntSerializer,
 };
@@ -10,8 +10,17 @@ use peernet::{
         MessagesHandler as PeerNetMessagesHandler, MessagesSerializer as PeerNetMessagesSerializer,
     },
 };
+use parking_lot::RwLock;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 use tracing::debug;
 
+use crate::bandwidth::{SharedBandwidthLimiter, TrafficClass};
 use crate::handlers::{
     block_handler::{BlockMessage, BlockMessageSerializer},
     endorsement_handler::{EndorsementMessage, EndorsementMessageSerializer},
@@ -217,6 +226,41 @@ impl PeerNetMessagesSerializer<Message> for MessagesSerializer {
     }
 }
 
+/// Number of messages received from a peer, by handler
+#[derive(Default)]
+pub struct PeerMessageCounters {
+    block: AtomicU64,
+    operation: AtomicU64,
+    endorsement: AtomicU64,
+    peer_management: AtomicU64,
+}
+
+impl PeerMessageCounters {
+    fn increment(&self, id: &MessageTypeId) {
+        let counter = match id {
+            MessageTypeId::Block => &self.block,
+            MessageTypeId::Endorsement => &self.endorsement,
+            MessageTypeId::Operation => &self.operation,
+            MessageTypeId::PeerManagement => &self.peer_management,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get the current counts
+    pub fn get(&self) -> PeerMessageCounts {
+        PeerMessageCounts {
+            block: self.block.load(Ordering::Relaxed),
+            operation: self.operation.load(Ordering::Relaxed),
+            endorsement: self.endorsement.load(Ordering::Relaxed),
+            peer_management: self.peer_management.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Message counters of each peer.
+/// The lock is only taken for writing on the first message of a peer, the counters are atomic.
+pub type SharedMessageCounts = Arc<RwLock<HashMap<PeerId, PeerMessageCounters>>>;
+
 #[derive(Clone)]
 pub struct MessagesHandler {
     pub id_deserializer: U64VarIntDeserializer,
@@ -224,6 +268,22 @@ pub struct MessagesHandler {
     pub sender_endorsements: MassaSender<PeerMessageTuple>,
     pub sender_operations: MassaSender<PeerMessageTuple>,
     pub sender_peers: MassaSender<PeerMessageTuple>,
+    pub message_counts: SharedMessageCounts,
+    pub bandwidth_limiter: SharedBandwidthLimiter,
+}
+
+impl MessagesHandler {
+    fn count_message(&self, peer_id: &PeerId, id: &MessageTypeId) {
+        if let Some(counters) = self.message_counts.read().get(peer_id) {
+            counters.increment(id);
+            return;
+        }
+        self.message_counts
+            .write()
+            .entry(peer_id.clone())
+            .or_default()
+            .increment(id);
+    }
 }
 
 impl PeerNetMessagesHandler<PeerId> for MessagesHandler {
@@ -243,6 +303,18 @@ impl PeerNetMessagesHandler<PeerId> for MessagesHandler {
                 Some(String::from("Invalid message type id")),
             )
         })?;
+        self.count_message(peer_id, &id);
+        if !self.bandwidth_limiter.lock().allow_inbound(
+            peer_id,
+            TrafficClass::from(&id),
+            data.len() as u64,
+        ) {
+            debug!(
+                "Inbound bandwidth quota exceeded, dropping {:?} message from peer {}",
+                id, peer_id
+            );
+            return Ok(());
+        }
         match id {
             // Blocks are high-priority: we block if the channel is full.
             // This means that the sender will be blocked until the message is sent.
@@ -4,7 +4,13 @@ use std::collections::HashSet;
 use std::time::Duration;
 
 use massa_consensus_exports::test_exports::MockConsensusControllerMessage;
-use massa_models::{block_id::BlockId, prehash::PreHashSet, slot::Slot};
+use massa_models::{
+    block_header::{BlockHeader, BlockHeaderSerializer},
+    block_id::BlockId,
+    prehash::PreHashSet,
+    secure_share::SecureShareContent,
+    slot::Slot,
+};
 use massa_protocol_exports::PeerId;
 use massa_protocol_exports::{test_exports::tools, ProtocolConfig};
 use massa_signature::KeyPair;
@@ -127,6 +133,80 @@ fn test_protocol_bans_node_sending_block_header_with_invalid_signature() {
     )
 }
 
+#[test]
+#[serial]
+fn test_protocol_bans_node_sending_block_header_with_incompatible_version() {
+    let default_panic = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_panic(info);
+        std::process::exit(1);
+    }));
+
+    let mut protocol_config = ProtocolConfig::default();
+    protocol_config.thread_count = 2;
+    protocol_config.initial_peers = "./src/tests/empty_initial_peers.json".to_string().into();
+    protocol_test(
+        &protocol_config,
+        move |mut network_controller,
+              protocol_controller,
+              protocol_manager,
+              mut consensus_event_receiver,
+              pool_event_receiver,
+              selector_event_receiver| {
+            //1. Create 1 node
+            let node_a_keypair = KeyPair::generate(0).unwrap();
+            let (node_a_peer_id, _node_a) = network_controller
+                .create_fake_connection(PeerId::from_public_key(node_a_keypair.get_public_key()));
+
+            //2. Create a header with a network version that is not active.
+            let header = BlockHeader::new_verifiable(
+                BlockHeader {
+                    current_version: 1,
+                    ..tools::create_block(&node_a_keypair).content.header.content
+                },
+                BlockHeaderSerializer::new(),
+                &node_a_keypair,
+            )
+            .unwrap();
+            //end setup
+
+            //3. Send header to protocol.
+            network_controller
+                .send_from_peer(
+                    &node_a_peer_id,
+                    Message::Block(Box::new(BlockMessage::Header(header))),
+                )
+                .unwrap();
+
+            std::thread::sleep(std::time::Duration::from_millis(1000));
+            //4. Check that node connection is closed (node should be banned)
+            assert_eq!(
+                network_controller
+                    .get_connections()
+                    .get_peer_ids_connected()
+                    .len(),
+                0
+            );
+
+            //5. Check that protocol does not send block to consensus.
+            match consensus_event_receiver.wait_command(MassaTime::from_millis(500), |_| Some(())) {
+                Some(()) => {
+                    panic!("Protocol sent block to consensus.");
+                }
+                None => {}
+            }
+            (
+                network_controller,
+                protocol_controller,
+                protocol_manager,
+                consensus_event_receiver,
+                pool_event_receiver,
+                selector_event_receiver,
+            )
+        },
+    )
+}
+
 #[test]
 #[serial]
 fn test_protocol_bans_node_sending_operation_with_invalid_signature() {
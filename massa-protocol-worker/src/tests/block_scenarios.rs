@@ -7,7 +7,10 @@ use crate::handlers::block_handler::{AskForBlockInfo, BlockInfoReply, BlockMessa
 use crate::messages::Message;
 
 use super::context::{protocol_test, protocol_test_with_storage};
-use super::tools::{assert_block_info_sent_to_node, assert_hash_asked_to_node};
+use super::tools::{
+    assert_block_info_sent_to_node, assert_compact_relay_support_sent_to_node,
+    assert_hash_asked_to_node,
+};
 use massa_consensus_exports::test_exports::MockConsensusControllerMessage;
 use massa_models::operation::OperationId;
 use massa_models::prehash::PreHashSet;
@@ -202,6 +205,208 @@ fn test_full_ask_block_workflow() {
     )
 }
 
+#[test]
+#[serial]
+fn test_compact_block_workflow() {
+    let default_panic = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_panic(info);
+        std::process::exit(1);
+    }));
+
+    let mut protocol_config = ProtocolConfig::default();
+    protocol_config.thread_count = 2;
+    protocol_config.compact_block_relay = true;
+    protocol_config.initial_peers = "./src/tests/empty_initial_peers.json".to_string().into();
+    protocol_test(
+        &protocol_config,
+        move |mut network_controller,
+              protocol_controller,
+              protocol_manager,
+              mut consensus_event_receiver,
+              pool_event_receiver,
+              selector_event_receiver| {
+            //1. Create a node, and check that we announce it our support of compact block relay
+            let node_a_keypair = KeyPair::generate(0).unwrap();
+            let (node_a_peer_id, node_a) = network_controller
+                .create_fake_connection(PeerId::from_public_key(node_a_keypair.get_public_key()));
+            assert_compact_relay_support_sent_to_node(&node_a);
+
+            //2. Create a block coming from node a.
+            let op_1 = tools::create_operation_with_expire_period(&node_a_keypair, 5);
+            let op_2 = tools::create_operation_with_expire_period(&node_a_keypair, 5);
+            let op_thread = op_1
+                .content_creator_address
+                .get_thread(protocol_config.thread_count);
+            let block = tools::create_block_with_operations(
+                &node_a_keypair,
+                Slot::new(1, op_thread),
+                vec![op_1.clone(), op_2.clone()],
+            );
+
+            //3. Send the compact header from node a
+            network_controller
+                .send_from_peer(
+                    &node_a_peer_id,
+                    Message::Block(Box::new(BlockMessage::CompactHeader {
+                        header: block.content.header.clone(),
+                        operation_prefix_ids: vec![op_1.id.prefix(), op_2.id.prefix()],
+                    })),
+                )
+                .unwrap();
+
+            //4. Send a wishlist that asks for the block
+            protocol_controller
+                .send_wishlist_delta(
+                    vec![(block.id, Some(block.content.header.clone()))]
+                        .into_iter()
+                        .collect(),
+                    PreHashSet::<BlockId>::default(),
+                )
+                .unwrap();
+
+            //5. Assert that we directly ask node a for the operations matching the unknown prefixes
+            match assert_hash_asked_to_node(&node_a, &block.id) {
+                AskForBlockInfo::OperationsByPrefix(prefixes) => {
+                    assert_eq!(prefixes, vec![op_1.id.prefix(), op_2.id.prefix()]);
+                }
+                request => panic!("unexpected block info request: {:?}", request),
+            }
+
+            //6. Node A answers with the operations
+            network_controller
+                .send_from_peer(
+                    &node_a_peer_id,
+                    Message::Block(Box::new(BlockMessage::DataResponse {
+                        block_id: block.id,
+                        block_info: BlockInfoReply::Operations(vec![op_1, op_2]),
+                    })),
+                )
+                .unwrap();
+
+            //7. Assert that we send the block to consensus without asking for the operation IDs
+            loop {
+                match consensus_event_receiver.wait_command(
+                    MassaTime::from_millis(100),
+                    |command| match command {
+                        MockConsensusControllerMessage::RegisterBlock {
+                            slot,
+                            block_id,
+                            block_storage,
+                            created: _,
+                        } => {
+                            assert_eq!(slot, block.content.header.content.slot);
+                            assert_eq!(block_id, block.id);
+                            let received_block =
+                                block_storage.read_blocks().get(&block_id).cloned().unwrap();
+                            assert_eq!(received_block.content.operations, block.content.operations);
+                            Some(())
+                        }
+                        _evt => None,
+                    },
+                ) {
+                    Some(()) => {
+                        break;
+                    }
+                    None => {
+                        continue;
+                    }
+                }
+            }
+            let _ = node_a
+                .recv_timeout(Duration::from_millis(500))
+                .expect_err("Node a shouldn't be asked for anything else");
+            (
+                network_controller,
+                protocol_controller,
+                protocol_manager,
+                consensus_event_receiver,
+                pool_event_receiver,
+                selector_event_receiver,
+            )
+        },
+    )
+}
+
+#[test]
+#[serial]
+fn test_protocol_sends_compact_headers_only_to_nodes_supporting_them() {
+    let default_panic = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_panic(info);
+        std::process::exit(1);
+    }));
+
+    let mut protocol_config = ProtocolConfig::default();
+    protocol_config.thread_count = 2;
+    protocol_config.compact_block_relay = true;
+    protocol_config.initial_peers = "./src/tests/empty_initial_peers.json".to_string().into();
+    protocol_test_with_storage(
+        &protocol_config,
+        move |mut network_controller,
+              protocol_controller,
+              protocol_manager,
+              consensus_event_receiver,
+              pool_event_receiver,
+              selector_event_receiver,
+              mut storage| {
+            //1. Create 2 nodes, and check that we announce them our support of compact block relay
+            let node_a_keypair = KeyPair::generate(0).unwrap();
+            let node_b_keypair = KeyPair::generate(0).unwrap();
+            let (node_a_peer_id, node_a) = network_controller
+                .create_fake_connection(PeerId::from_public_key(node_a_keypair.get_public_key()));
+            let (_node_b_peer_id, node_b) = network_controller
+                .create_fake_connection(PeerId::from_public_key(node_b_keypair.get_public_key()));
+            assert_compact_relay_support_sent_to_node(&node_a);
+            assert_compact_relay_support_sent_to_node(&node_b);
+
+            //2. Only node A announces its support of compact block relay
+            network_controller
+                .send_from_peer(
+                    &node_a_peer_id,
+                    Message::Block(Box::new(BlockMessage::CompactRelaySupport)),
+                )
+                .unwrap();
+            std::thread::sleep(Duration::from_millis(100));
+
+            //3. Consensus inform us that a block has been integrated and so we propagate it
+            let block = tools::create_block(&KeyPair::generate(0).unwrap());
+            storage.store_block(block.clone());
+            protocol_controller
+                .integrated_block(block.id, storage)
+                .unwrap();
+
+            //4. Node A receives a compact header, node B a full header
+            match node_a.recv_timeout(Duration::from_millis(1500)) {
+                Ok(Message::Block(block_msg)) => match *block_msg {
+                    BlockMessage::CompactHeader { header, .. } => {
+                        assert_eq!(header.id, block.content.header.id);
+                    }
+                    _ => panic!("Node a should receive the compact block header"),
+                },
+                _ => panic!("Node a should receive the compact block header"),
+            }
+            match node_b.recv_timeout(Duration::from_millis(1500)) {
+                Ok(Message::Block(block_msg)) => match *block_msg {
+                    BlockMessage::Header(header) => {
+                        assert_eq!(header.id, block.content.header.id);
+                    }
+                    _ => panic!("Node b should receive the block header"),
+                },
+                _ => panic!("Node b should receive the block header"),
+            }
+            (
+                network_controller,
+                protocol_controller,
+                protocol_manager,
+                consensus_event_receiver,
+                pool_event_receiver,
+                selector_event_receiver,
+            )
+        },
+    )
+}
+
 #[test]
 #[serial]
 fn test_empty_block() {
@@ -96,6 +96,8 @@ pub fn start_protocol_controller_with_mock_network(
         sender_operations: sender_operations.clone(),
         sender_peers: sender_peers.clone(),
         id_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+        message_counts: Default::default(),
+        bandwidth_limiter: Default::default(),
     };
 
     let (controller, channels) = create_protocol_controller(config.clone());
@@ -132,6 +134,10 @@ pub fn start_protocol_controller_with_mock_network(
             max_in_connections: 10,
             target_out_connections: 10,
             max_in_connections_per_ip: 10,
+            max_in_bytes_per_second: None,
+            max_out_bytes_per_second: None,
+            max_in_bytes_per_second_per_peer: None,
+            max_out_bytes_per_second_per_peer: None,
         },
         config,
         mip_store,
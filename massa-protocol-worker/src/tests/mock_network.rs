@@ -14,6 +14,7 @@ use peernet::{
 };
 
 use crate::{
+    bandwidth::TrafficClass,
     handlers::{
         block_handler::BlockMessageSerializer,
         endorsement_handler::EndorsementMessageSerializer,
@@ -106,6 +107,10 @@ impl ActiveConnectionsTrait for SharedMockActiveConnections {
     fn get_peer_ids_out_connection_queue(&self) -> HashSet<std::net::SocketAddr> {
         HashSet::new()
     }
+
+    fn has_bandwidth(&self, _peer_id: &PeerId, _class: TrafficClass) -> bool {
+        true
+    }
 }
 
 pub struct MockNetworkController {
@@ -24,6 +24,7 @@ mod in_block_operations_scenarios;
 mod mock_network;
 mod operations_scenarios;
 mod peer_priorization;
+mod peers_scenarios;
 mod tools;
 
 #[test]
@@ -108,6 +109,10 @@ fn basic() {
             max_in_connections: 1,
             target_out_connections: 1,
             max_in_connections_per_ip: 1,
+            max_in_bytes_per_second: None,
+            max_out_bytes_per_second: None,
+            max_in_bytes_per_second_per_peer: None,
+            max_out_bytes_per_second_per_peer: None,
         },
     );
     config1.peers_categories = categories;
@@ -120,6 +125,10 @@ fn basic() {
             max_in_connections: 5,
             target_out_connections: 1,
             max_in_connections_per_ip: 1,
+            max_in_bytes_per_second: None,
+            max_out_bytes_per_second: None,
+            max_in_bytes_per_second_per_peer: None,
+            max_out_bytes_per_second_per_peer: None,
         },
     );
     config2.peers_categories = categories2;
@@ -259,6 +268,10 @@ fn stop_with_controller_still_exists() {
             max_in_connections: 1,
             target_out_connections: 1,
             max_in_connections_per_ip: 1,
+            max_in_bytes_per_second: None,
+            max_out_bytes_per_second: None,
+            max_in_bytes_per_second_per_peer: None,
+            max_out_bytes_per_second_per_peer: None,
         },
     );
     config1.peers_categories = categories;
@@ -271,6 +284,10 @@ fn stop_with_controller_still_exists() {
             max_in_connections: 5,
             target_out_connections: 1,
             max_in_connections_per_ip: 1,
+            max_in_bytes_per_second: None,
+            max_out_bytes_per_second: None,
+            max_in_bytes_per_second_per_peer: None,
+            max_out_bytes_per_second_per_peer: None,
         },
     );
     config2.peers_categories = categories2;
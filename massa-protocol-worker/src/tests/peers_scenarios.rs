@@ -0,0 +1,83 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_protocol_exports::{test_exports::tools, PeerId, PeerMessageCounts, ProtocolConfig};
+use massa_signature::KeyPair;
+use serial_test::serial;
+
+use crate::{handlers::operation_handler::OperationMessage, messages::Message};
+
+use super::context::protocol_test;
+
+#[test]
+#[serial]
+fn test_protocol_counts_messages_received_from_peers() {
+    let default_panic = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_panic(info);
+        std::process::exit(1);
+    }));
+
+    let mut protocol_config = ProtocolConfig::default();
+    protocol_config.thread_count = 2;
+    protocol_config.initial_peers = "./src/tests/empty_initial_peers.json".to_string().into();
+    protocol_test(
+        &protocol_config,
+        move |mut network_controller,
+              protocol_controller,
+              protocol_manager,
+              consensus_event_receiver,
+              pool_event_receiver,
+              selector_event_receiver| {
+            //1. Create 2 nodes
+            let node_a_keypair = KeyPair::generate(0).unwrap();
+            let node_b_keypair = KeyPair::generate(0).unwrap();
+            let (node_a_peer_id, _node_a) = network_controller
+                .create_fake_connection(PeerId::from_public_key(node_a_keypair.get_public_key()));
+            let (node_b_peer_id, _node_b) = network_controller
+                .create_fake_connection(PeerId::from_public_key(node_b_keypair.get_public_key()));
+
+            //2. Node A sends 2 operation messages, node B sends nothing
+            for _ in 0..2 {
+                network_controller
+                    .send_from_peer(
+                        &node_a_peer_id,
+                        Message::Operation(OperationMessage::Operations(vec![
+                            tools::create_operation_with_expire_period(&node_a_keypair, 1),
+                        ])),
+                    )
+                    .unwrap();
+            }
+
+            //3. Check the counts of each peer
+            let peers = protocol_controller.get_peers().unwrap();
+            assert_eq!(peers.len(), 2);
+            let node_a = peers
+                .iter()
+                .find(|peer| peer.peer_id == node_a_peer_id)
+                .unwrap();
+            assert_eq!(
+                node_a.messages_received,
+                PeerMessageCounts {
+                    operation: 2,
+                    ..Default::default()
+                }
+            );
+            let node_b = peers
+                .iter()
+                .find(|peer| peer.peer_id == node_b_peer_id)
+                .unwrap();
+            assert_eq!(node_b.messages_received, PeerMessageCounts::default());
+            // no handshake happened with the fake connections
+            assert!(node_a.ping.is_none());
+            assert!(node_a.handshake_at.is_none());
+            (
+                network_controller,
+                protocol_controller,
+                protocol_manager,
+                consensus_event_receiver,
+                pool_event_receiver,
+                selector_event_receiver,
+            )
+        },
+    )
+}
@@ -38,6 +38,21 @@ pub fn assert_hash_asked_to_node(
     }
 }
 
+pub fn assert_compact_relay_support_sent_to_node(node: &MassaReceiver<Message>) {
+    let msg = node
+        .recv_timeout(Duration::from_millis(1500))
+        .expect("Node didn't receive the compact block relay support message");
+    match msg {
+        Message::Block(message) => {
+            assert!(
+                matches!(*message, BlockMessage::CompactRelaySupport),
+                "Node didn't receive the compact block relay support message"
+            );
+        }
+        _ => panic!("Node didn't receive the compact block relay support message"),
+    }
+}
+
 pub fn assert_block_info_sent_to_node(node: &MassaReceiver<Message>, block_id: &BlockId) {
     let msg = node
         .recv_timeout(Duration::from_millis(1500))
@@ -5,8 +5,8 @@ use massa_models::node::NodeId;
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
 use massa_protocol_exports::{
-    BootstrapPeers, PeerData, PeerId, ProtocolConfig, ProtocolController, ProtocolError,
-    ProtocolManager,
+    BootstrapPeers, PeerCategoryInfo, PeerData, PeerId, ProtocolConfig, ProtocolController,
+    ProtocolError, ProtocolManager,
 };
 use massa_serialization::U64VarIntDeserializer;
 use massa_signature::KeyPair;
@@ -17,15 +17,16 @@ use massa_versioning::{
     versioning::MipStore,
     versioning_factory::{FactoryStrategy, VersioningFactory},
 };
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use peernet::{
     config::{PeerNetCategoryInfo, PeerNetConfiguration},
     network_manager::PeerNetManager,
 };
-use std::{collections::HashMap, fs::read_to_string, ops::Bound::Included, sync::Arc};
+use std::{collections::HashMap, fs::read_to_string, net::IpAddr, ops::Bound::Included, sync::Arc};
 use tracing::{debug, log::warn};
 
 use crate::{
+    bandwidth::BandwidthLimiter,
     connectivity::{start_connectivity_thread, ConnectivityCommand},
     context::Context,
     controller::ProtocolControllerImpl,
@@ -188,7 +189,18 @@ pub fn start_protocol_controller(
     massa_metrics: MassaMetrics,
 ) -> Result<(Box<dyn ProtocolManager>, KeyPair, NodeId), ProtocolError> {
     debug!("starting protocol controller");
-    let peer_db = Arc::new(RwLock::new(PeerDB::default()));
+    let peer_db = match PeerDB::load(&config.peers_file, config.max_size_listeners_per_peer) {
+        Ok(peer_db) => peer_db,
+        Err(err) => {
+            warn!(
+                "could not load the peer database from {}, starting with an empty one: {}",
+                config.peers_file.display(),
+                err
+            );
+            PeerDB::default()
+        }
+    };
+    let peer_db = Arc::new(RwLock::new(peer_db));
 
     let (sender_operations, receiver_operations) = MassaChannel::new(
         "sender_operations".to_string(),
@@ -207,6 +219,41 @@ pub fn start_protocol_controller(
         Some(config.max_size_channel_network_to_peer_handler),
     );
 
+    let initial_peers_infos = serde_json::from_str::<HashMap<PeerId, PeerData>>(
+        &std::fs::read_to_string(&config.initial_peers)?,
+    )?;
+
+    let peer_categories: HashMap<String, (Vec<IpAddr>, PeerCategoryInfo)> = config
+        .peers_categories
+        .iter()
+        .map(|(category_name, infos)| {
+            (
+                category_name.clone(),
+                (
+                    initial_peers_infos
+                        .iter()
+                        .filter_map(|info| {
+                            if info.1.category == *category_name {
+                                //TODO: Adapt for multiple listeners
+                                Some(
+                                    info.1
+                                        .listeners
+                                        .iter()
+                                        .next()
+                                        .map(|addr| to_canonical(addr.0.ip()))
+                                        .unwrap(),
+                                )
+                            } else {
+                                None
+                            }
+                        })
+                        .collect(),
+                    *infos,
+                ),
+            )
+        })
+        .collect();
+
     // Register channels for handlers
     let message_handlers: MessagesHandler = MessagesHandler {
         sender_blocks: sender_blocks.clone(),
@@ -214,6 +261,11 @@ pub fn start_protocol_controller(
         sender_operations: sender_operations.clone(),
         sender_peers: sender_peers.clone(),
         id_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+        message_counts: Default::default(),
+        bandwidth_limiter: Arc::new(Mutex::new(BandwidthLimiter::new(
+            peer_categories.clone(),
+            config.default_category_info,
+        ))),
     };
 
     // try to read node keypair from file, otherwise generate it & write to file. Then derive nodeId
@@ -252,10 +304,6 @@ pub fn start_protocol_controller(
     peernet_config.rate_limit = config.rate_limit;
     peernet_config.rate_bucket_size = config.rate_limit.saturating_mul(2);
 
-    let initial_peers_infos = serde_json::from_str::<HashMap<PeerId, PeerData>>(
-        &std::fs::read_to_string(&config.initial_peers)?,
-    )?;
-
     let initial_peers = if let Some(bootstrap_peers) = bootstrap_peers {
         //TODO: Remove when we will be able to test the bootstrap peer even if someone else found them full
         bootstrap_peers
@@ -274,31 +322,13 @@ pub fn start_protocol_controller(
             .collect()
     };
 
-    let peernet_categories = config
-        .peers_categories
+    let peernet_categories = peer_categories
         .iter()
-        .map(|(category_name, infos)| {
+        .map(|(category_name, (ips, infos))| {
             (
                 category_name.clone(),
                 (
-                    initial_peers_infos
-                        .iter()
-                        .filter_map(|info| {
-                            if info.1.category == *category_name {
-                                //TODO: Adapt for multiple listeners
-                                Some(
-                                    info.1
-                                        .listeners
-                                        .iter()
-                                        .next()
-                                        .map(|addr| to_canonical(addr.0.ip()))
-                                        .unwrap(),
-                                )
-                            } else {
-                                None
-                            }
-                        })
-                        .collect(),
+                    ips.clone(),
                     PeerNetCategoryInfo {
                         max_in_connections: infos.max_in_connections,
                         max_in_connections_per_ip: infos.max_in_connections_per_ip,
@@ -316,9 +346,10 @@ pub fn start_protocol_controller(
     };
     peernet_config.max_in_connections = config.max_in_connections;
 
-    let network_controller = Box::new(NetworkControllerImpl::new(PeerNetManager::new(
-        peernet_config,
-    )));
+    let network_controller = Box::new(NetworkControllerImpl::new(
+        PeerNetManager::new(peernet_config),
+        message_handlers.bandwidth_limiter.clone(),
+    ));
 
     let connectivity_thread_handle = start_connectivity_thread(
         PeerId::from_public_key(keypair.get_public_key()),
@@ -335,36 +366,7 @@ pub fn start_protocol_controller(
         storage,
         protocol_channels,
         message_handlers,
-        config
-            .peers_categories
-            .iter()
-            .map(|(category_name, infos)| {
-                (
-                    category_name.clone(),
-                    (
-                        initial_peers_infos
-                            .iter()
-                            .filter_map(|info| {
-                                if info.1.category == *category_name {
-                                    //TODO: Adapt for multiple listeners
-                                    Some(
-                                        info.1
-                                            .listeners
-                                            .iter()
-                                            .next()
-                                            .map(|addr| to_canonical(addr.0.ip()))
-                                            .unwrap(),
-                                    )
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect(),
-                        *infos,
-                    ),
-                )
-            })
-            .collect(),
+        peer_categories,
         config.default_category_info,
         config,
         mip_store,
@@ -11,6 +11,7 @@ use peernet::{
 };
 
 use crate::{
+    bandwidth::{SharedBandwidthLimiter, TrafficClass},
     context::Context,
     handlers::peer_handler::MassaHandshake,
     messages::{Message, MessagesHandler, MessagesSerializer},
@@ -34,6 +35,8 @@ pub trait ActiveConnectionsTrait: Send + Sync {
     fn get_nb_in_connections(&self) -> usize;
     fn shutdown_connection(&mut self, peer_id: &PeerId);
     fn get_peers_connections_bandwidth(&self) -> HashMap<String, (u64, u64)>;
+    /// Returns true if the outbound bandwidth quotas of the peer allow sending a message of the given class
+    fn has_bandwidth(&self, peer_id: &PeerId, class: TrafficClass) -> bool;
 }
 
 impl Clone for Box<dyn ActiveConnectionsTrait> {
@@ -42,7 +45,13 @@ impl Clone for Box<dyn ActiveConnectionsTrait> {
     }
 }
 
-impl ActiveConnectionsTrait for SharedActiveConnections<PeerId> {
+#[derive(Clone)]
+pub struct ActiveConnectionsImpl {
+    connections: SharedActiveConnections<PeerId>,
+    bandwidth_limiter: SharedBandwidthLimiter,
+}
+
+impl ActiveConnectionsTrait for ActiveConnectionsImpl {
     fn send_to_peer(
         &self,
         peer_id: &PeerId,
@@ -50,7 +59,7 @@ impl ActiveConnectionsTrait for SharedActiveConnections<PeerId> {
         message: Message,
         high_priority: bool,
     ) -> Result<(), ProtocolError> {
-        if let Some(connection) = self.read().connections.get(peer_id) {
+        if let Some(connection) = self.connections.read().connections.get(peer_id) {
             connection
                 .send_channels
                 .try_send(message_serializer, message, high_priority)
@@ -65,13 +74,19 @@ impl ActiveConnectionsTrait for SharedActiveConnections<PeerId> {
     }
 
     fn get_peer_ids_connected(&self) -> HashSet<PeerId> {
-        self.read().connections.keys().cloned().collect()
+        self.connections
+            .read()
+            .connections
+            .keys()
+            .cloned()
+            .collect()
     }
 
     fn get_peers_connected(
         &self,
     ) -> HashMap<PeerId, (SocketAddr, PeerConnectionType, Option<String>)> {
-        self.read()
+        self.connections
+            .read()
             .connections
             .iter()
             .map(|(peer_id, connection)| {
@@ -88,29 +103,44 @@ impl ActiveConnectionsTrait for SharedActiveConnections<PeerId> {
     }
 
     fn get_nb_out_connections(&self) -> usize {
-        self.read().nb_out_connections
+        self.connections.read().nb_out_connections
     }
 
     fn get_nb_in_connections(&self) -> usize {
-        self.read().nb_in_connections
+        self.connections.read().nb_in_connections
     }
 
     fn shutdown_connection(&mut self, peer_id: &PeerId) {
-        if let Some(connection) = self.write().connections.get_mut(peer_id) {
+        if let Some(connection) = self.connections.write().connections.get_mut(peer_id) {
             connection.shutdown();
         }
     }
 
     fn get_peers_connections_bandwidth(&self) -> HashMap<String, (u64, u64)> {
         let mut map = HashMap::new();
-        for (peerid, conn) in self.read().connections.iter() {
+        for (peerid, conn) in self.connections.read().connections.iter() {
             map.insert(peerid.to_string(), conn.endpoint.get_bandwidth());
         }
         map
     }
 
     fn get_peer_ids_out_connection_queue(&self) -> HashSet<SocketAddr> {
-        self.read().out_connection_queue.clone()
+        self.connections.read().out_connection_queue.clone()
+    }
+
+    fn has_bandwidth(&self, peer_id: &PeerId, class: TrafficClass) -> bool {
+        let Some(bytes_sent) = self
+            .connections
+            .read()
+            .connections
+            .get(peer_id)
+            .map(|connection| connection.endpoint.get_bandwidth().0)
+        else {
+            return true;
+        };
+        self.bandwidth_limiter
+            .lock()
+            .allow_outbound(peer_id, class, bytes_sent)
     }
 }
 
@@ -137,19 +167,27 @@ pub trait NetworkController: Send + Sync {
 
 pub struct NetworkControllerImpl {
     peernet_manager: PeerNetManager<PeerId, Context, MassaHandshake, MessagesHandler>,
+    bandwidth_limiter: SharedBandwidthLimiter,
 }
 
 impl NetworkControllerImpl {
     pub fn new(
         peernet_manager: PeerNetManager<PeerId, Context, MassaHandshake, MessagesHandler>,
+        bandwidth_limiter: SharedBandwidthLimiter,
     ) -> Self {
-        Self { peernet_manager }
+        Self {
+            peernet_manager,
+            bandwidth_limiter,
+        }
     }
 }
 
 impl NetworkController for NetworkControllerImpl {
     fn get_active_connections(&self) -> Box<dyn ActiveConnectionsTrait> {
-        Box::new(self.peernet_manager.active_connections.clone())
+        Box::new(ActiveConnectionsImpl {
+            connections: self.peernet_manager.active_connections.clone(),
+            bandwidth_limiter: self.bandwidth_limiter.clone(),
+        })
     }
 
     fn start_listener(
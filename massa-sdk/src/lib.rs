@@ -21,11 +21,16 @@ use massa_api_exports::{
     block::{BlockInfo, BlockSummary},
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
     endorsement::EndorsementInfo,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
-    node::NodeStatus,
-    operation::{OperationInfo, OperationInput},
+    execution::{
+        ExecuteReadOnlyResponse, GasEstimationRequest, GasEstimationResponse, OperationSimulation,
+        OperationSimulationResponse, ReadOnlyBytecodeExecution, ReadOnlyCall,
+    },
+    node::{NodeStatus, PeerInfo},
+    operation::{OperationInfo, OperationInput, OperationStatusUpdate},
+    page::PageRequest,
     TimeInterval,
 };
+use massa_hash::Hash;
 use massa_models::secure_share::SecureShare;
 use massa_models::{
     address::Address,
@@ -35,15 +40,22 @@ use massa_models::{
     clique::Clique,
     composite::PubkeySig,
     endorsement::EndorsementId,
-    execution::EventFilter,
+    execution::{
+        AddressHistoryEntry, AsyncMessageEntry, AsyncMessageFilter, EventFilter, ExecutionTrace,
+        ExecutionTraceId,
+    },
+    ip_list::{IpListEntry, IpNetwork},
     node::NodeId,
     operation::{Operation, OperationId},
     output_event::SCOutputEvent,
     prehash::{PreHashMap, PreHashSet},
+    state_proof::StateProof,
+    stats::FeeStats,
     version::Version,
 };
 use massa_proto_rs::massa::api::v1::private_service_client::PrivateServiceClient;
 use massa_proto_rs::massa::api::v1::public_service_client::PublicServiceClient;
+use massa_time::MassaTime;
 use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
 use thiserror::Error;
@@ -194,11 +206,16 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Bans given node id(s)
+    /// Bans given node id(s), for `duration` if set
     /// No confirmation to expect.
-    pub async fn node_ban_by_id(&self, ids: Vec<NodeId>) -> RpcResult<()> {
+    pub async fn node_ban_by_id(
+        &self,
+        ids: Vec<NodeId>,
+        reason: Option<String>,
+        duration: Option<MassaTime>,
+    ) -> RpcResult<()> {
         self.http_client
-            .request("node_ban_by_id", rpc_params![ids])
+            .request("node_ban_by_id", rpc_params![ids, reason, duration])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
@@ -221,6 +238,14 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Returns the connected peers, with their traffic, message counts and connection statistics.
+    pub async fn get_peers(&self) -> RpcResult<Vec<PeerInfo>> {
+        self.http_client
+            .request("get_peers", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Returns node peers whitelist IP address(es).
     pub async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
         self.http_client
@@ -245,8 +270,8 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Returns node bootstrap whitelist IP address(es).
-    pub async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
+    /// Returns node bootstrap whitelist entries.
+    pub async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpListEntry>> {
         self.http_client
             .request("node_bootstrap_whitelist", rpc_params![])
             .await
@@ -262,42 +287,60 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Add IP address(es) to node bootstrap whitelist.
-    pub async fn node_add_to_bootstrap_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    /// Add entries (IP address or CIDR range, optional expiry time and reason) to node bootstrap whitelist.
+    pub async fn node_add_to_bootstrap_whitelist(
+        &self,
+        entries: Vec<IpListEntry>,
+    ) -> RpcResult<()> {
         self.http_client
-            .request("node_add_to_bootstrap_whitelist", rpc_params![ips])
+            .request("node_add_to_bootstrap_whitelist", rpc_params![entries])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Remove IP address(es) to bootstrap whitelist.
-    pub async fn node_remove_from_bootstrap_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    /// Remove IP address(es) or CIDR range(s) from bootstrap whitelist.
+    pub async fn node_remove_from_bootstrap_whitelist(
+        &self,
+        networks: Vec<IpNetwork>,
+    ) -> RpcResult<()> {
         self.http_client
-            .request("node_remove_from_bootstrap_whitelist", rpc_params![ips])
+            .request(
+                "node_remove_from_bootstrap_whitelist",
+                rpc_params![networks],
+            )
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Returns node bootstrap blacklist IP address(es).
-    pub async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpAddr>> {
+    /// Returns node bootstrap blacklist entries.
+    pub async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpListEntry>> {
         self.http_client
             .request("node_bootstrap_blacklist", rpc_params![])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Add IP address(es) to node bootstrap blacklist.
-    pub async fn node_add_to_bootstrap_blacklist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    /// Add entries (IP address or CIDR range, optional expiry time and reason) to node bootstrap blacklist.
+    pub async fn node_add_to_bootstrap_blacklist(
+        &self,
+        entries: Vec<IpListEntry>,
+    ) -> RpcResult<()> {
         self.http_client
-            .request("node_add_to_bootstrap_blacklist", rpc_params![ips])
+            .request("node_add_to_bootstrap_blacklist", rpc_params![entries])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Remove IP address(es) to bootstrap blacklist.
-    pub async fn node_remove_from_bootstrap_blacklist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    /// Remove IP address(es) or CIDR range(s) from bootstrap blacklist.
+    pub async fn node_remove_from_bootstrap_blacklist(
+        &self,
+        networks: Vec<IpNetwork>,
+    ) -> RpcResult<()> {
         self.http_client
-            .request("node_remove_from_bootstrap_blacklist", rpc_params![ips])
+            .request(
+                "node_remove_from_bootstrap_blacklist",
+                rpc_params![networks],
+            )
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
@@ -394,6 +437,77 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Get the final executed operations involving an address, most recent first
+    pub async fn get_address_history(
+        &self,
+        address: Address,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<Vec<AddressHistoryEntry>> {
+        self.http_client
+            .request("get_address_history", rpc_params![address, page_request])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Get the messages of the final asynchronous pool matching a filter, in expected execution order
+    pub async fn get_async_messages(
+        &self,
+        filter: AsyncMessageFilter,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<Vec<AsyncMessageEntry>> {
+        self.http_client
+            .request("get_async_messages", rpc_params![filter, page_request])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Get the proof of inclusion (or exclusion) of a raw key in the final state
+    pub async fn get_state_proof(&self, key: Vec<u8>) -> RpcResult<StateProof> {
+        self.http_client
+            .request("get_state_proof", rpc_params![key])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Get the fee statistics of the operation pool,
+    /// with the fees recommended for an inclusion within `target_delay` periods
+    pub async fn get_fee_estimate(&self, target_delay: u64) -> RpcResult<FeeStats> {
+        self.http_client
+            .request("get_fee_estimate", rpc_params![target_delay])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Get the traces of executed operations and asynchronous messages
+    pub async fn get_execution_traces(
+        &self,
+        ids: Vec<ExecutionTraceId>,
+    ) -> RpcResult<Vec<Option<ExecutionTrace>>> {
+        self.http_client
+            .request("get_execution_traces", rpc_params![ids])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Get the final value of a raw state key, checked against a state tree root obtained from a trusted source.
+    /// The state tree root is not committed to by blocks: `trusted_root` must be the root of the final state
+    /// at the slot the node builds the proof at, for example as announced by several independent nodes.
+    /// Returns `None` if the key is proven to be absent from the final state.
+    pub async fn get_verified_state_value(
+        &self,
+        key: Vec<u8>,
+        trusted_root: &Hash,
+    ) -> RpcResult<Option<Vec<u8>>> {
+        let proof = self.get_state_proof(key.clone()).await?;
+        if proof.key != key || !proof.verify(trusted_root) {
+            return Err(to_error_obj(format!(
+                "invalid state proof for root {} at slot {}",
+                trusted_root, proof.slot
+            )));
+        }
+        Ok(proof.value)
+    }
+
     /// Get datastore entries
     pub async fn get_datastore_entries(
         &self,
@@ -453,6 +567,38 @@ impl RpcClient {
                 to_error_obj("missing return value on execute_read_only_call".to_owned())
             })
     }
+
+    /// simulate the execution of an unsigned operation
+    pub async fn simulate_operation(
+        &self,
+        simulation: OperationSimulation,
+    ) -> RpcResult<OperationSimulationResponse> {
+        self.http_client
+            .request::<Vec<OperationSimulationResponse>, Vec<Vec<OperationSimulation>>>(
+                "simulate_operation",
+                vec![vec![simulation]],
+            )
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))?
+            .pop()
+            .ok_or_else(|| to_error_obj("missing return value on simulate_operation".to_owned()))
+    }
+
+    /// estimate the gas limit of a `CallSC` or `ExecuteSC` operation
+    pub async fn estimate_gas(
+        &self,
+        estimation: GasEstimationRequest,
+    ) -> RpcResult<GasEstimationResponse> {
+        self.http_client
+            .request::<Vec<GasEstimationResponse>, Vec<Vec<GasEstimationRequest>>>(
+                "estimate_gas",
+                vec![vec![estimation]],
+            )
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))?
+            .pop()
+            .ok_or_else(|| to_error_obj("missing return value on estimate_gas".to_owned()))
+    }
 }
 
 /// Client V2
@@ -626,6 +772,24 @@ impl RpcClientV2 {
             Err(to_error_obj("no WebSocket client instance found".to_owned()).into())
         }
     }
+
+    /// Status changes of operations.
+    pub async fn subscribe_operations_status(
+        &self,
+        operation_ids: Vec<OperationId>,
+    ) -> Result<Subscription<OperationStatusUpdate>, jsonrpsee::core::Error> {
+        if let Some(client) = self.ws_client.as_ref() {
+            client
+                .subscribe(
+                    "subscribe_operations_status",
+                    rpc_params![operation_ids],
+                    "unsubscribe_operations_status",
+                )
+                .await
+        } else {
+            Err(to_error_obj("no WebSocket client instance found".to_owned()).into())
+        }
+    }
 }
 
 fn http_client_from_url(url: &str, http_config: &HttpConfig) -> HttpClient<HttpBackend> {
@@ -24,4 +24,8 @@ pub enum WalletError {
     MissingKeyError(Address),
     /// `MassaCipher` error: {0}
     MassaCipherError(#[from] massa_cipher::CipherError),
+    /// JSON error: {0}
+    JSONError(#[from] serde_json::Error),
+    /// Signer error: {0}
+    SignerError(String),
 }
@@ -5,6 +5,7 @@
 #![warn(unused_crate_dependencies)]
 
 pub use error::WalletError;
+pub use signer::*;
 
 use massa_cipher::{decrypt, encrypt, CipherData, Salt};
 use massa_hash::Hash;
@@ -21,6 +22,7 @@ use std::path::PathBuf;
 use std::str::FromStr;
 
 mod error;
+mod signer;
 
 /// Contains the keypairs created in the wallet.
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -0,0 +1,282 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Signing abstraction for the staking keys.
+//!
+//! The block and endorsement factories only need the public key of a staking address
+//! and a signature of the hash they produce. A `Signer` provides both, either from the keys
+//! of the node wallet (`WalletSigner`) or from an external signer process reached through
+//! a local Unix socket (`UnixSocketSigner`), so that the secret keys never enter the node.
+//!
+//! The Unix socket protocol exchanges one JSON `SignerRequest` per line,
+//! each answered by one JSON `SignerResponse` per line.
+//! `serve_unix_socket_signer` implements the signer side of the protocol.
+
+use crate::{Wallet, WalletError};
+use massa_hash::Hash;
+use massa_models::address::Address;
+use massa_signature::{PublicKey, Signature};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Provides the public keys and signatures of staking addresses
+pub trait Signer: Send + Sync {
+    /// Get the public key of an address, or `None` if the address is not managed by this signer
+    fn get_public_key(&self, address: &Address) -> Result<Option<PublicKey>, WalletError>;
+
+    /// Sign a hash with the key of an address
+    fn sign_hash(&self, address: &Address, hash: &Hash) -> Result<Signature, WalletError>;
+}
+
+/// Signer using the keys held by the node wallet
+pub struct WalletSigner {
+    wallet: Arc<RwLock<Wallet>>,
+}
+
+impl WalletSigner {
+    /// Creates a signer from a shared wallet
+    pub fn new(wallet: Arc<RwLock<Wallet>>) -> Self {
+        Self { wallet }
+    }
+}
+
+impl Signer for WalletSigner {
+    fn get_public_key(&self, address: &Address) -> Result<Option<PublicKey>, WalletError> {
+        Ok(self.wallet.read().find_associated_public_key(address))
+    }
+
+    fn sign_hash(&self, address: &Address, hash: &Hash) -> Result<Signature, WalletError> {
+        let wallet = self.wallet.read();
+        let keypair = wallet
+            .find_associated_keypair(address)
+            .ok_or(WalletError::MissingKeyError(*address))?;
+        Ok(keypair.sign(hash)?)
+    }
+}
+
+/// Request sent to an external signer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SignerRequest {
+    /// get the public key of an address
+    GetPublicKey {
+        /// staking address
+        address: Address,
+    },
+    /// sign a hash with the key of an address
+    SignHash {
+        /// staking address
+        address: Address,
+        /// hash to sign
+        hash: Hash,
+    },
+}
+
+/// Response of an external signer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SignerResponse {
+    /// public key of the address, `None` if the address is not managed by the signer
+    PublicKey(Option<PublicKey>),
+    /// signature of the hash
+    Signature(Signature),
+    /// the request failed
+    Error(String),
+}
+
+/// Answer a request with a given signer
+pub fn process_signer_request(signer: &dyn Signer, request: SignerRequest) -> SignerResponse {
+    let result = match request {
+        SignerRequest::GetPublicKey { address } => signer
+            .get_public_key(&address)
+            .map(SignerResponse::PublicKey),
+        SignerRequest::SignHash { address, hash } => signer
+            .sign_hash(&address, &hash)
+            .map(SignerResponse::Signature),
+    };
+    result.unwrap_or_else(|err| SignerResponse::Error(err.to_string()))
+}
+
+#[cfg(unix)]
+pub use unix_socket::{serve_unix_socket_signer, UnixSocketSigner};
+
+#[cfg(unix)]
+mod unix_socket {
+    use super::{process_signer_request, Signer, SignerRequest, SignerResponse};
+    use crate::WalletError;
+    use massa_hash::Hash;
+    use massa_models::{address::Address, prehash::PreHashMap};
+    use massa_signature::{PublicKey, Signature};
+    use parking_lot::Mutex;
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+    use tracing::warn;
+
+    /// Duration during which an address unknown to the external signer is not asked again
+    const UNKNOWN_ADDRESS_CACHE_DURATION: Duration = Duration::from_secs(60);
+
+    /// Signer delegating to an external signer process listening on a local Unix socket
+    pub struct UnixSocketSigner {
+        /// path of the socket
+        socket_path: PathBuf,
+        /// maximum duration of a request
+        timeout: Duration,
+        /// public keys returned by the external signer (`None` for unknown addresses), and when they were asked
+        public_keys: Mutex<PreHashMap<Address, (Option<PublicKey>, Instant)>>,
+    }
+
+    impl UnixSocketSigner {
+        /// Creates a signer reaching the external signer at `socket_path`
+        pub fn new(socket_path: PathBuf, timeout: Duration) -> Self {
+            Self {
+                socket_path,
+                timeout,
+                public_keys: Default::default(),
+            }
+        }
+
+        fn request(&self, request: &SignerRequest) -> Result<SignerResponse, WalletError> {
+            let mut stream = UnixStream::connect(&self.socket_path)?;
+            stream.set_read_timeout(Some(self.timeout))?;
+            stream.set_write_timeout(Some(self.timeout))?;
+            let mut line = serde_json::to_string(request)?;
+            line.push('\n');
+            stream.write_all(line.as_bytes())?;
+
+            let mut response = String::new();
+            BufReader::new(stream).read_line(&mut response)?;
+            Ok(serde_json::from_str(&response)?)
+        }
+    }
+
+    impl Signer for UnixSocketSigner {
+        fn get_public_key(&self, address: &Address) -> Result<Option<PublicKey>, WalletError> {
+            // the factories ask for the public key of every drawn address:
+            // the key of an address never changes, but an unknown address may be added to the external signer
+            if let Some((public_key, asked_at)) = self.public_keys.lock().get(address) {
+                if public_key.is_some() || asked_at.elapsed() < UNKNOWN_ADDRESS_CACHE_DURATION {
+                    return Ok(*public_key);
+                }
+            }
+            let public_key =
+                match self.request(&SignerRequest::GetPublicKey { address: *address })? {
+                    SignerResponse::PublicKey(public_key) => public_key,
+                    SignerResponse::Error(err) => return Err(WalletError::SignerError(err)),
+                    response => {
+                        return Err(WalletError::SignerError(format!(
+                            "unexpected response: {:?}",
+                            response
+                        )))
+                    }
+                };
+            self.public_keys
+                .lock()
+                .insert(*address, (public_key, Instant::now()));
+            Ok(public_key)
+        }
+
+        fn sign_hash(&self, address: &Address, hash: &Hash) -> Result<Signature, WalletError> {
+            let response = self.request(&SignerRequest::SignHash {
+                address: *address,
+                hash: *hash,
+            })?;
+            match response {
+                SignerResponse::Signature(signature) => Ok(signature),
+                SignerResponse::Error(err) => Err(WalletError::SignerError(err)),
+                response => Err(WalletError::SignerError(format!(
+                    "unexpected response: {:?}",
+                    response
+                ))),
+            }
+        }
+    }
+
+    /// Serve the requests received on a Unix socket with a given signer.
+    /// This is the signer side of `UnixSocketSigner`, to be run by an external signer process.
+    /// A failing connection is logged and dropped without stopping the signer.
+    pub fn serve_unix_socket_signer(listener: UnixListener, signer: &dyn Signer) {
+        for stream in listener.incoming() {
+            let result = stream
+                .map_err(WalletError::from)
+                .and_then(|stream| serve_connection(stream, signer));
+            if let Err(err) = result {
+                warn!("signer connection failed: {}", err);
+            }
+        }
+    }
+
+    /// Answer the requests of a single connection until it is closed
+    fn serve_connection(mut stream: UnixStream, signer: &dyn Signer) -> Result<(), WalletError> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut line = String::new();
+        while reader.read_line(&mut line)? > 0 {
+            let response = match serde_json::from_str::<SignerRequest>(&line) {
+                Ok(request) => process_signer_request(signer, request),
+                Err(err) => SignerResponse::Error(format!("invalid request: {}", err)),
+            };
+            let mut response_line = serde_json::to_string(&response)?;
+            response_line.push('\n');
+            stream.write_all(response_line.as_bytes())?;
+            line.clear();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+    use std::io::Write;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::time::Duration;
+
+    #[test]
+    fn test_unix_socket_signer() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let address = Address::from_public_key(&keypair.get_public_key());
+        let wallet_dir = tempfile::TempDir::new().unwrap();
+        let mut wallet = Wallet::new(wallet_dir.path().to_path_buf(), "test".to_string()).unwrap();
+        wallet.add_keypairs(vec![keypair.clone()]).unwrap();
+        let wallet_signer = WalletSigner::new(Arc::new(RwLock::new(wallet)));
+
+        let socket_dir = tempfile::TempDir::new().unwrap();
+        let socket_path = socket_dir.path().join("signer.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+        std::thread::spawn(move || serve_unix_socket_signer(listener, &wallet_signer));
+
+        // a client disconnecting before reading its response does not stop the signer
+        let mut stream = UnixStream::connect(&socket_path).unwrap();
+        let request = SignerRequest::GetPublicKey { address };
+        stream
+            .write_all(format!("{}\n", serde_json::to_string(&request).unwrap()).as_bytes())
+            .unwrap();
+        drop(stream);
+
+        let signer = UnixSocketSigner::new(socket_path.clone(), Duration::from_secs(5));
+        assert_eq!(
+            signer.get_public_key(&address).unwrap(),
+            Some(keypair.get_public_key())
+        );
+        let unknown_address =
+            Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        assert_eq!(signer.get_public_key(&unknown_address).unwrap(), None);
+
+        let hash = Hash::compute_from(b"content");
+        let signature = signer.sign_hash(&address, &hash).unwrap();
+        keypair
+            .get_public_key()
+            .verify_signature(&hash, &signature)
+            .unwrap();
+        assert!(signer.sign_hash(&unknown_address, &hash).is_err());
+
+        // the public keys are cached: they are still known once the external signer is unreachable
+        std::fs::remove_file(&socket_path).unwrap();
+        assert_eq!(
+            signer.get_public_key(&address).unwrap(),
+            Some(keypair.get_public_key())
+        );
+        assert_eq!(signer.get_public_key(&unknown_address).unwrap(), None);
+        assert!(signer.sign_hash(&address, &hash).is_err());
+    }
+}